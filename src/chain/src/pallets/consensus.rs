@@ -0,0 +1,454 @@
+//! Consensus pallet.
+//!
+//! Active validators set, for each subnet, the weights they assign to
+//! the miners registered there.  Their weights are normalized to add
+//! up to `u16::MAX`, so every validator weighs miners on the same scale
+//! however it expressed them.  At the end of every epoch the weights
+//! set for each subnet are aggregated into one set of consensus weights
+//! by the subnet's `AggregationMethod`: a stake‑weighted mean by
+//! default, a stake‑weighted median, or the runtime's own `Aggregator`.
+//! Only the `MaxWeightsPerValidator` highest aggregated weights are
+//! kept, and a subnet for which no active validator set weights during
+//! the epoch is left without consensus weights.
+//!
+//! Any validator registered on a subnet may set weights there, but only
+//! those of active validators are aggregated.  The epoch in which each
+//! validator last set weights is kept so that the validator registry
+//! can tell which validators took part in consensus.
+
+pub use pallet::*;
+
+use sp_neuro_core::{distribute, Rounding};
+use sp_runtime::traits::{AtLeast32BitUnsigned, UniqueSaturatedInto};
+use sp_std::{collections::btree_map::BTreeMap, prelude::*};
+
+/// Stake of the validators taking part in consensus, kept by the
+/// validator registry.
+pub trait ValidatorStakes<AccountId, Balance> {
+    /// Stake of `validator` on `subnet_id`, if it is registered and
+    /// active there.
+    fn active_stake(subnet_id: u32, validator: &AccountId) -> Option<Balance>;
+
+    /// Whether `validator` is registered on `subnet_id`, active or not.
+    fn is_validator(subnet_id: u32, validator: &AccountId) -> bool;
+}
+
+/// Miners that validators may weigh, kept by the miner registry.
+pub trait SubnetMiners<AccountId> {
+    /// Whether `miner` is registered on `subnet_id`.
+    fn is_miner(subnet_id: u32, miner: &AccountId) -> bool;
+}
+
+/// A validator, its stake and the weights it assigned to miners.
+pub type Submission<AccountId, Balance> = (AccountId, Balance, Vec<(AccountId, u16)>);
+
+/// Combines the weights submitted for a subnet into consensus weights.
+/// Miners missing from the result get no weight.
+pub trait WeightAggregator<AccountId, Balance> {
+    fn aggregate(&self, submissions: &[Submission<AccountId, Balance>])
+        -> BTreeMap<AccountId, u16>;
+}
+
+/// Mean of the submitted weights, weighted by stake.  A validator that
+/// did not weigh a miner counts as having given it zero.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StakeWeightedMean;
+
+impl<AccountId, Balance> WeightAggregator<AccountId, Balance> for StakeWeightedMean
+where
+    AccountId: Ord + Clone,
+    Balance: AtLeast32BitUnsigned + Copy,
+{
+    fn aggregate(
+        &self,
+        submissions: &[Submission<AccountId, Balance>],
+    ) -> BTreeMap<AccountId, u16> {
+        let total = total_stake(submissions);
+        if total == 0 {
+            return BTreeMap::new();
+        }
+        let mut sums = BTreeMap::<AccountId, u128>::new();
+        for (_, stake, weights) in submissions {
+            let stake: u128 = (*stake).unique_saturated_into();
+            for (miner, weight) in weights {
+                let sum = sums.entry(miner.clone()).or_default();
+                *sum = sum.saturating_add(stake.saturating_mul(*weight as u128));
+            }
+        }
+        sums.into_iter()
+            .map(|(miner, sum)| (miner, (sum / total) as u16))
+            .filter(|(_, weight)| *weight > 0)
+            .collect()
+    }
+}
+
+/// Median of the submitted weights, weighted by stake: the lowest
+/// weight such that validators holding at least half the stake gave
+/// that weight or less.  A validator that did not weigh a miner counts
+/// as having given it zero.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StakeWeightedMedian;
+
+impl<AccountId, Balance> WeightAggregator<AccountId, Balance> for StakeWeightedMedian
+where
+    AccountId: Ord + Clone,
+    Balance: AtLeast32BitUnsigned + Copy,
+{
+    fn aggregate(
+        &self,
+        submissions: &[Submission<AccountId, Balance>],
+    ) -> BTreeMap<AccountId, u16> {
+        let total = total_stake(submissions);
+        if total == 0 {
+            return BTreeMap::new();
+        }
+        let mut votes = BTreeMap::<AccountId, Vec<(u16, u128)>>::new();
+        for (_, stake, weights) in submissions {
+            let stake: u128 = (*stake).unique_saturated_into();
+            for (miner, weight) in weights {
+                votes
+                    .entry(miner.clone())
+                    .or_default()
+                    .push((*weight, stake));
+            }
+        }
+        votes
+            .into_iter()
+            .filter_map(|(miner, mut votes)| {
+                votes.sort_unstable();
+                // Stake that gave this miner nothing sits below every vote.
+                let mut below: u128 = total.saturating_sub(
+                    votes
+                        .iter()
+                        .fold(0, |sum, (_, stake)| sum.saturating_add(*stake)),
+                );
+                if below.saturating_mul(2) >= total {
+                    return None;
+                }
+                for (weight, stake) in votes {
+                    below = below.saturating_add(stake);
+                    if below.saturating_mul(2) >= total {
+                        return (weight > 0).then_some((miner, weight));
+                    }
+                }
+                None
+            })
+            .collect()
+    }
+}
+
+/// Scale `weights` so that they add up to `u16::MAX`, keeping their
+/// proportions.  Weights that are all zero stay zero.
+pub fn normalize_weights<AccountId>(weights: Vec<(AccountId, u16)>) -> Vec<(AccountId, u16)> {
+    let raw: Vec<u32> = weights.iter().map(|(_, weight)| *weight as u32).collect();
+    let shares = distribute(u16::MAX as u128, &raw, Rounding::Floor);
+    weights
+        .into_iter()
+        .zip(shares)
+        .map(|((miner, _), share)| (miner, share as u16))
+        .collect()
+}
+
+fn total_stake<AccountId, Balance>(submissions: &[Submission<AccountId, Balance>]) -> u128
+where
+    Balance: AtLeast32BitUnsigned + Copy,
+{
+    submissions
+        .iter()
+        .map(|(_, stake, _)| UniqueSaturatedInto::<u128>::unique_saturated_into(*stake))
+        .fold(0, u128::saturating_add)
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+    use super::{
+        normalize_weights, StakeWeightedMean, StakeWeightedMedian, SubnetMiners, ValidatorStakes,
+        WeightAggregator,
+    };
+    use crate::pallets::emissions::RewardRecipients;
+    use crate::pallets::subnet_registry;
+    use crate::pallets::validator_registry::WeightSubmissions;
+    use frame_support::pallet_prelude::*;
+    use frame_system::pallet_prelude::*;
+    use sp_runtime::traits::{AtLeast32BitUnsigned, UniqueSaturatedInto, Zero};
+    use sp_std::{collections::btree_map::BTreeMap, prelude::*};
+
+    /// How a subnet's weight submissions are aggregated.
+    #[derive(
+        Clone, Copy, Default, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen,
+    )]
+    pub enum AggregationMethod {
+        #[default]
+        StakeWeightedMean,
+        StakeWeightedMedian,
+        /// The runtime's `Aggregator`.
+        Custom,
+    }
+
+    /// Weights assigned to miners.
+    pub type WeightsOf<T> = BoundedVec<
+        (<T as frame_system::Config>::AccountId, u16),
+        <T as Config>::MaxWeightsPerValidator,
+    >;
+
+    #[pallet::pallet]
+    pub struct Pallet<T>(_);
+
+    #[pallet::config]
+    pub trait Config: frame_system::Config + subnet_registry::Config {
+        type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+        /// Balance in which validator stake is held.
+        type Balance: AtLeast32BitUnsigned + Copy;
+
+        /// Stake of active validators.
+        type ValidatorStakes: ValidatorStakes<Self::AccountId, Self::Balance>;
+
+        /// Miners registered on each subnet.
+        type Miners: SubnetMiners<Self::AccountId>;
+
+        /// Aggregation used by subnets set to `AggregationMethod::Custom`.
+        type Aggregator: WeightAggregator<Self::AccountId, Self::Balance> + Default;
+
+        /// Maximum number of miners one validator may weigh, and kept in
+        /// a subnet's consensus weights.
+        #[pallet::constant]
+        type MaxWeightsPerValidator: Get<u32>;
+
+        /// Number of blocks in an epoch.  Epochs are numbered from zero
+        /// at genesis.
+        #[pallet::constant]
+        type EpochLength: Get<BlockNumberFor<Self>>;
+
+        /// Origin allowed to set how a subnet's weights are aggregated.
+        type AdminOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+    }
+
+    /// Weights set during the current epoch, by subnet and validator.
+    #[pallet::storage]
+    #[pallet::getter(fn weights)]
+    pub type Weights<T: Config> =
+        StorageDoubleMap<_, Blake2_128Concat, u32, Blake2_128Concat, T::AccountId, WeightsOf<T>>;
+
+    /// Epoch in which each validator last set weights, by subnet and
+    /// validator.
+    #[pallet::storage]
+    #[pallet::getter(fn last_submission)]
+    pub type LastSubmission<T: Config> =
+        StorageDoubleMap<_, Blake2_128Concat, u32, Blake2_128Concat, T::AccountId, u32>;
+
+    /// Consensus weights of each subnet as of the last epoch, ordered by
+    /// miner.
+    #[pallet::storage]
+    #[pallet::getter(fn consensus_weights)]
+    pub type ConsensusWeights<T: Config> =
+        StorageMap<_, Blake2_128Concat, u32, WeightsOf<T>, ValueQuery>;
+
+    /// Aggregation method of each subnet.
+    #[pallet::storage]
+    #[pallet::getter(fn aggregation_method)]
+    pub type AggregationMethods<T> =
+        StorageMap<_, Blake2_128Concat, u32, AggregationMethod, ValueQuery>;
+
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        /// A validator set its weights for a subnet.
+        WeightsSet {
+            subnet_id: u32,
+            validator: T::AccountId,
+            /// Number of miners weighed.
+            count: u32,
+        },
+        /// A subnet's aggregation method was changed.
+        AggregationMethodSet {
+            subnet_id: u32,
+            method: AggregationMethod,
+        },
+        /// New consensus weights were stored for a subnet.
+        ConsensusReached {
+            subnet_id: u32,
+            method: AggregationMethod,
+        },
+    }
+
+    #[pallet::error]
+    pub enum Error<T> {
+        /// The caller is not a validator registered on the subnet.
+        NotValidator,
+        /// More than `MaxWeightsPerValidator` miners were weighed.
+        TooManyWeights,
+        /// A miner was weighed more than once.
+        DuplicateMiner,
+        /// A weighed account is not a miner registered on the subnet.
+        UnknownMiner,
+        /// No subnet exists with the given id.
+        SubnetNotFound,
+    }
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_initialize(n: BlockNumberFor<T>) -> Weight {
+            let epoch_length = T::EpochLength::get();
+            if n.is_zero() || epoch_length.is_zero() || !(n % epoch_length).is_zero() {
+                return Weight::zero();
+            }
+            let (submissions, subnets) = Self::end_epoch();
+            T::DbWeight::get().reads_writes(
+                (submissions as u64)
+                    .saturating_add(subnets as u64)
+                    .saturating_mul(2),
+                (submissions as u64).saturating_add(subnets as u64),
+            )
+        }
+    }
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        /// Set the caller's weights for `subnet_id`, replacing any it set
+        /// earlier in the epoch.  The weights are normalized to add up
+        /// to `u16::MAX` before they are stored.
+        #[pallet::call_index(0)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(
+            1u64.saturating_add(T::MaxWeightsPerValidator::get().into()),
+            2,
+        ))]
+        pub fn set_weights(
+            origin: OriginFor<T>,
+            subnet_id: u32,
+            weights: Vec<(T::AccountId, u16)>,
+        ) -> DispatchResult {
+            let validator = ensure_signed(origin)?;
+            ensure!(
+                T::ValidatorStakes::is_validator(subnet_id, &validator),
+                Error::<T>::NotValidator
+            );
+            let weights: WeightsOf<T> =
+                weights.try_into().map_err(|_| Error::<T>::TooManyWeights)?;
+            let mut miners: Vec<_> = weights.iter().map(|(miner, _)| miner).collect();
+            miners.sort();
+            miners.dedup();
+            ensure!(miners.len() == weights.len(), Error::<T>::DuplicateMiner);
+            ensure!(
+                miners
+                    .into_iter()
+                    .all(|miner| T::Miners::is_miner(subnet_id, miner)),
+                Error::<T>::UnknownMiner
+            );
+
+            let count = weights.len() as u32;
+            let weights = BoundedVec::truncate_from(normalize_weights(weights.into_inner()));
+            Weights::<T>::insert(subnet_id, &validator, weights);
+            LastSubmission::<T>::insert(subnet_id, &validator, Self::current_epoch());
+            Self::deposit_event(Event::WeightsSet {
+                subnet_id,
+                validator,
+                count,
+            });
+            Ok(())
+        }
+
+        /// Set how the weights submitted for `subnet_id` are
+        /// aggregated.  Only `AdminOrigin` may do so.
+        #[pallet::call_index(1)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+        pub fn set_aggregation_method(
+            origin: OriginFor<T>,
+            subnet_id: u32,
+            method: AggregationMethod,
+        ) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+            ensure!(
+                subnet_registry::Pallet::<T>::subnets(subnet_id).is_some(),
+                Error::<T>::SubnetNotFound
+            );
+            AggregationMethods::<T>::insert(subnet_id, method);
+            Self::deposit_event(Event::AggregationMethodSet { subnet_id, method });
+            Ok(())
+        }
+    }
+
+    /// Miners are rewarded by their consensus weights.
+    impl<T: Config> RewardRecipients<T::AccountId> for Pallet<T> {
+        fn reward_weights(subnet_id: u32) -> Vec<(T::AccountId, u32)> {
+            ConsensusWeights::<T>::get(subnet_id)
+                .into_iter()
+                .map(|(miner, weight)| (miner, weight.into()))
+                .collect()
+        }
+    }
+
+    /// Validators take part in consensus by setting weights.
+    impl<T: Config> WeightSubmissions<T::AccountId> for Pallet<T> {
+        fn has_submitted(subnet_id: u32, validator: &T::AccountId, epoch: u32) -> bool {
+            LastSubmission::<T>::get(subnet_id, validator) == Some(epoch)
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// The epoch in progress.
+        fn current_epoch() -> u32 {
+            let epoch_length = T::EpochLength::get();
+            if epoch_length.is_zero() {
+                return 0;
+            }
+            (frame_system::Pallet::<T>::block_number() / epoch_length).unique_saturated_into()
+        }
+
+        /// Aggregate and clear the submissions of the epoch that just
+        /// ended.  Submissions of validators that are no longer active
+        /// are dropped, and subnets left without any lose their
+        /// consensus weights.  Returns the number of submissions and of
+        /// subnets processed.
+        fn end_epoch() -> (u32, u32) {
+            let mut count = 0;
+            let mut by_subnet = BTreeMap::<u32, Vec<_>>::new();
+            for (subnet_id, validator, weights) in Weights::<T>::drain() {
+                count += 1;
+                if let Some(stake) = T::ValidatorStakes::active_stake(subnet_id, &validator) {
+                    by_subnet.entry(subnet_id).or_default().push((
+                        validator,
+                        stake,
+                        weights.into_inner(),
+                    ));
+                }
+            }
+
+            let stale: Vec<u32> = ConsensusWeights::<T>::iter_keys()
+                .filter(|subnet_id| !by_subnet.contains_key(subnet_id))
+                .collect();
+            for subnet_id in &stale {
+                ConsensusWeights::<T>::remove(subnet_id);
+            }
+
+            let subnets = (by_subnet.len() + stale.len()) as u32;
+            for (subnet_id, submissions) in by_subnet {
+                let method = AggregationMethods::<T>::get(subnet_id);
+                let aggregated = match method {
+                    AggregationMethod::StakeWeightedMean => {
+                        StakeWeightedMean.aggregate(&submissions)
+                    }
+                    AggregationMethod::StakeWeightedMedian => {
+                        StakeWeightedMedian.aggregate(&submissions)
+                    }
+                    AggregationMethod::Custom => T::Aggregator::default().aggregate(&submissions),
+                };
+                ConsensusWeights::<T>::insert(subnet_id, Self::strongest(aggregated));
+                Self::deposit_event(Event::ConsensusReached { subnet_id, method });
+            }
+            (count, subnets)
+        }
+
+        /// The `MaxWeightsPerValidator` highest of `weights`, ordered by miner.
+        fn strongest(weights: BTreeMap<T::AccountId, u16>) -> WeightsOf<T> {
+            let mut weights: Vec<_> = weights.into_iter().collect();
+            let max = T::MaxWeightsPerValidator::get() as usize;
+            if weights.len() > max {
+                weights.sort_by(|a, b| b.1.cmp(&a.1));
+                weights.truncate(max);
+                weights.sort_by(|a, b| a.0.cmp(&b.0));
+            }
+            BoundedVec::truncate_from(weights)
+        }
+    }
+}
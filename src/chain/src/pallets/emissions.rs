@@ -0,0 +1,275 @@
+//! Emissions pallet.
+//!
+//! Every `EmissionInterval` blocks, which make up one emission epoch,
+//! `BlockEmission` is split between the active subnets in proportion
+//! to their emission weights.  A subnet's share is further split
+//! between its miners, who get `MinerValidatorSplit` of it, and its
+//! validators, who get the rest.  Each side is divided in proportion
+//! to the weights its `RewardRecipients` report and added to
+//! `PendingRewards`.  A share with nobody to receive it is not paid
+//! out, and not counted as emitted either.
+//!
+//! What was paid out is credited to `SubnetEmissions`, and the total
+//! emitted in each epoch is kept in `EmissionHistory` for the last
+//! `HistoryDepth` epochs; older entries are pruned as new ones are
+//! recorded.  Per subnet and per account, amounts accrue in an
+//! [`Accrual`](sp_neuro_core::Accrual), which refuses a credit that
+//! would overflow rather than saturate.  Rewards are minted when
+//! claimed with `claim_rewards`, into accounts that exist.
+
+pub use pallet::*;
+
+use sp_runtime::Percent;
+use sp_std::prelude::*;
+
+/// Emission weights of the subnets, kept by the subnet registry.
+pub trait EmissionWeights {
+    /// Id and emission weight of every active subnet.
+    fn emission_weights() -> Vec<(u32, Percent)>;
+}
+
+/// Accounts sharing one side of a subnet's emission, reported by the
+/// consensus layer for miners and by the validator registry for
+/// validators.
+pub trait RewardRecipients<AccountId> {
+    /// Accounts of `subnet_id` that earn a share of its emission, each
+    /// with its weight.
+    fn reward_weights(subnet_id: u32) -> Vec<(AccountId, u32)>;
+}
+
+impl<AccountId> RewardRecipients<AccountId> for () {
+    fn reward_weights(_: u32) -> Vec<(AccountId, u32)> {
+        Vec::new()
+    }
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+    use super::{EmissionWeights, RewardRecipients};
+    use frame_support::{pallet_prelude::*, traits::Currency};
+    use frame_system::pallet_prelude::*;
+    use sp_neuro_core::{distribute, Accrual, Rounding};
+    use sp_runtime::{
+        traits::{UniqueSaturatedInto, Zero},
+        Percent,
+    };
+    use sp_std::prelude::*;
+
+    pub type BalanceOf<T> =
+        <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+    #[pallet::pallet]
+    pub struct Pallet<T>(_);
+
+    #[pallet::config]
+    pub trait Config: frame_system::Config {
+        type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+        /// Currency emissions are denominated in.
+        type Currency: Currency<Self::AccountId>;
+
+        /// Emission weights of the active subnets.
+        type EmissionWeights: EmissionWeights;
+
+        /// Number of blocks in an emission epoch.
+        #[pallet::constant]
+        type EmissionInterval: Get<BlockNumberFor<Self>>;
+
+        /// Amount emitted at the end of every epoch.
+        #[pallet::constant]
+        type BlockEmission: Get<BalanceOf<Self>>;
+
+        /// Number of epochs whose totals are kept in `EmissionHistory`.
+        #[pallet::constant]
+        type HistoryDepth: Get<u32>;
+
+        /// Share of a subnet's emission that goes to its miners.  The
+        /// rest goes to its validators.
+        #[pallet::constant]
+        type MinerValidatorSplit: Get<Percent>;
+
+        /// Miners of each subnet and their weights.
+        type Miners: RewardRecipients<Self::AccountId>;
+
+        /// Validators of each subnet and their weights.
+        type Validators: RewardRecipients<Self::AccountId>;
+    }
+
+    /// Emissions paid out to the members of each subnet.
+    #[pallet::storage]
+    pub type SubnetEmissions<T: Config> = StorageMap<_, Blake2_128Concat, u32, Accrual, ValueQuery>;
+
+    /// Total emitted in each of the last `HistoryDepth` epochs, by
+    /// epoch.
+    #[pallet::storage]
+    pub type EmissionHistory<T: Config> = StorageMap<_, Blake2_128Concat, u32, BalanceOf<T>>;
+
+    /// Rewards each account has earned and not claimed yet.
+    #[pallet::storage]
+    pub type PendingRewards<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, Accrual, ValueQuery>;
+
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        /// The emission of `epoch` was split between the active subnets.
+        EmissionDistributed { epoch: u32, total: BalanceOf<T> },
+        /// An account was paid its pending rewards.
+        RewardsClaimed {
+            account: T::AccountId,
+            amount: BalanceOf<T>,
+        },
+    }
+
+    #[pallet::error]
+    pub enum Error<T> {
+        /// The caller has no pending rewards.
+        NothingToClaim,
+    }
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_initialize(n: BlockNumberFor<T>) -> Weight {
+            let interval = T::EmissionInterval::get();
+            if n.is_zero() || interval.is_zero() || !(n % interval).is_zero() {
+                return Weight::zero();
+            }
+            let (subnets, recipients) = Self::end_epoch((n / interval).unique_saturated_into());
+            T::DbWeight::get().reads_writes(
+                (subnets as u64)
+                    .saturating_mul(3)
+                    .saturating_add(recipients as u64)
+                    .saturating_add(1),
+                (subnets as u64)
+                    .saturating_add(recipients as u64)
+                    .saturating_add(2),
+            )
+        }
+    }
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        /// Pay the caller its pending rewards.  The caller's account
+        /// must exist; otherwise the rewards stay pending.
+        #[pallet::call_index(0)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(2, 2))]
+        pub fn claim_rewards(origin: OriginFor<T>) -> DispatchResult {
+            let account = ensure_signed(origin)?;
+            let mut pending = PendingRewards::<T>::get(&account);
+            let amount: BalanceOf<T> = pending.drain().unique_saturated_into();
+            ensure!(!amount.is_zero(), Error::<T>::NothingToClaim);
+
+            T::Currency::deposit_into_existing(&account, amount)?;
+            // Keep the entry only for its count of refused rewards.
+            if pending == Accrual::new() {
+                PendingRewards::<T>::remove(&account);
+            } else {
+                PendingRewards::<T>::insert(&account, pending);
+            }
+            Self::deposit_event(Event::RewardsClaimed { account, amount });
+            Ok(())
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// Emissions paid out to the members of `subnet_id` so far.
+        pub fn subnet_emission(subnet_id: u32) -> BalanceOf<T> {
+            SubnetEmissions::<T>::get(subnet_id)
+                .accrued()
+                .unique_saturated_into()
+        }
+
+        /// Rewards `account` has earned and not claimed yet.
+        pub fn pending_rewards(account: T::AccountId) -> BalanceOf<T> {
+            PendingRewards::<T>::get(account)
+                .accrued()
+                .unique_saturated_into()
+        }
+
+        /// Total emitted in each of the last `HistoryDepth` epochs,
+        /// oldest first.  Epochs not recorded yet are left out.
+        pub fn emission_history() -> Vec<(u32, BalanceOf<T>)> {
+            let interval = T::EmissionInterval::get();
+            if interval.is_zero() {
+                return Vec::new();
+            }
+            let current: u32 =
+                (frame_system::Pallet::<T>::block_number() / interval).unique_saturated_into();
+            let oldest = current.saturating_sub(T::HistoryDepth::get().saturating_sub(1));
+            (oldest..=current)
+                .filter_map(|epoch| Some((epoch, EmissionHistory::<T>::get(epoch)?)))
+                .collect()
+        }
+
+        /// Split `BlockEmission` between the active subnets and their
+        /// members, record the total paid out as the emission of
+        /// `epoch` and prune the entry that fell out of the history.
+        /// Returns the number of subnets and of reward recipients
+        /// credited.
+        fn end_epoch(epoch: u32) -> (u32, u32) {
+            let subnets = T::EmissionWeights::emission_weights();
+            let weights: Vec<u32> = subnets
+                .iter()
+                .map(|(_, weight)| weight.deconstruct() as u32)
+                .collect();
+            let shares = distribute(
+                T::BlockEmission::get().unique_saturated_into(),
+                &weights,
+                Rounding::Floor,
+            );
+
+            let mut total = Accrual::new();
+            let mut recipients = 0;
+            for ((subnet_id, _), share) in subnets.iter().zip(shares) {
+                let miners_share = T::MinerValidatorSplit::get().mul_floor(share);
+                let (miners, to_miners) =
+                    Self::reward(T::Miners::reward_weights(*subnet_id), miners_share);
+                let (validators, to_validators) = Self::reward(
+                    T::Validators::reward_weights(*subnet_id),
+                    share.saturating_sub(miners_share),
+                );
+                recipients += miners + validators;
+
+                // A credit that would overflow is refused and counted by
+                // the accrual.  What is paid out in one epoch adds up to
+                // at most `BlockEmission`, so `total` cannot overflow.
+                let paid = to_miners.saturating_add(to_validators);
+                if paid != 0 {
+                    let _ = SubnetEmissions::<T>::mutate(subnet_id, |emission| {
+                        emission.checked_add_reward(paid)
+                    });
+                }
+                let _ = total.checked_add_reward(paid);
+            }
+
+            let total: BalanceOf<T> = total.drain().unique_saturated_into();
+            EmissionHistory::<T>::insert(epoch, total);
+            if let Some(expired) = epoch.checked_sub(T::HistoryDepth::get()) {
+                EmissionHistory::<T>::remove(expired);
+            }
+            Self::deposit_event(Event::EmissionDistributed { epoch, total });
+            (subnets.len() as u32, recipients)
+        }
+
+        /// Add `amount` to the pending rewards of `recipients` in
+        /// proportion to their weights.  A share that would overflow a
+        /// recipient's pending rewards is refused.  Returns the number
+        /// of recipients and the amount actually credited.
+        fn reward(recipients: Vec<(T::AccountId, u32)>, amount: u128) -> (u32, u128) {
+            let weights: Vec<u32> = recipients.iter().map(|(_, weight)| *weight).collect();
+            let shares = distribute(amount, &weights, Rounding::Floor);
+            let mut credited = 0u128;
+            for ((account, _), share) in recipients.iter().zip(shares) {
+                if share != 0
+                    && PendingRewards::<T>::mutate(account, |pending| {
+                        pending.checked_add_reward(share)
+                    })
+                    .is_ok()
+                {
+                    credited = credited.saturating_add(share);
+                }
+            }
+            (recipients.len() as u32, credited)
+        }
+    }
+}
@@ -0,0 +1,270 @@
+//! Miner registry pallet.
+//!
+//! Miners register on an active subnet by reserving at least the
+//! subnet's `min_stake_miner`.  Along with its stake a miner publishes
+//! the endpoint it serves tasks on and declares its capabilities,
+//! typically a SCALE‑encoded [`MinerCapabilities`], so validators can
+//! route tasks to miners able to run them.  The pallet only bounds the
+//! declaration by `MaxCapabilities` bytes; it does not interpret it.
+//! Miners may replace their declaration at any time.
+//!
+//! A miner may deregister once `DeregistrationCooldown` blocks have
+//! passed since it registered, which unreserves its stake.
+
+pub use pallet::*;
+
+use codec::{Decode, Encode};
+use scale_info::TypeInfo;
+use sp_runtime::RuntimeDebug;
+use sp_std::prelude::*;
+
+/// Hardware a miner declares.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+pub struct MinerCapabilities {
+    /// GPU model, e.g. `b"A100"`.  Empty for CPU‑only miners.
+    pub gpu: Vec<u8>,
+    /// GPU memory, in GiB.
+    #[codec(compact)]
+    pub memory_gib: u32,
+    /// Largest model the miner can serve, in parameters.
+    #[codec(compact)]
+    pub max_model_params: u64,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+    use crate::pallets::consensus::SubnetMiners;
+    use crate::pallets::subnet_registry::{self, BalanceOf};
+    use frame_support::{pallet_prelude::*, traits::ReservableCurrency};
+    use frame_system::pallet_prelude::*;
+    use sp_runtime::traits::Saturating;
+    use sp_std::prelude::*;
+
+    /// On‑chain record of a miner on one subnet.
+    #[derive(
+        CloneNoBound,
+        Encode,
+        Decode,
+        EqNoBound,
+        PartialEqNoBound,
+        RuntimeDebugNoBound,
+        TypeInfo,
+        MaxEncodedLen,
+    )]
+    #[scale_info(skip_type_params(T))]
+    #[codec(mel_bound())]
+    pub struct MinerInfo<T: Config> {
+        pub account: T::AccountId,
+        pub subnet_id: u32,
+        /// Address the miner serves tasks on, e.g. a multiaddr.
+        pub endpoint: BoundedVec<u8, T::MaxEndpointLen>,
+        /// Amount reserved as stake.
+        pub stake: BalanceOf<T>,
+        /// Block in which the miner registered.
+        pub registered_at: BlockNumberFor<T>,
+        /// Declared capabilities, see [`MinerCapabilities`](super::MinerCapabilities).
+        pub capabilities: BoundedVec<u8, T::MaxCapabilities>,
+    }
+
+    #[pallet::pallet]
+    pub struct Pallet<T>(_);
+
+    #[pallet::config]
+    pub trait Config: frame_system::Config + subnet_registry::Config {
+        type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+        /// Maximum length of a capability declaration, in bytes.
+        #[pallet::constant]
+        type MaxCapabilities: Get<u32>;
+
+        /// Maximum length of a miner endpoint, in bytes.
+        #[pallet::constant]
+        type MaxEndpointLen: Get<u32>;
+
+        /// Blocks after registering before a miner may deregister and
+        /// have its stake unreserved.
+        #[pallet::constant]
+        type DeregistrationCooldown: Get<BlockNumberFor<Self>>;
+    }
+
+    /// Registered miners by subnet.
+    #[pallet::storage]
+    #[pallet::getter(fn miners)]
+    pub type Miners<T: Config> =
+        StorageDoubleMap<_, Blake2_128Concat, u32, Blake2_128Concat, T::AccountId, MinerInfo<T>>;
+
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        /// A miner registered on a subnet.
+        MinerRegistered {
+            subnet_id: u32,
+            miner: T::AccountId,
+            stake: BalanceOf<T>,
+        },
+        /// A miner replaced its capability declaration.
+        CapabilitiesUpdated { subnet_id: u32, miner: T::AccountId },
+        /// A miner left a subnet and its stake was unreserved.
+        MinerDeregistered {
+            subnet_id: u32,
+            miner: T::AccountId,
+            stake: BalanceOf<T>,
+        },
+    }
+
+    #[pallet::error]
+    pub enum Error<T> {
+        /// No subnet exists with the given id.
+        SubnetNotFound,
+        /// The subnet has been retired.
+        SubnetNotActive,
+        /// The subnet is paused by its owner.
+        SubnetPaused,
+        /// The caller is already a miner on the subnet.
+        AlreadyRegistered,
+        /// The caller is not a miner on the subnet.
+        NotRegistered,
+        /// The stake is below the subnet's `min_stake_miner`.
+        StakeTooLow,
+        /// The caller cannot cover the stake.
+        InsufficientBalance,
+        /// The capability declaration exceeds `MaxCapabilities`.
+        CapabilitiesTooLong,
+        /// The endpoint exceeds `MaxEndpointLen`.
+        EndpointTooLong,
+        /// `DeregistrationCooldown` has not passed since the miner
+        /// registered.
+        DeregistrationCooldownActive,
+    }
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        /// Register the caller as a miner on an active subnet serving
+        /// on `endpoint`, reserving `stake` and declaring
+        /// `capabilities`.
+        #[pallet::call_index(0)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(3, 3))]
+        pub fn register_miner(
+            origin: OriginFor<T>,
+            subnet_id: u32,
+            endpoint: Vec<u8>,
+            stake: BalanceOf<T>,
+            capabilities: Vec<u8>,
+        ) -> DispatchResult {
+            let miner = ensure_signed(origin)?;
+
+            let subnet = subnet_registry::Pallet::<T>::subnets(subnet_id)
+                .ok_or(Error::<T>::SubnetNotFound)?;
+            ensure!(
+                subnet.status != subnet_registry::SubnetStatus::Paused,
+                Error::<T>::SubnetPaused
+            );
+            ensure!(
+                subnet_registry::Pallet::<T>::is_subnet_active(subnet_id),
+                Error::<T>::SubnetNotActive
+            );
+            ensure!(
+                !Miners::<T>::contains_key(subnet_id, &miner),
+                Error::<T>::AlreadyRegistered
+            );
+            ensure!(stake >= subnet.min_stake_miner, Error::<T>::StakeTooLow);
+            let endpoint: BoundedVec<u8, T::MaxEndpointLen> = endpoint
+                .try_into()
+                .map_err(|_| Error::<T>::EndpointTooLong)?;
+            let capabilities = Self::bounded_capabilities(capabilities)?;
+
+            T::Currency::reserve(&miner, stake).map_err(|_| Error::<T>::InsufficientBalance)?;
+            Miners::<T>::insert(
+                subnet_id,
+                &miner,
+                MinerInfo {
+                    account: miner.clone(),
+                    subnet_id,
+                    endpoint,
+                    stake,
+                    registered_at: frame_system::Pallet::<T>::block_number(),
+                    capabilities,
+                },
+            );
+            subnet_registry::Pallet::<T>::note_member_registered(subnet_id);
+
+            Self::deposit_event(Event::MinerRegistered {
+                subnet_id,
+                miner,
+                stake,
+            });
+            Ok(())
+        }
+
+        /// Replace the caller's capability declaration on `subnet_id`.
+        #[pallet::call_index(1)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+        pub fn set_capabilities(
+            origin: OriginFor<T>,
+            subnet_id: u32,
+            capabilities: Vec<u8>,
+        ) -> DispatchResult {
+            let miner = ensure_signed(origin)?;
+            let capabilities = Self::bounded_capabilities(capabilities)?;
+
+            Miners::<T>::try_mutate(subnet_id, &miner, |maybe_info| -> DispatchResult {
+                let info = maybe_info.as_mut().ok_or(Error::<T>::NotRegistered)?;
+                info.capabilities = capabilities;
+                Ok(())
+            })?;
+
+            Self::deposit_event(Event::CapabilitiesUpdated { subnet_id, miner });
+            Ok(())
+        }
+
+        /// Leave `subnet_id` and unreserve the caller's stake.  Only
+        /// allowed once `DeregistrationCooldown` blocks have passed
+        /// since the caller registered; retired subnets are no
+        /// exception.
+        #[pallet::call_index(2)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(2, 2))]
+        pub fn deregister_miner(origin: OriginFor<T>, subnet_id: u32) -> DispatchResult {
+            let miner = ensure_signed(origin)?;
+            let info = Miners::<T>::get(subnet_id, &miner).ok_or(Error::<T>::NotRegistered)?;
+            ensure!(
+                frame_system::Pallet::<T>::block_number()
+                    >= info
+                        .registered_at
+                        .saturating_add(T::DeregistrationCooldown::get()),
+                Error::<T>::DeregistrationCooldownActive
+            );
+
+            Miners::<T>::remove(subnet_id, &miner);
+            T::Currency::unreserve(&miner, info.stake);
+
+            Self::deposit_event(Event::MinerDeregistered {
+                subnet_id,
+                miner,
+                stake: info.stake,
+            });
+            Ok(())
+        }
+    }
+
+    impl<T: Config> SubnetMiners<T::AccountId> for Pallet<T> {
+        fn is_miner(subnet_id: u32, miner: &T::AccountId) -> bool {
+            Miners::<T>::contains_key(subnet_id, miner)
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// Capabilities declared by `miner` on `subnet_id`, or `None` if
+        /// it is not registered there.
+        pub fn miner_capabilities(subnet_id: u32, miner: &T::AccountId) -> Option<Vec<u8>> {
+            Miners::<T>::get(subnet_id, miner).map(|info| info.capabilities.into_inner())
+        }
+
+        fn bounded_capabilities(
+            capabilities: Vec<u8>,
+        ) -> Result<BoundedVec<u8, T::MaxCapabilities>, Error<T>> {
+            capabilities
+                .try_into()
+                .map_err(|_| Error::<T>::CapabilitiesTooLong)
+        }
+    }
+}
@@ -5,15 +5,19 @@
 //! planned pallets:
 //!
 //! * Subnet registry
+//! * Task queue
 //! * Miner registry
 //! * Validator registry
+//! * Consensus
 //! * Emissions & rewards
 //! * Governance
 
 // Define a module for each pallet once implemented.
 
-// pub mod subnet_registry;
-// pub mod miner_registry;
-// pub mod validator_registry;
-// pub mod emissions;
+pub mod consensus;
+pub mod emissions;
+pub mod miner_registry;
+pub mod subnet_registry;
+pub mod task_queue;
+pub mod validator_registry;
 // pub mod governance;
\ No newline at end of file
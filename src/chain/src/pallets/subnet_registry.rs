@@ -0,0 +1,1570 @@
+//! Subnet registry pallet.
+//!
+//! Stores the definition of every subnet: its name and description,
+//! task type, input and output schemas, evaluation spec, emission
+//! weight, staking thresholds, task limits and owner.  Creating a
+//! subnet reserves `SubnetDeposit` from the owner, and the subnet
+//! records the amount reserved so that exactly that is released
+//! later, whatever `SubnetDeposit` has become.  Owners may update the
+//! mutable parameters of their subnets, extend their output schema in
+//! place, hand them over to another account, which takes over the
+//! deposit, and retire them; `ForceOrigin` may retire any subnet.
+//! Retiring a subnet releases its deposit to the owner.  The block and
+//! reason of a retirement are kept for audits until the owner
+//! reactivates the subnet, which it may do as long as the subnet has
+//! not been purged, by reserving the deposit again.
+//!
+//! Owners may also pause a subnet for a while, which closes it to new
+//! miners, validators and tasks and stops its emissions without giving
+//! up its emission weight, and resume it later.
+//!
+//! Subnets may also be listed in the genesis config, so a devnet starts
+//! with them.  No deposit is reserved for genesis subnets.
+//!
+//! `create_subnet` takes an optional client‑chosen request id.  A call
+//! repeating a request id used by the same owner within
+//! `RequestIdWindow` blocks creates nothing, so clients may safely
+//! retry.
+//!
+//! `RetirementCooldown` blocks after a subnet is retired its owner may
+//! reclaim it, which removes the subnet from storage.  Subnets retired
+//! before deposits were released on retirement get their deposit back
+//! then.  Subnets that still have members when they are reclaimed are
+//! purged once they have been retired for `RetiredSubnetRetention`
+//! blocks and their members are gone.  Each block examines at most
+//! `MaxPurgesPerBlock` of them, carrying on from where the last block
+//! stopped.
+//!
+//! With the `schema-validation` feature, which is on by default,
+//! schemas must be well‑formed JSON with an object at their root.
+//! Without it they are only checked against `MaxSchemaSize`, except
+//! when extended in place.
+//!
+//! Every change to a subnet's schemas is recorded as a new entry in
+//! `SubnetSchemas` rather than overwriting the previous one, so tasks
+//! issued against an older version can still be checked against it.
+//!
+//! Active subnets nobody takes part in should not keep their share of
+//! emissions.  Every `IdleDecayPeriod` blocks, each active subnet that
+//! has had no members for a whole period loses `IdleDecayRate` of its
+//! emission weight, down to zero.  Subnets are checked at most
+//! `MaxIdleChecksPerBlock` per block, so a check of every subnet may
+//! take several blocks.  A member registering resets the subnet's idle
+//! timer.
+//!
+//! `UniqueEvalSpec` counts, by owner, the subnets that have not been
+//! retired with each evaluation spec.  With `UniqueEvaluationSpecs` on,
+//! an owner cannot have two such subnets with the same evaluation spec,
+//! which catches a `create_subnet` sent twice by mistake.  Duplicates
+//! made while it was off are counted, so retiring one of them leaves
+//! the spec taken by the other.  Subnets without an evaluation spec are
+//! not counted.
+
+pub use pallet::*;
+
+/// Membership of subnets, kept by the miner and validator registries.
+pub trait SubnetMembers {
+    /// Whether any miner or validator is still registered on
+    /// `subnet_id`.
+    fn has_members(subnet_id: u32) -> bool;
+}
+
+impl SubnetMembers for () {
+    fn has_members(_: u32) -> bool {
+        false
+    }
+}
+
+pub mod migrations;
+#[cfg(feature = "std")]
+mod serialization;
+
+#[frame_support::pallet]
+pub mod pallet {
+    use super::SubnetMembers;
+    use crate::pallets::emissions::EmissionWeights;
+    use frame_support::{
+        pallet_prelude::*,
+        traits::{Currency, ReservableCurrency},
+    };
+    use frame_system::pallet_prelude::*;
+    use sp_neuro_core::json;
+    use sp_runtime::{
+        traits::{One, Saturating, Zero},
+        Percent,
+    };
+    use sp_std::prelude::*;
+
+    #[cfg(feature = "std")]
+    use serde::{Deserialize, Serialize};
+
+    pub type BalanceOf<T> =
+        <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+    /// UTF‑8 text of at most `N` bytes.
+    pub type BoundedString<const N: u32> = BoundedVec<u8, ConstU32<N>>;
+
+    /// Why a subnet was retired.
+    pub type RetirementReason = BoundedString<128>;
+
+    /// Id chosen by a client for a `create_subnet` call, so the call can
+    /// be retried without creating the subnet twice.
+    pub type RequestId = [u8; 16];
+
+    /// The kind of intelligence task a subnet serves.
+    #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub enum TaskType {
+        CodeGen,
+        ImageGen,
+        ProteinFolding,
+        Custom(BoundedVec<u8, ConstU32<64>>),
+    }
+
+    /// A parameter of a subnet its owner can change after creation.
+    #[derive(Clone, Copy, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub enum SubnetField {
+        InputSchema,
+        OutputSchema,
+        EvaluationSpec,
+        EmissionWeight,
+        MinStakeMiner,
+        MinStakeValidator,
+        MaxInputBytes,
+        MaxOpenTasks,
+        Name,
+        Description,
+    }
+
+    /// The fields changed by one update, at most one entry per
+    /// [`SubnetField`].
+    pub type ChangedFields = BoundedVec<SubnetField, ConstU32<10>>;
+
+    /// Lifecycle state of a subnet.
+    #[derive(Clone, Copy, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+    pub enum SubnetStatus {
+        Active,
+        Retired,
+        /// Temporarily closed to new members and tasks by its owner.
+        Paused,
+    }
+
+    /// On‑chain definition of a subnet.  With `std` it also serializes
+    /// to readable JSON for chain specs.
+    #[derive(
+        CloneNoBound,
+        Encode,
+        Decode,
+        EqNoBound,
+        PartialEqNoBound,
+        RuntimeDebugNoBound,
+        TypeInfo,
+        MaxEncodedLen,
+    )]
+    #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+    #[cfg_attr(
+        feature = "std",
+        serde(bound = "", rename_all = "camelCase", deny_unknown_fields)
+    )]
+    #[scale_info(skip_type_params(T))]
+    #[codec(mel_bound())]
+    pub struct SubnetInfo<T: Config> {
+        pub owner: T::AccountId,
+        pub task_type: TaskType,
+        #[cfg_attr(feature = "std", serde(with = "super::serialization::utf8"))]
+        pub input_schema: BoundedVec<u8, T::MaxSchemaSize>,
+        #[cfg_attr(feature = "std", serde(with = "super::serialization::utf8"))]
+        pub output_schema: BoundedVec<u8, T::MaxSchemaSize>,
+        /// Version in `SubnetSchemas` that `input_schema` and
+        /// `output_schema` were taken from.
+        pub current_schema_version: u32,
+        /// URI of the scoring logic validators run for this subnet.
+        #[cfg_attr(feature = "std", serde(with = "super::serialization::utf8"))]
+        pub evaluation_spec: BoundedVec<u8, T::MaxUriSize>,
+        /// Share of total emissions allocated to this subnet.
+        #[cfg_attr(feature = "std", serde(with = "super::serialization::percent"))]
+        pub emission_weight: Percent,
+        pub min_stake_miner: BalanceOf<T>,
+        pub min_stake_validator: BalanceOf<T>,
+        /// Largest task input, in bytes, accepted for this subnet.
+        /// Never above `MaxInputBytes`.
+        pub max_input_bytes: u32,
+        /// Most tasks that may be open on this subnet at once.  Never
+        /// above `MaxOpenTasks`.
+        pub max_open_tasks: u32,
+        /// Experimental subnets are exempt from `MinEmissionWeight`.
+        pub experimental: bool,
+        pub status: SubnetStatus,
+        /// Block in which the subnet was retired.
+        pub retired_at: Option<BlockNumberFor<T>>,
+        /// Why the subnet was retired, if a reason was given.
+        #[cfg_attr(feature = "std", serde(with = "super::serialization::option_utf8"))]
+        pub retirement_reason: Option<RetirementReason>,
+        /// Amount reserved from the owner for this subnet.  Zero for
+        /// genesis subnets, and once the subnet is retired.
+        pub deposit: BalanceOf<T>,
+        /// Human readable name chosen by the owner.  Not unique.
+        #[cfg_attr(feature = "std", serde(with = "super::serialization::utf8"))]
+        pub name: BoundedVec<u8, T::MaxNameSize>,
+        /// Free‑form description of what the subnet does.
+        #[cfg_attr(feature = "std", serde(with = "super::serialization::utf8"))]
+        pub description: BoundedVec<u8, T::MaxDescriptionSize>,
+    }
+
+    /// One version of a subnet's input and output schemas.
+    #[derive(
+        CloneNoBound,
+        Encode,
+        Decode,
+        EqNoBound,
+        PartialEqNoBound,
+        RuntimeDebugNoBound,
+        TypeInfo,
+        MaxEncodedLen,
+    )]
+    #[scale_info(skip_type_params(T))]
+    #[codec(mel_bound())]
+    pub struct SchemaVersion<T: Config> {
+        pub version: u32,
+        pub input_schema: BoundedVec<u8, T::MaxSchemaSize>,
+        pub output_schema: BoundedVec<u8, T::MaxSchemaSize>,
+    }
+
+    /// Version 1 added `SubnetInfo::max_input_bytes`, version 2
+    /// `SubnetInfo::max_open_tasks`, version 3 the retirement fields,
+    /// version 4 `SubnetsByTaskType`, version 5 `TotalEmissionWeight`,
+    /// version 6 `SubnetInfo::deposit`, version 7 `SubnetInfo::name`
+    /// and `SubnetInfo::description` and version 8 `UniqueEvalSpec`.
+    /// Bump it together with a new step in
+    /// [`migrations::Migrate`](super::migrations::Migrate).
+    pub const STORAGE_VERSION: StorageVersion = StorageVersion::new(8);
+
+    /// Most subnets [`Pallet::list_subnets`] returns in one page.
+    pub const MAX_SUBNETS_PER_PAGE: u32 = 100;
+
+    #[pallet::pallet]
+    #[pallet::storage_version(STORAGE_VERSION)]
+    pub struct Pallet<T>(_);
+
+    #[pallet::config]
+    pub trait Config: frame_system::Config {
+        type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+        /// Currency used for the subnet deposit.
+        type Currency: ReservableCurrency<Self::AccountId>;
+
+        /// Amount reserved from the owner when a subnet is created.
+        #[pallet::constant]
+        type SubnetDeposit: Get<BalanceOf<Self>>;
+
+        /// Maximum size in bytes of an input or output schema.
+        #[pallet::constant]
+        type MaxSchemaSize: Get<u32>;
+
+        /// Maximum size in bytes of an evaluation spec URI.
+        #[pallet::constant]
+        type MaxUriSize: Get<u32>;
+
+        /// Maximum size in bytes of a subnet name.
+        #[pallet::constant]
+        type MaxNameSize: Get<u32>;
+
+        /// Maximum size in bytes of a subnet description.
+        #[pallet::constant]
+        type MaxDescriptionSize: Get<u32>;
+
+        /// Maximum number of subnets a single account may own.
+        #[pallet::constant]
+        type MaxSubnets: Get<u32>;
+
+        /// Lowest emission weight a non‑experimental subnet may have.
+        /// Zero disables the check.
+        #[pallet::constant]
+        type MinEmissionWeight: Get<Percent>;
+
+        /// Maximum number of schema versions kept per subnet.
+        #[pallet::constant]
+        type MaxSchemaVersions: Get<u32>;
+
+        /// Ceiling on the task input size a subnet may allow, in bytes.
+        #[pallet::constant]
+        type MaxInputBytes: Get<u32>;
+
+        /// Hard cap on the number of open tasks a subnet may allow.
+        #[pallet::constant]
+        type MaxOpenTasks: Get<u32>;
+
+        /// Origin allowed to retire any subnet.
+        type ForceOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// Blocks after retirement before the owner may reclaim the
+        /// deposit.
+        #[pallet::constant]
+        type RetirementCooldown: Get<BlockNumberFor<Self>>;
+
+        /// Blocks a retired subnet is kept before it may be purged.
+        #[pallet::constant]
+        type RetiredSubnetRetention: Get<BlockNumberFor<Self>>;
+
+        /// Maximum number of purge candidates examined, and so purged,
+        /// in one block.
+        #[pallet::constant]
+        type MaxPurgesPerBlock: Get<u32>;
+
+        /// Blocks for which the request id of a `create_subnet` call is
+        /// remembered.  Zero is treated as one.
+        #[pallet::constant]
+        type RequestIdWindow: Get<BlockNumberFor<Self>>;
+
+        /// Who is registered on each subnet.
+        type SubnetMembers: SubnetMembers;
+
+        /// Blocks an active subnet may go without members before its
+        /// emission weight decays, and between decay steps.  Zero
+        /// disables the decay.
+        #[pallet::constant]
+        type IdleDecayPeriod: Get<BlockNumberFor<Self>>;
+
+        /// Share of its emission weight an idle subnet loses every
+        /// `IdleDecayPeriod`.  At least one percentage point is taken
+        /// each step, so the weight reaches zero.
+        #[pallet::constant]
+        type IdleDecayRate: Get<Percent>;
+
+        /// Maximum number of subnets checked for idleness in one block.
+        /// A check of every subnet that does not fit in one block goes
+        /// on in the next.
+        #[pallet::constant]
+        type MaxIdleChecksPerBlock: Get<u32>;
+
+        /// Whether creating or updating a subnet fails with
+        /// `DuplicateEvaluationSpec` when the owner already has a subnet
+        /// with the same evaluation spec.
+        #[pallet::constant]
+        type UniqueEvaluationSpecs: Get<bool>;
+    }
+
+    /// Subnet definitions keyed by subnet id.
+    #[pallet::storage]
+    #[pallet::getter(fn subnets)]
+    pub type Subnets<T: Config> = StorageMap<_, Blake2_128Concat, u32, SubnetInfo<T>>;
+
+    /// Every schema version of each subnet, oldest first.
+    #[pallet::storage]
+    #[pallet::getter(fn subnet_schemas)]
+    pub type SubnetSchemas<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        u32,
+        BoundedVec<SchemaVersion<T>, T::MaxSchemaVersions>,
+        ValueQuery,
+    >;
+
+    /// Id assigned to the next subnet created.
+    #[pallet::storage]
+    #[pallet::getter(fn next_subnet_id)]
+    pub type NextSubnetId<T> = StorageValue<_, u32, ValueQuery>;
+
+    /// Number of subnets that have not been retired.
+    #[pallet::storage]
+    #[pallet::getter(fn subnet_count)]
+    pub type SubnetCount<T> = StorageValue<_, u32, ValueQuery>;
+
+    /// Sum of the emission weights of the subnets that have not been
+    /// retired, which is kept at or below 100%.
+    #[pallet::storage]
+    #[pallet::getter(fn total_emission_weight)]
+    pub type TotalEmissionWeight<T> = StorageValue<_, Percent, ValueQuery>;
+
+    /// Ids of the subnets owned by each account.
+    #[pallet::storage]
+    #[pallet::getter(fn owner_subnets)]
+    pub type OwnerSubnets<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, BoundedVec<u32, T::MaxSubnets>, ValueQuery>;
+
+    /// Ids of the subnets serving each task type that have not been
+    /// retired.  A `TaskType` key encodes as its variant index, followed
+    /// by the name for `Custom`, so every custom name is a separate
+    /// prefix.
+    #[pallet::storage]
+    pub type SubnetsByTaskType<T> =
+        StorageDoubleMap<_, Blake2_128Concat, TaskType, Blake2_128Concat, u32, ()>;
+
+    /// Number of subnets that have not been retired with each
+    /// evaluation spec, by owner.  Empty specs are left out, and specs
+    /// no such subnet has are removed.
+    #[pallet::storage]
+    pub type UniqueEvalSpec<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        (T::AccountId, BoundedVec<u8, T::MaxUriSize>),
+        u32,
+        ValueQuery,
+    >;
+
+    /// Block from which each active subnet without members is counted as
+    /// idle: when it was first seen without members, or last decayed.
+    #[pallet::storage]
+    #[pallet::getter(fn idle_since)]
+    pub type IdleSince<T: Config> = StorageMap<_, Blake2_128Concat, u32, BlockNumberFor<T>>;
+
+    /// Last subnet checked for idleness, after which the next block
+    /// carries on, while a check of every subnet is in progress.
+    #[pallet::storage]
+    pub type IdleCheckCursor<T> = StorageValue<_, u32, OptionQuery>;
+
+    /// Retired subnets whose deposit has been reclaimed while they still
+    /// had members, waiting to be purged.
+    #[pallet::storage]
+    pub type PurgeCandidates<T> = StorageMap<_, Blake2_128Concat, u32, (), OptionQuery>;
+
+    /// Last purge candidate examined, after which the next block
+    /// carries on.
+    #[pallet::storage]
+    pub type PurgeCursor<T> = StorageValue<_, u32, OptionQuery>;
+
+    /// Subnet created by each owner's recent `create_subnet` request
+    /// ids.
+    #[pallet::storage]
+    #[pallet::getter(fn seen_requests)]
+    pub type SeenRequests<T: Config> =
+        StorageDoubleMap<_, Blake2_128Concat, T::AccountId, Blake2_128Concat, RequestId, u32>;
+
+    /// Entries of `SeenRequests` by the block they expire in.
+    #[pallet::storage]
+    pub type ExpiringRequests<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        BlockNumberFor<T>,
+        Blake2_128Concat,
+        (T::AccountId, RequestId),
+        (),
+    >;
+
+    /// Schema given to the subnets created at genesis.
+    pub const GENESIS_SCHEMA: &[u8] = br#"{"type":"object"}"#;
+
+    #[pallet::genesis_config]
+    #[derive(frame_support::DefaultNoBound)]
+    pub struct GenesisConfig<T: Config> {
+        /// Subnets created at block zero, as `(task_type,
+        /// emission_weight, min_stake_miner, min_stake_validator,
+        /// owner)`, in id order.  No deposit is reserved for them.
+        /// They get [`GENESIS_SCHEMA`] as both schemas, no evaluation
+        /// spec, no name or description and the largest input size and
+        /// open task limits.
+        pub subnets: Vec<(TaskType, Percent, BalanceOf<T>, BalanceOf<T>, T::AccountId)>,
+    }
+
+    #[pallet::genesis_build]
+    impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
+        fn build(&self) {
+            for (task_type, emission_weight, min_stake_miner, min_stake_validator, owner) in
+                &self.subnets
+            {
+                let schema: BoundedVec<u8, T::MaxSchemaSize> = GENESIS_SCHEMA
+                    .to_vec()
+                    .try_into()
+                    .expect("MaxSchemaSize is too small for the genesis schema");
+                Pallet::<T>::insert_subnet(SubnetInfo {
+                    owner: owner.clone(),
+                    task_type: task_type.clone(),
+                    input_schema: schema.clone(),
+                    output_schema: schema,
+                    current_schema_version: 0,
+                    evaluation_spec: BoundedVec::new(),
+                    emission_weight: *emission_weight,
+                    min_stake_miner: *min_stake_miner,
+                    min_stake_validator: *min_stake_validator,
+                    max_input_bytes: T::MaxInputBytes::get(),
+                    max_open_tasks: T::MaxOpenTasks::get(),
+                    experimental: false,
+                    status: SubnetStatus::Active,
+                    retired_at: None,
+                    retirement_reason: None,
+                    deposit: Zero::zero(),
+                    name: BoundedVec::new(),
+                    description: BoundedVec::new(),
+                })
+                .expect("invalid genesis subnet");
+            }
+        }
+    }
+
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        /// A subnet was created.
+        SubnetCreated {
+            subnet_id: u32,
+            owner: T::AccountId,
+            name: BoundedVec<u8, T::MaxNameSize>,
+        },
+        /// A subnet's parameters were updated.  `changed` lists the
+        /// fields the update set, in `update_subnet` argument order.
+        SubnetUpdated {
+            subnet_id: u32,
+            owner: T::AccountId,
+            changed: ChangedFields,
+        },
+        /// A subnet was retired.
+        SubnetRetired { subnet_id: u32, owner: T::AccountId },
+        /// A subnet was retired by `ForceOrigin`.  `forced_by` is the
+        /// signing account, if the origin was signed.
+        SubnetForceRetired {
+            subnet_id: u32,
+            owner: T::AccountId,
+            forced_by: Option<T::AccountId>,
+        },
+        /// A new schema version became current for a subnet.
+        SchemaVersionAdded { subnet_id: u32, version: u32 },
+        /// A subnet, and its deposit, moved to a new owner.
+        OwnershipTransferred {
+            subnet_id: u32,
+            old_owner: T::AccountId,
+            new_owner: T::AccountId,
+        },
+        /// The deposit of a retired subnet was released to its owner.
+        DepositReclaimed {
+            subnet_id: u32,
+            owner: T::AccountId,
+            amount: BalanceOf<T>,
+        },
+        /// A retired subnet was removed from storage.
+        SubnetPurged { subnet_id: u32 },
+        /// A retired subnet was brought back into service.
+        SubnetReactivated { subnet_id: u32, owner: T::AccountId },
+        /// A subnet was paused by its owner.
+        SubnetPaused { subnet_id: u32, owner: T::AccountId },
+        /// A paused subnet was resumed by its owner.
+        SubnetResumed { subnet_id: u32, owner: T::AccountId },
+        /// An idle subnet's emission weight decayed.
+        SubnetWeightDecayed {
+            subnet_id: u32,
+            old_weight: Percent,
+            new_weight: Percent,
+        },
+        /// A `create_subnet` call repeated a recent request id, so no
+        /// subnet was created.  `subnet_id` is the subnet created by the
+        /// first call.
+        SubnetAlreadyCreated {
+            subnet_id: u32,
+            owner: T::AccountId,
+            request_id: RequestId,
+        },
+    }
+
+    #[pallet::error]
+    pub enum Error<T> {
+        /// No subnet exists with the given id.
+        SubnetNotFound,
+        /// The caller does not own the subnet.
+        NotOwner,
+        /// A schema exceeds `MaxSchemaSize`.
+        SchemaTooLarge,
+        /// The evaluation spec URI exceeds `MaxUriSize`.
+        UriTooLarge,
+        /// The subnet name exceeds `MaxNameSize`.
+        NameTooLarge,
+        /// The subnet description exceeds `MaxDescriptionSize`.
+        DescriptionTooLarge,
+        /// The owner already has a subnet with this evaluation spec.
+        DuplicateEvaluationSpec,
+        /// The owner already owns `MaxSubnets` subnets.
+        TooManyOwnedSubnets,
+        /// The subnet has been retired.
+        SubnetNotActive,
+        /// The owner cannot cover the subnet deposit.
+        InsufficientBalance,
+        /// Subnet ids are exhausted.
+        SubnetIdOverflow,
+        /// The emission weight is below `MinEmissionWeight`.
+        EmissionWeightTooLow,
+        /// The emission weights of the active subnets would add up to
+        /// more than 100%.
+        EmissionWeightExceedsTotal,
+        /// A schema is not well‑formed JSON, or cannot be extended.  With
+        /// the `schema-validation` feature, also a schema whose root is
+        /// not an object.
+        InvalidSchema,
+        /// The subnet already has `MaxSchemaVersions` schema versions.
+        TooManySchemaVersions,
+        /// Schema version numbers are exhausted.
+        SchemaVersionOverflow,
+        /// The task input size limit exceeds `MaxInputBytes`.
+        InputLimitTooLarge,
+        /// The retirement reason is longer than 128 bytes.
+        ReasonTooLong,
+        /// The new owner already owns the subnet.
+        AlreadyOwner,
+        /// The subnet has not been retired.
+        SubnetNotRetired,
+        /// The subnet's deposit has already been reclaimed.
+        DepositAlreadyReclaimed,
+        /// `RetirementCooldown` has not passed since the subnet was
+        /// retired.
+        RetirementCooldownActive,
+        /// The subnet is paused.
+        SubnetPaused,
+        /// The subnet is not paused.
+        SubnetNotPaused,
+    }
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_initialize(n: BlockNumberFor<T>) -> Weight {
+            let expired = Self::expire_requests(n) as u64;
+            let (examined, purged) = Self::purge_retired(n);
+            let (idle_examined, decayed) = Self::decay_idle_subnets(n);
+            T::DbWeight::get().reads_writes(
+                (examined as u64)
+                    .saturating_add(idle_examined as u64)
+                    .saturating_mul(2)
+                    .saturating_add(expired)
+                    .saturating_add(1),
+                (purged as u64)
+                    .saturating_add(decayed as u64)
+                    .saturating_mul(3)
+                    .saturating_add(expired.saturating_mul(2))
+                    .saturating_add(1),
+            )
+        }
+    }
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        /// Create a new subnet owned by the caller, reserving
+        /// `SubnetDeposit`.  Experimental subnets may use an emission
+        /// weight below `MinEmissionWeight`.  `max_input_bytes` may not
+        /// exceed `MaxInputBytes`; `max_open_tasks` is clamped to
+        /// `MaxOpenTasks`.
+        ///
+        /// If `request_id` is given and the caller used it in the last
+        /// `RequestIdWindow` blocks, nothing is created or reserved and
+        /// `SubnetAlreadyCreated` names the subnet created the first
+        /// time.
+        #[pallet::call_index(0)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(6, 11))]
+        #[allow(clippy::too_many_arguments)]
+        pub fn create_subnet(
+            origin: OriginFor<T>,
+            task_type: TaskType,
+            name: Vec<u8>,
+            description: Vec<u8>,
+            input_schema: Vec<u8>,
+            output_schema: Vec<u8>,
+            evaluation_spec: Vec<u8>,
+            emission_weight: Percent,
+            min_stake_miner: BalanceOf<T>,
+            min_stake_validator: BalanceOf<T>,
+            max_input_bytes: u32,
+            max_open_tasks: u32,
+            experimental: bool,
+            request_id: Option<RequestId>,
+        ) -> DispatchResult {
+            let owner = ensure_signed(origin)?;
+            if let Some(request_id) = request_id {
+                if let Some(subnet_id) = SeenRequests::<T>::get(&owner, request_id) {
+                    Self::deposit_event(Event::SubnetAlreadyCreated {
+                        subnet_id,
+                        owner,
+                        request_id,
+                    });
+                    return Ok(());
+                }
+            }
+            Self::ensure_emission_weight(emission_weight, experimental)?;
+            Self::ensure_input_limit(max_input_bytes)?;
+
+            let input_schema = Self::bounded_schema(input_schema)?;
+            let output_schema = Self::bounded_schema(output_schema)?;
+            let evaluation_spec = Self::bounded_uri(evaluation_spec)?;
+            let name = Self::bounded_name(name)?;
+            let description = Self::bounded_description(description)?;
+
+            let deposit = T::SubnetDeposit::get();
+            let subnet_id = Self::insert_subnet(SubnetInfo {
+                owner: owner.clone(),
+                task_type,
+                input_schema,
+                output_schema,
+                current_schema_version: 0,
+                evaluation_spec,
+                emission_weight,
+                min_stake_miner,
+                min_stake_validator,
+                max_input_bytes,
+                max_open_tasks: max_open_tasks.min(T::MaxOpenTasks::get()),
+                experimental,
+                status: SubnetStatus::Active,
+                retired_at: None,
+                retirement_reason: None,
+                deposit,
+                name: name.clone(),
+                description,
+            })?;
+            T::Currency::reserve(&owner, deposit).map_err(|_| Error::<T>::InsufficientBalance)?;
+            if let Some(request_id) = request_id {
+                let window = T::RequestIdWindow::get().max(One::one());
+                let expires_at = frame_system::Pallet::<T>::block_number().saturating_add(window);
+                SeenRequests::<T>::insert(&owner, request_id, subnet_id);
+                ExpiringRequests::<T>::insert(expires_at, (owner.clone(), request_id), ());
+            }
+
+            Self::deposit_event(Event::SubnetCreated {
+                subnet_id,
+                owner,
+                name,
+            });
+            Ok(())
+        }
+
+        /// Update the mutable parameters of an active subnet.  Only the
+        /// fields passed as `Some` are changed.  Changing either schema
+        /// records a new schema version.
+        #[pallet::call_index(1)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(4, 5))]
+        #[allow(clippy::too_many_arguments)]
+        pub fn update_subnet(
+            origin: OriginFor<T>,
+            subnet_id: u32,
+            input_schema: Option<Vec<u8>>,
+            output_schema: Option<Vec<u8>>,
+            evaluation_spec: Option<Vec<u8>>,
+            emission_weight: Option<Percent>,
+            min_stake_miner: Option<BalanceOf<T>>,
+            min_stake_validator: Option<BalanceOf<T>>,
+            max_input_bytes: Option<u32>,
+            max_open_tasks: Option<u32>,
+            name: Option<Vec<u8>>,
+            description: Option<Vec<u8>>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let changed = ChangedFields::truncate_from(
+                [
+                    (input_schema.is_some(), SubnetField::InputSchema),
+                    (output_schema.is_some(), SubnetField::OutputSchema),
+                    (evaluation_spec.is_some(), SubnetField::EvaluationSpec),
+                    (emission_weight.is_some(), SubnetField::EmissionWeight),
+                    (min_stake_miner.is_some(), SubnetField::MinStakeMiner),
+                    (
+                        min_stake_validator.is_some(),
+                        SubnetField::MinStakeValidator,
+                    ),
+                    (max_input_bytes.is_some(), SubnetField::MaxInputBytes),
+                    (max_open_tasks.is_some(), SubnetField::MaxOpenTasks),
+                    (name.is_some(), SubnetField::Name),
+                    (description.is_some(), SubnetField::Description),
+                ]
+                .into_iter()
+                .filter_map(|(set, field)| set.then_some(field))
+                .collect(),
+            );
+
+            let new_version = Subnets::<T>::try_mutate(
+                subnet_id,
+                |maybe_info| -> Result<Option<u32>, DispatchError> {
+                    let info = maybe_info.as_mut().ok_or(Error::<T>::SubnetNotFound)?;
+                    ensure!(info.owner == who, Error::<T>::NotOwner);
+                    ensure!(
+                        info.status != SubnetStatus::Retired,
+                        Error::<T>::SubnetNotActive
+                    );
+
+                    let new_version = if input_schema.is_some() || output_schema.is_some() {
+                        let input_schema = match input_schema {
+                            Some(schema) => Self::bounded_schema(schema)?,
+                            None => info.input_schema.clone(),
+                        };
+                        let output_schema = match output_schema {
+                            Some(schema) => Self::bounded_schema(schema)?,
+                            None => info.output_schema.clone(),
+                        };
+                        Some(Self::push_schema_version(
+                            subnet_id,
+                            info,
+                            input_schema,
+                            output_schema,
+                        )?)
+                    } else {
+                        None
+                    };
+                    if let Some(uri) = evaluation_spec {
+                        let uri = Self::bounded_uri(uri)?;
+                        if uri != info.evaluation_spec {
+                            Self::index_evaluation_spec(&info.owner, &uri)?;
+                            Self::unindex_evaluation_spec(&info.owner, &info.evaluation_spec);
+                            info.evaluation_spec = uri;
+                        }
+                    }
+                    if let Some(weight) = emission_weight {
+                        Self::ensure_emission_weight(weight, info.experimental)?;
+                        TotalEmissionWeight::<T>::put(Self::total_emission_weight_with(
+                            info.emission_weight,
+                            weight,
+                        )?);
+                        info.emission_weight = weight;
+                    }
+                    if let Some(stake) = min_stake_miner {
+                        info.min_stake_miner = stake;
+                    }
+                    if let Some(stake) = min_stake_validator {
+                        info.min_stake_validator = stake;
+                    }
+                    if let Some(limit) = max_input_bytes {
+                        Self::ensure_input_limit(limit)?;
+                        info.max_input_bytes = limit;
+                    }
+                    if let Some(limit) = max_open_tasks {
+                        info.max_open_tasks = limit.min(T::MaxOpenTasks::get());
+                    }
+                    if let Some(name) = name {
+                        info.name = Self::bounded_name(name)?;
+                    }
+                    if let Some(description) = description {
+                        info.description = Self::bounded_description(description)?;
+                    }
+                    Ok(new_version)
+                },
+            )?;
+
+            if let Some(version) = new_version {
+                Self::deposit_event(Event::SchemaVersionAdded { subnet_id, version });
+            }
+            Self::deposit_event(Event::SubnetUpdated {
+                subnet_id,
+                owner: who,
+                changed,
+            });
+            Ok(())
+        }
+
+        /// Retire an active subnet and release its deposit.  Retired
+        /// subnets keep their entry in storage but can no longer be
+        /// updated.
+        #[pallet::call_index(2)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(4, 6))]
+        pub fn retire_subnet(
+            origin: OriginFor<T>,
+            subnet_id: u32,
+            reason: Option<Vec<u8>>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            Self::do_retire(subnet_id, reason, Some(&who))?;
+
+            Self::deposit_event(Event::SubnetRetired {
+                subnet_id,
+                owner: who,
+            });
+            Ok(())
+        }
+
+        /// Extend the output schema of an active subnet without
+        /// replacing it.  `fragment` is inserted as the last member of
+        /// the schema's root object or array; the combined schema must
+        /// fit in `MaxSchemaSize` and be well‑formed JSON.  The result
+        /// is recorded as a new schema version.
+        #[pallet::call_index(3)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(2, 2))]
+        pub fn append_output_schema(
+            origin: OriginFor<T>,
+            subnet_id: u32,
+            fragment: Vec<u8>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let version =
+                Subnets::<T>::try_mutate(subnet_id, |maybe_info| -> Result<u32, DispatchError> {
+                    let info = maybe_info.as_mut().ok_or(Error::<T>::SubnetNotFound)?;
+                    ensure!(info.owner == who, Error::<T>::NotOwner);
+                    ensure!(
+                        info.status != SubnetStatus::Retired,
+                        Error::<T>::SubnetNotActive
+                    );
+
+                    let combined = Self::append_to_root(&info.output_schema, &fragment)
+                        .ok_or(Error::<T>::InvalidSchema)?;
+                    let combined = Self::bounded_schema(combined)?;
+                    json::validate(&combined).map_err(|_| Error::<T>::InvalidSchema)?;
+                    let input_schema = info.input_schema.clone();
+                    Self::push_schema_version(subnet_id, info, input_schema, combined)
+                })?;
+
+            Self::deposit_event(Event::SchemaVersionAdded { subnet_id, version });
+            Self::deposit_event(Event::SubnetUpdated {
+                subnet_id,
+                owner: who,
+                changed: ChangedFields::truncate_from(vec![SubnetField::OutputSchema]),
+            });
+            Ok(())
+        }
+
+        /// Record new input and output schemas for an active subnet as
+        /// its next schema version.  Earlier versions stay available
+        /// through [`Pallet::schema_version`].
+        #[pallet::call_index(4)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(2, 2))]
+        pub fn add_schema_version(
+            origin: OriginFor<T>,
+            subnet_id: u32,
+            input_schema: Vec<u8>,
+            output_schema: Vec<u8>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let input_schema = Self::bounded_schema(input_schema)?;
+            let output_schema = Self::bounded_schema(output_schema)?;
+
+            let version =
+                Subnets::<T>::try_mutate(subnet_id, |maybe_info| -> Result<u32, DispatchError> {
+                    let info = maybe_info.as_mut().ok_or(Error::<T>::SubnetNotFound)?;
+                    ensure!(info.owner == who, Error::<T>::NotOwner);
+                    ensure!(
+                        info.status != SubnetStatus::Retired,
+                        Error::<T>::SubnetNotActive
+                    );
+                    Self::push_schema_version(subnet_id, info, input_schema, output_schema)
+                })?;
+
+            Self::deposit_event(Event::SchemaVersionAdded { subnet_id, version });
+            Ok(())
+        }
+
+        /// Retire any active subnet on behalf of `ForceOrigin`, releasing
+        /// its deposit to the owner.
+        #[pallet::call_index(5)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(4, 6))]
+        pub fn force_retire_subnet(
+            origin: OriginFor<T>,
+            subnet_id: u32,
+            reason: Option<Vec<u8>>,
+        ) -> DispatchResult {
+            T::ForceOrigin::ensure_origin(origin.clone())?;
+            let forced_by = ensure_signed(origin).ok();
+            let owner = Self::do_retire(subnet_id, reason, None)?;
+
+            Self::deposit_event(Event::SubnetForceRetired {
+                subnet_id,
+                owner,
+                forced_by,
+            });
+            Ok(())
+        }
+
+        /// Hand `subnet_id` over to `new_owner`.  The deposit moves with
+        /// it: `new_owner` must be able to cover the amount the caller
+        /// reserved, which is then released to the caller.
+        #[pallet::call_index(6)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(6, 7))]
+        pub fn transfer_ownership(
+            origin: OriginFor<T>,
+            subnet_id: u32,
+            new_owner: T::AccountId,
+        ) -> DispatchResult {
+            let old_owner = ensure_signed(origin)?;
+            ensure!(old_owner != new_owner, Error::<T>::AlreadyOwner);
+            ensure!(
+                !PurgeCandidates::<T>::contains_key(subnet_id),
+                Error::<T>::DepositAlreadyReclaimed
+            );
+
+            let deposit = Subnets::<T>::try_mutate(
+                subnet_id,
+                |maybe_info| -> Result<BalanceOf<T>, DispatchError> {
+                    let info = maybe_info.as_mut().ok_or(Error::<T>::SubnetNotFound)?;
+                    ensure!(info.owner == old_owner, Error::<T>::NotOwner);
+                    if info.status != SubnetStatus::Retired {
+                        Self::index_evaluation_spec(&new_owner, &info.evaluation_spec)?;
+                        Self::unindex_evaluation_spec(&old_owner, &info.evaluation_spec);
+                    }
+                    info.owner = new_owner.clone();
+                    Ok(info.deposit)
+                },
+            )?;
+            OwnerSubnets::<T>::try_mutate(&new_owner, |owned| {
+                owned
+                    .try_push(subnet_id)
+                    .map_err(|_| Error::<T>::TooManyOwnedSubnets)
+            })?;
+            OwnerSubnets::<T>::mutate(&old_owner, |owned| owned.retain(|&id| id != subnet_id));
+
+            T::Currency::reserve(&new_owner, deposit)
+                .map_err(|_| Error::<T>::InsufficientBalance)?;
+            T::Currency::unreserve(&old_owner, deposit);
+
+            Self::deposit_event(Event::OwnershipTransferred {
+                subnet_id,
+                old_owner,
+                new_owner,
+            });
+            Ok(())
+        }
+
+        /// Remove a retired subnet once `RetirementCooldown` has passed.
+        /// A subnet that still has members is only removed from the
+        /// owner's subnets; it is purged later.  A deposit still
+        /// reserved, by a subnet retired before deposits were released
+        /// on retirement, is released now.
+        #[pallet::call_index(7)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(3, 6))]
+        pub fn reclaim_deposit(origin: OriginFor<T>, subnet_id: u32) -> DispatchResult {
+            let owner = ensure_signed(origin)?;
+            let mut info = Subnets::<T>::get(subnet_id).ok_or(Error::<T>::SubnetNotFound)?;
+            ensure!(info.owner == owner, Error::<T>::NotOwner);
+            let retired_at = info.retired_at.ok_or(Error::<T>::SubnetNotRetired)?;
+            ensure!(
+                !PurgeCandidates::<T>::contains_key(subnet_id),
+                Error::<T>::DepositAlreadyReclaimed
+            );
+            ensure!(
+                frame_system::Pallet::<T>::block_number()
+                    >= retired_at.saturating_add(T::RetirementCooldown::get()),
+                Error::<T>::RetirementCooldownActive
+            );
+
+            OwnerSubnets::<T>::mutate(&owner, |owned| owned.retain(|&id| id != subnet_id));
+            let deposit = sp_std::mem::replace(&mut info.deposit, Zero::zero());
+            Subnets::<T>::insert(subnet_id, info);
+            Self::release_deposit(subnet_id, &owner, deposit);
+
+            if T::SubnetMembers::has_members(subnet_id) {
+                PurgeCandidates::<T>::insert(subnet_id, ());
+            } else {
+                Self::purge(subnet_id);
+            }
+            Ok(())
+        }
+
+        /// Bring a retired subnet back into service.  Its emission
+        /// weight must again fit within 100%, and `SubnetDeposit` is
+        /// reserved anew unless the subnet still holds a deposit.
+        #[pallet::call_index(8)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(5, 7))]
+        pub fn reactivate_subnet(origin: OriginFor<T>, subnet_id: u32) -> DispatchResult {
+            let owner = ensure_signed(origin)?;
+            let mut info = Subnets::<T>::get(subnet_id).ok_or(Error::<T>::SubnetNotFound)?;
+            ensure!(info.owner == owner, Error::<T>::NotOwner);
+            ensure!(
+                info.status == SubnetStatus::Retired,
+                Error::<T>::SubnetNotRetired
+            );
+            let total_emission_weight =
+                Self::total_emission_weight_with(Percent::zero(), info.emission_weight)?;
+            Self::index_evaluation_spec(&owner, &info.evaluation_spec)?;
+
+            if info.deposit.is_zero() {
+                let deposit = T::SubnetDeposit::get();
+                T::Currency::reserve(&owner, deposit)
+                    .map_err(|_| Error::<T>::InsufficientBalance)?;
+                info.deposit = deposit;
+            }
+            if PurgeCandidates::<T>::take(subnet_id).is_some() {
+                OwnerSubnets::<T>::try_mutate(&owner, |owned| {
+                    owned
+                        .try_push(subnet_id)
+                        .map_err(|_| Error::<T>::TooManyOwnedSubnets)
+                })?;
+            }
+            info.status = SubnetStatus::Active;
+            info.retired_at = None;
+            info.retirement_reason = None;
+            SubnetsByTaskType::<T>::insert(&info.task_type, subnet_id, ());
+            Subnets::<T>::insert(subnet_id, info);
+            SubnetCount::<T>::mutate(|count| *count = count.saturating_add(1));
+            TotalEmissionWeight::<T>::put(total_emission_weight);
+
+            Self::deposit_event(Event::SubnetReactivated { subnet_id, owner });
+            Ok(())
+        }
+
+        /// Pause an active subnet, e.g. for maintenance.  A paused subnet
+        /// takes no new miners, validators or tasks and receives no
+        /// emissions, but keeps its members and emission weight.
+        #[pallet::call_index(9)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(1, 2))]
+        pub fn pause_subnet(origin: OriginFor<T>, subnet_id: u32) -> DispatchResult {
+            let owner = ensure_signed(origin)?;
+            Subnets::<T>::try_mutate(subnet_id, |maybe_info| -> DispatchResult {
+                let info = maybe_info.as_mut().ok_or(Error::<T>::SubnetNotFound)?;
+                ensure!(info.owner == owner, Error::<T>::NotOwner);
+                match info.status {
+                    SubnetStatus::Active => {}
+                    SubnetStatus::Paused => return Err(Error::<T>::SubnetPaused.into()),
+                    SubnetStatus::Retired => return Err(Error::<T>::SubnetNotActive.into()),
+                }
+                info.status = SubnetStatus::Paused;
+                Ok(())
+            })?;
+            IdleSince::<T>::remove(subnet_id);
+
+            Self::deposit_event(Event::SubnetPaused { subnet_id, owner });
+            Ok(())
+        }
+
+        /// Resume a paused subnet.  Retired subnets are brought back with
+        /// `reactivate_subnet` instead.
+        #[pallet::call_index(10)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+        pub fn resume_subnet(origin: OriginFor<T>, subnet_id: u32) -> DispatchResult {
+            let owner = ensure_signed(origin)?;
+            Subnets::<T>::try_mutate(subnet_id, |maybe_info| -> DispatchResult {
+                let info = maybe_info.as_mut().ok_or(Error::<T>::SubnetNotFound)?;
+                ensure!(info.owner == owner, Error::<T>::NotOwner);
+                ensure!(
+                    info.status == SubnetStatus::Paused,
+                    Error::<T>::SubnetNotPaused
+                );
+                info.status = SubnetStatus::Active;
+                Ok(())
+            })?;
+
+            Self::deposit_event(Event::SubnetResumed { subnet_id, owner });
+            Ok(())
+        }
+    }
+
+    impl<T: Config> EmissionWeights for Pallet<T> {
+        fn emission_weights() -> Vec<(u32, Percent)> {
+            Subnets::<T>::iter()
+                .filter(|(_, info)| info.status == SubnetStatus::Active)
+                .map(|(subnet_id, info)| (subnet_id, info.emission_weight))
+                .collect()
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// Whether `subnet_id` exists and is neither paused nor retired.
+        pub fn is_subnet_active(subnet_id: u32) -> bool {
+            Subnets::<T>::get(subnet_id)
+                .map(|info| info.status == SubnetStatus::Active)
+                .unwrap_or(false)
+        }
+
+        /// When and why `subnet_id` was retired, if it has been.
+        pub fn retirement(subnet_id: u32) -> Option<(BlockNumberFor<T>, Option<RetirementReason>)> {
+            let info = Subnets::<T>::get(subnet_id)?;
+            Some((info.retired_at?, info.retirement_reason))
+        }
+
+        /// A page of at most `limit` subnets, capped at
+        /// [`MAX_SUBNETS_PER_PAGE`], skipping the first `start`.
+        ///
+        /// Subnets are returned in the order of their hashed
+        /// `Blake2_128Concat` keys, not by id, so pages are only
+        /// consistent while no subnet is created or purged between
+        /// calls.  Purged subnets leave no gaps.
+        pub fn list_subnets(start: u32, limit: u32) -> Vec<(u32, SubnetInfo<T>)> {
+            Subnets::<T>::iter()
+                .skip(start as usize)
+                .take(limit.min(MAX_SUBNETS_PER_PAGE) as usize)
+                .collect()
+        }
+
+        /// The subnets among `subnet_ids` that exist, in the order of
+        /// `subnet_ids`.  Backs `SubnetRegistryApi::subnets`.
+        pub fn subnets_with_ids(subnet_ids: &[u32]) -> Vec<(u32, SubnetInfo<T>)> {
+            subnet_ids
+                .iter()
+                .filter_map(|&id| Some((id, Subnets::<T>::get(id)?)))
+                .collect()
+        }
+
+        /// Ids of the subnets that are neither paused nor retired,
+        /// ascending.
+        pub fn active_subnets() -> Vec<u32> {
+            let mut subnet_ids: Vec<u32> = Subnets::<T>::iter()
+                .filter(|(_, info)| info.status == SubnetStatus::Active)
+                .map(|(subnet_id, _)| subnet_id)
+                .collect();
+            subnet_ids.sort_unstable();
+            subnet_ids
+        }
+
+        /// Ids of the subnets serving `task_type` that are neither paused
+        /// nor retired, as in `active_subnets`.  They are looked up in
+        /// `SubnetsByTaskType` rather than by scanning every subnet.
+        pub fn subnets_by_task_type(task_type: TaskType) -> Vec<u32> {
+            SubnetsByTaskType::<T>::iter_key_prefix(task_type)
+                .filter(|subnet_id| {
+                    Subnets::<T>::get(subnet_id)
+                        .is_some_and(|info| info.status == SubnetStatus::Active)
+                })
+                .collect()
+        }
+
+        /// Restart the idle timer of `subnet_id`, for the registries to
+        /// call when a member registers.
+        pub fn note_member_registered(subnet_id: u32) {
+            IdleSince::<T>::remove(subnet_id);
+        }
+
+        /// The schemas of `subnet_id` as of `version`, if recorded.
+        pub fn schema_version(subnet_id: u32, version: u32) -> Option<SchemaVersion<T>> {
+            SubnetSchemas::<T>::get(subnet_id)
+                .into_iter()
+                .find(|entry| entry.version == version)
+        }
+
+        /// Append a schema version for `subnet_id` and make it current
+        /// in `info`.  Returns the new version number.
+        fn push_schema_version(
+            subnet_id: u32,
+            info: &mut SubnetInfo<T>,
+            input_schema: BoundedVec<u8, T::MaxSchemaSize>,
+            output_schema: BoundedVec<u8, T::MaxSchemaSize>,
+        ) -> Result<u32, DispatchError> {
+            let version = info
+                .current_schema_version
+                .checked_add(1)
+                .ok_or(Error::<T>::SchemaVersionOverflow)?;
+            SubnetSchemas::<T>::try_mutate(subnet_id, |versions| {
+                versions
+                    .try_push(SchemaVersion {
+                        version,
+                        input_schema: input_schema.clone(),
+                        output_schema: output_schema.clone(),
+                    })
+                    .map_err(|_| Error::<T>::TooManySchemaVersions)
+            })?;
+            info.input_schema = input_schema;
+            info.output_schema = output_schema;
+            info.current_schema_version = version;
+            Ok(version)
+        }
+
+        /// Retire `subnet_id`, checking that `owner` owns it if given,
+        /// and release its deposit.  Returns the subnet's owner.
+        fn do_retire(
+            subnet_id: u32,
+            reason: Option<Vec<u8>>,
+            owner: Option<&T::AccountId>,
+        ) -> Result<T::AccountId, DispatchError> {
+            let reason = reason
+                .map(RetirementReason::try_from)
+                .transpose()
+                .map_err(|_| Error::<T>::ReasonTooLong)?;
+
+            let (owner, deposit) =
+                Subnets::<T>::try_mutate(subnet_id, |maybe_info| -> Result<_, DispatchError> {
+                    let info = maybe_info.as_mut().ok_or(Error::<T>::SubnetNotFound)?;
+                    if let Some(owner) = owner {
+                        ensure!(info.owner == *owner, Error::<T>::NotOwner);
+                    }
+                    ensure!(
+                        info.status != SubnetStatus::Retired,
+                        Error::<T>::SubnetNotActive
+                    );
+                    info.status = SubnetStatus::Retired;
+                    info.retired_at = Some(frame_system::Pallet::<T>::block_number());
+                    info.retirement_reason = reason;
+                    SubnetsByTaskType::<T>::remove(&info.task_type, subnet_id);
+                    Self::unindex_evaluation_spec(&info.owner, &info.evaluation_spec);
+                    IdleSince::<T>::remove(subnet_id);
+                    TotalEmissionWeight::<T>::mutate(|total| {
+                        *total = total.saturating_sub(info.emission_weight)
+                    });
+                    Ok((
+                        info.owner.clone(),
+                        sp_std::mem::replace(&mut info.deposit, Zero::zero()),
+                    ))
+                })?;
+            SubnetCount::<T>::mutate(|count| *count = count.saturating_sub(1));
+            Self::release_deposit(subnet_id, &owner, deposit);
+            Ok(owner)
+        }
+
+        /// Unreserve `amount` from `owner` as the deposit of `subnet_id`.
+        fn release_deposit(subnet_id: u32, owner: &T::AccountId, amount: BalanceOf<T>) {
+            if amount.is_zero() {
+                return;
+            }
+            T::Currency::unreserve(owner, amount);
+            Self::deposit_event(Event::DepositReclaimed {
+                subnet_id,
+                owner: owner.clone(),
+                amount,
+            });
+        }
+
+        /// Forget the request ids expiring at `now`.  Returns how many
+        /// were forgotten.
+        fn expire_requests(now: BlockNumberFor<T>) -> u32 {
+            let mut expired = 0;
+            for ((owner, request_id), ()) in ExpiringRequests::<T>::drain_prefix(now) {
+                SeenRequests::<T>::remove(owner, request_id);
+                expired += 1;
+            }
+            expired
+        }
+
+        /// Examine up to `MaxPurgesPerBlock` candidates, starting after
+        /// `PurgeCursor` and wrapping around, and purge those retired at
+        /// least `RetiredSubnetRetention` blocks before `now` and
+        /// without members.  Returns the number of candidates examined
+        /// and purged.
+        fn purge_retired(now: BlockNumberFor<T>) -> (u32, u32) {
+            let max = T::MaxPurgesPerBlock::get() as usize;
+            let retention = T::RetiredSubnetRetention::get();
+            let mut candidates: Vec<u32> = match PurgeCursor::<T>::get() {
+                Some(last) => {
+                    PurgeCandidates::<T>::iter_keys_from(PurgeCandidates::<T>::hashed_key_for(last))
+                        .take(max)
+                        .collect()
+                }
+                None => Vec::new(),
+            };
+            for subnet_id in PurgeCandidates::<T>::iter_keys() {
+                if candidates.len() >= max || candidates.contains(&subnet_id) {
+                    break;
+                }
+                candidates.push(subnet_id);
+            }
+            PurgeCursor::<T>::set(candidates.last().copied());
+
+            let (mut examined, mut purged) = (0, 0);
+            for subnet_id in candidates {
+                examined += 1;
+                let Some(info) = Subnets::<T>::get(subnet_id) else {
+                    PurgeCandidates::<T>::remove(subnet_id);
+                    continue;
+                };
+                let expired = info
+                    .retired_at
+                    .is_some_and(|retired_at| now >= retired_at.saturating_add(retention));
+                if !expired || T::SubnetMembers::has_members(subnet_id) {
+                    continue;
+                }
+                Self::purge(subnet_id);
+                purged += 1;
+            }
+            (examined, purged)
+        }
+
+        /// Every `IdleDecayPeriod` blocks, start checking every subnet,
+        /// `MaxIdleChecksPerBlock` per block, and take `IdleDecayRate`
+        /// of the emission weight of each active subnet that has had no
+        /// members for a whole period.  Returns the number of subnets
+        /// examined and decayed.
+        fn decay_idle_subnets(now: BlockNumberFor<T>) -> (u32, u32) {
+            let period = T::IdleDecayPeriod::get();
+            if period.is_zero() {
+                return (0, 0);
+            }
+            let max = T::MaxIdleChecksPerBlock::get() as usize;
+            let batch: Vec<(u32, SubnetInfo<T>)> = match IdleCheckCursor::<T>::take() {
+                Some(last) => Subnets::<T>::iter_from(Subnets::<T>::hashed_key_for(last))
+                    .take(max)
+                    .collect(),
+                None if (now % period).is_zero() => Subnets::<T>::iter().take(max).collect(),
+                None => return (0, 0),
+            };
+            if batch.len() == max {
+                IdleCheckCursor::<T>::set(batch.last().map(|(subnet_id, _)| *subnet_id));
+            }
+
+            let rate = T::IdleDecayRate::get();
+            let (mut examined, mut decayed) = (0, 0);
+            for (subnet_id, info) in batch {
+                examined += 1;
+                if info.status != SubnetStatus::Active {
+                    continue;
+                }
+                let weight = info.emission_weight;
+                if T::SubnetMembers::has_members(subnet_id) {
+                    IdleSince::<T>::remove(subnet_id);
+                    continue;
+                }
+                let Some(idle_since) = IdleSince::<T>::get(subnet_id) else {
+                    IdleSince::<T>::insert(subnet_id, now);
+                    continue;
+                };
+                let cut = Percent::from_parts(rate.mul_ceil(weight.deconstruct()));
+                if now < idle_since.saturating_add(period) || cut.is_zero() {
+                    continue;
+                }
+                let new_weight = weight.saturating_sub(cut);
+                Subnets::<T>::mutate(subnet_id, |maybe_info| {
+                    if let Some(info) = maybe_info {
+                        info.emission_weight = new_weight;
+                    }
+                });
+                TotalEmissionWeight::<T>::mutate(|total| *total = total.saturating_sub(cut));
+                IdleSince::<T>::insert(subnet_id, now);
+                Self::deposit_event(Event::SubnetWeightDecayed {
+                    subnet_id,
+                    old_weight: weight,
+                    new_weight,
+                });
+                decayed += 1;
+            }
+            (examined, decayed)
+        }
+
+        /// Remove every trace of `subnet_id` from storage.
+        fn purge(subnet_id: u32) {
+            Subnets::<T>::remove(subnet_id);
+            SubnetSchemas::<T>::remove(subnet_id);
+            PurgeCandidates::<T>::remove(subnet_id);
+            IdleSince::<T>::remove(subnet_id);
+            Self::deposit_event(Event::SubnetPurged { subnet_id });
+        }
+
+        /// Store `info` as a new subnet under the next free id and
+        /// record it in the indexes and totals.  Returns the id.  The
+        /// deposit is left to the caller.
+        fn insert_subnet(info: SubnetInfo<T>) -> Result<u32, DispatchError> {
+            let total_emission_weight =
+                Self::total_emission_weight_with(Percent::zero(), info.emission_weight)?;
+            let subnet_id = NextSubnetId::<T>::get();
+            let next_id = subnet_id
+                .checked_add(1)
+                .ok_or(Error::<T>::SubnetIdOverflow)?;
+
+            OwnerSubnets::<T>::try_mutate(&info.owner, |owned| {
+                owned
+                    .try_push(subnet_id)
+                    .map_err(|_| Error::<T>::TooManyOwnedSubnets)
+            })?;
+            SubnetSchemas::<T>::try_mutate(subnet_id, |versions| {
+                versions
+                    .try_push(SchemaVersion {
+                        version: 0,
+                        input_schema: info.input_schema.clone(),
+                        output_schema: info.output_schema.clone(),
+                    })
+                    .map_err(|_| Error::<T>::TooManySchemaVersions)
+            })?;
+            Self::index_evaluation_spec(&info.owner, &info.evaluation_spec)?;
+            SubnetsByTaskType::<T>::insert(&info.task_type, subnet_id, ());
+            Subnets::<T>::insert(subnet_id, info);
+            NextSubnetId::<T>::put(next_id);
+            SubnetCount::<T>::mutate(|count| *count = count.saturating_add(1));
+            TotalEmissionWeight::<T>::put(total_emission_weight);
+            Ok(subnet_id)
+        }
+
+        /// Count one more subnet of `owner` with `spec` in
+        /// `UniqueEvalSpec`, failing if `UniqueEvaluationSpecs` is on
+        /// and the owner already has one.
+        fn index_evaluation_spec(
+            owner: &T::AccountId,
+            spec: &BoundedVec<u8, T::MaxUriSize>,
+        ) -> DispatchResult {
+            if spec.is_empty() {
+                return Ok(());
+            }
+            UniqueEvalSpec::<T>::try_mutate((owner.clone(), spec.clone()), |count| {
+                ensure!(
+                    !T::UniqueEvaluationSpecs::get() || *count == 0,
+                    Error::<T>::DuplicateEvaluationSpec
+                );
+                *count = count.saturating_add(1);
+                Ok(())
+            })
+        }
+
+        /// Count one subnet of `owner` with `spec` less, removing the
+        /// entry once none is left.
+        fn unindex_evaluation_spec(owner: &T::AccountId, spec: &BoundedVec<u8, T::MaxUriSize>) {
+            UniqueEvalSpec::<T>::mutate_exists((owner.clone(), spec.clone()), |count| {
+                *count = count
+                    .and_then(|count| count.checked_sub(1))
+                    .filter(|count| *count > 0);
+            });
+        }
+
+        fn ensure_emission_weight(weight: Percent, experimental: bool) -> DispatchResult {
+            ensure!(
+                experimental || weight >= T::MinEmissionWeight::get(),
+                Error::<T>::EmissionWeightTooLow
+            );
+            Ok(())
+        }
+
+        /// The total emission weight once a subnet's weight changes from
+        /// `old` to `new`, provided it stays at or below 100%.
+        fn total_emission_weight_with(
+            old: Percent,
+            new: Percent,
+        ) -> Result<Percent, DispatchError> {
+            let total = u16::from(TotalEmissionWeight::<T>::get().deconstruct())
+                .saturating_sub(old.deconstruct().into())
+                .saturating_add(new.deconstruct().into());
+            ensure!(total <= 100, Error::<T>::EmissionWeightExceedsTotal);
+            Ok(Percent::from_percent(total as u8))
+        }
+
+        fn ensure_input_limit(max_input_bytes: u32) -> DispatchResult {
+            ensure!(
+                max_input_bytes <= T::MaxInputBytes::get(),
+                Error::<T>::InputLimitTooLarge
+            );
+            Ok(())
+        }
+
+        fn bounded_schema(
+            schema: Vec<u8>,
+        ) -> Result<BoundedVec<u8, T::MaxSchemaSize>, DispatchError> {
+            let schema: BoundedVec<u8, T::MaxSchemaSize> =
+                schema.try_into().map_err(|_| Error::<T>::SchemaTooLarge)?;
+            Self::ensure_valid_schema(&schema)?;
+            Ok(schema)
+        }
+
+        /// Reject `schema` unless it is well‑formed JSON with an object
+        /// at its root.
+        #[cfg(feature = "schema-validation")]
+        fn ensure_valid_schema(schema: &[u8]) -> DispatchResult {
+            ensure!(
+                json::validate(schema) == Ok(json::JsonKind::Object),
+                Error::<T>::InvalidSchema
+            );
+            Ok(())
+        }
+
+        #[cfg(not(feature = "schema-validation"))]
+        fn ensure_valid_schema(_: &[u8]) -> DispatchResult {
+            Ok(())
+        }
+
+        /// Insert `fragment` before the closing `}` or `]` of `schema`,
+        /// separated from any existing members by a comma.  Returns
+        /// `None` if `schema` does not end with a container.
+        fn append_to_root(schema: &[u8], fragment: &[u8]) -> Option<Vec<u8>> {
+            let end = schema.iter().rposition(|b| !b.is_ascii_whitespace())?;
+            let close = schema[end];
+            if close != b'}' && close != b']' {
+                return None;
+            }
+            let body = &schema[..end];
+            let empty = matches!(
+                body.iter().rev().find(|b| !b.is_ascii_whitespace()),
+                Some(b'{' | b'[')
+            );
+
+            let mut combined = Vec::with_capacity(end + fragment.len() + 2);
+            combined.extend_from_slice(body);
+            if !empty {
+                combined.push(b',');
+            }
+            combined.extend_from_slice(fragment);
+            combined.push(close);
+            Some(combined)
+        }
+
+        fn bounded_uri(uri: Vec<u8>) -> Result<BoundedVec<u8, T::MaxUriSize>, DispatchError> {
+            uri.try_into().map_err(|_| Error::<T>::UriTooLarge.into())
+        }
+
+        fn bounded_name(name: Vec<u8>) -> Result<BoundedVec<u8, T::MaxNameSize>, DispatchError> {
+            name.try_into().map_err(|_| Error::<T>::NameTooLarge.into())
+        }
+
+        fn bounded_description(
+            description: Vec<u8>,
+        ) -> Result<BoundedVec<u8, T::MaxDescriptionSize>, DispatchError> {
+            description
+                .try_into()
+                .map_err(|_| Error::<T>::DescriptionTooLarge.into())
+        }
+    }
+}
@@ -0,0 +1,543 @@
+//! Storage migrations for the subnet registry.
+//!
+//! Each step reads `Subnets` in the layout of the version it starts
+//! from, so the steps must run in order.  Runtimes should list
+//! [`Migrate`], which chains them, rather than the individual steps.
+
+use frame_support::{
+    pallet_prelude::*,
+    traits::{GetStorageVersion, OnRuntimeUpgrade},
+};
+use frame_system::pallet_prelude::BlockNumberFor;
+use sp_runtime::{
+    traits::{Saturating, Zero},
+    Percent,
+};
+use sp_std::marker::PhantomData;
+#[cfg(feature = "try-runtime")]
+use sp_std::vec::Vec;
+
+use super::*;
+
+/// Layout of `SubnetInfo` before version 1.
+mod v0 {
+    use super::*;
+
+    #[derive(Decode)]
+    pub(super) struct SubnetInfo<AccountId, Balance, SchemaSize: Get<u32>, UriSize: Get<u32>> {
+        pub owner: AccountId,
+        pub task_type: TaskType,
+        pub input_schema: BoundedVec<u8, SchemaSize>,
+        pub output_schema: BoundedVec<u8, SchemaSize>,
+        pub current_schema_version: u32,
+        pub evaluation_spec: BoundedVec<u8, UriSize>,
+        pub emission_weight: Percent,
+        pub min_stake_miner: Balance,
+        pub min_stake_validator: Balance,
+        pub experimental: bool,
+        pub status: SubnetStatus,
+    }
+
+    pub(super) type SubnetInfoOf<T> = SubnetInfo<
+        <T as frame_system::Config>::AccountId,
+        BalanceOf<T>,
+        <T as Config>::MaxSchemaSize,
+        <T as Config>::MaxUriSize,
+    >;
+}
+
+/// Version 0 to 1: add `SubnetInfo::max_input_bytes`, defaulting every
+/// existing subnet to `MaxInputBytes`.
+pub mod v1 {
+    use super::*;
+
+    /// Layout of `SubnetInfo` at version 1.
+    #[derive(Encode, Decode)]
+    pub(super) struct SubnetInfo<AccountId, Balance, SchemaSize: Get<u32>, UriSize: Get<u32>> {
+        pub owner: AccountId,
+        pub task_type: TaskType,
+        pub input_schema: BoundedVec<u8, SchemaSize>,
+        pub output_schema: BoundedVec<u8, SchemaSize>,
+        pub current_schema_version: u32,
+        pub evaluation_spec: BoundedVec<u8, UriSize>,
+        pub emission_weight: Percent,
+        pub min_stake_miner: Balance,
+        pub min_stake_validator: Balance,
+        pub max_input_bytes: u32,
+        pub experimental: bool,
+        pub status: SubnetStatus,
+    }
+
+    pub(super) type SubnetInfoOf<T> = SubnetInfo<
+        <T as frame_system::Config>::AccountId,
+        BalanceOf<T>,
+        <T as Config>::MaxSchemaSize,
+        <T as Config>::MaxUriSize,
+    >;
+
+    #[frame_support::storage_alias]
+    pub(super) type Subnets<T: Config> =
+        StorageMap<Pallet<T>, Blake2_128Concat, u32, SubnetInfoOf<T>>;
+
+    pub struct MigrateToV1<T>(PhantomData<T>);
+
+    impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T> {
+        fn on_runtime_upgrade() -> Weight {
+            if Pallet::<T>::on_chain_storage_version() != 0 {
+                return T::DbWeight::get().reads(1);
+            }
+
+            let max_input_bytes = T::MaxInputBytes::get();
+            let mut translated = 0u64;
+            Subnets::<T>::translate::<v0::SubnetInfoOf<T>, _>(|_, old| {
+                translated += 1;
+                Some(SubnetInfo {
+                    owner: old.owner,
+                    task_type: old.task_type,
+                    input_schema: old.input_schema,
+                    output_schema: old.output_schema,
+                    current_schema_version: old.current_schema_version,
+                    evaluation_spec: old.evaluation_spec,
+                    emission_weight: old.emission_weight,
+                    min_stake_miner: old.min_stake_miner,
+                    min_stake_validator: old.min_stake_validator,
+                    max_input_bytes,
+                    experimental: old.experimental,
+                    status: old.status,
+                })
+            });
+            StorageVersion::new(1).put::<Pallet<T>>();
+
+            T::DbWeight::get().reads_writes(translated + 1, translated + 1)
+        }
+    }
+}
+
+/// Version 1 to 2: add `SubnetInfo::max_open_tasks`, defaulting every
+/// existing subnet to `MaxOpenTasks`.
+pub mod v2 {
+    use super::*;
+
+    /// Layout of `SubnetInfo` at version 2.
+    #[derive(Encode, Decode)]
+    pub(super) struct SubnetInfo<AccountId, Balance, SchemaSize: Get<u32>, UriSize: Get<u32>> {
+        pub owner: AccountId,
+        pub task_type: TaskType,
+        pub input_schema: BoundedVec<u8, SchemaSize>,
+        pub output_schema: BoundedVec<u8, SchemaSize>,
+        pub current_schema_version: u32,
+        pub evaluation_spec: BoundedVec<u8, UriSize>,
+        pub emission_weight: Percent,
+        pub min_stake_miner: Balance,
+        pub min_stake_validator: Balance,
+        pub max_input_bytes: u32,
+        pub max_open_tasks: u32,
+        pub experimental: bool,
+        pub status: SubnetStatus,
+    }
+
+    pub(super) type SubnetInfoOf<T> = SubnetInfo<
+        <T as frame_system::Config>::AccountId,
+        BalanceOf<T>,
+        <T as Config>::MaxSchemaSize,
+        <T as Config>::MaxUriSize,
+    >;
+
+    #[frame_support::storage_alias]
+    pub(super) type Subnets<T: Config> =
+        StorageMap<Pallet<T>, Blake2_128Concat, u32, SubnetInfoOf<T>>;
+
+    pub struct MigrateToV2<T>(PhantomData<T>);
+
+    impl<T: Config> OnRuntimeUpgrade for MigrateToV2<T> {
+        fn on_runtime_upgrade() -> Weight {
+            if Pallet::<T>::on_chain_storage_version() != 1 {
+                return T::DbWeight::get().reads(1);
+            }
+
+            let max_open_tasks = T::MaxOpenTasks::get();
+            let mut translated = 0u64;
+            Subnets::<T>::translate::<v1::SubnetInfoOf<T>, _>(|_, old| {
+                translated += 1;
+                Some(SubnetInfo {
+                    owner: old.owner,
+                    task_type: old.task_type,
+                    input_schema: old.input_schema,
+                    output_schema: old.output_schema,
+                    current_schema_version: old.current_schema_version,
+                    evaluation_spec: old.evaluation_spec,
+                    emission_weight: old.emission_weight,
+                    min_stake_miner: old.min_stake_miner,
+                    min_stake_validator: old.min_stake_validator,
+                    max_input_bytes: old.max_input_bytes,
+                    max_open_tasks,
+                    experimental: old.experimental,
+                    status: old.status,
+                })
+            });
+            StorageVersion::new(2).put::<Pallet<T>>();
+
+            T::DbWeight::get().reads_writes(translated + 1, translated + 1)
+        }
+    }
+}
+
+/// Version 2 to 3: add `SubnetInfo::retired_at` and
+/// `SubnetInfo::retirement_reason`.  Nothing was recorded for subnets
+/// retired before the upgrade, so both default to `None`.
+pub mod v3 {
+    use super::*;
+
+    /// Layout of `SubnetInfo` at versions 3 to 5.
+    #[derive(Encode, Decode)]
+    pub(super) struct SubnetInfo<
+        AccountId,
+        Balance,
+        BlockNumber,
+        SchemaSize: Get<u32>,
+        UriSize: Get<u32>,
+    > {
+        pub owner: AccountId,
+        pub task_type: TaskType,
+        pub input_schema: BoundedVec<u8, SchemaSize>,
+        pub output_schema: BoundedVec<u8, SchemaSize>,
+        pub current_schema_version: u32,
+        pub evaluation_spec: BoundedVec<u8, UriSize>,
+        pub emission_weight: Percent,
+        pub min_stake_miner: Balance,
+        pub min_stake_validator: Balance,
+        pub max_input_bytes: u32,
+        pub max_open_tasks: u32,
+        pub experimental: bool,
+        pub status: SubnetStatus,
+        pub retired_at: Option<BlockNumber>,
+        pub retirement_reason: Option<RetirementReason>,
+    }
+
+    pub(super) type SubnetInfoOf<T> = SubnetInfo<
+        <T as frame_system::Config>::AccountId,
+        BalanceOf<T>,
+        BlockNumberFor<T>,
+        <T as Config>::MaxSchemaSize,
+        <T as Config>::MaxUriSize,
+    >;
+
+    #[frame_support::storage_alias]
+    pub(super) type Subnets<T: Config> =
+        StorageMap<Pallet<T>, Blake2_128Concat, u32, SubnetInfoOf<T>>;
+
+    pub struct MigrateToV3<T>(PhantomData<T>);
+
+    impl<T: Config> OnRuntimeUpgrade for MigrateToV3<T> {
+        fn on_runtime_upgrade() -> Weight {
+            if Pallet::<T>::on_chain_storage_version() != 2 {
+                return T::DbWeight::get().reads(1);
+            }
+
+            let mut translated = 0u64;
+            Subnets::<T>::translate::<v2::SubnetInfoOf<T>, _>(|_, old| {
+                translated += 1;
+                Some(SubnetInfo {
+                    owner: old.owner,
+                    task_type: old.task_type,
+                    input_schema: old.input_schema,
+                    output_schema: old.output_schema,
+                    current_schema_version: old.current_schema_version,
+                    evaluation_spec: old.evaluation_spec,
+                    emission_weight: old.emission_weight,
+                    min_stake_miner: old.min_stake_miner,
+                    min_stake_validator: old.min_stake_validator,
+                    max_input_bytes: old.max_input_bytes,
+                    max_open_tasks: old.max_open_tasks,
+                    experimental: old.experimental,
+                    status: old.status,
+                    retired_at: None,
+                    retirement_reason: None,
+                })
+            });
+            StorageVersion::new(3).put::<Pallet<T>>();
+
+            T::DbWeight::get().reads_writes(translated + 1, translated + 1)
+        }
+    }
+}
+
+/// Version 3 to 4: index the subnets that have not been retired in
+/// `SubnetsByTaskType`.
+pub mod v4 {
+    use super::*;
+
+    pub struct MigrateToV4<T>(PhantomData<T>);
+
+    impl<T: Config> OnRuntimeUpgrade for MigrateToV4<T> {
+        fn on_runtime_upgrade() -> Weight {
+            if Pallet::<T>::on_chain_storage_version() != 3 {
+                return T::DbWeight::get().reads(1);
+            }
+
+            let (mut read, mut indexed) = (0u64, 0u64);
+            for (subnet_id, info) in v3::Subnets::<T>::iter() {
+                read += 1;
+                if info.status != SubnetStatus::Retired {
+                    SubnetsByTaskType::<T>::insert(info.task_type, subnet_id, ());
+                    indexed += 1;
+                }
+            }
+            StorageVersion::new(4).put::<Pallet<T>>();
+
+            T::DbWeight::get().reads_writes(read + 1, indexed + 1)
+        }
+    }
+}
+
+/// Version 4 to 5: record the sum of the emission weights of the
+/// subnets that have not been retired in `TotalEmissionWeight`.  A sum
+/// above 100% is stored as 100%, so no weight can be raised until
+/// others are lowered.
+pub mod v5 {
+    use super::*;
+
+    pub struct MigrateToV5<T>(PhantomData<T>);
+
+    impl<T: Config> OnRuntimeUpgrade for MigrateToV5<T> {
+        fn on_runtime_upgrade() -> Weight {
+            if Pallet::<T>::on_chain_storage_version() != 4 {
+                return T::DbWeight::get().reads(1);
+            }
+
+            let mut read = 0u64;
+            let mut total = Percent::zero();
+            for info in v3::Subnets::<T>::iter_values() {
+                read += 1;
+                if info.status != SubnetStatus::Retired {
+                    total = total.saturating_add(info.emission_weight);
+                }
+            }
+            TotalEmissionWeight::<T>::put(total);
+            StorageVersion::new(5).put::<Pallet<T>>();
+
+            T::DbWeight::get().reads_writes(read + 1, 2)
+        }
+    }
+}
+
+/// Version 5 to 6: add `SubnetInfo::deposit`.  Until then every
+/// deposit was released as the current `SubnetDeposit`, so existing
+/// subnets record that amount.  Subnets whose deposit was already
+/// reclaimed, and that only wait to be purged, record none.
+pub mod v6 {
+    use super::*;
+
+    /// Layout of `SubnetInfo` at version 6.
+    #[derive(Encode, Decode)]
+    pub(super) struct SubnetInfo<
+        AccountId,
+        Balance,
+        BlockNumber,
+        SchemaSize: Get<u32>,
+        UriSize: Get<u32>,
+    > {
+        pub owner: AccountId,
+        pub task_type: TaskType,
+        pub input_schema: BoundedVec<u8, SchemaSize>,
+        pub output_schema: BoundedVec<u8, SchemaSize>,
+        pub current_schema_version: u32,
+        pub evaluation_spec: BoundedVec<u8, UriSize>,
+        pub emission_weight: Percent,
+        pub min_stake_miner: Balance,
+        pub min_stake_validator: Balance,
+        pub max_input_bytes: u32,
+        pub max_open_tasks: u32,
+        pub experimental: bool,
+        pub status: SubnetStatus,
+        pub retired_at: Option<BlockNumber>,
+        pub retirement_reason: Option<RetirementReason>,
+        pub deposit: Balance,
+    }
+
+    pub(super) type SubnetInfoOf<T> = SubnetInfo<
+        <T as frame_system::Config>::AccountId,
+        BalanceOf<T>,
+        BlockNumberFor<T>,
+        <T as Config>::MaxSchemaSize,
+        <T as Config>::MaxUriSize,
+    >;
+
+    #[frame_support::storage_alias]
+    pub(super) type Subnets<T: Config> =
+        StorageMap<Pallet<T>, Blake2_128Concat, u32, SubnetInfoOf<T>>;
+
+    pub struct MigrateToV6<T>(PhantomData<T>);
+
+    impl<T: Config> OnRuntimeUpgrade for MigrateToV6<T> {
+        fn on_runtime_upgrade() -> Weight {
+            if Pallet::<T>::on_chain_storage_version() != 5 {
+                return T::DbWeight::get().reads(1);
+            }
+
+            let deposit = T::SubnetDeposit::get();
+            let mut translated = 0u64;
+            v6::Subnets::<T>::translate::<v3::SubnetInfoOf<T>, _>(|subnet_id, old| {
+                translated += 1;
+                let deposit = if PurgeCandidates::<T>::contains_key(subnet_id) {
+                    Zero::zero()
+                } else {
+                    deposit
+                };
+                Some(SubnetInfo {
+                    owner: old.owner,
+                    task_type: old.task_type,
+                    input_schema: old.input_schema,
+                    output_schema: old.output_schema,
+                    current_schema_version: old.current_schema_version,
+                    evaluation_spec: old.evaluation_spec,
+                    emission_weight: old.emission_weight,
+                    min_stake_miner: old.min_stake_miner,
+                    min_stake_validator: old.min_stake_validator,
+                    max_input_bytes: old.max_input_bytes,
+                    max_open_tasks: old.max_open_tasks,
+                    experimental: old.experimental,
+                    status: old.status,
+                    retired_at: old.retired_at,
+                    retirement_reason: old.retirement_reason,
+                    deposit,
+                })
+            });
+            StorageVersion::new(6).put::<Pallet<T>>();
+
+            T::DbWeight::get().reads_writes(2 * translated + 1, translated + 1)
+        }
+    }
+}
+
+/// Version 6 to 7: add `SubnetInfo::name` and
+/// `SubnetInfo::description`, both empty for existing subnets.
+pub mod v7 {
+    use super::*;
+
+    pub struct MigrateToV7<T>(PhantomData<T>);
+
+    impl<T: Config> OnRuntimeUpgrade for MigrateToV7<T> {
+        fn on_runtime_upgrade() -> Weight {
+            if Pallet::<T>::on_chain_storage_version() != 6 {
+                return T::DbWeight::get().reads(1);
+            }
+
+            let mut translated = 0u64;
+            Subnets::<T>::translate::<v6::SubnetInfoOf<T>, _>(|_, old| {
+                translated += 1;
+                Some(SubnetInfo {
+                    owner: old.owner,
+                    task_type: old.task_type,
+                    input_schema: old.input_schema,
+                    output_schema: old.output_schema,
+                    current_schema_version: old.current_schema_version,
+                    evaluation_spec: old.evaluation_spec,
+                    emission_weight: old.emission_weight,
+                    min_stake_miner: old.min_stake_miner,
+                    min_stake_validator: old.min_stake_validator,
+                    max_input_bytes: old.max_input_bytes,
+                    max_open_tasks: old.max_open_tasks,
+                    experimental: old.experimental,
+                    status: old.status,
+                    retired_at: old.retired_at,
+                    retirement_reason: old.retirement_reason,
+                    deposit: old.deposit,
+                    name: BoundedVec::new(),
+                    description: BoundedVec::new(),
+                })
+            });
+            StorageVersion::new(7).put::<Pallet<T>>();
+
+            T::DbWeight::get().reads_writes(translated + 1, translated + 1)
+        }
+    }
+}
+
+/// Version 7 to 8: count the evaluation specs of the subnets that have
+/// not been retired in `UniqueEvalSpec`.  Owners who already have
+/// duplicates keep them, and each of them is counted.
+pub mod v8 {
+    use super::*;
+
+    pub struct MigrateToV8<T>(PhantomData<T>);
+
+    impl<T: Config> OnRuntimeUpgrade for MigrateToV8<T> {
+        fn on_runtime_upgrade() -> Weight {
+            if Pallet::<T>::on_chain_storage_version() != 7 {
+                return T::DbWeight::get().reads(1);
+            }
+
+            let (mut read, mut indexed) = (0u64, 0u64);
+            for info in Subnets::<T>::iter_values() {
+                read += 1;
+                if info.status != SubnetStatus::Retired && !info.evaluation_spec.is_empty() {
+                    UniqueEvalSpec::<T>::mutate((info.owner, info.evaluation_spec), |count| {
+                        *count = count.saturating_add(1)
+                    });
+                    indexed += 1;
+                }
+            }
+            StorageVersion::new(8).put::<Pallet<T>>();
+
+            T::DbWeight::get().reads_writes(read + 1, indexed + 1)
+        }
+    }
+}
+
+/// Every step, in order, from the on-chain version up to
+/// [`STORAGE_VERSION`].  Each step only runs on the version it starts
+/// from, so running this again after an upgrade does nothing.
+pub struct Migrate<T>(PhantomData<T>);
+
+type Steps<T> = (
+    v1::MigrateToV1<T>,
+    v2::MigrateToV2<T>,
+    v3::MigrateToV3<T>,
+    v4::MigrateToV4<T>,
+    v5::MigrateToV5<T>,
+    v6::MigrateToV6<T>,
+    v7::MigrateToV7<T>,
+    v8::MigrateToV8<T>,
+);
+
+impl<T: Config> OnRuntimeUpgrade for Migrate<T> {
+    fn on_runtime_upgrade() -> Weight {
+        if Pallet::<T>::on_chain_storage_version() >= STORAGE_VERSION {
+            return T::DbWeight::get().reads(1);
+        }
+        Steps::<T>::on_runtime_upgrade().saturating_add(T::DbWeight::get().reads(1))
+    }
+
+    #[cfg(feature = "try-runtime")]
+    fn pre_upgrade() -> Result<Vec<u8>, sp_runtime::TryRuntimeError> {
+        let subnets = Subnets::<T>::iter_keys().count() as u32;
+        Ok(subnets.encode())
+    }
+
+    #[cfg(feature = "try-runtime")]
+    fn post_upgrade(state: Vec<u8>) -> Result<(), sp_runtime::TryRuntimeError> {
+        let subnets = u32::decode(&mut &state[..]).map_err(|_| "invalid pre-upgrade state")?;
+        ensure!(
+            Pallet::<T>::on_chain_storage_version() == STORAGE_VERSION,
+            "storage version not bumped"
+        );
+
+        // Entries that fail to decode in the current layout are skipped
+        // by the iterator, so a translation gone wrong shows up here.
+        let mut migrated = 0u32;
+        let mut total = Percent::zero();
+        for info in Subnets::<T>::iter_values() {
+            migrated += 1;
+            if info.status != SubnetStatus::Retired {
+                total = total.saturating_add(info.emission_weight);
+            }
+        }
+        ensure!(migrated == subnets, "subnets lost in migration");
+        ensure!(
+            TotalEmissionWeight::<T>::get() == total,
+            "total emission weight does not match the subnets not retired"
+        );
+        Ok(())
+    }
+}
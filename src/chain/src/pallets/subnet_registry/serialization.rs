@@ -0,0 +1,103 @@
+//! Readable serde encodings for chain specs.
+//!
+//! Genesis subnets are written by hand, so schemas and other byte
+//! strings are rendered as UTF‑8 text instead of SCALE hex, emission
+//! weights as whole percents and task types by name.
+
+use frame_support::{pallet_prelude::*, BoundedVec};
+use serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
+use sp_runtime::Percent;
+
+use super::TaskType;
+
+/// `BoundedVec<u8, _>` as a UTF‑8 string.
+pub mod utf8 {
+    use super::*;
+
+    pub fn serialize<S: Serializer, Bound>(
+        bytes: &BoundedVec<u8, Bound>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let text = std::str::from_utf8(bytes).map_err(ser::Error::custom)?;
+        serializer.serialize_str(text)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>, Bound: Get<u32>>(
+        deserializer: D,
+    ) -> Result<BoundedVec<u8, Bound>, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        BoundedVec::try_from(text.into_bytes())
+            .map_err(|_| de::Error::custom(format!("longer than {} bytes", Bound::get())))
+    }
+}
+
+/// `Option<BoundedVec<u8, _>>` as a UTF‑8 string or `null`.
+pub mod option_utf8 {
+    use super::*;
+
+    pub fn serialize<S: Serializer, Bound>(
+        bytes: &Option<BoundedVec<u8, Bound>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match bytes {
+            Some(bytes) => utf8::serialize(bytes, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>, Bound: Get<u32>>(
+        deserializer: D,
+    ) -> Result<Option<BoundedVec<u8, Bound>>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|text| {
+                BoundedVec::try_from(text.into_bytes())
+                    .map_err(|_| de::Error::custom(format!("longer than {} bytes", Bound::get())))
+            })
+            .transpose()
+    }
+}
+
+/// `Percent` as a whole number from 0 to 100.
+pub mod percent {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(percent: &Percent, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(percent.deconstruct())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Percent, D::Error> {
+        let percent = u8::deserialize(deserializer)?;
+        if percent > 100 {
+            return Err(de::Error::custom("percent above 100"));
+        }
+        Ok(Percent::from_percent(percent))
+    }
+}
+
+/// Task types are written by name; any name other than a built‑in one
+/// is a custom task type.
+impl Serialize for TaskType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            TaskType::CodeGen => serializer.serialize_str("CodeGen"),
+            TaskType::ImageGen => serializer.serialize_str("ImageGen"),
+            TaskType::ProteinFolding => serializer.serialize_str("ProteinFolding"),
+            TaskType::Custom(name) => utf8::serialize(name, serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for TaskType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Ok(match name.as_str() {
+            "CodeGen" => TaskType::CodeGen,
+            "ImageGen" => TaskType::ImageGen,
+            "ProteinFolding" => TaskType::ProteinFolding,
+            _ => TaskType::Custom(
+                BoundedVec::try_from(name.into_bytes())
+                    .map_err(|_| de::Error::custom("task type longer than 64 bytes"))?,
+            ),
+        })
+    }
+}
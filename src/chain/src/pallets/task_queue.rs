@@ -0,0 +1,220 @@
+//! Task queue pallet.
+//!
+//! Holds the tasks posted to subnets.  A task carries an opaque input
+//! that miners of the subnet work on; its size is limited by the
+//! subnet's `max_input_bytes`.  A task stays open until a miner
+//! registered on its subnet accepts it, and a subnet holds at most
+//! `max_open_tasks` open tasks.  Accepted tasks are removed
+//! `AcceptedTaskRetention` blocks after they were accepted.
+
+pub use pallet::*;
+
+#[frame_support::pallet]
+pub mod pallet {
+    use crate::pallets::{consensus::SubnetMiners, subnet_registry};
+    use frame_support::pallet_prelude::*;
+    use frame_system::pallet_prelude::*;
+    use sp_runtime::traits::{One, Saturating};
+    use sp_std::prelude::*;
+
+    /// Bound on stored task inputs: the registry's ceiling.
+    pub type MaxInputBytesOf<T> = <T as subnet_registry::Config>::MaxInputBytes;
+
+    /// Lifecycle state of a task.
+    #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub enum TaskStatus<AccountId> {
+        /// Waiting for a miner.
+        Open,
+        /// Taken on by `miner`.
+        Accepted { miner: AccountId },
+    }
+
+    /// A task posted to a subnet.
+    #[derive(
+        CloneNoBound,
+        Encode,
+        Decode,
+        EqNoBound,
+        PartialEqNoBound,
+        RuntimeDebugNoBound,
+        TypeInfo,
+        MaxEncodedLen,
+    )]
+    #[scale_info(skip_type_params(T))]
+    #[codec(mel_bound())]
+    pub struct TaskInfo<T: Config> {
+        pub subnet_id: u32,
+        pub poster: T::AccountId,
+        pub input: BoundedVec<u8, MaxInputBytesOf<T>>,
+        pub status: TaskStatus<T::AccountId>,
+    }
+
+    #[pallet::pallet]
+    pub struct Pallet<T>(_);
+
+    #[pallet::config]
+    pub trait Config: frame_system::Config + subnet_registry::Config {
+        type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+        /// Miners registered on each subnet, who may accept its tasks.
+        type Miners: SubnetMiners<Self::AccountId>;
+
+        /// Blocks an accepted task is kept before it is removed.  Zero
+        /// is treated as one.
+        #[pallet::constant]
+        type AcceptedTaskRetention: Get<BlockNumberFor<Self>>;
+    }
+
+    /// Posted tasks keyed by task id.
+    #[pallet::storage]
+    #[pallet::getter(fn tasks)]
+    pub type Tasks<T: Config> = StorageMap<_, Blake2_128Concat, u64, TaskInfo<T>>;
+
+    /// Id assigned to the next task posted.
+    #[pallet::storage]
+    #[pallet::getter(fn next_task_id)]
+    pub type NextTaskId<T> = StorageValue<_, u64, ValueQuery>;
+
+    /// Number of open tasks on each subnet.
+    #[pallet::storage]
+    #[pallet::getter(fn open_tasks)]
+    pub type OpenTasks<T> = StorageMap<_, Blake2_128Concat, u32, u32, ValueQuery>;
+
+    /// Accepted tasks by the block in which they are removed.
+    #[pallet::storage]
+    pub type ExpiringTasks<T: Config> =
+        StorageDoubleMap<_, Twox64Concat, BlockNumberFor<T>, Blake2_128Concat, u64, ()>;
+
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        /// A task was posted to a subnet.
+        TaskSubmitted {
+            task_id: u64,
+            subnet_id: u32,
+            poster: T::AccountId,
+        },
+        /// A miner accepted an open task.
+        TaskAccepted { task_id: u64, miner: T::AccountId },
+    }
+
+    #[pallet::error]
+    pub enum Error<T> {
+        /// No subnet exists with the given id.
+        SubnetNotFound,
+        /// The subnet has been paused or retired.
+        SubnetNotActive,
+        /// The input exceeds the subnet's `max_input_bytes`.
+        InputTooLarge,
+        /// Task ids are exhausted.
+        TaskIdOverflow,
+        /// The subnet already has `max_open_tasks` open tasks.
+        TooManyOpenTasks,
+        /// No task exists with the given id.
+        TaskNotFound,
+        /// The task has already been accepted.
+        TaskNotOpen,
+        /// The caller is not a miner registered on the task's subnet.
+        NotMiner,
+    }
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_initialize(n: BlockNumberFor<T>) -> Weight {
+            let expired = Self::expire_tasks(n) as u64;
+            T::DbWeight::get().reads_writes(expired, expired.saturating_mul(2))
+        }
+    }
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        /// Post a task with `input` to an active subnet that has room
+        /// for another open task.
+        #[pallet::call_index(0)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(3, 3))]
+        pub fn submit_task(origin: OriginFor<T>, subnet_id: u32, input: Vec<u8>) -> DispatchResult {
+            let poster = ensure_signed(origin)?;
+
+            let subnet = subnet_registry::Pallet::<T>::subnets(subnet_id)
+                .ok_or(Error::<T>::SubnetNotFound)?;
+            ensure!(
+                subnet.status == subnet_registry::SubnetStatus::Active,
+                Error::<T>::SubnetNotActive
+            );
+            ensure!(
+                input.len() <= subnet.max_input_bytes as usize,
+                Error::<T>::InputTooLarge
+            );
+            let input: BoundedVec<u8, MaxInputBytesOf<T>> =
+                input.try_into().map_err(|_| Error::<T>::InputTooLarge)?;
+            ensure!(
+                OpenTasks::<T>::get(subnet_id) < subnet.max_open_tasks,
+                Error::<T>::TooManyOpenTasks
+            );
+
+            let task_id = NextTaskId::<T>::get();
+            let next_id = task_id.checked_add(1).ok_or(Error::<T>::TaskIdOverflow)?;
+            Tasks::<T>::insert(
+                task_id,
+                TaskInfo {
+                    subnet_id,
+                    poster: poster.clone(),
+                    input,
+                    status: TaskStatus::Open,
+                },
+            );
+            NextTaskId::<T>::put(next_id);
+            OpenTasks::<T>::mutate(subnet_id, |count| *count = count.saturating_add(1));
+
+            Self::deposit_event(Event::TaskSubmitted {
+                task_id,
+                subnet_id,
+                poster,
+            });
+            Ok(())
+        }
+
+        /// Accept an open task as a miner registered on its subnet,
+        /// freeing a slot on the subnet.  The task is removed
+        /// `AcceptedTaskRetention` blocks later.
+        #[pallet::call_index(1)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(3, 3))]
+        pub fn accept_task(origin: OriginFor<T>, task_id: u64) -> DispatchResult {
+            let miner = ensure_signed(origin)?;
+
+            let subnet_id =
+                Tasks::<T>::try_mutate(task_id, |maybe_task| -> Result<u32, DispatchError> {
+                    let task = maybe_task.as_mut().ok_or(Error::<T>::TaskNotFound)?;
+                    ensure!(task.status == TaskStatus::Open, Error::<T>::TaskNotOpen);
+                    ensure!(
+                        T::Miners::is_miner(task.subnet_id, &miner),
+                        Error::<T>::NotMiner
+                    );
+                    task.status = TaskStatus::Accepted {
+                        miner: miner.clone(),
+                    };
+                    Ok(task.subnet_id)
+                })?;
+            OpenTasks::<T>::mutate(subnet_id, |count| *count = count.saturating_sub(1));
+            let retention = T::AcceptedTaskRetention::get().max(One::one());
+            let expires_at = frame_system::Pallet::<T>::block_number().saturating_add(retention);
+            ExpiringTasks::<T>::insert(expires_at, task_id, ());
+
+            Self::deposit_event(Event::TaskAccepted { task_id, miner });
+            Ok(())
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// Remove the accepted tasks whose retention ends at `now`.
+        /// Returns the number removed.
+        fn expire_tasks(now: BlockNumberFor<T>) -> u32 {
+            let mut expired = 0;
+            for (task_id, ()) in ExpiringTasks::<T>::drain_prefix(now) {
+                Tasks::<T>::remove(task_id);
+                expired += 1;
+            }
+            expired
+        }
+    }
+}
@@ -0,0 +1,418 @@
+//! Validator registry pallet.
+//!
+//! Validators register on an active subnet by reserving at least the
+//! subnet's `min_stake_validator`, and may later raise or lower their
+//! stake.  Each subnet has `MaxValidatorsPerSubnet` slots.  Once they
+//! are taken, a newcomer staking more than the lowest‑staked validator
+//! evicts it, which unreserves the evicted validator's stake.  A
+//! validator that deregisters frees its slot and gets its stake back.
+//!
+//! Rewards are only fair if validators actually take part in
+//! consensus, so at the start of every epoch the pallet checks who
+//! submitted weights for each subnet during the epoch that just ended.
+//! A validator that misses `MaxMissedEpochs` epochs in a row on a
+//! subnet is marked inactive and loses `SlashFraction` of its stake
+//! there; a validator that submits again has its missed counter
+//! cleared and is active again.
+
+pub use pallet::*;
+
+/// Record of which validators submitted weights in an epoch, kept by
+/// the consensus layer.
+pub trait WeightSubmissions<AccountId> {
+    /// Whether `validator` submitted weights for `subnet_id` during
+    /// `epoch`.
+    fn has_submitted(subnet_id: u32, validator: &AccountId, epoch: u32) -> bool;
+}
+
+/// Without a consensus layer no validator is held to account.
+impl<AccountId> WeightSubmissions<AccountId> for () {
+    fn has_submitted(_: u32, _: &AccountId, _: u32) -> bool {
+        true
+    }
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+    use super::WeightSubmissions;
+    use crate::pallets::consensus::ValidatorStakes;
+    use crate::pallets::emissions::RewardRecipients;
+    use crate::pallets::subnet_registry::{self, BalanceOf};
+    use frame_support::{pallet_prelude::*, traits::ReservableCurrency};
+    use frame_system::pallet_prelude::*;
+    use sp_runtime::{
+        traits::{Saturating, UniqueSaturatedInto, Zero},
+        Perbill,
+    };
+    use sp_std::prelude::*;
+
+    /// On‑chain record of a validator on one subnet.
+    #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub struct ValidatorInfo<Balance> {
+        /// Amount reserved as stake.
+        pub stake: Balance,
+        /// Inactive validators have missed too many epochs.
+        pub active: bool,
+        /// Consecutive epochs without a weight submission.
+        pub missed_epochs: u32,
+    }
+
+    #[pallet::pallet]
+    pub struct Pallet<T>(_);
+
+    #[pallet::config]
+    pub trait Config: frame_system::Config + subnet_registry::Config {
+        type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+        /// Maximum number of validators registered on one subnet.
+        #[pallet::constant]
+        type MaxValidatorsPerSubnet: Get<u32>;
+
+        /// Number of blocks in an epoch.  Must match the epoch length of
+        /// the consensus layer, which numbers epochs the same way.
+        #[pallet::constant]
+        type EpochLength: Get<BlockNumberFor<Self>>;
+
+        /// Consecutive missed epochs after which a validator is marked
+        /// inactive.
+        #[pallet::constant]
+        type MaxMissedEpochs: Get<u32>;
+
+        /// Share of stake slashed when a validator is marked inactive.
+        /// Zero disables slashing.
+        #[pallet::constant]
+        type SlashFraction: Get<Perbill>;
+
+        /// Where weight submissions are recorded.
+        type WeightSubmissions: WeightSubmissions<Self::AccountId>;
+    }
+
+    /// Registered validators by subnet.
+    #[pallet::storage]
+    #[pallet::getter(fn validators)]
+    pub type Validators<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        u32,
+        Blake2_128Concat,
+        T::AccountId,
+        ValidatorInfo<BalanceOf<T>>,
+    >;
+
+    /// Number of validators registered on each subnet.
+    #[pallet::storage]
+    #[pallet::getter(fn validator_count)]
+    pub type ValidatorCount<T> = StorageMap<_, Blake2_128Concat, u32, u32, ValueQuery>;
+
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        /// A validator registered on a subnet.
+        ValidatorRegistered {
+            subnet_id: u32,
+            validator: T::AccountId,
+            stake: BalanceOf<T>,
+        },
+        /// A validator changed its stake on a subnet.
+        StakeUpdated {
+            subnet_id: u32,
+            validator: T::AccountId,
+            stake: BalanceOf<T>,
+        },
+        /// A validator left a subnet and had its `stake` unreserved.
+        ValidatorDeregistered {
+            subnet_id: u32,
+            validator: T::AccountId,
+            stake: BalanceOf<T>,
+        },
+        /// A validator lost its slot to a newcomer with a higher stake
+        /// and had its `stake` unreserved.
+        ValidatorEvicted {
+            subnet_id: u32,
+            validator: T::AccountId,
+            stake: BalanceOf<T>,
+        },
+        /// A validator missed `MaxMissedEpochs` epochs, was marked
+        /// inactive and lost `slashed` of its stake.
+        ValidatorPenalized {
+            subnet_id: u32,
+            validator: T::AccountId,
+            missed_epochs: u32,
+            slashed: BalanceOf<T>,
+        },
+    }
+
+    #[pallet::error]
+    pub enum Error<T> {
+        /// No subnet exists with the given id.
+        SubnetNotFound,
+        /// The subnet has been retired.
+        SubnetNotActive,
+        /// The subnet is paused by its owner.
+        SubnetPaused,
+        /// The caller is already a validator on the subnet.
+        AlreadyRegistered,
+        /// The caller is not a validator on the subnet.
+        NotRegistered,
+        /// The stake is below the subnet's `min_stake_validator`.
+        StakeTooLow,
+        /// The caller cannot cover the stake.
+        InsufficientBalance,
+        /// All `MaxValidatorsPerSubnet` slots are taken by validators
+        /// staking at least as much.
+        ValidatorSlotsFull,
+    }
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_initialize(n: BlockNumberFor<T>) -> Weight {
+            let epoch_length = T::EpochLength::get();
+            if n.is_zero() || epoch_length.is_zero() || !(n % epoch_length).is_zero() {
+                return Weight::zero();
+            }
+            let count = Self::end_epoch() as u64;
+            T::DbWeight::get().reads_writes(count.saturating_add(1), count)
+        }
+    }
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        /// Register the caller as a validator on an active subnet,
+        /// reserving `stake`.  When the subnet's slots are full, the
+        /// lowest‑staked validator is evicted if `stake` is higher.
+        #[pallet::call_index(0)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(4, 5))]
+        pub fn register_validator(
+            origin: OriginFor<T>,
+            subnet_id: u32,
+            stake: BalanceOf<T>,
+        ) -> DispatchResult {
+            let validator = ensure_signed(origin)?;
+
+            let subnet = subnet_registry::Pallet::<T>::subnets(subnet_id)
+                .ok_or(Error::<T>::SubnetNotFound)?;
+            ensure!(
+                subnet.status != subnet_registry::SubnetStatus::Paused,
+                Error::<T>::SubnetPaused
+            );
+            ensure!(
+                subnet_registry::Pallet::<T>::is_subnet_active(subnet_id),
+                Error::<T>::SubnetNotActive
+            );
+            ensure!(
+                !Validators::<T>::contains_key(subnet_id, &validator),
+                Error::<T>::AlreadyRegistered
+            );
+            ensure!(stake >= subnet.min_stake_validator, Error::<T>::StakeTooLow);
+
+            let full = ValidatorCount::<T>::get(subnet_id) >= T::MaxValidatorsPerSubnet::get();
+            let evicted = full
+                .then(|| Self::lowest_staked_below(subnet_id, stake))
+                .transpose()?;
+
+            T::Currency::reserve(&validator, stake).map_err(|_| Error::<T>::InsufficientBalance)?;
+            if let Some((evicted, evicted_stake)) = evicted {
+                Self::evict(subnet_id, evicted, evicted_stake);
+            }
+            Validators::<T>::insert(
+                subnet_id,
+                &validator,
+                ValidatorInfo {
+                    stake,
+                    active: true,
+                    missed_epochs: 0,
+                },
+            );
+            ValidatorCount::<T>::mutate(subnet_id, |count| *count = count.saturating_add(1));
+            subnet_registry::Pallet::<T>::note_member_registered(subnet_id);
+
+            Self::deposit_event(Event::ValidatorRegistered {
+                subnet_id,
+                validator,
+                stake,
+            });
+            Ok(())
+        }
+
+        /// Change the caller's stake on `subnet_id` to `stake`,
+        /// reserving or releasing the difference.
+        #[pallet::call_index(1)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(3, 2))]
+        pub fn update_stake(
+            origin: OriginFor<T>,
+            subnet_id: u32,
+            stake: BalanceOf<T>,
+        ) -> DispatchResult {
+            let validator = ensure_signed(origin)?;
+            let subnet = subnet_registry::Pallet::<T>::subnets(subnet_id)
+                .ok_or(Error::<T>::SubnetNotFound)?;
+            ensure!(stake >= subnet.min_stake_validator, Error::<T>::StakeTooLow);
+
+            Validators::<T>::try_mutate(subnet_id, &validator, |maybe_info| -> DispatchResult {
+                let info = maybe_info.as_mut().ok_or(Error::<T>::NotRegistered)?;
+                if stake > info.stake {
+                    T::Currency::reserve(&validator, stake - info.stake)
+                        .map_err(|_| Error::<T>::InsufficientBalance)?;
+                } else {
+                    T::Currency::unreserve(&validator, info.stake - stake);
+                }
+                info.stake = stake;
+                Ok(())
+            })?;
+
+            Self::deposit_event(Event::StakeUpdated {
+                subnet_id,
+                validator,
+                stake,
+            });
+            Ok(())
+        }
+
+        /// Remove the caller from the validators of `subnet_id`, freeing
+        /// its slot and unreserving its stake.
+        #[pallet::call_index(2)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(2, 3))]
+        pub fn deregister_validator(origin: OriginFor<T>, subnet_id: u32) -> DispatchResult {
+            let validator = ensure_signed(origin)?;
+            let info =
+                Validators::<T>::take(subnet_id, &validator).ok_or(Error::<T>::NotRegistered)?;
+            ValidatorCount::<T>::mutate(subnet_id, |count| *count = count.saturating_sub(1));
+            T::Currency::unreserve(&validator, info.stake);
+
+            Self::deposit_event(Event::ValidatorDeregistered {
+                subnet_id,
+                validator,
+                stake: info.stake,
+            });
+            Ok(())
+        }
+    }
+
+    impl<T: Config> ValidatorStakes<T::AccountId, BalanceOf<T>> for Pallet<T> {
+        fn active_stake(subnet_id: u32, validator: &T::AccountId) -> Option<BalanceOf<T>> {
+            Validators::<T>::get(subnet_id, validator)
+                .filter(|info| info.active)
+                .map(|info| info.stake)
+        }
+
+        fn is_validator(subnet_id: u32, validator: &T::AccountId) -> bool {
+            Validators::<T>::contains_key(subnet_id, validator)
+        }
+    }
+
+    /// Active validators are rewarded in proportion to their stake.
+    impl<T: Config> RewardRecipients<T::AccountId> for Pallet<T> {
+        fn reward_weights(subnet_id: u32) -> Vec<(T::AccountId, u32)> {
+            let validators = Self::active_validators(subnet_id);
+            let total = validators
+                .iter()
+                .fold(BalanceOf::<T>::zero(), |total, (_, stake)| {
+                    total.saturating_add(*stake)
+                });
+            validators
+                .into_iter()
+                .map(|(validator, stake)| {
+                    let share = Perbill::from_rational(stake, total);
+                    (validator, share.deconstruct())
+                })
+                .collect()
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// Whether `validator` is registered and active on `subnet_id`.
+        pub fn is_active_validator(subnet_id: u32, validator: &T::AccountId) -> bool {
+            Validators::<T>::get(subnet_id, validator)
+                .map(|info| info.active)
+                .unwrap_or(false)
+        }
+
+        /// The active validators of `subnet_id` and their stakes,
+        /// highest stake first.
+        pub fn active_validators(subnet_id: u32) -> Vec<(T::AccountId, BalanceOf<T>)> {
+            let mut validators: Vec<_> = Validators::<T>::iter_prefix(subnet_id)
+                .filter(|(_, info)| info.active)
+                .map(|(validator, info)| (validator, info.stake))
+                .collect();
+            validators.sort_by(|(a, a_stake), (b, b_stake)| b_stake.cmp(a_stake).then(a.cmp(b)));
+            validators
+        }
+
+        /// The lowest‑staked validator of `subnet_id` and its stake,
+        /// provided it staked less than `stake`.
+        fn lowest_staked_below(
+            subnet_id: u32,
+            stake: BalanceOf<T>,
+        ) -> Result<(T::AccountId, BalanceOf<T>), Error<T>> {
+            Validators::<T>::iter_prefix(subnet_id)
+                .map(|(validator, info)| (validator, info.stake))
+                .min_by(|(a, a_stake), (b, b_stake)| a_stake.cmp(b_stake).then(a.cmp(b)))
+                .filter(|(_, lowest)| *lowest < stake)
+                .ok_or(Error::<T>::ValidatorSlotsFull)
+        }
+
+        /// Remove `validator` from `subnet_id` to free its slot and
+        /// unreserve its `stake`.
+        fn evict(subnet_id: u32, validator: T::AccountId, stake: BalanceOf<T>) {
+            Validators::<T>::remove(subnet_id, &validator);
+            ValidatorCount::<T>::mutate(subnet_id, |count| *count = count.saturating_sub(1));
+            T::Currency::unreserve(&validator, stake);
+            Self::deposit_event(Event::ValidatorEvicted {
+                subnet_id,
+                validator,
+                stake,
+            });
+        }
+
+        /// The epoch in progress, counted in blocks from genesis.
+        pub fn current_epoch() -> u32 {
+            let epoch_length = T::EpochLength::get();
+            if epoch_length.is_zero() {
+                return 0;
+            }
+            (frame_system::Pallet::<T>::block_number() / epoch_length).unique_saturated_into()
+        }
+
+        /// Check weight submissions for the epoch that just ended and
+        /// penalize validators that reached `MaxMissedEpochs`.  Returns
+        /// the number of validators checked.
+        fn end_epoch() -> u32 {
+            let epoch = Self::current_epoch().saturating_sub(1);
+            let validators: Vec<(u32, T::AccountId)> = Validators::<T>::iter_keys().collect();
+            for (subnet_id, validator) in &validators {
+                Validators::<T>::mutate_extant(subnet_id, validator, |info| {
+                    if T::WeightSubmissions::has_submitted(*subnet_id, validator, epoch) {
+                        info.missed_epochs = 0;
+                        info.active = true;
+                        return;
+                    }
+                    info.missed_epochs = info.missed_epochs.saturating_add(1);
+                    if info.active && info.missed_epochs >= T::MaxMissedEpochs::get() {
+                        info.active = false;
+                        let slashed = Self::slash(validator, info);
+                        Self::deposit_event(Event::ValidatorPenalized {
+                            subnet_id: *subnet_id,
+                            validator: validator.clone(),
+                            missed_epochs: info.missed_epochs,
+                            slashed,
+                        });
+                    }
+                });
+            }
+            validators.len() as u32
+        }
+
+        /// Slash `SlashFraction` of the validator's stake.  Returns the
+        /// amount slashed.
+        fn slash(validator: &T::AccountId, info: &mut ValidatorInfo<BalanceOf<T>>) -> BalanceOf<T> {
+            let amount = T::SlashFraction::get().mul_floor(info.stake);
+            if amount.is_zero() {
+                return amount;
+            }
+            let (_, unslashed) = T::Currency::slash_reserved(validator, amount);
+            let slashed = amount.saturating_sub(unslashed);
+            info.stake = info.stake.saturating_sub(slashed);
+            slashed
+        }
+    }
+}
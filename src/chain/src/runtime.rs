@@ -0,0 +1,336 @@
+//! The NeuroChain runtime.
+//!
+//! Assembles `frame_system`, `pallet_timestamp`, `pallet_balances`, the
+//! subnet registry, the miner and validator registries, consensus,
+//! emissions and the task queue into a single [`Runtime`].  The
+//! remaining NeuroMesh pallets are added here as they are wired up.
+//! The runtime APIs it implements are listed in `impl_runtime_apis!`
+//! at the end.
+
+use frame_support::{
+    derive_impl, parameter_types,
+    traits::{ConstBool, ConstU128, ConstU32, ConstU64},
+};
+use sp_api::impl_runtime_apis;
+use sp_core::OpaqueMetadata;
+use sp_runtime::{
+    generic,
+    traits::{BlakeTwo256, Block as BlockT, IdentifyAccount, Verify},
+    MultiAddress, MultiSignature, Perbill, Percent,
+};
+use sp_std::prelude::*;
+use sp_version::RuntimeVersion;
+
+use crate::pallets::{
+    consensus, emissions, miner_registry, subnet_registry, task_queue, validator_registry,
+};
+use crate::runtime_api::SubnetParticipation;
+
+/// Signature type used by extrinsics.
+pub type Signature = MultiSignature;
+
+/// Account id derived from the signer's public key.
+pub type AccountId = <<Signature as Verify>::Signer as IdentifyAccount>::AccountId;
+
+/// Balance of an account.
+pub type Balance = u128;
+
+/// Index of a block.
+pub type BlockNumber = u32;
+
+/// Transaction index of an account.
+pub type Nonce = u32;
+
+/// The address format for describing accounts.
+pub type Address = MultiAddress<AccountId, ()>;
+
+/// Block header type.
+pub type Header = generic::Header<BlockNumber, BlakeTwo256>;
+
+/// Extensions checked for every signed extrinsic, in encoding order.
+pub type SignedExtra = (
+    frame_system::CheckNonZeroSender<Runtime>,
+    frame_system::CheckSpecVersion<Runtime>,
+    frame_system::CheckTxVersion<Runtime>,
+    frame_system::CheckGenesis<Runtime>,
+    frame_system::CheckEra<Runtime>,
+    frame_system::CheckNonce<Runtime>,
+    frame_system::CheckWeight<Runtime>,
+);
+
+/// Unchecked extrinsic type as expected by this runtime.
+pub type UncheckedExtrinsic =
+    generic::UncheckedExtrinsic<Address, RuntimeCall, Signature, SignedExtra>;
+
+/// Block type as expected by this runtime.
+pub type Block = generic::Block<Header, UncheckedExtrinsic>;
+
+/// Dispatches extrinsics to the pallets.
+pub type Executive = frame_executive::Executive<
+    Runtime,
+    Block,
+    frame_system::ChainContext<Runtime>,
+    Runtime,
+    AllPalletsWithSystem,
+>;
+
+/// One token, in the smallest unit.
+pub const UNIT: Balance = 1_000_000_000_000;
+
+/// Smallest balance an account may hold.
+pub const EXISTENTIAL_DEPOSIT: Balance = UNIT / 1_000;
+
+/// Target block time.
+pub const MILLISECS_PER_BLOCK: u64 = 6_000;
+
+pub const MINUTES: BlockNumber = 60_000 / (MILLISECS_PER_BLOCK as BlockNumber);
+pub const HOURS: BlockNumber = MINUTES * 60;
+pub const DAYS: BlockNumber = HOURS * 24;
+
+/// Blocks in a consensus epoch, shared by the validator registry and
+/// consensus so that both number epochs alike.
+pub const EPOCH_LENGTH: BlockNumber = HOURS;
+
+/// The runtime version.  `spec_version` follows [`crate::VERSION`].
+pub const RUNTIME_VERSION: RuntimeVersion = RuntimeVersion {
+    spec_name: sp_version::create_runtime_str!("neurochain"),
+    impl_name: sp_version::create_runtime_str!("neurochain"),
+    authoring_version: 1,
+    spec_version: crate::VERSION,
+    impl_version: 1,
+    apis: RUNTIME_API_VERSIONS,
+    transaction_version: 1,
+    state_version: 1,
+};
+
+frame_support::construct_runtime!(
+    pub enum Runtime {
+        System: frame_system,
+        Timestamp: pallet_timestamp,
+        Balances: pallet_balances,
+        SubnetRegistry: subnet_registry,
+        MinerRegistry: miner_registry,
+        ValidatorRegistry: validator_registry,
+        Consensus: consensus,
+        Emissions: emissions,
+        TaskQueue: task_queue,
+    }
+);
+
+parameter_types! {
+    pub const Version: RuntimeVersion = RUNTIME_VERSION;
+}
+
+#[derive_impl(
+    frame_system::config_preludes::SolochainDefaultConfig as frame_system::DefaultConfig
+)]
+impl frame_system::Config for Runtime {
+    type Block = Block;
+    type AccountId = AccountId;
+    type Nonce = Nonce;
+    type BlockHashCount = ConstU32<2400>;
+    type Version = Version;
+    type AccountData = pallet_balances::AccountData<Balance>;
+}
+
+impl pallet_timestamp::Config for Runtime {
+    type Moment = u64;
+    type OnTimestampSet = ();
+    type MinimumPeriod = ConstU64<{ MILLISECS_PER_BLOCK / 2 }>;
+    type WeightInfo = ();
+}
+
+#[derive_impl(
+    pallet_balances::config_preludes::TestDefaultConfig as pallet_balances::DefaultConfig
+)]
+impl pallet_balances::Config for Runtime {
+    type Balance = Balance;
+    type ExistentialDeposit = ConstU128<EXISTENTIAL_DEPOSIT>;
+    type AccountStore = System;
+}
+
+parameter_types! {
+    pub const MinEmissionWeight: Percent = Percent::from_percent(1);
+    pub const IdleDecayRate: Percent = Percent::from_percent(10);
+}
+
+impl subnet_registry::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type Currency = Balances;
+    type SubnetDeposit = ConstU128<{ 100 * UNIT }>;
+    type MaxSchemaSize = ConstU32<4096>;
+    type MaxUriSize = ConstU32<256>;
+    type MaxNameSize = ConstU32<64>;
+    type MaxDescriptionSize = ConstU32<1024>;
+    type MaxSubnets = ConstU32<16>;
+    type MinEmissionWeight = MinEmissionWeight;
+    type MaxSchemaVersions = ConstU32<8>;
+    type MaxInputBytes = ConstU32<{ 1024 * 1024 }>;
+    type MaxOpenTasks = ConstU32<1024>;
+    type ForceOrigin = frame_system::EnsureRoot<AccountId>;
+    type RetirementCooldown = ConstU32<{ 7 * DAYS }>;
+    type RetiredSubnetRetention = ConstU32<{ 30 * DAYS }>;
+    type MaxPurgesPerBlock = ConstU32<4>;
+    type SubnetMembers = RegisteredMembers;
+    type RequestIdWindow = ConstU32<HOURS>;
+    type IdleDecayPeriod = ConstU32<{ 7 * DAYS }>;
+    type IdleDecayRate = IdleDecayRate;
+    type MaxIdleChecksPerBlock = ConstU32<16>;
+    type UniqueEvaluationSpecs = ConstBool<false>;
+}
+
+/// Subnets have members while a miner or validator is registered on
+/// them.
+pub struct RegisteredMembers;
+
+impl subnet_registry::SubnetMembers for RegisteredMembers {
+    fn has_members(subnet_id: u32) -> bool {
+        ValidatorRegistry::validator_count(subnet_id) > 0
+            || miner_registry::Miners::<Runtime>::iter_key_prefix(subnet_id)
+                .next()
+                .is_some()
+    }
+}
+
+impl miner_registry::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type MaxCapabilities = ConstU32<256>;
+    type MaxEndpointLen = ConstU32<128>;
+    type DeregistrationCooldown = ConstU32<DAYS>;
+}
+
+impl task_queue::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type Miners = MinerRegistry;
+    type AcceptedTaskRetention = ConstU32<DAYS>;
+}
+
+parameter_types! {
+    pub const SlashFraction: Perbill = Perbill::from_percent(1);
+}
+
+impl validator_registry::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type MaxValidatorsPerSubnet = ConstU32<64>;
+    type EpochLength = ConstU32<EPOCH_LENGTH>;
+    type MaxMissedEpochs = ConstU32<3>;
+    type SlashFraction = SlashFraction;
+    type WeightSubmissions = Consensus;
+}
+
+impl consensus::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type Balance = Balance;
+    type ValidatorStakes = ValidatorRegistry;
+    type Miners = MinerRegistry;
+    type Aggregator = consensus::StakeWeightedMean;
+    type MaxWeightsPerValidator = ConstU32<256>;
+    type EpochLength = ConstU32<EPOCH_LENGTH>;
+    type AdminOrigin = frame_system::EnsureRoot<AccountId>;
+}
+
+parameter_types! {
+    pub const MinerValidatorSplit: Percent = Percent::from_percent(80);
+}
+
+impl emissions::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type Currency = Balances;
+    type EmissionWeights = SubnetRegistry;
+    type EmissionInterval = ConstU32<HOURS>;
+    type BlockEmission = ConstU128<{ 1_000 * UNIT }>;
+    type HistoryDepth = ConstU32<{ 7 * 24 }>;
+    type MinerValidatorSplit = MinerValidatorSplit;
+    type Miners = Consensus;
+    type Validators = ValidatorRegistry;
+}
+
+/// Miners and active validators of `subnet_id` and their stake, or
+/// `None` if the subnet does not exist.
+fn subnet_participation(subnet_id: u32) -> Option<SubnetParticipation> {
+    SubnetRegistry::subnets(subnet_id)?;
+    let mut participation = SubnetParticipation::default();
+    for miner in miner_registry::Miners::<Runtime>::iter_prefix_values(subnet_id) {
+        participation.miners += 1;
+        participation.total_stake = participation.total_stake.saturating_add(miner.stake);
+    }
+    for (_, stake) in ValidatorRegistry::active_validators(subnet_id) {
+        participation.validators += 1;
+        participation.total_stake = participation.total_stake.saturating_add(stake);
+    }
+    Some(participation)
+}
+
+impl_runtime_apis! {
+    impl sp_api::Core<Block> for Runtime {
+        fn version() -> RuntimeVersion {
+            RUNTIME_VERSION
+        }
+
+        fn execute_block(block: Block) {
+            Executive::execute_block(block);
+        }
+
+        fn initialize_block(header: &<Block as BlockT>::Header) {
+            Executive::initialize_block(header)
+        }
+    }
+
+    impl sp_api::Metadata<Block> for Runtime {
+        fn metadata() -> OpaqueMetadata {
+            OpaqueMetadata::new(Runtime::metadata().into())
+        }
+
+        fn metadata_at_version(version: u32) -> Option<OpaqueMetadata> {
+            Runtime::metadata_at_version(version)
+        }
+
+        fn metadata_versions() -> Vec<u32> {
+            Runtime::metadata_versions()
+        }
+    }
+
+    impl crate::runtime_api::SubnetRegistryApi<
+        Block,
+        AccountId,
+        BlockNumber,
+        subnet_registry::SubnetInfo<Runtime>,
+    > for Runtime {
+        fn subnet_retirement(subnet_id: u32) -> Option<(BlockNumber, Option<Vec<u8>>)> {
+            SubnetRegistry::retirement(subnet_id)
+                .map(|(retired_at, reason)| (retired_at, reason.map(|reason| reason.into_inner())))
+        }
+
+        fn subnets(subnet_ids: Vec<u32>) -> Vec<(u32, subnet_registry::SubnetInfo<Runtime>)> {
+            SubnetRegistry::subnets_with_ids(&subnet_ids)
+        }
+
+        fn subnets_of(owner: AccountId) -> Vec<u32> {
+            SubnetRegistry::owner_subnets(owner).into_inner()
+        }
+
+        fn subnet_info(subnet_id: u32) -> Option<subnet_registry::SubnetInfo<Runtime>> {
+            SubnetRegistry::subnets(subnet_id)
+        }
+
+        fn active_subnets() -> Vec<u32> {
+            SubnetRegistry::active_subnets()
+        }
+
+        fn subnet_participants(subnet_id: u32) -> Option<SubnetParticipation> {
+            subnet_participation(subnet_id)
+        }
+    }
+
+    impl crate::runtime_api::MinerRegistryApi<Block, AccountId> for Runtime {
+        fn miner_capabilities(subnet_id: u32, account: AccountId) -> Option<Vec<u8>> {
+            MinerRegistry::miner_capabilities(subnet_id, &account)
+        }
+    }
+
+    impl crate::runtime_api::EmissionsApi<Block, Balance> for Runtime {
+        fn emission_history() -> Vec<(u32, Balance)> {
+            Emissions::emission_history()
+        }
+    }
+}
@@ -0,0 +1,65 @@
+//! Runtime APIs exposed to clients.
+
+use codec::{Codec, Decode, Encode};
+use scale_info::TypeInfo;
+use sp_runtime::RuntimeDebug;
+use sp_std::vec::Vec;
+
+/// How many miners and active validators take part in a subnet, and
+/// the stake they hold on it together.
+#[derive(Clone, Copy, Default, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+pub struct SubnetParticipation {
+    pub miners: u32,
+    pub validators: u32,
+    pub total_stake: u128,
+}
+
+sp_api::decl_runtime_apis! {
+    /// Queries against the subnet registry.
+    ///
+    /// Version 2 added `subnets`, version 3 `subnets_of`, `subnet_info`
+    /// and `active_subnets`, and version 4 `subnet_participants`.
+    #[api_version(4)]
+    pub trait SubnetRegistryApi<AccountId: Codec, BlockNumber: Codec, SubnetInfo: Codec> {
+        /// The block in which `subnet_id` was retired and the reason
+        /// given, or `None` if the subnet is unknown or still active.
+        fn subnet_retirement(subnet_id: u32) -> Option<(BlockNumber, Option<Vec<u8>>)>;
+
+        /// The subnets among `subnet_ids` that exist, with their info,
+        /// in the order of `subnet_ids`.
+        #[api_version(2)]
+        fn subnets(subnet_ids: Vec<u32>) -> Vec<(u32, SubnetInfo)>;
+
+        /// Ids of the subnets owned by `owner`, in creation order.
+        #[api_version(3)]
+        fn subnets_of(owner: AccountId) -> Vec<u32>;
+
+        /// The info of `subnet_id`, or `None` if it does not exist.
+        #[api_version(3)]
+        fn subnet_info(subnet_id: u32) -> Option<SubnetInfo>;
+
+        /// Ids of the subnets that are neither paused nor retired,
+        /// ascending.
+        #[api_version(3)]
+        fn active_subnets() -> Vec<u32>;
+
+        /// Miners and active validators of `subnet_id`, or `None` if the
+        /// subnet does not exist.
+        #[api_version(4)]
+        fn subnet_participants(subnet_id: u32) -> Option<SubnetParticipation>;
+    }
+
+    /// Queries against the miner registry.
+    pub trait MinerRegistryApi<AccountId: Codec> {
+        /// Capabilities `account` declared on `subnet_id`, or `None` if
+        /// it is not a miner there.
+        fn miner_capabilities(subnet_id: u32, account: AccountId) -> Option<Vec<u8>>;
+    }
+
+    /// Queries against the emissions pallet.
+    pub trait EmissionsApi<Balance: Codec> {
+        /// Total emitted in each epoch still kept in the history, by
+        /// epoch, oldest first.
+        fn emission_history() -> Vec<(u32, Balance)>;
+    }
+}
@@ -0,0 +1,286 @@
+mod mock;
+
+use frame_support::{assert_noop, assert_ok, traits::Currency};
+use mock::*;
+use neurochain::pallets::consensus::{
+    normalize_weights, AggregationMethod, ConsensusWeights, Error, Event, StakeWeightedMean,
+    StakeWeightedMedian, WeightAggregator,
+};
+use sp_runtime::DispatchError;
+
+const SUBNET: u32 = 0;
+const MINER_A: u64 = 10;
+const MINER_B: u64 = 11;
+
+/// Register `miner` on [`SUBNET`] so that validators may weigh it.
+fn register_miner(miner: u64) {
+    Balances::make_free_balance_be(&miner, 100);
+    assert_ok!(MinerRegistry::register_miner(
+        RuntimeOrigin::signed(miner),
+        SUBNET,
+        b"/ip4/127.0.0.1/tcp/4001".to_vec(),
+        10,
+        vec![],
+    ));
+}
+
+/// Create [`SUBNET`], owned by ALICE, and register [`MINER_A`] and
+/// [`MINER_B`] on it.
+fn create_subnet() {
+    assert_ok!(SubnetBuilder::new(ALICE)
+        .min_stake_validator(MIN_VALIDATOR_STAKE)
+        .create());
+    register_miner(MINER_A);
+    register_miner(MINER_B);
+}
+
+/// Create [`SUBNET`], register ALICE on it with 300 and BOB with 100
+/// stake, and set their weights for it.
+fn set_weights() {
+    create_subnet();
+    for (validator, stake, weights) in [
+        (ALICE, 300, vec![(MINER_A, 1000), (MINER_B, 200)]),
+        (BOB, 100, vec![(MINER_A, 200)]),
+    ] {
+        assert_ok!(ValidatorRegistry::register_validator(
+            RuntimeOrigin::signed(validator),
+            SUBNET,
+            stake
+        ));
+        assert_ok!(Consensus::set_weights(
+            RuntimeOrigin::signed(validator),
+            SUBNET,
+            weights
+        ));
+    }
+}
+
+fn set_method(method: AggregationMethod) {
+    assert_ok!(Consensus::set_aggregation_method(
+        RuntimeOrigin::root(),
+        SUBNET,
+        method
+    ));
+}
+
+#[test]
+fn stake_weighted_mean_is_the_default() {
+    new_test_ext().execute_with(|| {
+        set_weights();
+        run_to_block(EPOCH_LENGTH);
+
+        assert_eq!(
+            Consensus::consensus_weights(SUBNET).into_inner(),
+            vec![(MINER_A, 57343), (MINER_B, 8191)]
+        );
+        assert!(Consensus::weights(SUBNET, ALICE).is_none());
+        System::assert_has_event(
+            Event::ConsensusReached {
+                subnet_id: SUBNET,
+                method: AggregationMethod::StakeWeightedMean,
+            }
+            .into(),
+        );
+        assert!(MockAggregator::calls().is_empty());
+    });
+}
+
+#[test]
+fn consensus_weights_are_cleared_when_nobody_sets_weights() {
+    new_test_ext().execute_with(|| {
+        set_weights();
+        run_to_block(EPOCH_LENGTH);
+        assert!(!Consensus::consensus_weights(SUBNET).is_empty());
+
+        run_to_block(2 * EPOCH_LENGTH);
+        assert!(!ConsensusWeights::<Test>::contains_key(SUBNET));
+    });
+}
+
+#[test]
+fn stake_weighted_median_is_selectable_per_subnet() {
+    new_test_ext().execute_with(|| {
+        set_method(AggregationMethod::StakeWeightedMedian);
+        set_weights();
+        run_to_block(EPOCH_LENGTH);
+
+        assert_eq!(
+            Consensus::consensus_weights(SUBNET).into_inner(),
+            vec![(MINER_A, 54613), (MINER_B, 10922)]
+        );
+    });
+}
+
+#[test]
+fn custom_aggregator_is_invoked_and_its_output_stored() {
+    new_test_ext().execute_with(|| {
+        set_method(AggregationMethod::Custom);
+        set_weights();
+        run_to_block(EPOCH_LENGTH);
+
+        assert_eq!(MockAggregator::calls(), vec![vec![ALICE, BOB]]);
+        assert_eq!(
+            Consensus::consensus_weights(SUBNET).into_inner(),
+            vec![(MINER_A, 200), (MINER_B, 100)]
+        );
+    });
+}
+
+#[test]
+fn only_the_strongest_weights_are_kept() {
+    new_test_ext().execute_with(|| {
+        create_subnet();
+        assert_ok!(ValidatorRegistry::register_validator(
+            RuntimeOrigin::signed(ALICE),
+            SUBNET,
+            100
+        ));
+        let weights: Vec<_> = (20..20 + MAX_WEIGHTS_PER_VALIDATOR as u64)
+            .map(|miner| (miner, 100 * (miner as u16 - 19)))
+            .collect();
+        for (miner, _) in &weights {
+            register_miner(*miner);
+        }
+        assert_ok!(Consensus::set_weights(
+            RuntimeOrigin::signed(ALICE),
+            SUBNET,
+            weights
+        ));
+        assert_ok!(ValidatorRegistry::register_validator(
+            RuntimeOrigin::signed(BOB),
+            SUBNET,
+            100
+        ));
+        assert_ok!(Consensus::set_weights(
+            RuntimeOrigin::signed(BOB),
+            SUBNET,
+            vec![(MINER_A, 1000)]
+        ));
+        run_to_block(EPOCH_LENGTH);
+
+        // Miner 20 has the lowest weight and is dropped.
+        assert_eq!(
+            Consensus::consensus_weights(SUBNET).into_inner(),
+            vec![(MINER_A, 32767), (21, 6553), (22, 9830), (23, 13107)]
+        );
+    });
+}
+
+#[test]
+fn set_weights_are_normalized() {
+    new_test_ext().execute_with(|| {
+        create_subnet();
+        assert_ok!(ValidatorRegistry::register_validator(
+            RuntimeOrigin::signed(ALICE),
+            SUBNET,
+            100
+        ));
+        assert_ok!(Consensus::set_weights(
+            RuntimeOrigin::signed(ALICE),
+            SUBNET,
+            vec![(MINER_A, 2), (MINER_B, 1)]
+        ));
+
+        assert_eq!(
+            Consensus::weights(SUBNET, ALICE).unwrap().into_inner(),
+            vec![(MINER_A, 43690), (MINER_B, 21845)]
+        );
+        assert_eq!(Consensus::last_submission(SUBNET, ALICE), Some(0));
+        System::assert_last_event(
+            Event::WeightsSet {
+                subnet_id: SUBNET,
+                validator: ALICE,
+                count: 2,
+            }
+            .into(),
+        );
+
+        // Rounding dust goes to the largest weight, and weights that
+        // are all zero stay zero.
+        assert_eq!(
+            normalize_weights(vec![(MINER_A, 1000), (MINER_B, 200)]),
+            vec![(MINER_A, 54613), (MINER_B, 10922)]
+        );
+        assert_eq!(
+            normalize_weights(vec![(MINER_A, 0), (MINER_B, 0)]),
+            vec![(MINER_A, 0), (MINER_B, 0)]
+        );
+    });
+}
+
+#[test]
+fn set_weights_are_checked() {
+    new_test_ext().execute_with(|| {
+        create_subnet();
+        assert_noop!(
+            Consensus::set_weights(RuntimeOrigin::signed(ALICE), SUBNET, vec![]),
+            Error::<Test>::NotValidator
+        );
+        assert_ok!(ValidatorRegistry::register_validator(
+            RuntimeOrigin::signed(ALICE),
+            SUBNET,
+            100
+        ));
+        // Registration only counts on the subnet registered on.
+        assert_noop!(
+            Consensus::set_weights(RuntimeOrigin::signed(ALICE), SUBNET + 1, vec![]),
+            Error::<Test>::NotValidator
+        );
+        assert_noop!(
+            Consensus::set_weights(
+                RuntimeOrigin::signed(ALICE),
+                SUBNET,
+                vec![(MINER_A, 1); MAX_WEIGHTS_PER_VALIDATOR as usize + 1]
+            ),
+            Error::<Test>::TooManyWeights
+        );
+        assert_noop!(
+            Consensus::set_weights(
+                RuntimeOrigin::signed(ALICE),
+                SUBNET,
+                vec![(MINER_A, 1), (MINER_B, 2), (MINER_A, 3)]
+            ),
+            Error::<Test>::DuplicateMiner
+        );
+        // CHARLIE is not registered as a miner on the subnet.
+        assert_noop!(
+            Consensus::set_weights(
+                RuntimeOrigin::signed(ALICE),
+                SUBNET,
+                vec![(MINER_A, 1), (CHARLIE, 2)]
+            ),
+            Error::<Test>::UnknownMiner
+        );
+        assert_noop!(
+            Consensus::set_aggregation_method(
+                RuntimeOrigin::signed(ALICE),
+                SUBNET,
+                AggregationMethod::Custom
+            ),
+            DispatchError::BadOrigin
+        );
+        assert_noop!(
+            Consensus::set_aggregation_method(
+                RuntimeOrigin::root(),
+                SUBNET + 1,
+                AggregationMethod::Custom
+            ),
+            Error::<Test>::SubnetNotFound
+        );
+    });
+}
+
+#[test]
+fn built_in_aggregators_count_missing_weights_as_zero() {
+    // BOB holds most of the stake and gave MINER_A nothing.
+    let submissions = vec![(ALICE, 100u64, vec![(MINER_A, 1000)]), (BOB, 300, vec![])];
+
+    assert_eq!(
+        StakeWeightedMean
+            .aggregate(&submissions)
+            .into_iter()
+            .collect::<Vec<_>>(),
+        vec![(MINER_A, 250)]
+    );
+    assert!(StakeWeightedMedian.aggregate(&submissions).is_empty());
+}
@@ -0,0 +1,192 @@
+mod mock;
+
+use frame_support::{assert_noop, assert_ok, traits::Currency};
+use mock::*;
+use neurochain::pallets::emissions::{EmissionHistory, Error, Event, PendingRewards};
+use sp_runtime::Percent;
+
+const MINER_A: u64 = 10;
+const MINER_B: u64 = 11;
+
+/// Register `validator` on `subnet_id` and have it give all its weight
+/// to `miner`, registering `miner` there first if need be.
+fn validate(validator: u64, subnet_id: u32, stake: u64, miner: u64) {
+    if MinerRegistry::miners(subnet_id, miner).is_none() {
+        Balances::make_free_balance_be(&miner, 100);
+        assert_ok!(MinerRegistry::register_miner(
+            RuntimeOrigin::signed(miner),
+            subnet_id,
+            b"/ip4/127.0.0.1/tcp/4001".to_vec(),
+            10,
+            vec![],
+        ));
+    }
+    assert_ok!(ValidatorRegistry::register_validator(
+        RuntimeOrigin::signed(validator),
+        subnet_id,
+        stake
+    ));
+    assert_ok!(Consensus::set_weights(
+        RuntimeOrigin::signed(validator),
+        subnet_id,
+        vec![(miner, 1000)]
+    ));
+}
+
+#[test]
+fn emission_is_split_by_emission_weight() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(SubnetBuilder::new(ALICE).emission_weight(30).create());
+        assert_ok!(SubnetBuilder::new(BOB).create());
+        validate(ALICE, 0, 100, MINER_A);
+        validate(BOB, 1, 100, MINER_B);
+
+        run_to_block(EPOCH_LENGTH);
+        assert_eq!(Emissions::subnet_emission(0), 750);
+        assert_eq!(Emissions::subnet_emission(1), 250);
+        System::assert_has_event(
+            Event::EmissionDistributed {
+                epoch: 1,
+                total: BLOCK_EMISSION,
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn history_records_each_epoch_within_its_depth() {
+    new_test_ext().execute_with(|| {
+        // Nothing is emitted while there is no active subnet.
+        run_to_block(EPOCH_LENGTH);
+        assert_eq!(Emissions::emission_history(), vec![(1, 0)]);
+
+        assert_ok!(SubnetBuilder::new(ALICE).emission_weight(30).create());
+        validate(ALICE, 0, 100, MINER_A);
+        run_to_block(2 * EPOCH_LENGTH);
+        assert_eq!(
+            Emissions::emission_history(),
+            vec![(1, 0), (2, BLOCK_EMISSION)]
+        );
+
+        assert_ok!(SubnetRegistry::retire_subnet(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            None
+        ));
+        run_to_block(3 * EPOCH_LENGTH);
+        assert_eq!(
+            Emissions::emission_history(),
+            vec![(2, BLOCK_EMISSION), (3, 0)]
+        );
+        // The first epoch was pruned, not just hidden.
+        assert_eq!(EmissionHistory::<Test>::get(1), None);
+        assert_eq!(EmissionHistory::<Test>::iter().count(), 2);
+    });
+}
+
+#[test]
+fn shares_nobody_receives_are_not_counted_as_emitted() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(SubnetBuilder::new(ALICE).emission_weight(30).create());
+        assert_ok!(SubnetBuilder::new(BOB).create());
+        // Subnet 0 has a validator but no weights for its miners yet.
+        assert_ok!(ValidatorRegistry::register_validator(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            100
+        ));
+
+        run_to_block(EPOCH_LENGTH);
+        // Only the validators' 40% of subnet 0's 750 is paid out.
+        assert_eq!(Emissions::pending_rewards(ALICE), 300);
+        assert_eq!(Emissions::subnet_emission(0), 300);
+        assert_eq!(Emissions::subnet_emission(1), 0);
+        assert_eq!(Emissions::emission_history(), vec![(1, 300)]);
+    });
+}
+
+#[test]
+fn subnet_rewards_are_split_between_miners_and_validators() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(SubnetBuilder::new(ALICE).emission_weight(30).create());
+        assert_ok!(SubnetBuilder::new(BOB).create());
+        validate(ALICE, 0, 100, MINER_A);
+        validate(BOB, 1, 100, MINER_B);
+
+        run_to_block(EPOCH_LENGTH);
+        // Subnet 0 gets 750 of which miners take 60%, subnet 1 gets 250.
+        assert_eq!(Emissions::pending_rewards(MINER_A), 450);
+        assert_eq!(Emissions::pending_rewards(ALICE), 300);
+        assert_eq!(Emissions::pending_rewards(MINER_B), 150);
+        assert_eq!(Emissions::pending_rewards(BOB), 100);
+    });
+}
+
+#[test]
+fn validators_share_in_proportion_to_stake() {
+    new_test_ext().execute_with(|| {
+        MinerValidatorSplit::set(Percent::zero());
+        assert_ok!(SubnetBuilder::new(ALICE).emission_weight(100).create());
+        validate(ALICE, 0, 300, MINER_A);
+        validate(BOB, 0, 100, MINER_A);
+
+        run_to_block(EPOCH_LENGTH);
+        assert_eq!(Emissions::pending_rewards(ALICE), 750);
+        assert_eq!(Emissions::pending_rewards(BOB), 250);
+        assert_eq!(Emissions::pending_rewards(MINER_A), 0);
+    });
+}
+
+#[test]
+fn claiming_pays_out_and_clears_pending_rewards() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(SubnetBuilder::new(ALICE).emission_weight(30).create());
+        validate(BOB, 0, 100, MINER_A);
+        run_to_block(EPOCH_LENGTH);
+
+        let free = Balances::free_balance(MINER_A);
+        assert_ok!(Emissions::claim_rewards(RuntimeOrigin::signed(MINER_A)));
+        assert_eq!(Balances::free_balance(MINER_A), free + 600);
+        assert_eq!(Emissions::pending_rewards(MINER_A), 0);
+        System::assert_last_event(
+            Event::RewardsClaimed {
+                account: MINER_A,
+                amount: 600,
+            }
+            .into(),
+        );
+        assert_noop!(
+            Emissions::claim_rewards(RuntimeOrigin::signed(MINER_A)),
+            Error::<Test>::NothingToClaim
+        );
+        assert!(!PendingRewards::<Test>::contains_key(MINER_A));
+
+        // Rewards keep accruing after a claim.
+        assert_ok!(Consensus::set_weights(
+            RuntimeOrigin::signed(BOB),
+            0,
+            vec![(MINER_A, 1000)]
+        ));
+        run_to_block(2 * EPOCH_LENGTH);
+        assert_eq!(Emissions::pending_rewards(MINER_A), 600);
+        assert_eq!(Emissions::pending_rewards(BOB), 800);
+    });
+}
+
+#[test]
+fn rewards_of_an_account_that_does_not_exist_stay_pending() {
+    new_test_ext().execute_with(|| {
+        const NOBODY: u64 = 99;
+        assert_ok!(PendingRewards::<Test>::mutate(NOBODY, |pending| {
+            pending.checked_add_reward(1)
+        }));
+
+        assert_noop!(
+            Emissions::claim_rewards(RuntimeOrigin::signed(NOBODY)),
+            pallet_balances::Error::<Test>::DeadAccount
+        );
+        assert_eq!(Emissions::pending_rewards(NOBODY), 1);
+        assert_eq!(Balances::total_balance(&NOBODY), 0);
+    });
+}
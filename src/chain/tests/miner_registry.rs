@@ -0,0 +1,304 @@
+mod mock;
+
+use codec::{Decode, Encode};
+use frame_support::{assert_noop, assert_ok};
+use mock::*;
+use neurochain::pallets::miner_registry::{Error, Event, MinerCapabilities, MinerInfo};
+
+const MIN_STAKE_MINER: u64 = 10;
+const ENDPOINT: &[u8] = b"/ip4/127.0.0.1/tcp/4001";
+
+fn capabilities(gpu: &[u8], memory_gib: u32) -> Vec<u8> {
+    MinerCapabilities {
+        gpu: gpu.to_vec(),
+        memory_gib,
+        max_model_params: 7_000_000_000,
+    }
+    .encode()
+}
+
+#[test]
+fn capabilities_are_declared_and_updated() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(SubnetBuilder::new(ALICE)
+            .min_stake_miner(MIN_STAKE_MINER)
+            .create());
+        assert_ok!(MinerRegistry::register_miner(
+            RuntimeOrigin::signed(BOB),
+            0,
+            ENDPOINT.to_vec(),
+            MIN_STAKE_MINER,
+            capabilities(b"A100", 40),
+        ));
+        assert_eq!(Balances::reserved_balance(BOB), MIN_STAKE_MINER);
+        assert_eq!(
+            MinerRegistry::miner_capabilities(0, &BOB),
+            Some(capabilities(b"A100", 40))
+        );
+
+        assert_ok!(MinerRegistry::set_capabilities(
+            RuntimeOrigin::signed(BOB),
+            0,
+            capabilities(b"H100", 80),
+        ));
+        let declared = MinerRegistry::miner_capabilities(0, &BOB).unwrap();
+        assert_eq!(
+            MinerCapabilities::decode(&mut &declared[..]).unwrap(),
+            MinerCapabilities {
+                gpu: b"H100".to_vec(),
+                memory_gib: 80,
+                max_model_params: 7_000_000_000,
+            }
+        );
+        System::assert_last_event(
+            Event::CapabilitiesUpdated {
+                subnet_id: 0,
+                miner: BOB,
+            }
+            .into(),
+        );
+
+        assert_eq!(MinerRegistry::miner_capabilities(0, &ALICE), None);
+        assert_noop!(
+            MinerRegistry::set_capabilities(RuntimeOrigin::signed(ALICE), 0, Vec::new()),
+            Error::<Test>::NotRegistered
+        );
+    });
+}
+
+#[test]
+fn over_long_capabilities_are_rejected() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(SubnetBuilder::new(ALICE)
+            .min_stake_miner(MIN_STAKE_MINER)
+            .create());
+        let too_long = vec![0; MAX_CAPABILITIES as usize + 1];
+        assert_noop!(
+            MinerRegistry::register_miner(
+                RuntimeOrigin::signed(BOB),
+                0,
+                ENDPOINT.to_vec(),
+                MIN_STAKE_MINER,
+                too_long.clone(),
+            ),
+            Error::<Test>::CapabilitiesTooLong
+        );
+
+        assert_ok!(MinerRegistry::register_miner(
+            RuntimeOrigin::signed(BOB),
+            0,
+            ENDPOINT.to_vec(),
+            MIN_STAKE_MINER,
+            vec![0; MAX_CAPABILITIES as usize],
+        ));
+        assert_noop!(
+            MinerRegistry::set_capabilities(RuntimeOrigin::signed(BOB), 0, too_long),
+            Error::<Test>::CapabilitiesTooLong
+        );
+    });
+}
+
+#[test]
+fn registration_requires_an_active_subnet_and_its_minimum_stake() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            MinerRegistry::register_miner(
+                RuntimeOrigin::signed(BOB),
+                0,
+                ENDPOINT.to_vec(),
+                MIN_STAKE_MINER,
+                Vec::new()
+            ),
+            Error::<Test>::SubnetNotFound
+        );
+
+        assert_ok!(SubnetBuilder::new(ALICE)
+            .min_stake_miner(MIN_STAKE_MINER)
+            .create());
+        assert_noop!(
+            MinerRegistry::register_miner(
+                RuntimeOrigin::signed(BOB),
+                0,
+                ENDPOINT.to_vec(),
+                MIN_STAKE_MINER - 1,
+                Vec::new()
+            ),
+            Error::<Test>::StakeTooLow
+        );
+        assert_ok!(MinerRegistry::register_miner(
+            RuntimeOrigin::signed(BOB),
+            0,
+            ENDPOINT.to_vec(),
+            MIN_STAKE_MINER,
+            Vec::new()
+        ));
+        assert_noop!(
+            MinerRegistry::register_miner(
+                RuntimeOrigin::signed(BOB),
+                0,
+                ENDPOINT.to_vec(),
+                MIN_STAKE_MINER,
+                Vec::new()
+            ),
+            Error::<Test>::AlreadyRegistered
+        );
+
+        assert_ok!(SubnetRegistry::pause_subnet(
+            RuntimeOrigin::signed(ALICE),
+            0
+        ));
+        assert_noop!(
+            MinerRegistry::register_miner(
+                RuntimeOrigin::signed(ALICE),
+                0,
+                ENDPOINT.to_vec(),
+                MIN_STAKE_MINER,
+                Vec::new()
+            ),
+            Error::<Test>::SubnetPaused
+        );
+
+        assert_ok!(SubnetRegistry::retire_subnet(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            None
+        ));
+        assert_noop!(
+            MinerRegistry::register_miner(
+                RuntimeOrigin::signed(ALICE),
+                0,
+                ENDPOINT.to_vec(),
+                MIN_STAKE_MINER,
+                Vec::new()
+            ),
+            Error::<Test>::SubnetNotActive
+        );
+    });
+}
+
+#[test]
+fn registration_records_the_miner() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(SubnetBuilder::new(ALICE)
+            .min_stake_miner(MIN_STAKE_MINER)
+            .create());
+        run_to_block(3);
+        assert_ok!(MinerRegistry::register_miner(
+            RuntimeOrigin::signed(BOB),
+            0,
+            ENDPOINT.to_vec(),
+            MIN_STAKE_MINER,
+            Vec::new(),
+        ));
+
+        assert_eq!(
+            MinerRegistry::miners(0, BOB),
+            Some(MinerInfo {
+                account: BOB,
+                subnet_id: 0,
+                endpoint: ENDPOINT.to_vec().try_into().unwrap(),
+                stake: MIN_STAKE_MINER,
+                registered_at: 3,
+                capabilities: Default::default(),
+            })
+        );
+        System::assert_last_event(
+            Event::MinerRegistered {
+                subnet_id: 0,
+                miner: BOB,
+                stake: MIN_STAKE_MINER,
+            }
+            .into(),
+        );
+
+        assert_noop!(
+            MinerRegistry::register_miner(
+                RuntimeOrigin::signed(CHARLIE),
+                0,
+                vec![0; MAX_ENDPOINT_LEN as usize + 1],
+                MIN_STAKE_MINER,
+                Vec::new(),
+            ),
+            Error::<Test>::EndpointTooLong
+        );
+    });
+}
+
+#[test]
+fn deregistration_unreserves_the_stake_after_the_cooldown() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(SubnetBuilder::new(ALICE)
+            .min_stake_miner(MIN_STAKE_MINER)
+            .create());
+        assert_ok!(MinerRegistry::register_miner(
+            RuntimeOrigin::signed(BOB),
+            0,
+            ENDPOINT.to_vec(),
+            MIN_STAKE_MINER,
+            Vec::new(),
+        ));
+
+        assert_noop!(
+            MinerRegistry::deregister_miner(RuntimeOrigin::signed(ALICE), 0),
+            Error::<Test>::NotRegistered
+        );
+        run_to_block(DEREGISTRATION_COOLDOWN);
+        assert_noop!(
+            MinerRegistry::deregister_miner(RuntimeOrigin::signed(BOB), 0),
+            Error::<Test>::DeregistrationCooldownActive
+        );
+
+        run_to_block(1 + DEREGISTRATION_COOLDOWN);
+        assert_ok!(MinerRegistry::deregister_miner(
+            RuntimeOrigin::signed(BOB),
+            0
+        ));
+        assert_eq!(MinerRegistry::miners(0, BOB), None);
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+        System::assert_last_event(
+            Event::MinerDeregistered {
+                subnet_id: 0,
+                miner: BOB,
+                stake: MIN_STAKE_MINER,
+            }
+            .into(),
+        );
+
+        // The miner may register again.
+        assert_ok!(MinerRegistry::register_miner(
+            RuntimeOrigin::signed(BOB),
+            0,
+            ENDPOINT.to_vec(),
+            MIN_STAKE_MINER,
+            Vec::new(),
+        ));
+    });
+}
+
+#[test]
+fn miners_of_a_retired_subnet_may_deregister() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(SubnetBuilder::new(ALICE)
+            .min_stake_miner(MIN_STAKE_MINER)
+            .create());
+        assert_ok!(MinerRegistry::register_miner(
+            RuntimeOrigin::signed(BOB),
+            0,
+            ENDPOINT.to_vec(),
+            MIN_STAKE_MINER,
+            Vec::new(),
+        ));
+        assert_ok!(SubnetRegistry::retire_subnet(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            None
+        ));
+
+        run_to_block(1 + DEREGISTRATION_COOLDOWN);
+        assert_ok!(MinerRegistry::deregister_miner(
+            RuntimeOrigin::signed(BOB),
+            0
+        ));
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+    });
+}
@@ -0,0 +1,367 @@
+//! Mock runtime shared by the pallet tests.
+
+#![allow(dead_code)]
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+
+use frame_support::{
+    derive_impl,
+    dispatch::DispatchResult,
+    parameter_types,
+    traits::{ConstU32, ConstU64, Hooks},
+};
+use neurochain::pallets::subnet_registry::{RequestId, TaskType};
+use neurochain::pallets::{
+    consensus, emissions, miner_registry, subnet_registry, task_queue, validator_registry,
+};
+use sp_runtime::{BuildStorage, Perbill, Percent};
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+pub const ALICE: u64 = 1;
+pub const BOB: u64 = 2;
+/// An account that cannot afford the subnet deposit.
+pub const CHARLIE: u64 = 3;
+
+pub const SUBNET_DEPOSIT: u64 = 100;
+pub const MAX_INPUT_BYTES: u32 = 1024;
+pub const MAX_OPEN_TASKS: u32 = 16;
+pub const MIN_VALIDATOR_STAKE: u64 = 50;
+pub const MAX_VALIDATORS_PER_SUBNET: u32 = 2;
+pub const EPOCH_LENGTH: u64 = 10;
+pub const MAX_MISSED_EPOCHS: u32 = 2;
+pub const MAX_WEIGHTS_PER_VALIDATOR: u32 = 4;
+pub const RETIREMENT_COOLDOWN: u64 = 5;
+pub const RETIRED_SUBNET_RETENTION: u64 = 20;
+pub const REQUEST_ID_WINDOW: u64 = 10;
+pub const BLOCK_EMISSION: u64 = 1_000;
+pub const HISTORY_DEPTH: u32 = 2;
+pub const MAX_CAPABILITIES: u32 = 32;
+pub const MAX_ENDPOINT_LEN: u32 = 64;
+pub const DEREGISTRATION_COOLDOWN: u64 = 5;
+pub const ACCEPTED_TASK_RETENTION: u64 = 5;
+
+frame_support::construct_runtime!(
+    pub enum Test {
+        System: frame_system,
+        Balances: pallet_balances,
+        SubnetRegistry: subnet_registry,
+        TaskQueue: task_queue,
+        MinerRegistry: miner_registry,
+        ValidatorRegistry: validator_registry,
+        Consensus: consensus,
+        Emissions: emissions,
+    }
+);
+
+#[derive_impl(
+    frame_system::config_preludes::TestDefaultConfig as frame_system::DefaultConfig
+)]
+impl frame_system::Config for Test {
+    type Block = Block;
+    type AccountData = pallet_balances::AccountData<u64>;
+}
+
+#[derive_impl(
+    pallet_balances::config_preludes::TestDefaultConfig as pallet_balances::DefaultConfig
+)]
+impl pallet_balances::Config for Test {
+    type AccountStore = System;
+}
+
+parameter_types! {
+    pub static SubnetDeposit: u64 = SUBNET_DEPOSIT;
+    pub static MinEmissionWeight: Percent = Percent::zero();
+    pub static SlashFraction: Perbill = Perbill::from_percent(10);
+    /// Zero, so subnets never decay unless a test turns it on.
+    pub static IdleDecayPeriod: u64 = 0;
+    pub static IdleDecayRate: Percent = Percent::from_percent(50);
+    pub static MinerValidatorSplit: Percent = Percent::from_percent(60);
+    pub static UniqueEvaluationSpecs: bool = false;
+}
+
+impl subnet_registry::Config for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type Currency = Balances;
+    type SubnetDeposit = SubnetDeposit;
+    type MaxSchemaSize = ConstU32<256>;
+    type MaxUriSize = ConstU32<128>;
+    type MaxNameSize = ConstU32<32>;
+    type MaxDescriptionSize = ConstU32<128>;
+    type MaxSubnets = ConstU32<4>;
+    type MinEmissionWeight = MinEmissionWeight;
+    type MaxSchemaVersions = ConstU32<3>;
+    type MaxInputBytes = ConstU32<MAX_INPUT_BYTES>;
+    type MaxOpenTasks = ConstU32<MAX_OPEN_TASKS>;
+    type ForceOrigin = frame_system::EnsureRoot<u64>;
+    type RetirementCooldown = ConstU64<RETIREMENT_COOLDOWN>;
+    type RetiredSubnetRetention = ConstU64<RETIRED_SUBNET_RETENTION>;
+    type MaxPurgesPerBlock = ConstU32<2>;
+    type SubnetMembers = MockSubnetMembers;
+    type RequestIdWindow = ConstU64<REQUEST_ID_WINDOW>;
+    type IdleDecayPeriod = IdleDecayPeriod;
+    type IdleDecayRate = IdleDecayRate;
+    type MaxIdleChecksPerBlock = ConstU32<2>;
+    type UniqueEvaluationSpecs = UniqueEvaluationSpecs;
+}
+
+impl task_queue::Config for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type Miners = MinerRegistry;
+    type AcceptedTaskRetention = ConstU64<ACCEPTED_TASK_RETENTION>;
+}
+
+impl miner_registry::Config for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type MaxCapabilities = ConstU32<MAX_CAPABILITIES>;
+    type MaxEndpointLen = ConstU32<MAX_ENDPOINT_LEN>;
+    type DeregistrationCooldown = ConstU64<DEREGISTRATION_COOLDOWN>;
+}
+
+thread_local! {
+    static SUBNET_MEMBERS: RefCell<BTreeSet<u32>> = RefCell::new(BTreeSet::new());
+    static AGGREGATIONS: RefCell<Vec<Vec<u64>>> = RefCell::new(Vec::new());
+}
+
+/// Subnets the tests have marked as having members.
+pub struct MockSubnetMembers;
+
+impl MockSubnetMembers {
+    /// Mark whether `subnet_id` has members.
+    pub fn set(subnet_id: u32, has_members: bool) {
+        SUBNET_MEMBERS.with(|members| {
+            let mut members = members.borrow_mut();
+            if has_members {
+                members.insert(subnet_id);
+            } else {
+                members.remove(&subnet_id);
+            }
+        });
+    }
+}
+
+impl subnet_registry::SubnetMembers for MockSubnetMembers {
+    fn has_members(subnet_id: u32) -> bool {
+        SUBNET_MEMBERS.with(|members| members.borrow().contains(&subnet_id))
+    }
+}
+
+impl validator_registry::Config for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type MaxValidatorsPerSubnet = ConstU32<MAX_VALIDATORS_PER_SUBNET>;
+    type EpochLength = ConstU64<EPOCH_LENGTH>;
+    type MaxMissedEpochs = ConstU32<MAX_MISSED_EPOCHS>;
+    type SlashFraction = SlashFraction;
+    type WeightSubmissions = Consensus;
+}
+
+/// Custom aggregator that gives every miner 100 per validator that
+/// weighed it, and records the validators of every call.
+#[derive(Default)]
+pub struct MockAggregator;
+
+impl MockAggregator {
+    /// The validators whose submissions were passed to each call so far.
+    pub fn calls() -> Vec<Vec<u64>> {
+        AGGREGATIONS.with(|calls| calls.borrow().clone())
+    }
+}
+
+impl consensus::WeightAggregator<u64, u64> for MockAggregator {
+    fn aggregate(&self, submissions: &[consensus::Submission<u64, u64>]) -> BTreeMap<u64, u16> {
+        let mut validators: Vec<u64> = submissions
+            .iter()
+            .map(|(validator, ..)| *validator)
+            .collect();
+        validators.sort();
+        AGGREGATIONS.with(|calls| calls.borrow_mut().push(validators));
+
+        let mut weights = BTreeMap::new();
+        for (_, _, submitted) in submissions {
+            for (miner, _) in submitted {
+                *weights.entry(*miner).or_default() += 100;
+            }
+        }
+        weights
+    }
+}
+
+impl consensus::Config for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type Balance = u64;
+    type ValidatorStakes = ValidatorRegistry;
+    type Miners = MinerRegistry;
+    type Aggregator = MockAggregator;
+    type MaxWeightsPerValidator = ConstU32<MAX_WEIGHTS_PER_VALIDATOR>;
+    type EpochLength = ConstU64<EPOCH_LENGTH>;
+    type AdminOrigin = frame_system::EnsureRoot<u64>;
+}
+
+impl emissions::Config for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type Currency = Balances;
+    type EmissionWeights = SubnetRegistry;
+    type EmissionInterval = ConstU64<EPOCH_LENGTH>;
+    type BlockEmission = ConstU64<BLOCK_EMISSION>;
+    type HistoryDepth = ConstU32<HISTORY_DEPTH>;
+    type MinerValidatorSplit = MinerValidatorSplit;
+    type Miners = Consensus;
+    type Validators = ValidatorRegistry;
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let mut storage = frame_system::GenesisConfig::<Test>::default()
+        .build_storage()
+        .unwrap();
+    pallet_balances::GenesisConfig::<Test> {
+        balances: vec![(ALICE, 1_000), (BOB, 1_000), (CHARLIE, 10)],
+    }
+    .assimilate_storage(&mut storage)
+    .unwrap();
+
+    let mut ext = sp_io::TestExternalities::new(storage);
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
+
+/// Advance to block `n`, running `on_initialize` for every block.
+pub fn run_to_block(n: u64) {
+    while System::block_number() < n {
+        let next = System::block_number() + 1;
+        System::set_block_number(next);
+        SubnetRegistry::on_initialize(next);
+        TaskQueue::on_initialize(next);
+        ValidatorRegistry::on_initialize(next);
+        Consensus::on_initialize(next);
+        Emissions::on_initialize(next);
+    }
+}
+
+/// Arguments to `SubnetRegistry::create_subnet`, starting from values
+/// every pallet accepts.  Override the ones a test cares about, then
+/// call [`SubnetBuilder::create`].
+pub struct SubnetBuilder {
+    owner: u64,
+    task_type: TaskType,
+    name: Vec<u8>,
+    description: Vec<u8>,
+    input_schema: Vec<u8>,
+    output_schema: Vec<u8>,
+    evaluation_spec: Vec<u8>,
+    emission_weight: Percent,
+    min_stake_miner: u64,
+    min_stake_validator: u64,
+    max_input_bytes: u32,
+    max_open_tasks: u32,
+    experimental: bool,
+    request_id: Option<RequestId>,
+}
+
+impl SubnetBuilder {
+    /// A `CodeGen` subnet owned by `owner` with a 10% emission weight.
+    pub fn new(owner: u64) -> Self {
+        Self {
+            owner,
+            task_type: TaskType::CodeGen,
+            name: b"Test subnet".to_vec(),
+            description: Vec::new(),
+            input_schema: br#"{"type":"object"}"#.to_vec(),
+            output_schema: br#"{"type":"object"}"#.to_vec(),
+            evaluation_spec: b"ipfs://evaluation-spec".to_vec(),
+            emission_weight: Percent::from_percent(10),
+            min_stake_miner: 10,
+            min_stake_validator: 20,
+            max_input_bytes: MAX_INPUT_BYTES,
+            max_open_tasks: MAX_OPEN_TASKS,
+            experimental: false,
+            request_id: None,
+        }
+    }
+
+    pub fn task_type(mut self, task_type: TaskType) -> Self {
+        self.task_type = task_type;
+        self
+    }
+
+    pub fn name(mut self, name: &[u8]) -> Self {
+        self.name = name.to_vec();
+        self
+    }
+
+    pub fn description(mut self, description: &[u8]) -> Self {
+        self.description = description.to_vec();
+        self
+    }
+
+    pub fn input_schema(mut self, schema: &[u8]) -> Self {
+        self.input_schema = schema.to_vec();
+        self
+    }
+
+    pub fn output_schema(mut self, schema: &[u8]) -> Self {
+        self.output_schema = schema.to_vec();
+        self
+    }
+
+    pub fn evaluation_spec(mut self, spec: &[u8]) -> Self {
+        self.evaluation_spec = spec.to_vec();
+        self
+    }
+
+    /// Emission weight, in percent.
+    pub fn emission_weight(mut self, percent: u8) -> Self {
+        self.emission_weight = Percent::from_percent(percent);
+        self
+    }
+
+    pub fn min_stake_miner(mut self, stake: u64) -> Self {
+        self.min_stake_miner = stake;
+        self
+    }
+
+    pub fn min_stake_validator(mut self, stake: u64) -> Self {
+        self.min_stake_validator = stake;
+        self
+    }
+
+    pub fn max_input_bytes(mut self, bytes: u32) -> Self {
+        self.max_input_bytes = bytes;
+        self
+    }
+
+    pub fn max_open_tasks(mut self, tasks: u32) -> Self {
+        self.max_open_tasks = tasks;
+        self
+    }
+
+    pub fn experimental(mut self, experimental: bool) -> Self {
+        self.experimental = experimental;
+        self
+    }
+
+    pub fn request_id(mut self, request_id: RequestId) -> Self {
+        self.request_id = Some(request_id);
+        self
+    }
+
+    /// Dispatch `create_subnet` signed by the owner.
+    pub fn create(self) -> DispatchResult {
+        SubnetRegistry::create_subnet(
+            RuntimeOrigin::signed(self.owner),
+            self.task_type,
+            self.name,
+            self.description,
+            self.input_schema,
+            self.output_schema,
+            self.evaluation_spec,
+            self.emission_weight,
+            self.min_stake_miner,
+            self.min_stake_validator,
+            self.max_input_bytes,
+            self.max_open_tasks,
+            self.experimental,
+            self.request_id,
+        )
+    }
+}
@@ -0,0 +1,207 @@
+use frame_support::{
+    assert_ok,
+    traits::{Get, Hooks, ReservableCurrency},
+};
+use neurochain::pallets::subnet_registry::{self, Event, SubnetStatus, TaskType};
+use neurochain::pallets::{miner_registry, task_queue, validator_registry};
+use neurochain::runtime::{
+    AccountId, Balances, BalancesConfig, Emissions, Runtime, RuntimeCall, RuntimeEvent,
+    RuntimeGenesisConfig, RuntimeOrigin, SubnetRegistry, System, TaskQueue, DAYS, HOURS, UNIT,
+};
+use neurochain::runtime_api::{
+    runtime_decl_for_emissions_api::EmissionsApiV1,
+    runtime_decl_for_miner_registry_api::MinerRegistryApiV1,
+    runtime_decl_for_subnet_registry_api::SubnetRegistryApiV4, SubnetParticipation,
+};
+use sp_runtime::{traits::Dispatchable, BuildStorage, Percent};
+
+fn alice() -> AccountId {
+    AccountId::new([1; 32])
+}
+
+fn bob() -> AccountId {
+    AccountId::new([2; 32])
+}
+
+fn create_subnet_call() -> RuntimeCall {
+    RuntimeCall::SubnetRegistry(subnet_registry::Call::create_subnet {
+        task_type: TaskType::CodeGen,
+        name: b"Code generation".to_vec(),
+        description: b"Generates code from a prompt".to_vec(),
+        input_schema: br#"{"type":"object"}"#.to_vec(),
+        output_schema: br#"{"type":"object"}"#.to_vec(),
+        evaluation_spec: b"ipfs://evaluation-spec".to_vec(),
+        emission_weight: Percent::from_percent(10),
+        min_stake_miner: UNIT,
+        min_stake_validator: 10 * UNIT,
+        max_input_bytes: 1024,
+        max_open_tasks: 16,
+        experimental: false,
+        request_id: None,
+    })
+}
+
+fn new_test_ext() -> sp_io::TestExternalities {
+    let storage = RuntimeGenesisConfig {
+        balances: BalancesConfig {
+            balances: vec![(alice(), 1_000 * UNIT), (bob(), 1_000 * UNIT)],
+        },
+        ..Default::default()
+    }
+    .build_storage()
+    .unwrap();
+
+    let mut ext = sp_io::TestExternalities::new(storage);
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
+
+#[test]
+fn create_subnet_dispatches_through_the_runtime() {
+    new_test_ext().execute_with(|| {
+        let call = create_subnet_call();
+        assert_ok!(call.dispatch(RuntimeOrigin::signed(alice())));
+
+        let subnet = SubnetRegistry::subnets(0).expect("subnet was created");
+        assert_eq!(subnet.owner, alice());
+        assert_eq!(subnet.status, SubnetStatus::Active);
+        assert_eq!(
+            Balances::reserved_balance(&alice()),
+            <Runtime as subnet_registry::Config>::SubnetDeposit::get()
+        );
+        System::assert_last_event(RuntimeEvent::SubnetRegistry(Event::SubnetCreated {
+            subnet_id: 0,
+            owner: alice(),
+            name: b"Code generation".to_vec().try_into().unwrap(),
+        }));
+    });
+}
+
+#[test]
+fn subnet_registry_api_reads_subnets() {
+    new_test_ext().execute_with(|| {
+        for _ in 0..2 {
+            assert_ok!(create_subnet_call().dispatch(RuntimeOrigin::signed(alice())));
+        }
+        assert_ok!(
+            RuntimeCall::SubnetRegistry(subnet_registry::Call::retire_subnet {
+                subnet_id: 0,
+                reason: None,
+            })
+            .dispatch(RuntimeOrigin::signed(alice()))
+        );
+
+        assert_eq!(Runtime::subnets_of(alice()), vec![0, 1]);
+        assert_eq!(
+            Runtime::subnets_of(AccountId::new([2; 32])),
+            Vec::<u32>::new()
+        );
+        assert_eq!(Runtime::active_subnets(), vec![1]);
+        assert_eq!(Runtime::subnet_info(1), SubnetRegistry::subnets(1));
+        assert_eq!(
+            Runtime::subnet_info(0).map(|info| info.status),
+            Some(SubnetStatus::Retired)
+        );
+        assert_eq!(Runtime::subnet_info(2), None);
+    });
+}
+
+#[test]
+fn subnet_registry_api_counts_participants() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(Runtime::subnet_participants(0), None);
+        assert_ok!(create_subnet_call().dispatch(RuntimeOrigin::signed(alice())));
+        assert_eq!(
+            Runtime::subnet_participants(0),
+            Some(SubnetParticipation::default())
+        );
+
+        assert_ok!(
+            RuntimeCall::MinerRegistry(miner_registry::Call::register_miner {
+                subnet_id: 0,
+                endpoint: b"/ip4/127.0.0.1/tcp/4001".to_vec(),
+                stake: 2 * UNIT,
+                capabilities: vec![],
+            })
+            .dispatch(RuntimeOrigin::signed(bob()))
+        );
+        assert_ok!(
+            RuntimeCall::ValidatorRegistry(validator_registry::Call::register_validator {
+                subnet_id: 0,
+                stake: 10 * UNIT,
+            })
+            .dispatch(RuntimeOrigin::signed(alice()))
+        );
+
+        assert_eq!(
+            Runtime::subnet_participants(0),
+            Some(SubnetParticipation {
+                miners: 1,
+                validators: 1,
+                total_stake: 12 * UNIT,
+            })
+        );
+    });
+}
+
+#[test]
+fn miner_registry_api_reads_capabilities() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_subnet_call().dispatch(RuntimeOrigin::signed(alice())));
+        assert_eq!(Runtime::miner_capabilities(0, bob()), None);
+
+        assert_ok!(
+            RuntimeCall::MinerRegistry(miner_registry::Call::register_miner {
+                subnet_id: 0,
+                endpoint: b"/ip4/127.0.0.1/tcp/4001".to_vec(),
+                stake: UNIT,
+                capabilities: b"gpu".to_vec(),
+            })
+            .dispatch(RuntimeOrigin::signed(bob()))
+        );
+        assert_eq!(Runtime::miner_capabilities(0, bob()), Some(b"gpu".to_vec()));
+        assert_eq!(Runtime::miner_capabilities(1, bob()), None);
+    });
+}
+
+#[test]
+fn emissions_api_reads_emission_history() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(Runtime::emission_history(), vec![]);
+
+        System::set_block_number(HOURS);
+        Emissions::on_initialize(HOURS);
+        assert_eq!(Runtime::emission_history(), vec![(1, 0)]);
+    });
+}
+
+#[test]
+fn task_queue_dispatches_through_the_runtime() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_subnet_call().dispatch(RuntimeOrigin::signed(alice())));
+        assert_ok!(RuntimeCall::TaskQueue(task_queue::Call::submit_task {
+            subnet_id: 0,
+            input: b"fn main() {}".to_vec(),
+        })
+        .dispatch(RuntimeOrigin::signed(alice())));
+        assert_ok!(
+            RuntimeCall::MinerRegistry(miner_registry::Call::register_miner {
+                subnet_id: 0,
+                endpoint: b"/ip4/127.0.0.1/tcp/4001".to_vec(),
+                stake: UNIT,
+                capabilities: vec![],
+            })
+            .dispatch(RuntimeOrigin::signed(bob()))
+        );
+        assert_ok!(
+            RuntimeCall::TaskQueue(task_queue::Call::accept_task { task_id: 0 })
+                .dispatch(RuntimeOrigin::signed(bob()))
+        );
+        assert_eq!(TaskQueue::open_tasks(0), 0);
+
+        let expires_at = 1 + DAYS;
+        System::set_block_number(expires_at);
+        TaskQueue::on_initialize(expires_at);
+        assert!(TaskQueue::tasks(0).is_none());
+    });
+}
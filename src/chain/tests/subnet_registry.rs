@@ -0,0 +1,1799 @@
+mod mock;
+
+use frame_support::{assert_noop, assert_ok, dispatch::DispatchResult};
+use mock::*;
+use neurochain::pallets::subnet_registry::{
+    Error, Event, SubnetField, SubnetStatus, TaskType, MAX_SUBNETS_PER_PAGE,
+};
+use sp_runtime::Percent;
+
+fn update_weight(owner: u64, subnet_id: u32, weight: u8) -> DispatchResult {
+    SubnetRegistry::update_subnet(
+        RuntimeOrigin::signed(owner),
+        subnet_id,
+        None,
+        None,
+        None,
+        Some(Percent::from_percent(weight)),
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+    )
+}
+
+#[test]
+fn create_subnet_stores_info_and_reserves_deposit() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(SubnetBuilder::new(ALICE).create());
+
+        let info = SubnetRegistry::subnets(0).unwrap();
+        assert_eq!(info.owner, ALICE);
+        assert_eq!(info.emission_weight, Percent::from_percent(10));
+        assert_eq!(info.status, SubnetStatus::Active);
+        assert_eq!(SubnetRegistry::next_subnet_id(), 1);
+        assert_eq!(SubnetRegistry::subnet_count(), 1);
+        assert_eq!(SubnetRegistry::owner_subnets(ALICE).into_inner(), vec![0]);
+        assert_eq!(Balances::reserved_balance(ALICE), SUBNET_DEPOSIT);
+        System::assert_last_event(
+            Event::SubnetCreated {
+                subnet_id: 0,
+                owner: ALICE,
+                name: b"Test subnet".to_vec().try_into().unwrap(),
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn create_subnet_requires_deposit() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            SubnetBuilder::new(CHARLIE).create(),
+            Error::<Test>::InsufficientBalance
+        );
+    });
+}
+
+#[test]
+fn only_owner_can_update_or_retire() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(SubnetBuilder::new(ALICE).create());
+        assert_noop!(update_weight(BOB, 0, 20), Error::<Test>::NotOwner);
+        assert_noop!(
+            SubnetRegistry::retire_subnet(RuntimeOrigin::signed(BOB), 0, None),
+            Error::<Test>::NotOwner
+        );
+
+        assert_ok!(update_weight(ALICE, 0, 20));
+        assert_eq!(
+            SubnetRegistry::subnets(0).unwrap().emission_weight,
+            Percent::from_percent(20)
+        );
+
+        assert_ok!(SubnetRegistry::retire_subnet(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            None
+        ));
+        assert!(!SubnetRegistry::is_subnet_active(0));
+        assert_eq!(SubnetRegistry::subnet_count(), 0);
+        assert_noop!(update_weight(ALICE, 0, 30), Error::<Test>::SubnetNotActive);
+    });
+}
+
+#[test]
+fn retirement_records_block_and_reason() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(SubnetBuilder::new(ALICE).create());
+        System::set_block_number(7);
+        assert_noop!(
+            SubnetRegistry::retire_subnet(RuntimeOrigin::signed(ALICE), 0, Some(vec![b'x'; 129])),
+            Error::<Test>::ReasonTooLong
+        );
+
+        assert_ok!(SubnetRegistry::retire_subnet(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            Some(b"superseded by subnet 1".to_vec())
+        ));
+        let info = SubnetRegistry::subnets(0).unwrap();
+        assert_eq!(info.retired_at, Some(7));
+        assert_eq!(
+            info.retirement_reason.unwrap().into_inner(),
+            b"superseded by subnet 1".to_vec()
+        );
+        assert_eq!(
+            SubnetRegistry::retirement(0).map(|(block, _)| block),
+            Some(7)
+        );
+    });
+}
+
+#[test]
+fn force_retirement_records_who_forced_it() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(SubnetBuilder::new(ALICE).create());
+        assert_ok!(SubnetBuilder::new(ALICE).create());
+        assert_eq!(SubnetRegistry::retirement(0), None);
+        System::set_block_number(3);
+        assert_noop!(
+            SubnetRegistry::force_retire_subnet(RuntimeOrigin::signed(BOB), 0, None),
+            sp_runtime::DispatchError::BadOrigin
+        );
+
+        assert_ok!(SubnetRegistry::force_retire_subnet(
+            RuntimeOrigin::root(),
+            0,
+            Some(b"spam".to_vec())
+        ));
+        let info = SubnetRegistry::subnets(0).unwrap();
+        assert_eq!(info.status, SubnetStatus::Retired);
+        assert_eq!(info.retired_at, Some(3));
+        assert_eq!(
+            info.retirement_reason.unwrap().into_inner(),
+            b"spam".to_vec()
+        );
+        assert_eq!(SubnetRegistry::subnet_count(), 1);
+        System::assert_last_event(
+            Event::SubnetForceRetired {
+                subnet_id: 0,
+                owner: ALICE,
+                forced_by: None,
+            }
+            .into(),
+        );
+
+        assert_ok!(SubnetRegistry::force_retire_subnet(
+            RuntimeOrigin::root(),
+            1,
+            None
+        ));
+        assert_eq!(SubnetRegistry::retirement(1), Some((3, None)));
+        assert_noop!(
+            SubnetRegistry::force_retire_subnet(RuntimeOrigin::root(), 1, None),
+            Error::<Test>::SubnetNotActive
+        );
+    });
+}
+
+#[test]
+fn emission_weight_below_minimum_is_rejected() {
+    new_test_ext().execute_with(|| {
+        MinEmissionWeight::set(Percent::from_percent(5));
+
+        assert_noop!(
+            SubnetBuilder::new(ALICE).emission_weight(4).create(),
+            Error::<Test>::EmissionWeightTooLow
+        );
+        assert_ok!(SubnetBuilder::new(ALICE).emission_weight(5).create());
+        assert_noop!(
+            update_weight(ALICE, 0, 0),
+            Error::<Test>::EmissionWeightTooLow
+        );
+    });
+}
+
+#[test]
+fn experimental_subnet_is_exempt_from_minimum() {
+    new_test_ext().execute_with(|| {
+        MinEmissionWeight::set(Percent::from_percent(5));
+
+        assert_ok!(SubnetBuilder::new(ALICE)
+            .emission_weight(0)
+            .experimental(true)
+            .create());
+        assert_ok!(update_weight(ALICE, 0, 1));
+        assert_eq!(
+            SubnetRegistry::subnets(0).unwrap().emission_weight,
+            Percent::from_percent(1)
+        );
+    });
+}
+
+#[test]
+fn zero_minimum_accepts_any_weight() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(SubnetBuilder::new(ALICE).emission_weight(0).create());
+    });
+}
+
+#[test]
+fn append_output_schema_extends_root_object() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(SubnetBuilder::new(ALICE).create());
+        assert_ok!(SubnetRegistry::append_output_schema(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            br#""required":["answer"]"#.to_vec(),
+        ));
+
+        assert_eq!(
+            SubnetRegistry::subnets(0)
+                .unwrap()
+                .output_schema
+                .into_inner(),
+            br#"{"type":"object","required":["answer"]}"#.to_vec()
+        );
+        assert_eq!(
+            SubnetRegistry::subnets(0).unwrap().current_schema_version,
+            1
+        );
+        System::assert_last_event(
+            Event::SubnetUpdated {
+                subnet_id: 0,
+                owner: ALICE,
+                changed: vec![SubnetField::OutputSchema].try_into().unwrap(),
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn append_output_schema_rejects_oversized_result() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(SubnetBuilder::new(ALICE).create());
+        let fragment = format!(r#""description":"{}""#, "x".repeat(256));
+
+        assert_noop!(
+            SubnetRegistry::append_output_schema(
+                RuntimeOrigin::signed(ALICE),
+                0,
+                fragment.into_bytes(),
+            ),
+            Error::<Test>::SchemaTooLarge
+        );
+    });
+}
+
+#[test]
+fn append_output_schema_rejects_malformed_result() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(SubnetBuilder::new(ALICE).create());
+
+        assert_noop!(
+            SubnetRegistry::append_output_schema(
+                RuntimeOrigin::signed(ALICE),
+                0,
+                br#""required":"#.to_vec(),
+            ),
+            Error::<Test>::InvalidSchema
+        );
+        assert_noop!(
+            SubnetRegistry::append_output_schema(
+                RuntimeOrigin::signed(BOB),
+                0,
+                br#""required":[]"#.to_vec(),
+            ),
+            Error::<Test>::NotOwner
+        );
+    });
+}
+
+#[cfg(feature = "schema-validation")]
+#[test]
+fn schemas_must_be_json_objects() {
+    new_test_ext().execute_with(|| {
+        let create = |input_schema: &[u8]| {
+            SubnetBuilder::new(ALICE)
+                .input_schema(input_schema)
+                .create()
+        };
+        for invalid in [
+            &b"{\"type\":\xff}"[..],
+            br#"{"type":"#,
+            br#"["object"]"#,
+            b"42",
+        ] {
+            assert_noop!(create(invalid), Error::<Test>::InvalidSchema);
+        }
+
+        assert_ok!(create(br#" {"type":"object"} "#));
+        assert_noop!(
+            SubnetRegistry::update_subnet(
+                RuntimeOrigin::signed(ALICE),
+                0,
+                None,
+                Some(br#""object""#.to_vec()),
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+            ),
+            Error::<Test>::InvalidSchema
+        );
+        assert_noop!(
+            SubnetRegistry::add_schema_version(
+                RuntimeOrigin::signed(ALICE),
+                0,
+                b"{}".to_vec(),
+                b"{".to_vec(),
+            ),
+            Error::<Test>::InvalidSchema
+        );
+    });
+}
+
+#[test]
+fn subnet_updated_lists_the_fields_set() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(SubnetBuilder::new(ALICE).create());
+        assert_ok!(SubnetRegistry::update_subnet(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            Some(br#"{"type":"string"}"#.to_vec()),
+            None,
+            None,
+            Some(Percent::from_percent(20)),
+            None,
+            Some(30),
+            None,
+            Some(1),
+            None,
+            None,
+        ));
+        System::assert_last_event(
+            Event::SubnetUpdated {
+                subnet_id: 0,
+                owner: ALICE,
+                changed: vec![
+                    SubnetField::InputSchema,
+                    SubnetField::EmissionWeight,
+                    SubnetField::MinStakeValidator,
+                    SubnetField::MaxOpenTasks,
+                ]
+                .try_into()
+                .unwrap(),
+            }
+            .into(),
+        );
+
+        assert_ok!(update_weight(ALICE, 0, 10));
+        System::assert_last_event(
+            Event::SubnetUpdated {
+                subnet_id: 0,
+                owner: ALICE,
+                changed: vec![SubnetField::EmissionWeight].try_into().unwrap(),
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn name_and_description_are_bounded() {
+    new_test_ext().execute_with(|| {
+        let create = |name: Vec<u8>, description: Vec<u8>| {
+            SubnetBuilder::new(ALICE)
+                .name(&name)
+                .description(&description)
+                .create()
+        };
+        assert_noop!(
+            create(vec![b'n'; 33], Vec::new()),
+            Error::<Test>::NameTooLarge
+        );
+        assert_noop!(
+            create(Vec::new(), vec![b'd'; 129]),
+            Error::<Test>::DescriptionTooLarge
+        );
+        assert_ok!(create(vec![b'n'; 32], vec![b'd'; 128]));
+        let info = SubnetRegistry::subnets(0).unwrap();
+        assert_eq!(info.name.into_inner(), vec![b'n'; 32]);
+        assert_eq!(info.description.into_inner(), vec![b'd'; 128]);
+
+        let update = |name: Option<Vec<u8>>, description: Option<Vec<u8>>| {
+            SubnetRegistry::update_subnet(
+                RuntimeOrigin::signed(ALICE),
+                0,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                name,
+                description,
+            )
+        };
+        assert_noop!(
+            update(Some(vec![b'n'; 33]), None),
+            Error::<Test>::NameTooLarge
+        );
+        assert_noop!(
+            update(None, Some(vec![b'd'; 129])),
+            Error::<Test>::DescriptionTooLarge
+        );
+        assert_ok!(update(Some(b"Renamed".to_vec()), None));
+        let info = SubnetRegistry::subnets(0).unwrap();
+        assert_eq!(info.name.into_inner(), b"Renamed".to_vec());
+        assert_eq!(info.description.into_inner(), vec![b'd'; 128]);
+        System::assert_last_event(
+            Event::SubnetUpdated {
+                subnet_id: 0,
+                owner: ALICE,
+                changed: vec![SubnetField::Name].try_into().unwrap(),
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn duplicate_evaluation_specs_are_allowed_by_default() {
+    use neurochain::pallets::subnet_registry::UniqueEvalSpec;
+
+    new_test_ext().execute_with(|| {
+        assert_ok!(SubnetBuilder::new(ALICE).create());
+        assert_ok!(SubnetBuilder::new(ALICE).create());
+        let key = (ALICE, SubnetRegistry::subnets(0).unwrap().evaluation_spec);
+        assert_eq!(UniqueEvalSpec::<Test>::get(&key), 2);
+
+        // Retiring one duplicate leaves the spec taken by the other.
+        assert_ok!(SubnetRegistry::retire_subnet(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            None
+        ));
+        assert_eq!(UniqueEvalSpec::<Test>::get(&key), 1);
+        UniqueEvaluationSpecs::set(true);
+        assert_noop!(
+            SubnetBuilder::new(ALICE).create(),
+            Error::<Test>::DuplicateEvaluationSpec
+        );
+
+        assert_ok!(SubnetRegistry::retire_subnet(
+            RuntimeOrigin::signed(ALICE),
+            1,
+            None
+        ));
+        assert!(!UniqueEvalSpec::<Test>::contains_key(&key));
+        assert_ok!(SubnetBuilder::new(ALICE).create());
+    });
+}
+
+#[test]
+fn owner_cannot_duplicate_an_evaluation_spec_when_unique() {
+    let set_spec = |subnet_id: u32, spec: &[u8]| {
+        SubnetRegistry::update_subnet(
+            RuntimeOrigin::signed(ALICE),
+            subnet_id,
+            None,
+            None,
+            Some(spec.to_vec()),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+    };
+
+    new_test_ext().execute_with(|| {
+        UniqueEvaluationSpecs::set(true);
+        assert_ok!(SubnetBuilder::new(ALICE).create());
+        assert_noop!(
+            SubnetBuilder::new(ALICE).create(),
+            Error::<Test>::DuplicateEvaluationSpec
+        );
+        // Another owner may use the same spec.
+        assert_ok!(SubnetBuilder::new(BOB).create());
+
+        // Moving subnet 0 to another spec frees the first one.
+        assert_ok!(set_spec(0, b"ipfs://other-spec"));
+        assert_ok!(set_spec(0, b"ipfs://other-spec"));
+        assert_ok!(SubnetBuilder::new(ALICE).create());
+        assert_noop!(
+            set_spec(2, b"ipfs://other-spec"),
+            Error::<Test>::DuplicateEvaluationSpec
+        );
+
+        // A retired subnet holds no spec until it is reactivated.
+        assert_ok!(SubnetRegistry::retire_subnet(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            None
+        ));
+        assert_ok!(set_spec(2, b"ipfs://other-spec"));
+        assert_noop!(
+            SubnetRegistry::reactivate_subnet(RuntimeOrigin::signed(ALICE), 0),
+            Error::<Test>::DuplicateEvaluationSpec
+        );
+
+        // Nor may a subnet be handed to an owner who has its spec.
+        assert_ok!(SubnetBuilder::new(ALICE).create());
+        assert_noop!(
+            SubnetRegistry::transfer_ownership(RuntimeOrigin::signed(BOB), 1, ALICE),
+            Error::<Test>::DuplicateEvaluationSpec
+        );
+    });
+}
+
+#[test]
+fn schema_versions_are_kept_and_current_pointer_advances() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(SubnetBuilder::new(ALICE).create());
+        assert_eq!(
+            SubnetRegistry::subnets(0).unwrap().current_schema_version,
+            0
+        );
+
+        assert_ok!(SubnetRegistry::add_schema_version(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            br#"{"type":"string"}"#.to_vec(),
+            br#"{"type":"array"}"#.to_vec(),
+        ));
+        System::assert_last_event(
+            Event::SchemaVersionAdded {
+                subnet_id: 0,
+                version: 1,
+            }
+            .into(),
+        );
+        assert_ok!(SubnetRegistry::add_schema_version(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            br#"{"type":"number"}"#.to_vec(),
+            br#"{"type":"boolean"}"#.to_vec(),
+        ));
+
+        let info = SubnetRegistry::subnets(0).unwrap();
+        assert_eq!(info.current_schema_version, 2);
+        assert_eq!(
+            info.input_schema.into_inner(),
+            br#"{"type":"number"}"#.to_vec()
+        );
+
+        let first = SubnetRegistry::schema_version(0, 1).unwrap();
+        assert_eq!(
+            first.input_schema.into_inner(),
+            br#"{"type":"string"}"#.to_vec()
+        );
+        assert_eq!(
+            first.output_schema.into_inner(),
+            br#"{"type":"array"}"#.to_vec()
+        );
+        let original = SubnetRegistry::schema_version(0, 0).unwrap();
+        assert_eq!(
+            original.output_schema.into_inner(),
+            br#"{"type":"object"}"#.to_vec()
+        );
+        assert_eq!(SubnetRegistry::subnet_schemas(0).len(), 3);
+    });
+}
+
+#[test]
+fn schema_versions_are_bounded() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(SubnetBuilder::new(ALICE).create());
+        for _ in 0..2 {
+            assert_ok!(SubnetRegistry::add_schema_version(
+                RuntimeOrigin::signed(ALICE),
+                0,
+                b"{}".to_vec(),
+                b"{}".to_vec(),
+            ));
+        }
+
+        assert_noop!(
+            SubnetRegistry::add_schema_version(
+                RuntimeOrigin::signed(ALICE),
+                0,
+                b"{}".to_vec(),
+                b"{}".to_vec(),
+            ),
+            Error::<Test>::TooManySchemaVersions
+        );
+    });
+}
+
+#[test]
+fn input_limit_is_capped_by_ceiling() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            SubnetBuilder::new(ALICE)
+                .max_input_bytes(MAX_INPUT_BYTES + 1)
+                .create(),
+            Error::<Test>::InputLimitTooLarge
+        );
+
+        assert_ok!(SubnetBuilder::new(ALICE).create());
+        assert_noop!(
+            SubnetRegistry::update_subnet(
+                RuntimeOrigin::signed(ALICE),
+                0,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                Some(MAX_INPUT_BYTES + 1),
+                None,
+                None,
+                None,
+            ),
+            Error::<Test>::InputLimitTooLarge
+        );
+        assert_ok!(SubnetRegistry::update_subnet(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(16),
+            None,
+            None,
+            None,
+        ));
+        assert_eq!(SubnetRegistry::subnets(0).unwrap().max_input_bytes, 16);
+    });
+}
+
+#[test]
+fn emission_weights_add_up_to_at_most_100_percent() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(SubnetBuilder::new(ALICE).emission_weight(50).create());
+        assert_ok!(SubnetBuilder::new(BOB).emission_weight(40).create());
+        assert_eq!(
+            SubnetRegistry::total_emission_weight(),
+            Percent::from_percent(90)
+        );
+
+        assert_noop!(
+            SubnetBuilder::new(ALICE).emission_weight(20).create(),
+            Error::<Test>::EmissionWeightExceedsTotal
+        );
+        assert_ok!(SubnetBuilder::new(ALICE).create());
+        assert_eq!(
+            SubnetRegistry::total_emission_weight(),
+            Percent::from_percent(100)
+        );
+
+        // Lowering a weight makes room for raising another.
+        assert_noop!(
+            update_weight(BOB, 1, 41),
+            Error::<Test>::EmissionWeightExceedsTotal
+        );
+        assert_ok!(update_weight(ALICE, 0, 30));
+        assert_ok!(update_weight(BOB, 1, 60));
+
+        // Retiring a subnet gives its weight back.
+        assert_ok!(SubnetRegistry::retire_subnet(
+            RuntimeOrigin::signed(BOB),
+            1,
+            None
+        ));
+        assert_eq!(
+            SubnetRegistry::total_emission_weight(),
+            Percent::from_percent(40)
+        );
+    });
+}
+
+#[test]
+fn no_operation_takes_the_total_emission_weight_past_100_percent() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(SubnetBuilder::new(ALICE).emission_weight(60).create());
+        assert_noop!(
+            SubnetBuilder::new(BOB).emission_weight(50).create(),
+            Error::<Test>::EmissionWeightExceedsTotal
+        );
+        assert_ok!(SubnetBuilder::new(BOB).emission_weight(40).create());
+        assert_noop!(
+            update_weight(BOB, 1, 50),
+            Error::<Test>::EmissionWeightExceedsTotal
+        );
+        assert_eq!(
+            SubnetRegistry::total_emission_weight(),
+            Percent::from_percent(100)
+        );
+
+        assert_ok!(SubnetRegistry::retire_subnet(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            None
+        ));
+        assert_eq!(
+            SubnetRegistry::total_emission_weight(),
+            Percent::from_percent(40)
+        );
+        assert_ok!(update_weight(BOB, 1, 50));
+
+        // The retired subnet's 60% no longer fits.
+        assert_noop!(
+            SubnetRegistry::reactivate_subnet(RuntimeOrigin::signed(ALICE), 0),
+            Error::<Test>::EmissionWeightExceedsTotal
+        );
+        assert_ok!(update_weight(BOB, 1, 40));
+        assert_ok!(SubnetRegistry::reactivate_subnet(
+            RuntimeOrigin::signed(ALICE),
+            0
+        ));
+        assert_eq!(
+            SubnetRegistry::total_emission_weight(),
+            Percent::from_percent(100)
+        );
+    });
+}
+
+#[test]
+fn migrations_default_new_fields() {
+    use codec::Encode;
+    use frame_support::traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion};
+    use neurochain::pallets::subnet_registry::{
+        migrations, Subnets, SubnetsByTaskType, TotalEmissionWeight, UniqueEvalSpec,
+    };
+
+    new_test_ext().execute_with(|| {
+        assert_ok!(SubnetBuilder::new(ALICE).create());
+        let info = SubnetRegistry::subnets(0).unwrap();
+
+        // Rewrite the entry in the version 0 layout.
+        let old = (
+            info.owner,
+            info.task_type.clone(),
+            info.input_schema.clone(),
+            info.output_schema.clone(),
+            info.current_schema_version,
+            info.evaluation_spec.clone(),
+            info.emission_weight,
+            info.min_stake_miner,
+            info.min_stake_validator,
+            info.experimental,
+            info.status,
+        );
+        frame_support::storage::unhashed::put_raw(
+            &Subnets::<Test>::hashed_key_for(0),
+            &old.encode(),
+        );
+        SubnetsByTaskType::<Test>::remove(TaskType::CodeGen, 0);
+        UniqueEvalSpec::<Test>::remove((ALICE, info.evaluation_spec.clone()));
+        TotalEmissionWeight::<Test>::kill();
+        StorageVersion::new(0).put::<SubnetRegistry>();
+        assert!(SubnetRegistry::subnets(0).is_none());
+
+        migrations::v1::MigrateToV1::<Test>::on_runtime_upgrade();
+        migrations::v2::MigrateToV2::<Test>::on_runtime_upgrade();
+        migrations::v3::MigrateToV3::<Test>::on_runtime_upgrade();
+        migrations::v4::MigrateToV4::<Test>::on_runtime_upgrade();
+        migrations::v5::MigrateToV5::<Test>::on_runtime_upgrade();
+        migrations::v6::MigrateToV6::<Test>::on_runtime_upgrade();
+        migrations::v7::MigrateToV7::<Test>::on_runtime_upgrade();
+        migrations::v8::MigrateToV8::<Test>::on_runtime_upgrade();
+
+        assert_eq!(SubnetRegistry::on_chain_storage_version(), 8);
+        assert_eq!(
+            SubnetRegistry::total_emission_weight(),
+            Percent::from_percent(10)
+        );
+        assert_eq!(
+            SubnetRegistry::subnets_by_task_type(TaskType::CodeGen),
+            vec![0]
+        );
+        let migrated = SubnetRegistry::subnets(0).unwrap();
+        assert_eq!(migrated.max_input_bytes, MAX_INPUT_BYTES);
+        assert_eq!(migrated.max_open_tasks, MAX_OPEN_TASKS);
+        assert_eq!(migrated.owner, ALICE);
+        assert_eq!(migrated.output_schema, info.output_schema);
+        assert_eq!(migrated.status, SubnetStatus::Active);
+        assert_eq!(migrated.retired_at, None);
+        assert_eq!(migrated.retirement_reason, None);
+        assert_eq!(migrated.deposit, SUBNET_DEPOSIT);
+        assert!(migrated.name.is_empty());
+        assert!(migrated.description.is_empty());
+        assert_eq!(
+            UniqueEvalSpec::<Test>::get((ALICE, migrated.evaluation_spec)),
+            1
+        );
+    });
+}
+
+#[test]
+fn migrate_runs_only_the_steps_the_chain_is_behind_on() {
+    use codec::Encode;
+    use frame_support::traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion};
+    use neurochain::pallets::subnet_registry::{migrations::Migrate, Subnets, TotalEmissionWeight};
+
+    new_test_ext().execute_with(|| {
+        assert_ok!(SubnetBuilder::new(ALICE).create());
+        assert_ok!(SubnetBuilder::new(BOB).emission_weight(20).create());
+        assert_ok!(SubnetRegistry::retire_subnet(
+            RuntimeOrigin::signed(BOB),
+            1,
+            None
+        ));
+        // Paused subnets keep their emission weight.
+        assert_ok!(SubnetBuilder::new(ALICE).emission_weight(30).create());
+        assert_ok!(SubnetRegistry::pause_subnet(
+            RuntimeOrigin::signed(ALICE),
+            2
+        ));
+        // The deposit of a subnet waiting to be purged is already
+        // released.
+        MockSubnetMembers::set(1, true);
+        System::set_block_number(1 + RETIREMENT_COOLDOWN);
+        assert_ok!(SubnetRegistry::reclaim_deposit(
+            RuntimeOrigin::signed(BOB),
+            1
+        ));
+
+        // Storage as it was at version 4: no total, and no deposit,
+        // name or description at the end of each subnet.
+        for subnet_id in 0..3 {
+            let info = SubnetRegistry::subnets(subnet_id).unwrap();
+            let added = (info.deposit, info.name, info.description).encoded_size();
+            let key = Subnets::<Test>::hashed_key_for(subnet_id);
+            let raw = frame_support::storage::unhashed::get_raw(&key).unwrap();
+            let layout = &raw[..raw.len() - added];
+            frame_support::storage::unhashed::put_raw(&key, layout);
+        }
+        TotalEmissionWeight::<Test>::kill();
+        StorageVersion::new(4).put::<SubnetRegistry>();
+
+        #[cfg(feature = "try-runtime")]
+        let state = Migrate::<Test>::pre_upgrade().unwrap();
+        Migrate::<Test>::on_runtime_upgrade();
+        #[cfg(feature = "try-runtime")]
+        Migrate::<Test>::post_upgrade(state).unwrap();
+
+        assert_eq!(SubnetRegistry::on_chain_storage_version(), 8);
+        assert_eq!(
+            SubnetRegistry::total_emission_weight(),
+            Percent::from_percent(40)
+        );
+        let info = SubnetRegistry::subnets(0).unwrap();
+        assert_eq!(info.deposit, SUBNET_DEPOSIT);
+        assert_eq!(SubnetRegistry::subnets(1).unwrap().deposit, 0);
+
+        // Running it again changes nothing.
+        TotalEmissionWeight::<Test>::put(Percent::from_percent(50));
+        Migrate::<Test>::on_runtime_upgrade();
+        assert_eq!(SubnetRegistry::on_chain_storage_version(), 8);
+        assert_eq!(
+            SubnetRegistry::total_emission_weight(),
+            Percent::from_percent(50)
+        );
+        assert_eq!(SubnetRegistry::subnets(0).unwrap(), info);
+    });
+}
+
+#[test]
+fn open_task_limit_is_clamped_to_cap() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(SubnetBuilder::new(ALICE)
+            .max_open_tasks(MAX_OPEN_TASKS + 1)
+            .create());
+        assert_eq!(
+            SubnetRegistry::subnets(0).unwrap().max_open_tasks,
+            MAX_OPEN_TASKS
+        );
+
+        assert_ok!(SubnetRegistry::update_subnet(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(2),
+            None,
+            None,
+        ));
+        assert_eq!(SubnetRegistry::subnets(0).unwrap().max_open_tasks, 2);
+    });
+}
+
+#[test]
+fn subnet_info_round_trips_through_chain_spec_json() {
+    use neurochain::pallets::subnet_registry::SubnetInfo;
+
+    new_test_ext().execute_with(|| {
+        assert_ok!(SubnetBuilder::new(ALICE).create());
+        let info = SubnetRegistry::subnets(0).unwrap();
+
+        let json = serde_json::to_value(&info).unwrap();
+        assert_eq!(json["taskType"], "CodeGen");
+        assert_eq!(json["inputSchema"], r#"{"type":"object"}"#);
+        assert_eq!(json["evaluationSpec"], "ipfs://evaluation-spec");
+        assert_eq!(json["emissionWeight"], 10);
+        assert_eq!(json["name"], "Test subnet");
+        assert_eq!(json["status"], "Active");
+        assert_eq!(json["retirementReason"], serde_json::Value::Null);
+        assert_eq!(
+            serde_json::from_value::<SubnetInfo<Test>>(json).unwrap(),
+            info
+        );
+
+        let retired = SubnetInfo::<Test> {
+            task_type: TaskType::Custom(b"speech-to-text".to_vec().try_into().unwrap()),
+            status: SubnetStatus::Retired,
+            retired_at: Some(9),
+            retirement_reason: Some(b"superseded".to_vec().try_into().unwrap()),
+            ..info
+        };
+        let json = serde_json::to_string(&retired).unwrap();
+        assert!(json.contains(r#""taskType":"speech-to-text""#));
+        assert!(json.contains(r#""retirementReason":"superseded""#));
+        assert_eq!(
+            serde_json::from_str::<SubnetInfo<Test>>(&json).unwrap(),
+            retired
+        );
+    });
+}
+
+#[test]
+fn genesis_creates_subnets_without_reserving_deposits() {
+    use neurochain::pallets::subnet_registry::GenesisConfig;
+    use sp_runtime::BuildStorage;
+
+    let storage = RuntimeGenesisConfig {
+        balances: pallet_balances::GenesisConfig {
+            balances: vec![(ALICE, 1_000)],
+        },
+        subnet_registry: GenesisConfig {
+            subnets: vec![
+                (TaskType::CodeGen, Percent::from_percent(30), 10, 20, ALICE),
+                (TaskType::ImageGen, Percent::from_percent(20), 5, 15, BOB),
+            ],
+        },
+        ..Default::default()
+    }
+    .build_storage()
+    .unwrap();
+
+    sp_io::TestExternalities::new(storage).execute_with(|| {
+        assert_eq!(SubnetRegistry::subnet_count(), 2);
+        assert_eq!(SubnetRegistry::next_subnet_id(), 2);
+        assert_eq!(
+            SubnetRegistry::total_emission_weight(),
+            Percent::from_percent(50)
+        );
+        assert_eq!(SubnetRegistry::owner_subnets(ALICE).into_inner(), vec![0]);
+        assert_eq!(SubnetRegistry::owner_subnets(BOB).into_inner(), vec![1]);
+        assert_eq!(
+            SubnetRegistry::subnets_by_task_type(TaskType::ImageGen),
+            vec![1]
+        );
+
+        let info = SubnetRegistry::subnets(1).unwrap();
+        assert_eq!(info.owner, BOB);
+        assert_eq!(info.min_stake_miner, 5);
+        assert_eq!(info.min_stake_validator, 15);
+        assert_eq!(info.status, SubnetStatus::Active);
+        assert_eq!(info.deposit, 0);
+        assert_eq!(Balances::reserved_balance(ALICE), 0);
+
+        // Subnets created afterwards continue from the genesis ids.
+        System::set_block_number(1);
+        assert_ok!(SubnetBuilder::new(ALICE).create());
+        assert_eq!(SubnetRegistry::subnet_count(), 3);
+        assert!(SubnetRegistry::subnets(2).is_some());
+    });
+}
+
+#[test]
+fn transfer_ownership_moves_subnet_and_deposit() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(SubnetBuilder::new(ALICE).create());
+        assert_ok!(SubnetBuilder::new(ALICE).create());
+        assert_noop!(
+            SubnetRegistry::transfer_ownership(RuntimeOrigin::signed(BOB), 0, BOB),
+            Error::<Test>::AlreadyOwner
+        );
+        assert_noop!(
+            SubnetRegistry::transfer_ownership(RuntimeOrigin::signed(BOB), 0, CHARLIE),
+            Error::<Test>::NotOwner
+        );
+
+        assert_ok!(SubnetRegistry::transfer_ownership(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            BOB
+        ));
+        assert_eq!(SubnetRegistry::subnets(0).unwrap().owner, BOB);
+        assert_eq!(SubnetRegistry::owner_subnets(ALICE).into_inner(), vec![1]);
+        assert_eq!(SubnetRegistry::owner_subnets(BOB).into_inner(), vec![0]);
+        assert_eq!(Balances::reserved_balance(ALICE), SUBNET_DEPOSIT);
+        assert_eq!(Balances::reserved_balance(BOB), SUBNET_DEPOSIT);
+        System::assert_last_event(
+            Event::OwnershipTransferred {
+                subnet_id: 0,
+                old_owner: ALICE,
+                new_owner: BOB,
+            }
+            .into(),
+        );
+        assert_ok!(update_weight(BOB, 0, 20));
+        assert_noop!(update_weight(ALICE, 0, 30), Error::<Test>::NotOwner);
+    });
+}
+
+#[test]
+fn transfer_ownership_requires_new_owner_to_cover_deposit() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(SubnetBuilder::new(ALICE).create());
+        assert_noop!(
+            SubnetRegistry::transfer_ownership(RuntimeOrigin::signed(ALICE), 0, CHARLIE),
+            Error::<Test>::InsufficientBalance
+        );
+        assert_eq!(SubnetRegistry::subnets(0).unwrap().owner, ALICE);
+    });
+}
+
+#[test]
+fn transfer_ownership_respects_max_subnets() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(SubnetBuilder::new(ALICE).create());
+        for _ in 0..4 {
+            assert_ok!(SubnetBuilder::new(BOB).create());
+        }
+        assert_noop!(
+            SubnetRegistry::transfer_ownership(RuntimeOrigin::signed(ALICE), 0, BOB),
+            Error::<Test>::TooManyOwnedSubnets
+        );
+    });
+}
+
+#[test]
+fn reclaim_deposit_requires_retirement_and_cooldown() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(SubnetBuilder::new(ALICE).create());
+        assert_noop!(
+            SubnetRegistry::reclaim_deposit(RuntimeOrigin::signed(ALICE), 0),
+            Error::<Test>::SubnetNotRetired
+        );
+        assert_ok!(SubnetRegistry::retire_subnet(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            None
+        ));
+        assert_eq!(Balances::reserved_balance(ALICE), 0);
+        assert_noop!(
+            SubnetRegistry::reclaim_deposit(RuntimeOrigin::signed(BOB), 0),
+            Error::<Test>::NotOwner
+        );
+        run_to_block(RETIREMENT_COOLDOWN);
+        assert_noop!(
+            SubnetRegistry::reclaim_deposit(RuntimeOrigin::signed(ALICE), 0),
+            Error::<Test>::RetirementCooldownActive
+        );
+
+        run_to_block(1 + RETIREMENT_COOLDOWN);
+        assert_ok!(SubnetRegistry::reclaim_deposit(
+            RuntimeOrigin::signed(ALICE),
+            0
+        ));
+        assert!(SubnetRegistry::subnets(0).is_none());
+        assert!(SubnetRegistry::subnet_schemas(0).is_empty());
+        assert!(SubnetRegistry::owner_subnets(ALICE).is_empty());
+        System::assert_has_event(Event::SubnetPurged { subnet_id: 0 }.into());
+        assert_noop!(
+            SubnetRegistry::reclaim_deposit(RuntimeOrigin::signed(ALICE), 0),
+            Error::<Test>::SubnetNotFound
+        );
+    });
+}
+
+#[test]
+fn deposit_reserved_at_creation_is_what_is_released() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(SubnetBuilder::new(ALICE).create());
+        assert_eq!(SubnetRegistry::subnets(0).unwrap().deposit, SUBNET_DEPOSIT);
+
+        // Raising the deposit affects neither transfers nor refunds of
+        // subnets created before.
+        SubnetDeposit::set(2 * SUBNET_DEPOSIT);
+        assert_ok!(SubnetRegistry::transfer_ownership(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            BOB
+        ));
+        assert_eq!(Balances::reserved_balance(ALICE), 0);
+        assert_eq!(Balances::reserved_balance(BOB), SUBNET_DEPOSIT);
+
+        assert_ok!(SubnetRegistry::retire_subnet(
+            RuntimeOrigin::signed(BOB),
+            0,
+            None
+        ));
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+        assert_eq!(Balances::free_balance(BOB), 1_000);
+        System::assert_has_event(
+            Event::DepositReclaimed {
+                subnet_id: 0,
+                owner: BOB,
+                amount: SUBNET_DEPOSIT,
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn retiring_releases_the_deposit() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(SubnetBuilder::new(ALICE).create());
+        assert_ok!(SubnetBuilder::new(ALICE).create());
+        assert_eq!(Balances::free_balance(ALICE), 1_000 - 2 * SUBNET_DEPOSIT);
+
+        assert_ok!(SubnetRegistry::retire_subnet(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            None
+        ));
+        assert_eq!(Balances::free_balance(ALICE), 1_000 - SUBNET_DEPOSIT);
+        assert_eq!(Balances::reserved_balance(ALICE), SUBNET_DEPOSIT);
+        assert_eq!(SubnetRegistry::subnets(0).unwrap().deposit, 0);
+        System::assert_has_event(
+            Event::DepositReclaimed {
+                subnet_id: 0,
+                owner: ALICE,
+                amount: SUBNET_DEPOSIT,
+            }
+            .into(),
+        );
+
+        assert_ok!(SubnetRegistry::force_retire_subnet(
+            RuntimeOrigin::root(),
+            1,
+            None
+        ));
+        assert_eq!(Balances::free_balance(ALICE), 1_000);
+        assert_eq!(Balances::reserved_balance(ALICE), 0);
+
+        // Nothing is left to release when the subnets are reclaimed.
+        run_to_block(1 + RETIREMENT_COOLDOWN);
+        assert_ok!(SubnetRegistry::reclaim_deposit(
+            RuntimeOrigin::signed(ALICE),
+            0
+        ));
+        assert_eq!(Balances::free_balance(ALICE), 1_000);
+    });
+}
+
+#[test]
+fn retired_subnets_can_be_reactivated() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(SubnetBuilder::new(ALICE).create());
+        assert_noop!(
+            SubnetRegistry::reactivate_subnet(RuntimeOrigin::signed(ALICE), 0),
+            Error::<Test>::SubnetNotRetired
+        );
+        assert_ok!(SubnetRegistry::retire_subnet(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            Some(b"paused".to_vec())
+        ));
+        assert_noop!(
+            SubnetRegistry::reactivate_subnet(RuntimeOrigin::signed(BOB), 0),
+            Error::<Test>::NotOwner
+        );
+
+        assert_ok!(SubnetRegistry::reactivate_subnet(
+            RuntimeOrigin::signed(ALICE),
+            0
+        ));
+        let info = SubnetRegistry::subnets(0).unwrap();
+        assert_eq!(info.status, SubnetStatus::Active);
+        assert_eq!(info.retired_at, None);
+        assert_eq!(info.retirement_reason, None);
+        assert_eq!(SubnetRegistry::subnet_count(), 1);
+        assert_eq!(
+            SubnetRegistry::total_emission_weight(),
+            Percent::from_percent(10)
+        );
+        assert_eq!(
+            SubnetRegistry::subnets_by_task_type(TaskType::CodeGen),
+            vec![0]
+        );
+        // The deposit released on retirement is reserved again.
+        assert_eq!(Balances::reserved_balance(ALICE), SUBNET_DEPOSIT);
+        assert_eq!(SubnetRegistry::subnets(0).unwrap().deposit, SUBNET_DEPOSIT);
+        System::assert_last_event(
+            Event::SubnetReactivated {
+                subnet_id: 0,
+                owner: ALICE,
+            }
+            .into(),
+        );
+
+        assert_noop!(
+            SubnetRegistry::reactivate_subnet(RuntimeOrigin::signed(ALICE), 0),
+            Error::<Test>::SubnetNotRetired
+        );
+    });
+}
+
+#[test]
+fn paused_subnets_can_be_resumed() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(SubnetBuilder::new(ALICE).create());
+        assert_noop!(
+            SubnetRegistry::resume_subnet(RuntimeOrigin::signed(ALICE), 0),
+            Error::<Test>::SubnetNotPaused
+        );
+        assert_noop!(
+            SubnetRegistry::pause_subnet(RuntimeOrigin::signed(BOB), 0),
+            Error::<Test>::NotOwner
+        );
+
+        assert_ok!(SubnetRegistry::pause_subnet(
+            RuntimeOrigin::signed(ALICE),
+            0
+        ));
+        assert_eq!(
+            SubnetRegistry::subnets(0).unwrap().status,
+            SubnetStatus::Paused
+        );
+        assert!(!SubnetRegistry::is_subnet_active(0));
+        assert!(SubnetRegistry::active_subnets().is_empty());
+        // The subnet keeps its weight and can still be maintained.
+        assert_eq!(
+            SubnetRegistry::total_emission_weight(),
+            Percent::from_percent(10)
+        );
+        assert_ok!(update_weight(ALICE, 0, 20));
+        System::assert_has_event(
+            Event::SubnetPaused {
+                subnet_id: 0,
+                owner: ALICE,
+            }
+            .into(),
+        );
+        assert_noop!(
+            SubnetRegistry::pause_subnet(RuntimeOrigin::signed(ALICE), 0),
+            Error::<Test>::SubnetPaused
+        );
+        assert_noop!(
+            SubnetRegistry::resume_subnet(RuntimeOrigin::signed(BOB), 0),
+            Error::<Test>::NotOwner
+        );
+
+        assert_ok!(SubnetRegistry::resume_subnet(
+            RuntimeOrigin::signed(ALICE),
+            0
+        ));
+        assert!(SubnetRegistry::is_subnet_active(0));
+        System::assert_last_event(
+            Event::SubnetResumed {
+                subnet_id: 0,
+                owner: ALICE,
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn retired_subnets_cannot_be_paused_or_resumed() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(SubnetBuilder::new(ALICE).create());
+        assert_ok!(SubnetRegistry::pause_subnet(
+            RuntimeOrigin::signed(ALICE),
+            0
+        ));
+        // Paused subnets may still be retired for good.
+        assert_ok!(SubnetRegistry::retire_subnet(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            None
+        ));
+        assert_eq!(SubnetRegistry::total_emission_weight(), Percent::zero());
+
+        assert_noop!(
+            SubnetRegistry::resume_subnet(RuntimeOrigin::signed(ALICE), 0),
+            Error::<Test>::SubnetNotPaused
+        );
+        assert_noop!(
+            SubnetRegistry::pause_subnet(RuntimeOrigin::signed(ALICE), 0),
+            Error::<Test>::SubnetNotActive
+        );
+    });
+}
+
+#[test]
+fn reactivation_reserves_a_reclaimed_deposit_again() {
+    use neurochain::pallets::subnet_registry::PurgeCandidates;
+
+    new_test_ext().execute_with(|| {
+        assert_ok!(SubnetBuilder::new(ALICE).create());
+        assert_ok!(SubnetRegistry::retire_subnet(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            None
+        ));
+        // Members keep the subnet in storage after the deposit is
+        // reclaimed.
+        MockSubnetMembers::set(0, true);
+        run_to_block(1 + RETIREMENT_COOLDOWN);
+        assert_ok!(SubnetRegistry::reclaim_deposit(
+            RuntimeOrigin::signed(ALICE),
+            0
+        ));
+        assert_eq!(Balances::reserved_balance(ALICE), 0);
+
+        // Only as much weight as is left may come back.
+        assert_ok!(SubnetBuilder::new(BOB).emission_weight(95).create());
+        assert_noop!(
+            SubnetRegistry::reactivate_subnet(RuntimeOrigin::signed(ALICE), 0),
+            Error::<Test>::EmissionWeightExceedsTotal
+        );
+        assert_ok!(update_weight(BOB, 1, 90));
+
+        assert_ok!(SubnetRegistry::reactivate_subnet(
+            RuntimeOrigin::signed(ALICE),
+            0
+        ));
+        assert_eq!(Balances::reserved_balance(ALICE), SUBNET_DEPOSIT);
+        assert_eq!(SubnetRegistry::owner_subnets(ALICE).into_inner(), vec![0]);
+        assert_eq!(SubnetRegistry::subnets(0).unwrap().deposit, SUBNET_DEPOSIT);
+        assert!(!PurgeCandidates::<Test>::contains_key(0));
+
+        // Once purged, a subnet is gone for good.
+        assert_ok!(SubnetRegistry::retire_subnet(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            None
+        ));
+        MockSubnetMembers::set(0, false);
+        run_to_block(2 + 2 * RETIREMENT_COOLDOWN);
+        assert_ok!(SubnetRegistry::reclaim_deposit(
+            RuntimeOrigin::signed(ALICE),
+            0
+        ));
+        assert_noop!(
+            SubnetRegistry::reactivate_subnet(RuntimeOrigin::signed(ALICE), 0),
+            Error::<Test>::SubnetNotFound
+        );
+    });
+}
+
+#[test]
+fn legacy_deposit_is_released_only_once() {
+    use frame_support::traits::ReservableCurrency;
+    use neurochain::pallets::subnet_registry::Subnets;
+
+    new_test_ext().execute_with(|| {
+        assert_ok!(SubnetBuilder::new(ALICE).create());
+        // Reserved by ALICE for something else; it must stay reserved.
+        assert_ok!(Balances::reserve(&ALICE, 50));
+        assert_ok!(SubnetRegistry::retire_subnet(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            None
+        ));
+        // Retired before deposits were released on retirement.
+        assert_ok!(Balances::reserve(&ALICE, SUBNET_DEPOSIT));
+        Subnets::<Test>::mutate(0, |info| info.as_mut().unwrap().deposit = SUBNET_DEPOSIT);
+        assert_eq!(Balances::reserved_balance(ALICE), 50 + SUBNET_DEPOSIT);
+
+        MockSubnetMembers::set(0, true);
+        run_to_block(1 + RETIREMENT_COOLDOWN);
+        assert_ok!(SubnetRegistry::reclaim_deposit(
+            RuntimeOrigin::signed(ALICE),
+            0
+        ));
+        assert_eq!(Balances::reserved_balance(ALICE), 50);
+        assert_eq!(SubnetRegistry::subnets(0).unwrap().deposit, 0);
+
+        assert_ok!(SubnetRegistry::reactivate_subnet(
+            RuntimeOrigin::signed(ALICE),
+            0
+        ));
+        assert_eq!(Balances::reserved_balance(ALICE), 50 + SUBNET_DEPOSIT);
+
+        assert_ok!(SubnetRegistry::retire_subnet(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            None
+        ));
+        assert_eq!(Balances::reserved_balance(ALICE), 50);
+    });
+}
+
+#[test]
+fn subnets_with_members_are_purged_after_retention() {
+    new_test_ext().execute_with(|| {
+        for subnet_id in 0..2 {
+            assert_ok!(SubnetBuilder::new(ALICE).create());
+            assert_ok!(SubnetRegistry::retire_subnet(
+                RuntimeOrigin::signed(ALICE),
+                subnet_id,
+                None
+            ));
+            MockSubnetMembers::set(subnet_id, true);
+        }
+        run_to_block(1 + RETIREMENT_COOLDOWN);
+        for subnet_id in 0..2 {
+            assert_ok!(SubnetRegistry::reclaim_deposit(
+                RuntimeOrigin::signed(ALICE),
+                subnet_id
+            ));
+        }
+        assert_eq!(Balances::reserved_balance(ALICE), 0);
+        assert!(SubnetRegistry::owner_subnets(ALICE).is_empty());
+        assert!(SubnetRegistry::subnets(0).is_some());
+        assert_noop!(
+            SubnetRegistry::reclaim_deposit(RuntimeOrigin::signed(ALICE), 0),
+            Error::<Test>::DepositAlreadyReclaimed
+        );
+        assert_noop!(
+            SubnetRegistry::transfer_ownership(RuntimeOrigin::signed(ALICE), 0, BOB),
+            Error::<Test>::DepositAlreadyReclaimed
+        );
+
+        MockSubnetMembers::set(0, false);
+        run_to_block(RETIRED_SUBNET_RETENTION);
+        assert!(SubnetRegistry::subnets(0).is_some());
+
+        run_to_block(1 + RETIRED_SUBNET_RETENTION);
+        assert!(SubnetRegistry::subnets(0).is_none());
+        System::assert_has_event(Event::SubnetPurged { subnet_id: 0 }.into());
+        // Still has members.
+        assert!(SubnetRegistry::subnets(1).is_some());
+
+        MockSubnetMembers::set(1, false);
+        run_to_block(2 + RETIRED_SUBNET_RETENTION);
+        assert!(SubnetRegistry::subnets(1).is_none());
+    });
+}
+
+#[test]
+fn purges_are_bounded_per_block() {
+    new_test_ext().execute_with(|| {
+        for subnet_id in 0..3 {
+            assert_ok!(SubnetBuilder::new(ALICE).create());
+            assert_ok!(SubnetRegistry::retire_subnet(
+                RuntimeOrigin::signed(ALICE),
+                subnet_id,
+                None
+            ));
+            MockSubnetMembers::set(subnet_id, true);
+        }
+        run_to_block(1 + RETIREMENT_COOLDOWN);
+        for subnet_id in 0..3 {
+            assert_ok!(SubnetRegistry::reclaim_deposit(
+                RuntimeOrigin::signed(ALICE),
+                subnet_id
+            ));
+            MockSubnetMembers::set(subnet_id, false);
+        }
+
+        run_to_block(1 + RETIRED_SUBNET_RETENTION);
+        let remaining = (0..3)
+            .filter(|&subnet_id| SubnetRegistry::subnets(subnet_id).is_some())
+            .count();
+        assert_eq!(remaining, 1);
+        run_to_block(2 + RETIRED_SUBNET_RETENTION);
+        assert!((0..3).all(|subnet_id| SubnetRegistry::subnets(subnet_id).is_none()));
+    });
+}
+
+#[test]
+fn every_purge_candidate_is_reached_in_turn() {
+    // Whichever candidate loses its members, it is purged within the
+    // two blocks it takes to examine all three, two per block.
+    for freed in 0..3 {
+        new_test_ext().execute_with(|| {
+            for subnet_id in 0..3 {
+                assert_ok!(SubnetBuilder::new(ALICE).create());
+                assert_ok!(SubnetRegistry::retire_subnet(
+                    RuntimeOrigin::signed(ALICE),
+                    subnet_id,
+                    None
+                ));
+                MockSubnetMembers::set(subnet_id, true);
+            }
+            run_to_block(1 + RETIREMENT_COOLDOWN);
+            for subnet_id in 0..3 {
+                assert_ok!(SubnetRegistry::reclaim_deposit(
+                    RuntimeOrigin::signed(ALICE),
+                    subnet_id
+                ));
+            }
+
+            run_to_block(1 + RETIRED_SUBNET_RETENTION);
+            MockSubnetMembers::set(freed, false);
+            run_to_block(3 + RETIRED_SUBNET_RETENTION);
+            assert!(SubnetRegistry::subnets(freed).is_none());
+            assert_eq!(
+                (0..3)
+                    .filter(|&subnet_id| SubnetRegistry::subnets(subnet_id).is_some())
+                    .count(),
+                2
+            );
+        });
+    }
+}
+
+#[test]
+fn retired_subnets_not_reclaimed_are_not_purged() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(SubnetBuilder::new(ALICE).create());
+        assert_ok!(SubnetRegistry::retire_subnet(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            None
+        ));
+        run_to_block(2 * RETIRED_SUBNET_RETENTION);
+        assert!(SubnetRegistry::subnets(0).is_some());
+    });
+}
+
+#[test]
+fn subnets_are_read_by_id_skipping_unknown_ids() {
+    new_test_ext().execute_with(|| {
+        for owner in [ALICE, BOB] {
+            assert_ok!(SubnetBuilder::new(owner).create());
+        }
+        let subnets = SubnetRegistry::subnets_with_ids(&[1, 7, 0]);
+        assert_eq!(
+            subnets,
+            vec![
+                (1, SubnetRegistry::subnets(1).unwrap()),
+                (0, SubnetRegistry::subnets(0).unwrap()),
+            ]
+        );
+    });
+}
+
+#[test]
+fn subnets_are_listed_in_pages() {
+    new_test_ext().execute_with(|| {
+        for owner in [ALICE, ALICE, ALICE, BOB, BOB] {
+            assert_ok!(SubnetBuilder::new(owner).create());
+        }
+        let all = SubnetRegistry::list_subnets(0, MAX_SUBNETS_PER_PAGE);
+        let mut ids: Vec<u32> = all.iter().map(|(id, _)| *id).collect();
+        ids.sort();
+        assert_eq!(ids, vec![0, 1, 2, 3, 4]);
+
+        assert_eq!(SubnetRegistry::list_subnets(2, 2), all[2..4].to_vec());
+        assert_eq!(SubnetRegistry::list_subnets(4, 2), all[4..].to_vec());
+        assert!(SubnetRegistry::list_subnets(5, 2).is_empty());
+        assert_eq!(SubnetRegistry::list_subnets(0, u32::MAX), all);
+
+        // Purged subnets leave no gaps.
+        assert_ok!(SubnetRegistry::retire_subnet(
+            RuntimeOrigin::signed(ALICE),
+            1,
+            None
+        ));
+        run_to_block(1 + RETIREMENT_COOLDOWN);
+        assert_ok!(SubnetRegistry::reclaim_deposit(
+            RuntimeOrigin::signed(ALICE),
+            1
+        ));
+        let remaining = SubnetRegistry::list_subnets(0, MAX_SUBNETS_PER_PAGE);
+        assert_eq!(remaining.len(), 4);
+        assert!(remaining.iter().all(|(id, _)| *id != 1));
+    });
+}
+
+#[test]
+fn repeated_request_id_creates_one_subnet() {
+    new_test_ext().execute_with(|| {
+        let request_id = [7; 16];
+        assert_ok!(SubnetBuilder::new(ALICE).request_id(request_id).create());
+        assert_ok!(SubnetBuilder::new(ALICE).request_id(request_id).create());
+
+        assert_eq!(SubnetRegistry::next_subnet_id(), 1);
+        assert_eq!(SubnetRegistry::owner_subnets(ALICE).into_inner(), vec![0]);
+        assert_eq!(Balances::reserved_balance(ALICE), SUBNET_DEPOSIT);
+        System::assert_last_event(
+            Event::SubnetAlreadyCreated {
+                subnet_id: 0,
+                owner: ALICE,
+                request_id,
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn distinct_request_ids_create_distinct_subnets() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(SubnetBuilder::new(ALICE).request_id([1; 16]).create());
+        assert_ok!(SubnetBuilder::new(ALICE).request_id([2; 16]).create());
+        // Request ids are scoped to their owner.
+        assert_ok!(SubnetBuilder::new(BOB).request_id([1; 16]).create());
+
+        assert_eq!(SubnetRegistry::next_subnet_id(), 3);
+        assert_eq!(Balances::reserved_balance(ALICE), 2 * SUBNET_DEPOSIT);
+        assert_eq!(SubnetRegistry::seen_requests(BOB, [1; 16]), Some(2));
+    });
+}
+
+#[test]
+fn request_ids_expire_after_the_window() {
+    new_test_ext().execute_with(|| {
+        let request_id = [7; 16];
+        assert_ok!(SubnetBuilder::new(ALICE).request_id(request_id).create());
+
+        run_to_block(REQUEST_ID_WINDOW);
+        assert_ok!(SubnetBuilder::new(ALICE).request_id(request_id).create());
+        assert_eq!(SubnetRegistry::next_subnet_id(), 1);
+
+        run_to_block(1 + REQUEST_ID_WINDOW);
+        assert_eq!(SubnetRegistry::seen_requests(ALICE, request_id), None);
+        assert_ok!(SubnetBuilder::new(ALICE).request_id(request_id).create());
+        assert_eq!(SubnetRegistry::next_subnet_id(), 2);
+        assert_eq!(SubnetRegistry::seen_requests(ALICE, request_id), Some(1));
+    });
+}
+
+#[test]
+fn subnets_are_looked_up_by_task_type() {
+    let custom = |name: &[u8]| TaskType::Custom(name.to_vec().try_into().unwrap());
+
+    new_test_ext().execute_with(|| {
+        for task_type in [
+            TaskType::CodeGen,
+            TaskType::ImageGen,
+            custom(b"speech"),
+            TaskType::CodeGen,
+            custom(b"translation"),
+            TaskType::CodeGen,
+        ] {
+            assert_ok!(SubnetBuilder::new(ALICE).task_type(task_type).create());
+        }
+        assert_ok!(SubnetRegistry::retire_subnet(
+            RuntimeOrigin::signed(ALICE),
+            3,
+            None
+        ));
+
+        let sorted = |task_type| {
+            let mut ids = SubnetRegistry::subnets_by_task_type(task_type);
+            ids.sort();
+            ids
+        };
+        assert_eq!(sorted(TaskType::CodeGen), vec![0, 5]);
+        assert_eq!(sorted(TaskType::ImageGen), vec![1]);
+        assert_eq!(sorted(custom(b"speech")), vec![2]);
+        assert_eq!(sorted(custom(b"translation")), vec![4]);
+        assert!(sorted(custom(b"spee")).is_empty());
+        assert!(sorted(TaskType::ProteinFolding).is_empty());
+
+        // Paused subnets are left out, as from `active_subnets`.
+        assert_ok!(SubnetRegistry::pause_subnet(
+            RuntimeOrigin::signed(ALICE),
+            5
+        ));
+        assert_eq!(sorted(TaskType::CodeGen), vec![0]);
+        assert_ok!(SubnetRegistry::resume_subnet(
+            RuntimeOrigin::signed(ALICE),
+            5
+        ));
+        assert_eq!(sorted(TaskType::CodeGen), vec![0, 5]);
+    });
+}
+
+#[test]
+fn idle_subnets_decay_to_zero() {
+    new_test_ext().execute_with(|| {
+        IdleDecayPeriod::set(10);
+        assert_ok!(SubnetBuilder::new(ALICE).emission_weight(40).create());
+
+        // First seen idle at block 10, so the first step is a period
+        // later.
+        run_to_block(19);
+        assert_eq!(
+            SubnetRegistry::subnets(0).unwrap().emission_weight,
+            Percent::from_percent(40)
+        );
+        run_to_block(20);
+        System::assert_has_event(
+            Event::SubnetWeightDecayed {
+                subnet_id: 0,
+                old_weight: Percent::from_percent(40),
+                new_weight: Percent::from_percent(20),
+            }
+            .into(),
+        );
+        assert_eq!(
+            SubnetRegistry::total_emission_weight(),
+            Percent::from_percent(20)
+        );
+
+        // 20, 10, 5, 2, 1, 0: at least a point goes every step.
+        run_to_block(70);
+        assert_eq!(
+            SubnetRegistry::subnets(0).unwrap().emission_weight,
+            Percent::zero()
+        );
+        assert_eq!(SubnetRegistry::total_emission_weight(), Percent::zero());
+        System::reset_events();
+        run_to_block(100);
+        assert!(System::events().is_empty());
+    });
+}
+
+#[test]
+fn idle_checks_are_spread_over_blocks() {
+    use neurochain::pallets::subnet_registry::IdleCheckCursor;
+
+    new_test_ext().execute_with(|| {
+        IdleDecayPeriod::set(10);
+        for _ in 0..3 {
+            assert_ok!(SubnetBuilder::new(ALICE).emission_weight(20).create());
+        }
+        let idle = || {
+            (0..3)
+                .filter(|&subnet_id| SubnetRegistry::idle_since(subnet_id).is_some())
+                .count()
+        };
+
+        // Two subnets are checked at block 10 and the third at 11.
+        run_to_block(10);
+        assert_eq!(idle(), 2);
+        run_to_block(11);
+        assert_eq!(idle(), 3);
+        assert_eq!(IdleCheckCursor::<Test>::get(), None);
+
+        // Each is decayed a period after it was first seen idle.
+        run_to_block(20);
+        let decayed = || {
+            (0..3)
+                .filter(|&subnet_id| {
+                    SubnetRegistry::subnets(subnet_id).unwrap().emission_weight
+                        < Percent::from_percent(20)
+                })
+                .count()
+        };
+        assert_eq!(decayed(), 2);
+        run_to_block(21);
+        assert_eq!(decayed(), 3);
+    });
+}
+
+#[test]
+fn subnets_with_members_keep_their_weight() {
+    new_test_ext().execute_with(|| {
+        IdleDecayPeriod::set(10);
+        assert_ok!(SubnetBuilder::new(ALICE).emission_weight(40).create());
+        assert_ok!(SubnetBuilder::new(ALICE).emission_weight(40).create());
+        MockSubnetMembers::set(0, true);
+
+        run_to_block(20);
+        let weight = |subnet_id| SubnetRegistry::subnets(subnet_id).unwrap().emission_weight;
+        assert_eq!(weight(0), Percent::from_percent(40));
+        assert_eq!(weight(1), Percent::from_percent(20));
+
+        // A miner registering restarts the idle timer.
+        assert_ok!(MinerRegistry::register_miner(
+            RuntimeOrigin::signed(BOB),
+            1,
+            b"/ip4/127.0.0.1/tcp/4001".to_vec(),
+            10,
+            Vec::new()
+        ));
+        assert_eq!(SubnetRegistry::idle_since(1), None);
+        run_to_block(39);
+        assert_eq!(weight(1), Percent::from_percent(20));
+        run_to_block(40);
+        assert_eq!(weight(1), Percent::from_percent(10));
+        assert_eq!(weight(0), Percent::from_percent(40));
+    });
+}
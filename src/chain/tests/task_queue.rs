@@ -0,0 +1,173 @@
+mod mock;
+
+use frame_support::{assert_noop, assert_ok};
+use mock::*;
+use neurochain::pallets::task_queue::{Error, Event, TaskStatus};
+
+const MINER: u64 = 10;
+
+/// Register [`MINER`] on subnet 0 so that it may accept its tasks.
+fn register_miner() {
+    Balances::make_free_balance_be(&MINER, 100);
+    assert_ok!(MinerRegistry::register_miner(
+        RuntimeOrigin::signed(MINER),
+        0,
+        b"/ip4/127.0.0.1/tcp/4001".to_vec(),
+        10,
+        vec![],
+    ));
+}
+
+#[test]
+fn submit_task_stores_task() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(SubnetBuilder::new(ALICE).max_input_bytes(8).create());
+        assert_ok!(TaskQueue::submit_task(
+            RuntimeOrigin::signed(BOB),
+            0,
+            b"12345678".to_vec()
+        ));
+
+        let task = TaskQueue::tasks(0).unwrap();
+        assert_eq!(task.subnet_id, 0);
+        assert_eq!(task.poster, BOB);
+        assert_eq!(task.input.into_inner(), b"12345678".to_vec());
+        assert_eq!(task.status, TaskStatus::Open);
+        assert_eq!(TaskQueue::next_task_id(), 1);
+        assert_eq!(TaskQueue::open_tasks(0), 1);
+        System::assert_last_event(
+            Event::TaskSubmitted {
+                task_id: 0,
+                subnet_id: 0,
+                poster: BOB,
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn submit_task_rejects_input_over_subnet_limit() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(SubnetBuilder::new(ALICE).max_input_bytes(8).create());
+        assert_noop!(
+            TaskQueue::submit_task(RuntimeOrigin::signed(BOB), 0, b"123456789".to_vec()),
+            Error::<Test>::InputTooLarge
+        );
+    });
+}
+
+#[test]
+fn submit_task_requires_active_subnet() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            TaskQueue::submit_task(RuntimeOrigin::signed(BOB), 0, b"input".to_vec()),
+            Error::<Test>::SubnetNotFound
+        );
+
+        assert_ok!(SubnetBuilder::new(ALICE).max_input_bytes(8).create());
+        assert_ok!(SubnetRegistry::retire_subnet(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            None
+        ));
+        assert_noop!(
+            TaskQueue::submit_task(RuntimeOrigin::signed(BOB), 0, b"input".to_vec()),
+            Error::<Test>::SubnetNotActive
+        );
+    });
+}
+
+#[test]
+fn open_task_limit_frees_up_on_accept() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(SubnetBuilder::new(ALICE)
+            .max_input_bytes(8)
+            .max_open_tasks(2)
+            .create());
+        for _ in 0..2 {
+            assert_ok!(TaskQueue::submit_task(
+                RuntimeOrigin::signed(BOB),
+                0,
+                b"input".to_vec()
+            ));
+        }
+        assert_noop!(
+            TaskQueue::submit_task(RuntimeOrigin::signed(BOB), 0, b"input".to_vec()),
+            Error::<Test>::TooManyOpenTasks
+        );
+
+        register_miner();
+        assert_ok!(TaskQueue::accept_task(RuntimeOrigin::signed(MINER), 1));
+        assert_eq!(
+            TaskQueue::tasks(1).unwrap().status,
+            TaskStatus::Accepted { miner: MINER }
+        );
+        assert_eq!(TaskQueue::open_tasks(0), 1);
+        System::assert_last_event(
+            Event::TaskAccepted {
+                task_id: 1,
+                miner: MINER,
+            }
+            .into(),
+        );
+
+        assert_ok!(TaskQueue::submit_task(
+            RuntimeOrigin::signed(BOB),
+            0,
+            b"input".to_vec()
+        ));
+        assert_noop!(
+            TaskQueue::accept_task(RuntimeOrigin::signed(MINER), 1),
+            Error::<Test>::TaskNotOpen
+        );
+    });
+}
+
+#[test]
+fn only_miners_of_the_subnet_accept_its_tasks() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(SubnetBuilder::new(ALICE).create());
+        assert_ok!(SubnetBuilder::new(BOB).create());
+        assert_ok!(TaskQueue::submit_task(
+            RuntimeOrigin::signed(BOB),
+            1,
+            b"input".to_vec()
+        ));
+        register_miner();
+
+        assert_noop!(
+            TaskQueue::accept_task(RuntimeOrigin::signed(CHARLIE), 0),
+            Error::<Test>::NotMiner
+        );
+        // Registered on subnet 0, but the task is for subnet 1.
+        assert_noop!(
+            TaskQueue::accept_task(RuntimeOrigin::signed(MINER), 0),
+            Error::<Test>::NotMiner
+        );
+        assert_noop!(
+            TaskQueue::accept_task(RuntimeOrigin::signed(MINER), 1),
+            Error::<Test>::TaskNotFound
+        );
+    });
+}
+
+#[test]
+fn accepted_tasks_are_removed_after_retention() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(SubnetBuilder::new(ALICE).create());
+        register_miner();
+        assert_ok!(TaskQueue::submit_task(
+            RuntimeOrigin::signed(BOB),
+            0,
+            b"input".to_vec()
+        ));
+        assert_ok!(TaskQueue::accept_task(RuntimeOrigin::signed(MINER), 0));
+
+        run_to_block(ACCEPTED_TASK_RETENTION);
+        assert!(TaskQueue::tasks(0).is_some());
+        run_to_block(1 + ACCEPTED_TASK_RETENTION);
+        assert!(TaskQueue::tasks(0).is_none());
+        assert_eq!(TaskQueue::open_tasks(0), 0);
+    });
+}
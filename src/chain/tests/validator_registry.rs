@@ -0,0 +1,338 @@
+mod mock;
+
+use frame_support::{assert_noop, assert_ok, traits::Currency};
+use mock::*;
+use neurochain::pallets::validator_registry::{Error, Event};
+
+const SUBNET: u32 = 0;
+/// Owner of [`SUBNET`], so its deposit does not show up in the
+/// validators' reserved balances.
+const OWNER: u64 = 100;
+const DAVE: u64 = 4;
+
+/// Create [`SUBNET`] with a `min_stake_validator` of
+/// `MIN_VALIDATOR_STAKE`.
+fn create_subnet() {
+    Balances::make_free_balance_be(&OWNER, 1_000);
+    assert_ok!(SubnetBuilder::new(OWNER)
+        .min_stake_validator(MIN_VALIDATOR_STAKE)
+        .create());
+}
+
+fn register(validator: u64, stake: u64) {
+    assert_ok!(ValidatorRegistry::register_validator(
+        RuntimeOrigin::signed(validator),
+        SUBNET,
+        stake
+    ));
+}
+
+/// Have `validator` take part in consensus on [`SUBNET`] this epoch.
+fn set_weights(validator: u64) {
+    assert_ok!(Consensus::set_weights(
+        RuntimeOrigin::signed(validator),
+        SUBNET,
+        vec![]
+    ));
+}
+
+/// Run until the end of `epoch` has been processed.
+fn finish_epoch(epoch: u32) {
+    run_to_block((epoch as u64 + 1) * EPOCH_LENGTH);
+}
+
+#[test]
+fn update_stake_reserves_and_releases_the_difference() {
+    new_test_ext().execute_with(|| {
+        create_subnet();
+        register(ALICE, 100);
+        assert_eq!(Balances::reserved_balance(ALICE), 100);
+
+        assert_ok!(ValidatorRegistry::update_stake(
+            RuntimeOrigin::signed(ALICE),
+            SUBNET,
+            300
+        ));
+        assert_eq!(Balances::reserved_balance(ALICE), 300);
+        assert_ok!(ValidatorRegistry::update_stake(
+            RuntimeOrigin::signed(ALICE),
+            SUBNET,
+            60
+        ));
+        assert_eq!(Balances::reserved_balance(ALICE), 60);
+        assert_eq!(
+            ValidatorRegistry::validators(SUBNET, ALICE).unwrap().stake,
+            60
+        );
+
+        assert_noop!(
+            ValidatorRegistry::update_stake(
+                RuntimeOrigin::signed(ALICE),
+                SUBNET,
+                MIN_VALIDATOR_STAKE - 1
+            ),
+            Error::<Test>::StakeTooLow
+        );
+        assert_noop!(
+            ValidatorRegistry::update_stake(RuntimeOrigin::signed(BOB), SUBNET, 60),
+            Error::<Test>::NotRegistered
+        );
+    });
+}
+
+#[test]
+fn missing_epochs_marks_inactive_and_slashes() {
+    new_test_ext().execute_with(|| {
+        create_subnet();
+        register(ALICE, 200);
+        register(BOB, 200);
+
+        set_weights(BOB);
+        finish_epoch(0);
+        let alice = ValidatorRegistry::validators(SUBNET, ALICE).unwrap();
+        assert_eq!(alice.missed_epochs, 1);
+        assert!(alice.active);
+
+        set_weights(BOB);
+        finish_epoch(1);
+        let alice = ValidatorRegistry::validators(SUBNET, ALICE).unwrap();
+        assert_eq!(alice.missed_epochs, MAX_MISSED_EPOCHS);
+        assert!(!alice.active);
+        assert_eq!(alice.stake, 180);
+        assert_eq!(Balances::reserved_balance(ALICE), 180);
+        assert_eq!(Balances::total_balance(&ALICE), 980);
+        System::assert_has_event(
+            Event::ValidatorPenalized {
+                subnet_id: SUBNET,
+                validator: ALICE,
+                missed_epochs: MAX_MISSED_EPOCHS,
+                slashed: 20,
+            }
+            .into(),
+        );
+
+        let bob = ValidatorRegistry::validators(SUBNET, BOB).unwrap();
+        assert_eq!(bob.missed_epochs, 0);
+        assert!(bob.active);
+        assert_eq!(bob.stake, 200);
+    });
+}
+
+#[test]
+fn penalty_is_applied_once_and_submitting_again_resets() {
+    new_test_ext().execute_with(|| {
+        create_subnet();
+        register(ALICE, 200);
+        finish_epoch(2);
+        let alice = ValidatorRegistry::validators(SUBNET, ALICE).unwrap();
+        assert_eq!(alice.missed_epochs, 3);
+        assert_eq!(alice.stake, 180);
+
+        // Inactive validators may still set weights to recover.
+        set_weights(ALICE);
+        finish_epoch(3);
+        let alice = ValidatorRegistry::validators(SUBNET, ALICE).unwrap();
+        assert_eq!(alice.missed_epochs, 0);
+        assert!(alice.active);
+        assert!(ValidatorRegistry::is_active_validator(SUBNET, &ALICE));
+    });
+}
+
+#[test]
+fn epochs_are_numbered_from_genesis_like_consensus() {
+    new_test_ext().execute_with(|| {
+        // Validators only arrive well after genesis.
+        System::set_block_number(5 * EPOCH_LENGTH + 1);
+        create_subnet();
+        register(ALICE, 200);
+        assert_eq!(ValidatorRegistry::current_epoch(), 5);
+
+        set_weights(ALICE);
+        finish_epoch(5);
+        set_weights(ALICE);
+        finish_epoch(6);
+        assert_eq!(ValidatorRegistry::current_epoch(), 7);
+        let alice = ValidatorRegistry::validators(SUBNET, ALICE).unwrap();
+        assert_eq!(alice.missed_epochs, 0);
+        assert!(alice.active);
+        assert_eq!(alice.stake, 200);
+    });
+}
+
+#[test]
+fn zero_slash_fraction_only_deactivates() {
+    new_test_ext().execute_with(|| {
+        SlashFraction::set(sp_runtime::Perbill::zero());
+        create_subnet();
+        register(ALICE, 200);
+        finish_epoch(1);
+
+        let alice = ValidatorRegistry::validators(SUBNET, ALICE).unwrap();
+        assert!(!alice.active);
+        assert_eq!(alice.stake, 200);
+        System::assert_has_event(
+            Event::ValidatorPenalized {
+                subnet_id: SUBNET,
+                validator: ALICE,
+                missed_epochs: MAX_MISSED_EPOCHS,
+                slashed: 0,
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn registration_requires_an_active_subnet_and_its_minimum_stake() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            ValidatorRegistry::register_validator(RuntimeOrigin::signed(ALICE), SUBNET, 100),
+            Error::<Test>::SubnetNotFound
+        );
+
+        create_subnet();
+        assert_noop!(
+            ValidatorRegistry::register_validator(
+                RuntimeOrigin::signed(ALICE),
+                SUBNET,
+                MIN_VALIDATOR_STAKE - 1
+            ),
+            Error::<Test>::StakeTooLow
+        );
+        register(ALICE, MIN_VALIDATOR_STAKE);
+        assert_noop!(
+            ValidatorRegistry::register_validator(RuntimeOrigin::signed(ALICE), SUBNET, 100),
+            Error::<Test>::AlreadyRegistered
+        );
+        assert_eq!(ValidatorRegistry::validator_count(SUBNET), 1);
+        System::assert_last_event(
+            Event::ValidatorRegistered {
+                subnet_id: SUBNET,
+                validator: ALICE,
+                stake: MIN_VALIDATOR_STAKE,
+            }
+            .into(),
+        );
+
+        assert_ok!(SubnetRegistry::pause_subnet(
+            RuntimeOrigin::signed(OWNER),
+            SUBNET
+        ));
+        assert_noop!(
+            ValidatorRegistry::register_validator(RuntimeOrigin::signed(BOB), SUBNET, 100),
+            Error::<Test>::SubnetPaused
+        );
+
+        assert_ok!(SubnetRegistry::retire_subnet(
+            RuntimeOrigin::signed(OWNER),
+            SUBNET,
+            None
+        ));
+        assert_noop!(
+            ValidatorRegistry::register_validator(RuntimeOrigin::signed(BOB), SUBNET, 100),
+            Error::<Test>::SubnetNotActive
+        );
+    });
+}
+
+#[test]
+fn deregistering_frees_the_slot_and_unreserves_the_stake() {
+    new_test_ext().execute_with(|| {
+        create_subnet();
+        register(ALICE, 200);
+        register(BOB, 100);
+        assert_noop!(
+            ValidatorRegistry::deregister_validator(RuntimeOrigin::signed(DAVE), SUBNET),
+            Error::<Test>::NotRegistered
+        );
+
+        assert_ok!(ValidatorRegistry::deregister_validator(
+            RuntimeOrigin::signed(ALICE),
+            SUBNET
+        ));
+        assert_eq!(ValidatorRegistry::validators(SUBNET, ALICE), None);
+        assert_eq!(ValidatorRegistry::validator_count(SUBNET), 1);
+        assert_eq!(Balances::reserved_balance(ALICE), 0);
+        assert_eq!(Balances::free_balance(ALICE), 1_000);
+        System::assert_last_event(
+            Event::ValidatorDeregistered {
+                subnet_id: SUBNET,
+                validator: ALICE,
+                stake: 200,
+            }
+            .into(),
+        );
+
+        // The freed slot is taken without evicting anyone.
+        Balances::make_free_balance_be(&DAVE, 1_000);
+        register(DAVE, MIN_VALIDATOR_STAKE);
+        assert_eq!(
+            ValidatorRegistry::active_validators(SUBNET),
+            vec![(BOB, 100), (DAVE, MIN_VALIDATOR_STAKE)]
+        );
+    });
+}
+
+#[test]
+fn full_slots_evict_the_lowest_stake_for_a_higher_one() {
+    new_test_ext().execute_with(|| {
+        create_subnet();
+        Balances::make_free_balance_be(&DAVE, 1_000);
+        register(ALICE, 200);
+        register(BOB, 100);
+        assert_eq!(
+            ValidatorRegistry::validator_count(SUBNET),
+            MAX_VALIDATORS_PER_SUBNET
+        );
+
+        // Matching the lowest stake is not enough.
+        assert_noop!(
+            ValidatorRegistry::register_validator(RuntimeOrigin::signed(DAVE), SUBNET, 100),
+            Error::<Test>::ValidatorSlotsFull
+        );
+
+        register(DAVE, 150);
+        assert_eq!(ValidatorRegistry::validators(SUBNET, BOB), None);
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+        assert_eq!(Balances::reserved_balance(DAVE), 150);
+        assert_eq!(
+            ValidatorRegistry::validator_count(SUBNET),
+            MAX_VALIDATORS_PER_SUBNET
+        );
+        System::assert_has_event(
+            Event::ValidatorEvicted {
+                subnet_id: SUBNET,
+                validator: BOB,
+                stake: 100,
+            }
+            .into(),
+        );
+        assert_eq!(
+            ValidatorRegistry::active_validators(SUBNET),
+            vec![(ALICE, 200), (DAVE, 150)]
+        );
+    });
+}
+
+#[test]
+fn active_validators_are_sorted_by_stake() {
+    new_test_ext().execute_with(|| {
+        create_subnet();
+        register(BOB, 60);
+        register(ALICE, 300);
+        assert_eq!(
+            ValidatorRegistry::active_validators(SUBNET),
+            vec![(ALICE, 300), (BOB, 60)]
+        );
+
+        for epoch in 0..MAX_MISSED_EPOCHS {
+            set_weights(ALICE);
+            finish_epoch(epoch);
+        }
+        assert_eq!(
+            ValidatorRegistry::active_validators(SUBNET),
+            vec![(ALICE, 300)]
+        );
+        assert_eq!(ValidatorRegistry::active_validators(SUBNET + 1), vec![]);
+    });
+}
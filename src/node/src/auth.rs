@@ -0,0 +1,201 @@
+//! Account handshake for subnet topics.
+//!
+//! A node proves which on‑chain account operates it by signing its
+//! own peer id with the account's sr25519 key.  Right after the first
+//! connection to a peer is established it sends that
+//! [`AccountProof`] over a request‑response protocol.  The receiving
+//! node checks the signature against the peer id the connection was
+//! authenticated as, so a proof cannot be replayed by another peer,
+//! and asks its [`AccountResolver`] which account the key belongs to.
+//! With `require_authenticated_handshake` set, gossipsub messages
+//! forwarded by peers that have not been mapped to an account are
+//! rejected.
+//!
+//! A request is the 32 byte public key followed by the 64 byte
+//! signature; the response is a single byte, 1 if the proof was
+//! accepted and 0 otherwise.
+
+use std::sync::Arc;
+use std::{fmt, io};
+
+use async_trait::async_trait;
+use futures::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use libp2p::request_response::{self, ProtocolName};
+use libp2p::PeerId;
+use schnorrkel::{ExpansionMode, MiniSecretKey, PublicKey, Signature};
+
+/// Protocol name negotiated for the account handshake.
+pub const PROTOCOL_NAME: &str = "/neuromesh/account/1.0.0";
+
+/// Signing context of account proofs, the one Substrate uses for
+/// sr25519 signatures.
+pub const SIGNING_CONTEXT: &[u8] = b"substrate";
+
+/// On‑chain account a peer was mapped to.
+pub type AccountId = [u8; 32];
+
+/// The sr25519 key of the account operating this node.
+#[derive(Clone)]
+pub struct AccountKey(schnorrkel::Keypair);
+
+impl AccountKey {
+    /// The key Substrate derives from the 32 byte `seed`.
+    pub fn from_seed(seed: &[u8; 32]) -> Self {
+        let secret = MiniSecretKey::from_bytes(seed).expect("seed is 32 bytes");
+        Self(secret.expand_to_keypair(ExpansionMode::Ed25519))
+    }
+
+    pub fn public(&self) -> [u8; 32] {
+        self.0.public.to_bytes()
+    }
+}
+
+impl fmt::Debug for AccountKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("AccountKey").field(&self.public()).finish()
+    }
+}
+
+/// An sr25519 signature over a peer id, proving that the holder of
+/// `public` operates that peer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AccountProof {
+    pub public: [u8; 32],
+    pub signature: [u8; 64],
+}
+
+impl AccountProof {
+    /// Sign `peer` with `key`.
+    pub fn sign(key: &AccountKey, peer: &PeerId) -> Self {
+        let context = schnorrkel::signing_context(SIGNING_CONTEXT);
+        Self {
+            public: key.public(),
+            signature: key.0.sign(context.bytes(&peer.to_bytes())).to_bytes(),
+        }
+    }
+
+    /// Whether this proof was made for `peer`.
+    pub fn verify(&self, peer: &PeerId) -> bool {
+        let (Ok(public), Ok(signature)) = (
+            PublicKey::from_bytes(&self.public),
+            Signature::from_bytes(&self.signature),
+        ) else {
+            return false;
+        };
+        let context = schnorrkel::signing_context(SIGNING_CONTEXT);
+        public
+            .verify(context.bytes(&peer.to_bytes()), &signature)
+            .is_ok()
+    }
+}
+
+/// Maps the public key of a verified proof to the on‑chain account it
+/// belongs to, or to `None` if the key is not a registered account.
+/// Implemented for any suitable closure.
+pub trait AccountResolver: Send + Sync + 'static {
+    fn resolve(&self, public: &[u8; 32]) -> Option<AccountId>;
+}
+
+impl<F> AccountResolver for F
+where
+    F: Fn(&[u8; 32]) -> Option<AccountId> + Send + Sync + 'static,
+{
+    fn resolve(&self, public: &[u8; 32]) -> Option<AccountId> {
+        self(public)
+    }
+}
+
+/// Key to prove our own account with, if any, and resolver to map
+/// peers' proofs to accounts.
+#[derive(Clone)]
+pub struct AccountConfig {
+    pub key: Option<AccountKey>,
+    pub resolver: Arc<dyn AccountResolver>,
+}
+
+impl AccountConfig {
+    pub fn new(key: Option<AccountKey>, resolver: impl AccountResolver) -> Self {
+        Self {
+            key,
+            resolver: Arc::new(resolver),
+        }
+    }
+}
+
+impl fmt::Debug for AccountConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AccountConfig")
+            .field("key", &self.key)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The account handshake protocol.
+#[derive(Clone, Debug, Default)]
+pub struct AccountProtocol;
+
+impl ProtocolName for AccountProtocol {
+    fn protocol_name(&self) -> &[u8] {
+        PROTOCOL_NAME.as_bytes()
+    }
+}
+
+/// Wire codec for [`AccountProof`]s and whether they were accepted.
+#[derive(Clone, Debug, Default)]
+pub struct AccountCodec;
+
+#[async_trait]
+impl request_response::Codec for AccountCodec {
+    type Protocol = AccountProtocol;
+    type Request = AccountProof;
+    type Response = bool;
+
+    async fn read_request<T>(&mut self, _: &AccountProtocol, io: &mut T) -> io::Result<AccountProof>
+    where
+        T: AsyncRead + Unpin + Send,
+    {
+        let mut frame = [0; 96];
+        io.read_exact(&mut frame).await?;
+        let (public, signature) = frame.split_at(32);
+        Ok(AccountProof {
+            public: public.try_into().expect("split at 32 bytes"),
+            signature: signature.try_into().expect("64 bytes remain"),
+        })
+    }
+
+    async fn read_response<T>(&mut self, _: &AccountProtocol, io: &mut T) -> io::Result<bool>
+    where
+        T: AsyncRead + Unpin + Send,
+    {
+        let mut accepted = [0; 1];
+        io.read_exact(&mut accepted).await?;
+        Ok(accepted[0] == 1)
+    }
+
+    async fn write_request<T>(
+        &mut self,
+        _: &AccountProtocol,
+        io: &mut T,
+        proof: AccountProof,
+    ) -> io::Result<()>
+    where
+        T: AsyncWrite + Unpin + Send,
+    {
+        io.write_all(&proof.public).await?;
+        io.write_all(&proof.signature).await?;
+        io.close().await
+    }
+
+    async fn write_response<T>(
+        &mut self,
+        _: &AccountProtocol,
+        io: &mut T,
+        accepted: bool,
+    ) -> io::Result<()>
+    where
+        T: AsyncWrite + Unpin + Send,
+    {
+        io.write_all(&[accepted as u8]).await?;
+        io.close().await
+    }
+}
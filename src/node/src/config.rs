@@ -0,0 +1,236 @@
+//! Node configuration.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use libp2p::multiaddr::Protocol;
+use libp2p::Multiaddr;
+
+use crate::auth::AccountConfig;
+use crate::handshake::HandshakeConfig;
+use crate::task::PROTOCOL_NAME;
+use crate::NodeError;
+
+/// Default time allowed for a single address to connect and complete
+/// the handshake.
+pub const DEFAULT_DIAL_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Default head start given to each address over the next one when a
+/// peer is dialed on several addresses at once.
+pub const DEFAULT_DIAL_STAGGER: Duration = Duration::from_millis(250);
+
+/// Default number of bootstrap attempts made before discovery is
+/// reported unavailable.
+pub const DEFAULT_BOOTSTRAP_ATTEMPTS: u32 = 3;
+
+/// Default delay before the first bootstrap retry.  Later retries
+/// double it.
+pub const DEFAULT_BOOTSTRAP_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Default interval between background bootstrap retries once
+/// discovery is unavailable.
+pub const DEFAULT_BOOTSTRAP_RETRY_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Default largest gossipsub message payload, in bytes.
+pub const DEFAULT_MAX_GOSSIPSUB_MESSAGE_SIZE: usize = 64 * 1024;
+
+/// Default time a peer has to answer a task request.
+pub const DEFAULT_TASK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Default time a node stays subscribed to a subnet's previous topic
+/// after moving to a new schema version.
+pub const DEFAULT_TOPIC_GRACE_PERIOD: Duration = Duration::from_secs(60);
+
+/// Default number of peers a subnet topic needs before
+/// [`Node::publish_to_subnet`](crate::Node::publish_to_subnet)
+/// publishes on it.
+pub const DEFAULT_MIN_SUBNET_PEERS: usize = 1;
+
+/// Default most subnet messages held back waiting for peers.
+pub const DEFAULT_MAX_QUEUED_MESSAGES: usize = 64;
+
+/// Default time a subnet message is held back waiting for peers.
+pub const DEFAULT_QUEUED_MESSAGE_TTL: Duration = Duration::from_secs(30);
+
+/// Default most prioritized messages published per turn of the event
+/// loop.
+pub const DEFAULT_PUBLISH_BURST: usize = 32;
+
+/// Default most inbound connections open at once.
+pub const DEFAULT_MAX_ESTABLISHED_INCOMING: u32 = 128;
+
+/// Default most outbound connections open at once.
+pub const DEFAULT_MAX_ESTABLISHED_OUTGOING: u32 = 64;
+
+/// Default most connections, in each direction, being set up at once.
+pub const DEFAULT_MAX_PENDING: u32 = 32;
+
+/// Caps on the connections a node keeps, so that peers cannot exhaust
+/// its file descriptors.  `None` leaves a count unbounded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ConnectionLimits {
+    /// Most inbound connections open at once.
+    pub max_established_incoming: Option<u32>,
+    /// Most outbound connections open at once.
+    pub max_established_outgoing: Option<u32>,
+    /// Most connections being set up at once, counted separately for
+    /// inbound and outbound ones.
+    pub max_pending: Option<u32>,
+}
+
+impl Default for ConnectionLimits {
+    fn default() -> Self {
+        Self {
+            max_established_incoming: Some(DEFAULT_MAX_ESTABLISHED_INCOMING),
+            max_established_outgoing: Some(DEFAULT_MAX_ESTABLISHED_OUTGOING),
+            max_pending: Some(DEFAULT_MAX_PENDING),
+        }
+    }
+}
+
+/// Settings for a [`Node`](crate::Node).
+#[derive(Clone, Debug)]
+pub struct NodeConfig {
+    /// Addresses [`start_node_with_config`](crate::start_node_with_config)
+    /// listens on: `/ip4` or `/ip6` with `/tcp`, or with the `quic`
+    /// feature `/udp` and `/quic-v1`.  An empty list falls back to
+    /// [`DEFAULT_LISTEN_ADDR`](crate::DEFAULT_LISTEN_ADDR).
+    pub listen_addrs: Vec<Multiaddr>,
+    /// File [`start_node_with_config`](crate::start_node_with_config)
+    /// keeps the node's keypair in, so its peer id survives restarts.
+    /// `None` uses a fresh identity every time.
+    pub key_path: Option<PathBuf>,
+    /// Time after which a dial to one address is abandoned.
+    pub dial_timeout: Duration,
+    /// Delay between starting dials to successive addresses of the
+    /// same peer.
+    pub dial_stagger: Duration,
+    /// Kademlia bootnodes.  Each address must end in `/p2p/<peer id>`.
+    /// With no bootnodes the node relies on mDNS alone.
+    pub bootnodes: Vec<Multiaddr>,
+    /// Relays the node listens through once AutoNAT finds it is not
+    /// publicly reachable.  Each address must end in `/p2p/<peer id>`.
+    pub relay_addrs: Vec<Multiaddr>,
+    /// Whether to discover peers on the local network over mDNS.
+    pub enable_mdns: bool,
+    /// Whether the node stores the Kademlia records other peers put.
+    /// A node that is not a server still answers routing queries.
+    pub kademlia_server: bool,
+    /// Bootstrap attempts made, with exponential backoff, before
+    /// discovery is reported unavailable.
+    pub bootstrap_attempts: u32,
+    /// Delay before the first bootstrap retry.
+    pub bootstrap_backoff: Duration,
+    /// Interval between background retries once discovery is
+    /// unavailable.
+    pub bootstrap_retry_interval: Duration,
+    /// Task dispatch protocol names offered to peers, most preferred
+    /// first.  Later entries are older versions to fall back to when a
+    /// peer does not speak the preferred one.
+    pub task_protocols: Vec<String>,
+    /// Time a peer has to answer a task request before it fails with
+    /// [`OutboundFailure::Timeout`](libp2p::request_response::OutboundFailure::Timeout).
+    pub task_timeout: Duration,
+    /// Largest gossipsub message payload, in bytes, the node publishes
+    /// or accepts.  Peers sending larger messages are penalized.
+    pub max_gossipsub_message_size: usize,
+    /// Time [`Node::resubscribe_subnet_version`](crate::Node::resubscribe_subnet_version)
+    /// keeps the previous topic of a subnet, so messages in flight on
+    /// it are still received.
+    pub topic_grace_period: Duration,
+    /// Peers that must be subscribed to a subnet topic before
+    /// [`Node::publish_to_subnet`](crate::Node::publish_to_subnet)
+    /// publishes on it.
+    pub min_subnet_peers: usize,
+    /// Most messages
+    /// [`Node::publish_to_subnet_or_queue`](crate::Node::publish_to_subnet_or_queue)
+    /// holds back while their subnet topic lacks peers, and separately
+    /// most messages [`Node::publish_with_priority`](crate::Node::publish_with_priority)
+    /// holds back waiting for their turn.
+    pub max_queued_messages: usize,
+    /// Time a held‑back subnet message waits for peers before it is
+    /// dropped.
+    pub queued_message_ttl: Duration,
+    /// Most messages
+    /// [`Node::publish_with_priority`](crate::Node::publish_with_priority)
+    /// publishes per turn of the event loop.  Messages beyond it wait
+    /// for the next turn, highest priority first.
+    pub publish_burst: usize,
+    /// Connections beyond these limits are refused and reported as
+    /// [`NodeEvent::ConnectionRejected`](crate::NodeEvent::ConnectionRejected).
+    pub connection_limits: ConnectionLimits,
+    /// Credential handshake run on every connection.  `None` accepts
+    /// any authenticated peer.
+    pub handshake: Option<HandshakeConfig>,
+    /// Account key to prove to peers and resolver to map their proofs
+    /// to on‑chain accounts, see [`auth`](crate::auth).
+    pub account: Option<AccountConfig>,
+    /// Reject gossipsub messages forwarded by peers that have not
+    /// proven an on‑chain account.  Without an `account` resolver no
+    /// peer can prove one.
+    pub require_authenticated_handshake: bool,
+    /// Run as a passive observer that listens, discovers and receives
+    /// gossip but never dials or publishes.  Peers must connect to
+    /// it, and bootnodes are not contacted.
+    pub observer_mode: bool,
+    /// Faults injected into every connection, to test how the node
+    /// copes with an unreliable network.
+    #[cfg(feature = "testing")]
+    pub faults: crate::fault::FaultConfig,
+}
+
+impl Default for NodeConfig {
+    fn default() -> Self {
+        Self {
+            listen_addrs: Vec::new(),
+            key_path: None,
+            dial_timeout: DEFAULT_DIAL_TIMEOUT,
+            dial_stagger: DEFAULT_DIAL_STAGGER,
+            bootnodes: Vec::new(),
+            relay_addrs: Vec::new(),
+            enable_mdns: true,
+            kademlia_server: true,
+            bootstrap_attempts: DEFAULT_BOOTSTRAP_ATTEMPTS,
+            bootstrap_backoff: DEFAULT_BOOTSTRAP_BACKOFF,
+            bootstrap_retry_interval: DEFAULT_BOOTSTRAP_RETRY_INTERVAL,
+            task_protocols: vec![PROTOCOL_NAME.to_string()],
+            task_timeout: DEFAULT_TASK_TIMEOUT,
+            max_gossipsub_message_size: DEFAULT_MAX_GOSSIPSUB_MESSAGE_SIZE,
+            topic_grace_period: DEFAULT_TOPIC_GRACE_PERIOD,
+            min_subnet_peers: DEFAULT_MIN_SUBNET_PEERS,
+            max_queued_messages: DEFAULT_MAX_QUEUED_MESSAGES,
+            queued_message_ttl: DEFAULT_QUEUED_MESSAGE_TTL,
+            publish_burst: DEFAULT_PUBLISH_BURST,
+            connection_limits: ConnectionLimits::default(),
+            handshake: None,
+            account: None,
+            require_authenticated_handshake: false,
+            observer_mode: false,
+            #[cfg(feature = "testing")]
+            faults: Default::default(),
+        }
+    }
+}
+
+/// Check that the node's transport can listen on `addr`: an IPv4 or
+/// IPv6 address followed by a TCP port, or with the `quic` feature a
+/// UDP port and `/quic-v1`.
+pub(crate) fn validate_listen_addr(addr: &Multiaddr) -> Result<(), NodeError> {
+    let mut protocols = addr.iter();
+    match (
+        protocols.next(),
+        protocols.next(),
+        protocols.next(),
+        protocols.next(),
+    ) {
+        (Some(Protocol::Ip4(_) | Protocol::Ip6(_)), Some(Protocol::Tcp(_)), None, None) => Ok(()),
+        #[cfg(feature = "quic")]
+        (
+            Some(Protocol::Ip4(_) | Protocol::Ip6(_)),
+            Some(Protocol::Udp(_)),
+            Some(Protocol::QuicV1),
+            None,
+        ) => Ok(()),
+        _ => Err(NodeError::InvalidListenAddr(addr.clone())),
+    }
+}
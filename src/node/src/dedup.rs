@@ -0,0 +1,87 @@
+//! Suppression of duplicate gossipsub payloads.
+//!
+//! Gossipsub deduplicates on its own message id, which is derived from
+//! the publisher and sequence number.  When several peers publish the
+//! same payload (for example two validators relaying one weight
+//! vector) it is delivered once per publisher.  [`DuplicateFilter`]
+//! remembers a bounded window of recently delivered payloads so the
+//! node hands each one to the application only once, and counts the
+//! copies it dropped.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashSet, VecDeque};
+use std::hash::{Hash, Hasher};
+
+use libp2p::gossipsub::TopicHash;
+use prometheus_client::metrics::counter::Counter;
+use prometheus_client::registry::Registry;
+
+/// Default number of payload ids remembered by a node.
+pub const DEFAULT_CAPACITY: usize = 4096;
+
+/// Bounded set of recently seen `(topic, payload)` pairs.
+#[derive(Debug)]
+pub struct DuplicateFilter {
+    capacity: usize,
+    seen: HashSet<u64>,
+    order: VecDeque<u64>,
+    suppressed: Counter,
+}
+
+impl DuplicateFilter {
+    /// Create a filter remembering at most `capacity` payloads.  Once
+    /// full, the oldest payload is forgotten first.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            seen: HashSet::new(),
+            order: VecDeque::new(),
+            suppressed: Counter::default(),
+        }
+    }
+
+    /// Register the suppression counter with a metrics registry.
+    pub fn register(&self, registry: &mut Registry) {
+        registry.register(
+            "gossipsub_duplicate_messages_suppressed",
+            "Gossipsub messages dropped because the same payload was already delivered",
+            self.suppressed.clone(),
+        );
+    }
+
+    /// Record a received message.  Returns `true` if the payload is new
+    /// and should be delivered, or `false` if it is a duplicate that
+    /// was suppressed.
+    pub fn check(&mut self, topic: &TopicHash, data: &[u8]) -> bool {
+        let id = payload_id(topic, data);
+        if !self.seen.insert(id) {
+            self.suppressed.inc();
+            return false;
+        }
+        self.order.push_back(id);
+        if self.order.len() > self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.seen.remove(&oldest);
+            }
+        }
+        true
+    }
+
+    /// Number of duplicates suppressed so far.
+    pub fn suppressed(&self) -> u64 {
+        self.suppressed.get()
+    }
+}
+
+impl Default for DuplicateFilter {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+fn payload_id(topic: &TopicHash, data: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    topic.hash(&mut hasher);
+    data.hash(&mut hasher);
+    hasher.finish()
+}
@@ -0,0 +1,173 @@
+//! Happy‑eyeballs dialing.
+//!
+//! When a peer is dialed with several addresses the swarm starts one
+//! transport dial per address and keeps the first connection that
+//! succeeds, dropping (and so aborting) the others.  [`HappyEyeballs`]
+//! staggers those concurrent dials so that the first address gets a
+//! head start, and bounds each address by its own timeout so a dead
+//! address cannot hold up the race.
+
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures::future::{self, BoxFuture, Either, FutureExt, MapErr, TryFutureExt};
+use futures_timer::Delay;
+use libp2p::core::transport::{
+    timeout::TransportTimeoutError, ListenerId, TransportError, TransportEvent,
+};
+use libp2p::multiaddr::Protocol;
+use libp2p::{Multiaddr, PeerId, Transport};
+
+type InFlight = Arc<Mutex<HashMap<PeerId, usize>>>;
+
+/// Transport wrapper that staggers concurrent dials to the same peer
+/// by `stagger` and fails each one after `dial_timeout`.
+pub struct HappyEyeballs<T> {
+    inner: T,
+    stagger: Duration,
+    dial_timeout: Duration,
+    in_flight: InFlight,
+}
+
+impl<T> HappyEyeballs<T> {
+    pub fn new(inner: T, stagger: Duration, dial_timeout: Duration) -> Self {
+        Self {
+            inner,
+            stagger,
+            dial_timeout,
+            in_flight: InFlight::default(),
+        }
+    }
+}
+
+/// Position of a dial among those racing to the same peer.  Dropping
+/// it, either because the dial finished or because it was aborted,
+/// frees the position.
+struct RaceSlot {
+    peer: Option<PeerId>,
+    position: usize,
+    in_flight: InFlight,
+}
+
+impl RaceSlot {
+    fn claim(addr: &Multiaddr, in_flight: &InFlight) -> Self {
+        let peer = match addr.iter().last() {
+            Some(Protocol::P2p(hash)) => PeerId::from_multihash(hash).ok(),
+            _ => None,
+        };
+        let position = peer.map_or(0, |peer| {
+            let mut in_flight = in_flight.lock().expect("lock poisoned");
+            let count = in_flight.entry(peer).or_default();
+            *count += 1;
+            *count - 1
+        });
+        Self {
+            peer,
+            position,
+            in_flight: in_flight.clone(),
+        }
+    }
+}
+
+impl Drop for RaceSlot {
+    fn drop(&mut self) {
+        let Some(peer) = self.peer else { return };
+        let mut in_flight = self.in_flight.lock().expect("lock poisoned");
+        if let Some(count) = in_flight.get_mut(&peer) {
+            *count -= 1;
+            if *count == 0 {
+                in_flight.remove(&peer);
+            }
+        }
+    }
+}
+
+impl<T> HappyEyeballs<T>
+where
+    T: Transport,
+    T::Dial: Send + 'static,
+    T::Output: Send + 'static,
+    T::Error: Send + 'static,
+{
+    fn race(
+        &self,
+        slot: RaceSlot,
+        dial: T::Dial,
+    ) -> BoxFuture<'static, Result<T::Output, TransportTimeoutError<T::Error>>> {
+        let head_start = self.stagger * slot.position as u32;
+        let dial_timeout = self.dial_timeout;
+        async move {
+            let _slot = slot;
+            Delay::new(head_start).await;
+            match future::select(dial.boxed(), Delay::new(dial_timeout)).await {
+                Either::Left((result, _)) => result.map_err(TransportTimeoutError::Other),
+                Either::Right(_) => Err(TransportTimeoutError::Timeout),
+            }
+        }
+        .boxed()
+    }
+}
+
+impl<T> Transport for HappyEyeballs<T>
+where
+    T: Transport + Unpin,
+    T::Dial: Send + 'static,
+    T::Output: Send + 'static,
+    T::Error: Send + 'static,
+{
+    type Output = T::Output;
+    type Error = TransportTimeoutError<T::Error>;
+    type ListenerUpgrade = MapErr<T::ListenerUpgrade, fn(T::Error) -> Self::Error>;
+    type Dial = BoxFuture<'static, Result<T::Output, Self::Error>>;
+
+    fn listen_on(&mut self, addr: Multiaddr) -> Result<ListenerId, TransportError<Self::Error>> {
+        self.inner
+            .listen_on(addr)
+            .map_err(|err| err.map(TransportTimeoutError::Other))
+    }
+
+    fn remove_listener(&mut self, id: ListenerId) -> bool {
+        self.inner.remove_listener(id)
+    }
+
+    fn dial(&mut self, addr: Multiaddr) -> Result<Self::Dial, TransportError<Self::Error>> {
+        let slot = RaceSlot::claim(&addr, &self.in_flight);
+        let dial = self
+            .inner
+            .dial(addr)
+            .map_err(|err| err.map(TransportTimeoutError::Other))?;
+        Ok(self.race(slot, dial))
+    }
+
+    fn dial_as_listener(
+        &mut self,
+        addr: Multiaddr,
+    ) -> Result<Self::Dial, TransportError<Self::Error>> {
+        let slot = RaceSlot::claim(&addr, &self.in_flight);
+        let dial = self
+            .inner
+            .dial_as_listener(addr)
+            .map_err(|err| err.map(TransportTimeoutError::Other))?;
+        Ok(self.race(slot, dial))
+    }
+
+    fn poll(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<TransportEvent<Self::ListenerUpgrade, Self::Error>> {
+        Pin::new(&mut self.inner).poll(cx).map(|event| {
+            event
+                .map_upgrade(|upgrade| {
+                    upgrade.map_err(TransportTimeoutError::Other as fn(T::Error) -> Self::Error)
+                })
+                .map_err(TransportTimeoutError::Other)
+        })
+    }
+
+    fn address_translation(&self, listen: &Multiaddr, observed: &Multiaddr) -> Option<Multiaddr> {
+        self.inner.address_translation(listen, observed)
+    }
+}
@@ -0,0 +1,69 @@
+//! Kademlia bootstrap bookkeeping.
+//!
+//! A node joins the wider network by bootstrapping Kademlia through
+//! its configured bootnodes.  If none of them answer, the node keeps
+//! working with whatever peers mDNS finds on the local network, but
+//! it reports that wide‑area discovery is unavailable and keeps
+//! retrying in the background.  [`Bootstrap`] decides when to retry
+//! and when the health of discovery changes; the node performs the
+//! actual queries.
+
+use std::time::Duration;
+
+/// Outcome of a bootstrap attempt that the node should report.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum HealthChange {
+    Lost,
+    Restored,
+}
+
+/// Retry schedule and health state for Kademlia bootstrap.
+#[derive(Debug)]
+pub(crate) struct Bootstrap {
+    max_attempts: u32,
+    backoff: Duration,
+    retry_interval: Duration,
+    failed_attempts: u32,
+    healthy: bool,
+}
+
+impl Bootstrap {
+    pub(crate) fn new(max_attempts: u32, backoff: Duration, retry_interval: Duration) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            backoff,
+            retry_interval,
+            failed_attempts: 0,
+            healthy: true,
+        }
+    }
+
+    pub(crate) fn is_healthy(&self) -> bool {
+        self.healthy
+    }
+
+    /// Record a successful bootstrap.
+    pub(crate) fn succeeded(&mut self) -> Option<HealthChange> {
+        self.failed_attempts = 0;
+        if self.healthy {
+            return None;
+        }
+        self.healthy = true;
+        Some(HealthChange::Restored)
+    }
+
+    /// Record a failed bootstrap.  Returns how long to wait before the
+    /// next attempt and whether discovery just became unavailable.
+    pub(crate) fn failed(&mut self) -> (Duration, Option<HealthChange>) {
+        self.failed_attempts = self.failed_attempts.saturating_add(1);
+        if self.failed_attempts < self.max_attempts {
+            let exponent = (self.failed_attempts - 1).min(16);
+            return (self.backoff * 2u32.pow(exponent), None);
+        }
+        if self.healthy {
+            self.healthy = false;
+            return (self.retry_interval, Some(HealthChange::Lost));
+        }
+        (self.retry_interval, None)
+    }
+}
@@ -0,0 +1,196 @@
+//! Errors returned by the node.
+
+use std::{fmt, io};
+
+use libp2p::{
+    gossipsub, noise,
+    request_response::{OutboundFailure, RequestId},
+    swarm::DialError,
+    Multiaddr, PeerId, TransportError,
+};
+
+use crate::handshake::MAX_CREDENTIAL_LEN;
+
+/// Errors that can occur while building or operating a [`Node`](crate::Node).
+#[derive(Debug)]
+pub enum NodeError {
+    /// A socket or interface could not be set up.
+    Io(io::Error),
+    /// The noise handshake keys could not be derived from the identity.
+    Noise(noise::Error),
+    /// The gossipsub configuration was rejected.
+    Gossipsub(&'static str),
+    /// The transport could not listen on or dial an address.
+    Transport(TransportError<io::Error>),
+    /// Subscribing to a topic failed.
+    Subscription(gossipsub::SubscriptionError),
+    /// Publishing a message failed.
+    Publish(gossipsub::PublishError),
+    /// A peer could not be dialed.
+    Dial(DialError),
+    /// Probing this address failed or timed out.
+    Unreachable(Multiaddr),
+    /// No task request with this id is awaiting a response.
+    UnknownTaskRequest(RequestId),
+    /// The connection a task request arrived on is gone.
+    TaskResponseFailed(RequestId),
+    /// A bootnode address does not end in `/p2p/<peer id>`.
+    InvalidBootnode(Multiaddr),
+    /// A relay address does not end in `/p2p/<peer id>`.
+    InvalidRelayAddr(Multiaddr),
+    /// The node cannot listen on this address.  Only `/ip4` or `/ip6`
+    /// addresses followed by `/tcp` are supported.
+    InvalidListenAddr(Multiaddr),
+    /// The node could not listen on some of its configured addresses.
+    /// Each is listed with the reason.
+    ListenFailed(Vec<(Multiaddr, NodeError)>),
+    /// A gossipsub message of this many bytes exceeds the configured
+    /// limit and was not published.
+    MessageTooLarge(usize),
+    /// The node is in observer mode, which neither dials nor
+    /// publishes.
+    ObserverMode,
+    /// Fewer than the `required` peers are subscribed to the subnet
+    /// topic, so the message was not published.
+    InsufficientPeers { required: usize, present: usize },
+    /// The outbox of messages waiting for subnet peers is full.
+    PublishQueueFull,
+    /// A handshake credential of this many bytes exceeds
+    /// [`MAX_CREDENTIAL_LEN`](crate::handshake::MAX_CREDENTIAL_LEN).
+    CredentialTooLong(usize),
+    /// The node behind a [`NodeHandle`](crate::NodeHandle) has stopped.
+    NodeStopped,
+}
+
+impl fmt::Display for NodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NodeError::Io(err) => write!(f, "i/o error: {err}"),
+            NodeError::Noise(err) => write!(f, "noise error: {err}"),
+            NodeError::Gossipsub(err) => write!(f, "invalid gossipsub configuration: {err}"),
+            NodeError::Transport(err) => write!(f, "transport error: {err}"),
+            NodeError::Subscription(err) => write!(f, "failed to subscribe: {err:?}"),
+            NodeError::Publish(err) => write!(f, "failed to publish: {err:?}"),
+            NodeError::Dial(err) => write!(f, "failed to dial: {err}"),
+            NodeError::Unreachable(addr) => write!(f, "{addr} is unreachable"),
+            NodeError::UnknownTaskRequest(id) => write!(f, "no pending task request {id}"),
+            NodeError::TaskResponseFailed(id) => {
+                write!(f, "could not respond to task request {id}")
+            }
+            NodeError::InvalidBootnode(addr) => {
+                write!(f, "bootnode address {addr} has no peer id")
+            }
+            NodeError::InvalidRelayAddr(addr) => {
+                write!(f, "relay address {addr} has no peer id")
+            }
+            NodeError::InvalidListenAddr(addr) => write!(
+                f,
+                "cannot listen on {addr}: expected an /ip4 or /ip6 address followed by /tcp"
+            ),
+            NodeError::MessageTooLarge(size) => {
+                write!(
+                    f,
+                    "message of {size} bytes exceeds the gossipsub size limit"
+                )
+            }
+            NodeError::ObserverMode => write!(f, "observer nodes neither dial nor publish"),
+            NodeError::InsufficientPeers { required, present } => write!(
+                f,
+                "subnet topic has {present} peers, {required} are required to publish"
+            ),
+            NodeError::PublishQueueFull => write!(f, "too many messages are waiting for peers"),
+            NodeError::CredentialTooLong(len) => write!(
+                f,
+                "credential of {len} bytes exceeds {MAX_CREDENTIAL_LEN} bytes"
+            ),
+            NodeError::NodeStopped => write!(f, "the node has stopped"),
+            NodeError::ListenFailed(failures) => {
+                write!(f, "failed to listen on")?;
+                for (i, (addr, err)) in failures.iter().enumerate() {
+                    let separator = if i == 0 { " " } else { "; " };
+                    write!(f, "{separator}{addr} ({err})")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for NodeError {}
+
+/// Why [`Node::send_task`](crate::Node::send_task) got no response.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SendError {
+    /// No connection to the peer is open.
+    NotConnected(PeerId),
+    /// The peer did not answer within the configured task timeout.
+    Timeout,
+    /// The connection closed before the peer answered.
+    ConnectionClosed,
+    /// The peer speaks none of our task protocol versions.
+    UnsupportedProtocols,
+}
+
+impl SendError {
+    pub(crate) fn from_failure(peer: PeerId, failure: OutboundFailure) -> Self {
+        match failure {
+            OutboundFailure::DialFailure => SendError::NotConnected(peer),
+            OutboundFailure::Timeout => SendError::Timeout,
+            OutboundFailure::ConnectionClosed => SendError::ConnectionClosed,
+            OutboundFailure::UnsupportedProtocols => SendError::UnsupportedProtocols,
+        }
+    }
+}
+
+impl fmt::Display for SendError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SendError::NotConnected(peer) => write!(f, "not connected to {peer}"),
+            SendError::Timeout => write!(f, "the peer did not answer in time"),
+            SendError::ConnectionClosed => {
+                write!(f, "the connection closed before the peer answered")
+            }
+            SendError::UnsupportedProtocols => {
+                write!(f, "the peer speaks no task protocol we offer")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SendError {}
+
+impl From<io::Error> for NodeError {
+    fn from(err: io::Error) -> Self {
+        NodeError::Io(err)
+    }
+}
+
+impl From<noise::Error> for NodeError {
+    fn from(err: noise::Error) -> Self {
+        NodeError::Noise(err)
+    }
+}
+
+impl From<TransportError<io::Error>> for NodeError {
+    fn from(err: TransportError<io::Error>) -> Self {
+        NodeError::Transport(err)
+    }
+}
+
+impl From<gossipsub::SubscriptionError> for NodeError {
+    fn from(err: gossipsub::SubscriptionError) -> Self {
+        NodeError::Subscription(err)
+    }
+}
+
+impl From<gossipsub::PublishError> for NodeError {
+    fn from(err: gossipsub::PublishError) -> Self {
+        NodeError::Publish(err)
+    }
+}
+
+impl From<DialError> for NodeError {
+    fn from(err: DialError) -> Self {
+        NodeError::Dial(err)
+    }
+}
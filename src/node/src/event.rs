@@ -0,0 +1,171 @@
+//! Events surfaced by a running [`Node`](crate::Node).
+//!
+//! The node translates swarm events into [`NodeEvent`]s and hands a
+//! copy to every subscriber.  Subscribers that only care about task
+//! traffic can ask for [`TaskEvent`]s alone, which are derived from
+//! the same flow.
+
+use futures::channel::mpsc;
+use libp2p::kad::QueryId;
+use libp2p::request_response::{OutboundFailure, RequestId};
+use libp2p::{gossipsub, Multiaddr, PeerId};
+
+use crate::auth::AccountId;
+use crate::nat::NatStatus;
+use crate::task::{TaskRequest, TaskResponse};
+
+/// Number of events buffered per subscriber.  Events are dropped for
+/// a subscriber that falls further behind than this.
+pub const EVENT_CHANNEL_CAPACITY: usize = 256;
+
+/// Something that happened on the node.
+#[derive(Clone, Debug)]
+pub enum NodeEvent {
+    /// The node started listening on a new address.
+    NewListenAddr { address: Multiaddr },
+    /// `peer` was found over mDNS at `addresses`.
+    PeerDiscovered {
+        peer: PeerId,
+        addresses: Vec<Multiaddr>,
+    },
+    /// The mDNS announcement of `peer` expired.
+    PeerExpired { peer: PeerId },
+    /// A connection to `peer` was opened.
+    ConnectionEstablished { peer: PeerId },
+    /// A connection to `peer` was closed.
+    ConnectionClosed { peer: PeerId },
+    /// A connection was refused because it would have exceeded the
+    /// configured [`ConnectionLimits`](crate::ConnectionLimits).
+    /// Inbound connections carry the remote `address`, outbound ones
+    /// the `peer` dialed if it is known.
+    ConnectionRejected {
+        peer: Option<PeerId>,
+        address: Option<Multiaddr>,
+        reason: String,
+    },
+    /// A new gossipsub message, forwarded by `source`.
+    Message {
+        source: PeerId,
+        message: gossipsub::Message,
+    },
+    /// The grace period of a subnet's previous topic ended and the
+    /// node left it.
+    SubnetTopicLeft { subnet_id: u32, schema_version: u32 },
+    /// A message held back by
+    /// [`publish_to_subnet_or_queue`](crate::Node::publish_to_subnet_or_queue)
+    /// was dropped because too few peers joined its topic in time.
+    QueuedMessageExpired { subnet_id: u32, schema_version: u32 },
+    /// Task dispatch traffic.
+    Task(TaskEvent),
+    /// Kademlia bootstrap failed through every retry.  The node keeps
+    /// serving local and inbound peers and retries in the background.
+    DiscoveryUnavailable,
+    /// A background bootstrap retry succeeded.
+    DiscoveryRestored,
+    /// Kademlia added `peer` to its routing table, or learned a new
+    /// address for it.  `addresses` lists every known address of the
+    /// peer.
+    RoutingUpdated {
+        peer: PeerId,
+        is_new_peer: bool,
+        addresses: Vec<Multiaddr>,
+    },
+    /// `peer` identified itself.  `observed_addr` is the address it
+    /// sees us connecting from, which the node adds to its external
+    /// address candidates.  Compare `protocol_version` with
+    /// [`IDENTIFY_PROTOCOL_VERSION`](crate::IDENTIFY_PROTOCOL_VERSION)
+    /// to spot peers running an incompatible protocol.
+    PeerIdentified {
+        peer: PeerId,
+        protocol_version: String,
+        agent_version: String,
+        listen_addrs: Vec<Multiaddr>,
+        observed_addr: Multiaddr,
+    },
+    /// AutoNAT changed its verdict on whether the node is publicly
+    /// reachable.  A private node listens through its configured
+    /// relays until it becomes public again.
+    NatStatusChanged { status: NatStatus },
+    /// A [`get_closest_peers`](crate::Node::get_closest_peers) query
+    /// finished.  `peers` are the closest peers found, even if the
+    /// query timed out.
+    ClosestPeers { query: QueryId, peers: Vec<PeerId> },
+    /// A task could not be sent to `peer` because it speaks none of
+    /// the task protocol versions we offer.  `wanted` lists ours and
+    /// `available` the task protocols the peer reported through
+    /// identify.
+    ProtocolMismatch {
+        peer: PeerId,
+        wanted: Vec<String>,
+        available: Vec<String>,
+    },
+    /// `peer` sent a gossipsub message of `size` bytes, over the
+    /// configured limit.  The message was rejected and the peer's
+    /// score lowered.
+    OversizedMessageRejected { peer: PeerId, size: usize },
+    /// `peer` presented a credential our handshake validator refused
+    /// and its connection was dropped.
+    HandshakeRejected { peer: PeerId },
+    /// `peer` proved that `account` operates it.
+    PeerAuthenticated { peer: PeerId, account: AccountId },
+    /// `peer` forwarded a gossipsub message without having proven an
+    /// on‑chain account.  The message was rejected and the peer's
+    /// score lowered.
+    UnauthenticatedMessageRejected { peer: PeerId },
+}
+
+/// Task dispatch traffic seen by the node.
+#[derive(Clone, Debug)]
+pub enum TaskEvent {
+    /// `peer` sent us a task.  Answer it with
+    /// [`Node::respond_to_task`](crate::Node::respond_to_task).
+    TaskRequestReceived {
+        peer: PeerId,
+        request_id: RequestId,
+        request: TaskRequest,
+    },
+    /// `peer` answered a task we sent.
+    TaskResponseReceived {
+        peer: PeerId,
+        request_id: RequestId,
+        response: TaskResponse,
+    },
+    /// A task we sent to `peer` did not get an answer.
+    TaskRequestFailed {
+        peer: PeerId,
+        request_id: RequestId,
+        error: OutboundFailure,
+    },
+}
+
+/// Fan‑out of events to any number of bounded subscriber channels.
+#[derive(Debug)]
+pub(crate) struct Subscribers<T> {
+    senders: Vec<mpsc::Sender<T>>,
+}
+
+impl<T: Clone> Subscribers<T> {
+    pub(crate) fn subscribe(&mut self) -> mpsc::Receiver<T> {
+        let (sender, receiver) = mpsc::channel(EVENT_CHANNEL_CAPACITY);
+        self.senders.push(sender);
+        receiver
+    }
+
+    /// Send `event` to every subscriber, forgetting those whose
+    /// receiver has been dropped.
+    pub(crate) fn notify(&mut self, event: &T) {
+        self.senders
+            .retain_mut(|sender| match sender.try_send(event.clone()) {
+                Ok(()) => true,
+                Err(err) => !err.is_disconnected(),
+            });
+    }
+}
+
+impl<T> Default for Subscribers<T> {
+    fn default() -> Self {
+        Self {
+            senders: Vec::new(),
+        }
+    }
+}
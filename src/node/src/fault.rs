@@ -0,0 +1,211 @@
+//! Fault injection for resilience tests.
+//!
+//! [`FaultInjection`] wraps the raw TCP transport, below encryption and
+//! multiplexing, and degrades the connections it produces: each one is
+//! dropped before it is handed on with probability
+//! [`FaultConfig::drop_rate`], every write is held back by
+//! [`FaultConfig::latency`] and each chunk read has one bit flipped
+//! with probability [`FaultConfig::corruption_rate`].  Faults are drawn
+//! from a generator seeded with [`FaultConfig::seed`], so a test sees
+//! the same sequence of faults on every run.
+
+use std::io;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures::future::{BoxFuture, FutureExt};
+use futures::{AsyncRead, AsyncWrite};
+use futures_timer::Delay;
+use libp2p::core::transport::{ListenerId, TransportError, TransportEvent};
+use libp2p::{Multiaddr, Transport};
+
+/// Faults injected into every connection.  The default injects none.
+#[derive(Clone, Debug, Default)]
+pub struct FaultConfig {
+    /// Delay added to connection setup and to every write.
+    pub latency: Duration,
+    /// Probability, from 0 to 1, that a new connection is dropped.
+    pub drop_rate: f64,
+    /// Probability, from 0 to 1, that a chunk read from a connection
+    /// is corrupted.
+    pub corruption_rate: f64,
+    /// Seed of the generator faults are drawn from.
+    pub seed: u64,
+}
+
+/// SplitMix64, enough to draw faults from and reproducible from its
+/// seed.
+#[derive(Clone, Debug)]
+struct Rng(u64);
+
+impl Rng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// `true` with probability `rate`.
+    fn chance(&mut self, rate: f64) -> bool {
+        rate > 0.0 && ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < rate
+    }
+}
+
+/// Transport wrapper that injects the faults described by a
+/// [`FaultConfig`] into the connections of `T`.
+pub struct FaultInjection<T> {
+    inner: T,
+    config: FaultConfig,
+    rng: Arc<Mutex<Rng>>,
+}
+
+impl<T> FaultInjection<T> {
+    pub fn new(inner: T, config: FaultConfig) -> Self {
+        let rng = Arc::new(Mutex::new(Rng(config.seed)));
+        Self { inner, config, rng }
+    }
+}
+
+impl<T> FaultInjection<T>
+where
+    T: Transport<Error = io::Error>,
+    T::Output: Send + 'static,
+{
+    /// Wait for `connect` and the configured latency, then drop the
+    /// connection or hand it on wrapped in a [`FaultyStream`].
+    fn degrade<F>(&self, connect: F) -> BoxFuture<'static, io::Result<FaultyStream<T::Output>>>
+    where
+        F: std::future::Future<Output = io::Result<T::Output>> + Send + 'static,
+    {
+        let config = self.config.clone();
+        let rng = self.rng.clone();
+        async move {
+            let stream = connect.await?;
+            Delay::new(config.latency).await;
+            let rng = {
+                let mut rng = rng.lock().expect("lock poisoned");
+                if rng.chance(config.drop_rate) {
+                    return Err(io::Error::new(
+                        io::ErrorKind::ConnectionReset,
+                        "injected connection drop",
+                    ));
+                }
+                Rng(rng.next_u64())
+            };
+            Ok(FaultyStream {
+                inner: stream,
+                latency: config.latency,
+                corruption_rate: config.corruption_rate,
+                rng,
+                write_delay: None,
+            })
+        }
+        .boxed()
+    }
+}
+
+impl<T> Transport for FaultInjection<T>
+where
+    T: Transport<Error = io::Error> + Unpin,
+    T::Dial: Send + 'static,
+    T::ListenerUpgrade: Send + 'static,
+    T::Output: Send + 'static,
+{
+    type Output = FaultyStream<T::Output>;
+    type Error = io::Error;
+    type ListenerUpgrade = BoxFuture<'static, io::Result<Self::Output>>;
+    type Dial = BoxFuture<'static, io::Result<Self::Output>>;
+
+    fn listen_on(&mut self, addr: Multiaddr) -> Result<ListenerId, TransportError<Self::Error>> {
+        self.inner.listen_on(addr)
+    }
+
+    fn remove_listener(&mut self, id: ListenerId) -> bool {
+        self.inner.remove_listener(id)
+    }
+
+    fn dial(&mut self, addr: Multiaddr) -> Result<Self::Dial, TransportError<Self::Error>> {
+        let dial = self.inner.dial(addr)?;
+        Ok(self.degrade(dial))
+    }
+
+    fn dial_as_listener(
+        &mut self,
+        addr: Multiaddr,
+    ) -> Result<Self::Dial, TransportError<Self::Error>> {
+        let dial = self.inner.dial_as_listener(addr)?;
+        Ok(self.degrade(dial))
+    }
+
+    fn poll(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<TransportEvent<Self::ListenerUpgrade, Self::Error>> {
+        let event = match Pin::new(&mut self.inner).poll(cx) {
+            Poll::Ready(event) => event,
+            Poll::Pending => return Poll::Pending,
+        };
+        Poll::Ready(event.map_upgrade(|upgrade| self.degrade(upgrade)))
+    }
+
+    fn address_translation(&self, listen: &Multiaddr, observed: &Multiaddr) -> Option<Multiaddr> {
+        self.inner.address_translation(listen, observed)
+    }
+}
+
+/// A connection produced by [`FaultInjection`].
+pub struct FaultyStream<S> {
+    inner: S,
+    latency: Duration,
+    corruption_rate: f64,
+    rng: Rng,
+    /// Latency still to wait before the pending write goes through.
+    write_delay: Option<Delay>,
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for FaultyStream<S> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = &mut *self;
+        let read = futures::ready!(Pin::new(&mut this.inner).poll_read(cx, buf))?;
+        if read > 0 && this.rng.chance(this.corruption_rate) {
+            let bit = this.rng.next_u64() as usize % (read * 8);
+            buf[bit / 8] ^= 1 << (bit % 8);
+        }
+        Poll::Ready(Ok(read))
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for FaultyStream<S> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = &mut *self;
+        if !this.latency.is_zero() {
+            let delay = this
+                .write_delay
+                .get_or_insert_with(|| Delay::new(this.latency));
+            futures::ready!(delay.poll_unpin(cx));
+        }
+        let written = futures::ready!(Pin::new(&mut this.inner).poll_write(cx, buf));
+        this.write_delay = None;
+        Poll::Ready(written)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_close(cx)
+    }
+}
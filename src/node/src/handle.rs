@@ -0,0 +1,229 @@
+//! The mDNS demo node, run on a task of its own.
+//!
+//! [`spawn_mdns_node`] drives the swarm on a tokio task and hands back a
+//! [`NodeHandle`].  The handle talks to the task over channels: topic
+//! subscriptions and publications go in as commands, discovery events
+//! and gossipsub messages come out on [`NodeHandle::events`].
+
+use std::future::Future;
+
+use futures::channel::{mpsc, oneshot};
+use futures::StreamExt;
+use libp2p::gossipsub::{self, MessageAcceptance};
+use libp2p::swarm::{dial_opts::DialOpts, SwarmEvent};
+
+use libp2p::{PeerId, Swarm};
+
+use crate::{
+    config_identity, listen_addrs, listen_on_all, node, MyBehaviour, MyBehaviourEvent, NodeConfig,
+    NodeError, NodeEvent, EVENT_CHANNEL_CAPACITY,
+};
+
+/// A request from a [`NodeHandle`] to its node, answered on `reply`.
+#[derive(Debug)]
+pub(crate) enum Command {
+    Subscribe {
+        topic: String,
+        reply: oneshot::Sender<Result<(), NodeError>>,
+    },
+    Publish {
+        topic: String,
+        data: Vec<u8>,
+        reply: oneshot::Sender<Result<(), NodeError>>,
+    },
+}
+
+/// A node started by [`spawn_mdns_node`], running on a task of its
+/// own.  Dropping the handle stops the node as well, without waiting
+/// for it.
+#[derive(Debug)]
+pub struct NodeHandle {
+    /// [`NodeEvent::PeerDiscovered`] and [`NodeEvent::PeerExpired`] as
+    /// mDNS reports them, and [`NodeEvent::Message`] for every message
+    /// on a subscribed topic.  Up to [`EVENT_CHANNEL_CAPACITY`] events
+    /// are buffered; later ones are dropped until the receiver catches
+    /// up.
+    pub events: mpsc::Receiver<NodeEvent>,
+    peer_id: PeerId,
+    commands: mpsc::UnboundedSender<Command>,
+    shutdown: oneshot::Sender<()>,
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl NodeHandle {
+    pub fn local_peer_id(&self) -> PeerId {
+        self.peer_id
+    }
+
+    /// Subscribe to a gossipsub topic.  Fails with
+    /// [`NodeError::NodeStopped`] once the node has stopped.
+    pub async fn subscribe(&self, topic: &str) -> Result<(), NodeError> {
+        self.command(|reply| Command::Subscribe {
+            topic: topic.to_owned(),
+            reply,
+        })
+        .await
+    }
+
+    /// Publish `data` on a gossipsub topic.  Fails with
+    /// [`NodeError::NodeStopped`] once the node has stopped.
+    pub async fn publish(&self, topic: &str, data: Vec<u8>) -> Result<(), NodeError> {
+        self.command(|reply| Command::Publish {
+            topic: topic.to_owned(),
+            data,
+            reply,
+        })
+        .await
+    }
+
+    /// Stop the node and wait until its connections are closed.
+    pub async fn shutdown(self) {
+        // The node stops once the sender is gone, whether or not the
+        // signal was received.
+        let _ = self.shutdown.send(());
+        let _ = self.task.await;
+    }
+
+    /// Whether the node has stopped.
+    pub fn is_finished(&self) -> bool {
+        self.task.is_finished()
+    }
+
+    async fn command(
+        &self,
+        command: impl FnOnce(oneshot::Sender<Result<(), NodeError>>) -> Command,
+    ) -> Result<(), NodeError> {
+        let (reply, result) = oneshot::channel();
+        self.commands
+            .unbounded_send(command(reply))
+            .map_err(|_| NodeError::NodeStopped)?;
+        result.await.map_err(|_| NodeError::NodeStopped)?
+    }
+}
+
+/// Spawn [`run_mdns_node`](crate::run_mdns_node) on the current tokio
+/// runtime and return a handle to stop it, through which the node
+/// subscribes, publishes and reports the peers it discovers.
+/// Discovered peers are dialed.  Panics if called outside a tokio
+/// runtime.
+pub fn spawn_mdns_node() -> NodeHandle {
+    spawn_mdns_node_with_config(&NodeConfig::default()).expect("can start listening")
+}
+
+/// Spawn an mDNS node built from `config`, as
+/// [`spawn_mdns_node`] does.  Like
+/// [`start_node_with_config`](crate::start_node_with_config), the node
+/// keeps its identity at `config.key_path`, so its peer id survives
+/// restarts, and listens on `config.listen_addrs`.
+pub fn spawn_mdns_node_with_config(config: &NodeConfig) -> Result<NodeHandle, NodeError> {
+    let listen_addrs = listen_addrs(config)?;
+    let mut swarm = node::build_swarm(config_identity(config)?, config, Default::default())?;
+    listen_on_all(listen_addrs, |addr| {
+        swarm.listen_on(addr)?;
+        Ok(())
+    })?;
+    let peer_id = *swarm.local_peer_id();
+
+    let (shutdown, stopped) = oneshot::channel();
+    let (commands, received) = mpsc::unbounded();
+    let (sender, events) = mpsc::channel(EVENT_CHANNEL_CAPACITY);
+    let task = tokio::spawn(drive_mdns_node(
+        swarm,
+        async {
+            let _ = stopped.await;
+        },
+        received,
+        Some(sender),
+    ));
+    Ok(NodeHandle {
+        events,
+        peer_id,
+        commands,
+        shutdown,
+        task,
+    })
+}
+
+/// Drive an mDNS node's `swarm` until `shutdown` completes, carrying
+/// out `commands`, dialing every peer it discovers and sending its
+/// events to `events`, if any.
+pub(crate) async fn drive_mdns_node(
+    mut swarm: Swarm<MyBehaviour>,
+    shutdown: impl Future<Output = ()>,
+    mut commands: mpsc::UnboundedReceiver<Command>,
+    mut events: Option<mpsc::Sender<NodeEvent>>,
+) {
+    log::info!("Local node id: {}", swarm.local_peer_id());
+
+    futures::pin_mut!(shutdown);
+    loop {
+        let event = tokio::select! {
+            event = swarm.select_next_some() => event,
+            Some(command) = commands.next() => {
+                let gossipsub = &mut swarm.behaviour_mut().gossipsub;
+                let _ = match command {
+                    Command::Subscribe { topic, reply } => reply.send(
+                        gossipsub
+                            .subscribe(&gossipsub::IdentTopic::new(topic))
+                            .map(drop)
+                            .map_err(NodeError::from),
+                    ),
+                    Command::Publish { topic, data, reply } => reply.send(
+                        gossipsub
+                            .publish(gossipsub::IdentTopic::new(topic), data)
+                            .map(drop)
+                            .map_err(NodeError::from),
+                    ),
+                };
+                continue;
+            }
+            () = &mut shutdown => break,
+        };
+        let reported = match event {
+            SwarmEvent::Behaviour(MyBehaviourEvent::Mdns(event)) => {
+                node::handle_mdns_event(swarm.behaviour_mut(), event, true)
+            }
+            SwarmEvent::Behaviour(MyBehaviourEvent::Gossipsub(gossipsub::Event::Message {
+                propagation_source,
+                message_id,
+                message,
+            })) => {
+                // Messages are validated, so they are only forwarded
+                // once accepted.
+                let _ = swarm
+                    .behaviour_mut()
+                    .gossipsub
+                    .report_message_validation_result(
+                        &message_id,
+                        &propagation_source,
+                        MessageAcceptance::Accept,
+                    );
+                vec![NodeEvent::Message {
+                    source: propagation_source,
+                    message,
+                }]
+            }
+            _ => continue,
+        };
+        for event in reported {
+            if let NodeEvent::PeerDiscovered { peer, addresses } = &event {
+                // Fails harmlessly if the peer is connected already.
+                let _ = swarm.dial(
+                    DialOpts::peer_id(*peer)
+                        .addresses(addresses.clone())
+                        .build(),
+                );
+            }
+            // A receiver that fell behind misses events rather than
+            // holding up the node.
+            if let Some(sender) = &mut events {
+                if let Err(error) = sender.try_send(event) {
+                    if error.is_disconnected() {
+                        events = None;
+                    }
+                }
+            }
+        }
+    }
+    node::close_swarm(&mut swarm).await;
+}
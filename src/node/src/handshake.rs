@@ -0,0 +1,202 @@
+//! Credential handshake for permissioned meshes.
+//!
+//! When a [`HandshakeConfig`] is set, both sides of every connection
+//! send a credential right after noise has authenticated them and
+//! before the stream multiplexer starts.  Each side checks the other's
+//! credential with its [`HandshakeValidator`] and drops the connection
+//! if it is refused, so no protocol ever runs with an unapproved peer.
+//!
+//! Each credential is sent as a two byte big‑endian length followed by
+//! the credential itself.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::{fmt, io, iter};
+
+use futures::future::BoxFuture;
+use futures::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, FutureExt};
+use libp2p::core::upgrade::{InboundUpgrade, OutboundUpgrade, UpgradeInfo};
+use libp2p::{yamux, PeerId};
+
+use crate::NodeError;
+
+/// Largest credential, in bytes, a node may present or accept.
+pub const MAX_CREDENTIAL_LEN: usize = 1024;
+
+/// Protocol negotiated in place of plain yamux when a handshake is
+/// configured.  Nodes without a handshake cannot connect to nodes
+/// with one.
+pub const HANDSHAKE_PROTOCOL: &[u8] = b"/neuromesh/handshake/1.0.0";
+
+/// Credential presented to peers, at most [`MAX_CREDENTIAL_LEN`]
+/// bytes long.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Credential(Vec<u8>);
+
+impl TryFrom<Vec<u8>> for Credential {
+    type Error = NodeError;
+
+    fn try_from(bytes: Vec<u8>) -> Result<Self, NodeError> {
+        if bytes.len() > MAX_CREDENTIAL_LEN {
+            return Err(NodeError::CredentialTooLong(bytes.len()));
+        }
+        Ok(Self(bytes))
+    }
+}
+
+impl AsRef<[u8]> for Credential {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// Decides whether a peer may connect given the credential it
+/// presented.  Implemented for any suitable closure.
+pub trait HandshakeValidator: Send + Sync + 'static {
+    fn validate(&self, peer: &PeerId, credential: &[u8]) -> bool;
+}
+
+impl<F> HandshakeValidator for F
+where
+    F: Fn(&PeerId, &[u8]) -> bool + Send + Sync + 'static,
+{
+    fn validate(&self, peer: &PeerId, credential: &[u8]) -> bool {
+        self(peer, credential)
+    }
+}
+
+/// Credential to present and validator to check peers' credentials
+/// with.
+#[derive(Clone)]
+pub struct HandshakeConfig {
+    pub credential: Credential,
+    pub validator: Arc<dyn HandshakeValidator>,
+}
+
+impl HandshakeConfig {
+    pub fn new(credential: Credential, validator: impl HandshakeValidator) -> Self {
+        Self {
+            credential,
+            validator: Arc::new(validator),
+        }
+    }
+}
+
+impl fmt::Debug for HandshakeConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HandshakeConfig")
+            .field("credential", &self.credential)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Peers whose credentials were refused, waiting to be reported by the
+/// node.
+pub(crate) type Rejections = Arc<Mutex<VecDeque<PeerId>>>;
+
+/// Connection upgrade that runs the handshake, if one is configured,
+/// and then yamux.
+#[derive(Clone)]
+pub(crate) struct Handshake {
+    peer: PeerId,
+    config: Option<HandshakeConfig>,
+    rejections: Rejections,
+    yamux: yamux::Config,
+}
+
+impl Handshake {
+    pub(crate) fn new(
+        peer: PeerId,
+        config: Option<HandshakeConfig>,
+        rejections: Rejections,
+    ) -> Self {
+        Self {
+            peer,
+            config,
+            rejections,
+            yamux: yamux::Config::default(),
+        }
+    }
+
+    /// Send our credential and check the peer's.
+    async fn exchange<C>(&self, io: &mut C) -> io::Result<()>
+    where
+        C: AsyncRead + AsyncWrite + Unpin,
+    {
+        let Some(config) = &self.config else {
+            return Ok(());
+        };
+        let credential = config.credential.as_ref();
+        io.write_all(&(credential.len() as u16).to_be_bytes())
+            .await?;
+        io.write_all(credential).await?;
+        io.flush().await?;
+
+        let mut len = [0; 2];
+        io.read_exact(&mut len).await?;
+        let len = u16::from_be_bytes(len) as usize;
+        if len > MAX_CREDENTIAL_LEN {
+            return Err(self.reject());
+        }
+        let mut theirs = vec![0; len];
+        io.read_exact(&mut theirs).await?;
+        if !config.validator.validate(&self.peer, &theirs) {
+            return Err(self.reject());
+        }
+        Ok(())
+    }
+
+    fn reject(&self) -> io::Error {
+        self.rejections
+            .lock()
+            .expect("lock is not poisoned")
+            .push_back(self.peer);
+        io::Error::new(io::ErrorKind::PermissionDenied, "credential rejected")
+    }
+}
+
+impl UpgradeInfo for Handshake {
+    type Info = &'static [u8];
+    type InfoIter = iter::Once<Self::Info>;
+
+    fn protocol_info(&self) -> Self::InfoIter {
+        match self.config {
+            Some(_) => iter::once(HANDSHAKE_PROTOCOL),
+            None => self.yamux.protocol_info(),
+        }
+    }
+}
+
+impl<C> InboundUpgrade<C> for Handshake
+where
+    C: AsyncRead + AsyncWrite + Send + Unpin + 'static,
+{
+    type Output = <yamux::Config as InboundUpgrade<C>>::Output;
+    type Error = io::Error;
+    type Future = BoxFuture<'static, io::Result<Self::Output>>;
+
+    fn upgrade_inbound(self, mut io: C, info: Self::Info) -> Self::Future {
+        async move {
+            self.exchange(&mut io).await?;
+            self.yamux.upgrade_inbound(io, info).await
+        }
+        .boxed()
+    }
+}
+
+impl<C> OutboundUpgrade<C> for Handshake
+where
+    C: AsyncRead + AsyncWrite + Send + Unpin + 'static,
+{
+    type Output = <yamux::Config as OutboundUpgrade<C>>::Output;
+    type Error = io::Error;
+    type Future = BoxFuture<'static, io::Result<Self::Output>>;
+
+    fn upgrade_outbound(self, mut io: C, info: Self::Info) -> Self::Future {
+        async move {
+            self.exchange(&mut io).await?;
+            self.yamux.upgrade_outbound(io, info).await
+        }
+        .boxed()
+    }
+}
@@ -0,0 +1,40 @@
+//! Persistent node identity.
+//!
+//! A node's [`PeerId`](libp2p::PeerId) is derived from its keypair, so
+//! peers can only recognise a node across restarts if it keeps the
+//! same keypair.
+
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+
+use libp2p::identity;
+
+/// Read the protobuf‑encoded keypair stored at `path`, or generate an
+/// Ed25519 keypair and store it there if the file does not exist.
+///
+/// On Unix a new file is only readable and writable by its owner.
+pub fn load_or_create_identity(path: &Path) -> io::Result<identity::Keypair> {
+    match fs::read(path) {
+        Ok(bytes) => identity::Keypair::from_protobuf_encoding(&bytes)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            let keypair = identity::Keypair::generate_ed25519();
+            let bytes = keypair
+                .to_protobuf_encoding()
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            let mut options = OpenOptions::new();
+            options.write(true).create_new(true);
+            #[cfg(unix)]
+            std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+            let mut file = options.open(path)?;
+            file.write_all(&bytes)?;
+            file.sync_all()?;
+            Ok(keypair)
+        }
+        Err(err) => Err(err),
+    }
+}
@@ -2,43 +2,178 @@
 //!
 //! This crate implements the networking layer for miners and validators
 //! using libp2p and gRPC.  It provides functions for peer discovery,
-//! pub/sub topics, and service definitions.  At the moment, it
-//! contains placeholder code to illustrate the structure.
+//! pub/sub topics, and the gRPC [`service`] off‑chain workers talk to
+//! nodes through.
+//!
+//! Nodes run on tokio: swarms and [`Node`]s must be built and driven
+//! from within a tokio runtime.
 
-use async_std::task;
-use libp2p::{identity, mdns, swarm::{NetworkBehaviour, Swarm}, PeerId};
+pub mod auth;
+mod config;
+pub mod dedup;
+pub mod dial;
+mod discovery;
+mod error;
+mod event;
+#[cfg(feature = "testing")]
+pub mod fault;
+mod handle;
+pub mod handshake;
+mod keys;
+mod nat;
+mod node;
+mod observer;
+mod outbox;
+mod priority;
+pub mod service;
+pub mod task;
 
-/// Start a simple libp2p node that announces itself on the mDNS
-/// network.  This function is for demonstration purposes only and
-/// will be replaced by a full implementation.
-pub fn start_mdns_node() {
-    // Generate a random peer ID.
-    let id_keys = identity::Keypair::generate_ed25519();
-    let peer_id = PeerId::from(id_keys.public());
-    println!("Local node id: {}", peer_id);
-
-    #[derive(NetworkBehaviour)]
-    struct MyBehaviour {
-        mdns: mdns::async_io::Behaviour,
+pub use config::{
+    ConnectionLimits, NodeConfig, DEFAULT_BOOTSTRAP_ATTEMPTS, DEFAULT_BOOTSTRAP_BACKOFF,
+    DEFAULT_BOOTSTRAP_RETRY_INTERVAL, DEFAULT_DIAL_STAGGER, DEFAULT_DIAL_TIMEOUT,
+    DEFAULT_MAX_ESTABLISHED_INCOMING, DEFAULT_MAX_ESTABLISHED_OUTGOING,
+    DEFAULT_MAX_GOSSIPSUB_MESSAGE_SIZE, DEFAULT_MAX_PENDING, DEFAULT_MAX_QUEUED_MESSAGES,
+    DEFAULT_MIN_SUBNET_PEERS, DEFAULT_PUBLISH_BURST, DEFAULT_QUEUED_MESSAGE_TTL,
+    DEFAULT_TASK_TIMEOUT, DEFAULT_TOPIC_GRACE_PERIOD,
+};
+pub use error::{NodeError, SendError};
+pub use event::{NodeEvent, TaskEvent, EVENT_CHANNEL_CAPACITY};
+pub use handle::{spawn_mdns_node, spawn_mdns_node_with_config, NodeHandle};
+pub use keys::load_or_create_identity;
+pub use nat::NatStatus;
+pub use node::{
+    subnet_topic, Node, AGENT_VERSION, IDENTIFY_PROTOCOL_VERSION, SHUTDOWN_FLUSH_PERIOD,
+    SHUTDOWN_TIMEOUT,
+};
+
+use std::future::Future;
+
+use futures::{channel::mpsc, future};
+use libp2p::{
+    autonat, connection_limits, gossipsub, identify, identity,
+    kad::{store::MemoryStore, Kademlia},
+    mdns, relay, request_response,
+    swarm::{behaviour::toggle::Toggle, NetworkBehaviour},
+    Multiaddr, Swarm,
+};
+
+/// Address [`start_mdns_node`] listens on, and
+/// [`start_node_with_config`] when no address is configured: every
+/// interface, on a port picked by the OS.
+pub const DEFAULT_LISTEN_ADDR: &str = "/ip4/0.0.0.0/tcp/0";
+
+/// The libp2p behaviour shared by every NeuroMesh node: mDNS, unless
+/// disabled, for local peer discovery, Kademlia for discovery beyond
+/// the local network,
+/// gossipsub for subnet topics, request‑response for direct task
+/// dispatch, identify to learn which protocols each peer speaks, the
+/// account handshake to learn which on‑chain account operates it,
+/// connection limits to refuse connections beyond the configured caps,
+/// AutoNAT to learn whether the node is publicly reachable and the
+/// relay client to be reachable through relays when it is not.
+#[derive(NetworkBehaviour)]
+pub struct MyBehaviour {
+    pub mdns: Toggle<mdns::tokio::Behaviour>,
+    pub kademlia: Kademlia<MemoryStore>,
+    pub gossipsub: gossipsub::Behaviour,
+    pub task: request_response::Behaviour<task::TaskCodec>,
+    pub identify: identify::Behaviour,
+    pub account: request_response::Behaviour<auth::AccountCodec>,
+    pub limits: connection_limits::Behaviour,
+    pub autonat: autonat::Behaviour,
+    pub relay: relay::client::Behaviour,
+}
+
+/// Build a swarm for a fresh identity with the default
+/// [`NodeConfig`], listening on `listen_addr`.  Driving the swarm is
+/// left to the caller, who must also report the validation result of
+/// every gossipsub message, as the [`Node`] does.
+pub fn build_mdns_swarm(listen_addr: Multiaddr) -> Result<Swarm<MyBehaviour>, NodeError> {
+    let mut swarm = node::build_swarm(
+        identity::Keypair::generate_ed25519(),
+        &NodeConfig::default(),
+        Default::default(),
+    )?;
+    swarm.listen_on(listen_addr)?;
+    Ok(swarm)
+}
+
+/// Build a node from `config` and start it listening on
+/// `config.listen_addrs`, or on [`DEFAULT_LISTEN_ADDR`] if there are
+/// none.  Every address is checked before the node is built, and if
+/// listening fails on any of them the error lists each.  The
+/// node's identity is loaded from `config.key_path`, see
+/// [`load_or_create_identity`], or freshly generated if no path is
+/// set.  The caller drives the returned node and stops it with
+/// [`Node::shutdown`].
+pub fn start_node_with_config(config: NodeConfig) -> Result<Node, NodeError> {
+    let listen_addrs = listen_addrs(&config)?;
+    let keypair = config_identity(&config)?;
+    let mut registry = prometheus_client::registry::Registry::default();
+    let mut node = Node::with_config(keypair, config, &mut registry)?;
+    listen_on_all(listen_addrs, |addr| node.listen_on(addr))?;
+    Ok(node)
+}
+
+/// Call `listen_on` with every address, failing with
+/// [`NodeError::ListenFailed`] listing those that failed.
+fn listen_on_all(
+    addrs: Vec<Multiaddr>,
+    mut listen_on: impl FnMut(Multiaddr) -> Result<(), NodeError>,
+) -> Result<(), NodeError> {
+    let failures: Vec<_> = addrs
+        .into_iter()
+        .filter_map(|addr| listen_on(addr.clone()).err().map(|err| (addr, err)))
+        .collect();
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(NodeError::ListenFailed(failures))
     }
+}
 
-    let behaviour = MyBehaviour {
-        mdns: mdns::async_io::Behaviour::new(mdns::Config::default(), peer_id)
-            .expect("can create mdns behaviour"),
+/// `config.listen_addrs`, or [`DEFAULT_LISTEN_ADDR`] if there are
+/// none, each checked.
+fn listen_addrs(config: &NodeConfig) -> Result<Vec<Multiaddr>, NodeError> {
+    let listen_addrs = if config.listen_addrs.is_empty() {
+        vec![DEFAULT_LISTEN_ADDR.parse().expect("valid address")]
+    } else {
+        config.listen_addrs.clone()
     };
+    for addr in &listen_addrs {
+        config::validate_listen_addr(addr)?;
+    }
+    Ok(listen_addrs)
+}
+
+/// The identity kept at `config.key_path`, or a fresh one if no path
+/// is set.
+fn config_identity(config: &NodeConfig) -> Result<identity::Keypair, NodeError> {
+    Ok(match &config.key_path {
+        Some(path) => load_or_create_identity(path)?,
+        None => identity::Keypair::generate_ed25519(),
+    })
+}
 
-    let mut swarm = Swarm::with_async_std_executor(
-        libp2p::SwarmBuilder::new(id_keys, behaviour, peer_id)
-            .build(),
-    );
-
-    task::block_on(async move {
-        Swarm::listen_on(&mut swarm, "/ip4/0.0.0.0/tcp/0".parse().unwrap())
-            .expect("can start listening");
-        loop {
-            match swarm.next_event().await {
-                _ => {}
-            }
-        }
-    });
-}
\ No newline at end of file
+/// Start a simple libp2p node that announces itself on the mDNS
+/// network and drive it until `shutdown` completes, then close its
+/// connections as [`Node::shutdown`] does.  Must be awaited within a
+/// tokio runtime.  [`spawn_mdns_node`] runs it on a task and returns a
+/// [`NodeHandle`] to stop it.  This function is for demonstration
+/// purposes only; embed [`build_mdns_swarm`] or a [`Node`] instead.
+pub async fn run_mdns_node(shutdown: impl Future<Output = ()>) {
+    let swarm = build_mdns_swarm(DEFAULT_LISTEN_ADDR.parse().expect("valid address"))
+        .expect("can start listening");
+    handle::drive_mdns_node(swarm, shutdown, mpsc::unbounded().1, None).await
+}
+
+/// Run [`run_mdns_node`] on a runtime of its own, blocking the calling
+/// thread forever.  Panics if called from within a tokio runtime.
+#[deprecated(note = "use `spawn_mdns_node` on your own tokio runtime instead")]
+pub fn start_mdns_node() {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("can build a tokio runtime")
+        .block_on(run_mdns_node(future::pending()));
+}
@@ -0,0 +1,39 @@
+//! Reachability behind NATs.
+//!
+//! AutoNAT asks connected peers to dial us back and concludes from
+//! their answers whether the node is publicly reachable.  A node found
+//! to be private cannot accept connections, so it listens through its
+//! configured relays instead: each relay holds a reservation for it
+//! and forwards connections that peers open to its circuit address.
+
+use libp2p::multiaddr::Protocol;
+use libp2p::{autonat, Multiaddr};
+
+/// Whether the node can be reached from the outside, as last
+/// determined by AutoNAT.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub enum NatStatus {
+    /// Peers could dial us back on this address.
+    Public(Multiaddr),
+    /// Peers could not dial us back on any address.
+    Private,
+    /// Not enough peers have probed us yet.
+    #[default]
+    Unknown,
+}
+
+impl From<autonat::NatStatus> for NatStatus {
+    fn from(status: autonat::NatStatus) -> Self {
+        match status {
+            autonat::NatStatus::Public(addr) => NatStatus::Public(addr),
+            autonat::NatStatus::Private => NatStatus::Private,
+            autonat::NatStatus::Unknown => NatStatus::Unknown,
+        }
+    }
+}
+
+/// Address to listen on to be reachable through `relay`, which must
+/// end in `/p2p/<peer id>`.
+pub(crate) fn circuit_addr(relay: &Multiaddr) -> Multiaddr {
+    relay.clone().with(Protocol::P2pCircuit)
+}
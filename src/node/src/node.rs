@@ -0,0 +1,1491 @@
+//! The NeuroMesh node: a libp2p swarm plus the bookkeeping the
+//! protocol layers on top of it.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::io;
+use std::iter;
+use std::num::NonZeroU8;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use futures::{
+    channel::mpsc,
+    future::{self, BoxFuture, Either},
+    stream::FuturesUnordered,
+    FutureExt, StreamExt,
+};
+use futures_timer::Delay;
+use libp2p::{
+    autonat,
+    connection_limits::{self, Exceeded},
+    core::{
+        muxing::{StreamMuxerBox, StreamMuxerExt},
+        transport::{Boxed, ListenerId, OptionalTransport},
+        upgrade, ConnectedPoint,
+    },
+    gossipsub::{self, MessageAcceptance, PeerScoreParams, PeerScoreThresholds},
+    identify, identity,
+    kad::{
+        self, store::MemoryStore, GetClosestPeersError, Kademlia, KademliaConfig, KademliaEvent,
+        KademliaStoreInserts, ProgressStep, QueryId, QueryResult, QueryStats,
+    },
+    mdns,
+    multiaddr::Protocol,
+    noise, relay,
+    request_response::{self, OutboundFailure, ProtocolSupport, RequestId, ResponseChannel},
+    swarm::{
+        dial_opts::DialOpts, AddressScore, ConnectionDenied, DialError, ListenError, SwarmBuilder,
+        SwarmEvent, THandlerErr,
+    },
+    tcp, Multiaddr, PeerId, Swarm, Transport,
+};
+use prometheus_client::registry::Registry;
+
+use crate::auth::{AccountCodec, AccountId, AccountProof, AccountProtocol, AccountResolver};
+use crate::dedup::DuplicateFilter;
+use crate::dial::HappyEyeballs;
+use crate::discovery::{Bootstrap, HealthChange};
+use crate::event::Subscribers;
+use crate::handshake::{Handshake, Rejections};
+use crate::nat::{circuit_addr, NatStatus};
+use crate::observer::ListenOnly;
+use crate::outbox::Outbox;
+use crate::priority::{InboundTask, InboundTasks, OutboundMessage, OutboundMessages};
+use crate::task::{TaskCodec, TaskProtocol, TaskRequest, TaskResponse, PROTOCOL_PREFIX};
+use crate::{
+    MyBehaviour, MyBehaviourEvent, NodeConfig, NodeError, NodeEvent, SendError, TaskEvent,
+};
+
+/// Protocol version announced through identify.
+pub const IDENTIFY_PROTOCOL_VERSION: &str = "/neuromesh/1.0.0";
+
+/// Agent version announced through identify: this crate's name and
+/// version.
+pub const AGENT_VERSION: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
+/// How long [`Node::shutdown`] keeps driving the swarm after leaving
+/// every topic, so that pending gossipsub messages go out before
+/// connections are closed.
+pub const SHUTDOWN_FLUSH_PERIOD: Duration = Duration::from_millis(500);
+
+/// Longest [`Node::shutdown`] waits for connections to close.
+pub const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Name of the gossipsub topic carrying the traffic of `subnet_id`
+/// under `schema_version`.  Each schema version gets a topic of its
+/// own, so payloads of incompatible versions never mix.
+pub fn subnet_topic(subnet_id: u32, schema_version: u32) -> String {
+    format!("neuromesh/subnet/{subnet_id}/v{schema_version}")
+}
+
+/// What [`Node::next_event`] woke up for.
+enum Wake {
+    Swarm(SwarmEvent<MyBehaviourEvent, THandlerErr<MyBehaviour>>),
+    Bootstrap,
+    /// The grace period of this subnet topic is over.
+    LeaveTopic {
+        subnet_id: u32,
+        schema_version: u32,
+    },
+    /// The oldest queued subnet message has expired.
+    OutboxExpiry,
+}
+
+/// A running NeuroMesh peer.
+pub struct Node {
+    swarm: Swarm<MyBehaviour>,
+    /// Transport of [`Node::probe_address`], kept apart from the
+    /// swarm's so that probes never reach the behaviours.
+    probe_transport: Boxed<(PeerId, StreamMuxerBox)>,
+    duplicates: DuplicateFilter,
+    /// Inbound task requests awaiting [`Node::respond_to_task`].
+    pending_tasks: HashMap<RequestId, ResponseChannel<TaskResponse>>,
+    /// Inbound task requests not yet reported, in priority order.
+    inbound_tasks: InboundTasks,
+    event_subscribers: Subscribers<NodeEvent>,
+    task_subscribers: Subscribers<TaskEvent>,
+    bootnodes: Vec<(PeerId, Multiaddr)>,
+    bootstrap: Bootstrap,
+    /// The running bootstrap query and whether it has reached a peer.
+    bootstrap_query: Option<(QueryId, bool)>,
+    /// Fires when the next bootstrap attempt is due.
+    bootstrap_timer: Option<Delay>,
+    /// Task protocol names we offer, most preferred first.
+    task_protocols: Vec<String>,
+    /// Protocols each connected peer reported through identify.
+    peer_protocols: HashMap<PeerId, Vec<String>>,
+    /// Peers that rejected our task protocols before identifying
+    /// themselves.  The mismatch is reported once they do.
+    unidentified_mismatches: HashSet<PeerId>,
+    /// Events produced alongside another event, returned by the next
+    /// call to [`Node::next_event`].
+    queued_events: VecDeque<NodeEvent>,
+    /// Peers whose handshake credential was refused, filled in by the
+    /// transport.
+    handshake_rejections: Rejections,
+    /// Largest gossipsub payload published or accepted.
+    max_gossipsub_message_size: usize,
+    /// Gossipsub messages rejected from each connected peer.
+    rejected_messages: HashMap<PeerId, u32>,
+    /// Proof of our account, sent to every peer we connect to.
+    account_proof: Option<AccountProof>,
+    /// Maps the keys of peers' account proofs to accounts.
+    account_resolver: Option<Arc<dyn AccountResolver>>,
+    /// Account each connected peer has proven.
+    peer_accounts: HashMap<PeerId, AccountId>,
+    /// Whether gossip forwarded by peers without a proven account is
+    /// rejected.
+    require_authenticated_handshake: bool,
+    /// Whether the node refuses to dial and publish.
+    observer_mode: bool,
+    /// Schema version whose topic each subnet is subscribed to.
+    subnet_versions: HashMap<u32, u32>,
+    /// Previous subnet topics, resolving to their subnet and schema
+    /// version when their grace period ends.
+    leaving_topics: FuturesUnordered<BoxFuture<'static, (u32, u32)>>,
+    topic_grace_period: Duration,
+    /// Peers a subnet topic needs before messages are published on it.
+    min_subnet_peers: usize,
+    /// Subnet messages waiting for enough peers.
+    outbox: Outbox,
+    /// Fires when the oldest message in the outbox expires.
+    outbox_timer: Option<Delay>,
+    /// Prioritized messages waiting for their turn to be published.
+    outbound: OutboundMessages,
+    /// Most messages in `outbound`.
+    max_outbound_messages: usize,
+    /// Whether prioritized messages are held back until
+    /// [`Node::resume_publishing`].
+    publishing_paused: bool,
+    /// Most prioritized messages published per turn of the event loop.
+    publish_burst: usize,
+    /// Prioritized messages published in the current turn.
+    published_in_burst: usize,
+    /// Relays to listen through while the node is private.
+    relay_addrs: Vec<Multiaddr>,
+    /// Listeners on the circuit addresses of `relay_addrs`.
+    relay_listeners: Vec<ListenerId>,
+}
+
+impl Node {
+    /// Build a node for `keypair` with the default [`NodeConfig`],
+    /// registering its metrics with `registry`.  The node does not
+    /// listen until [`listen_on`](Self::listen_on) is called.
+    pub fn new(keypair: identity::Keypair, registry: &mut Registry) -> Result<Self, NodeError> {
+        Self::with_config(keypair, NodeConfig::default(), registry)
+    }
+
+    /// Build a node for `keypair` using `config`.
+    pub fn with_config(
+        keypair: identity::Keypair,
+        config: NodeConfig,
+        registry: &mut Registry,
+    ) -> Result<Self, NodeError> {
+        let handshake_rejections = Rejections::default();
+        let probe_transport = build_transport(&keypair, &config, None, Rejections::default())?;
+        let mut swarm = build_swarm(keypair, &config, handshake_rejections.clone())?;
+        let bootnodes = config
+            .bootnodes
+            .iter()
+            .map(|addr| {
+                addr_peer(addr)
+                    .map(|peer| (peer, addr.clone()))
+                    .ok_or_else(|| NodeError::InvalidBootnode(addr.clone()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let relays = config
+            .relay_addrs
+            .iter()
+            .map(|addr| {
+                addr_peer(addr)
+                    .map(|peer| (peer, addr.clone()))
+                    .ok_or_else(|| NodeError::InvalidRelayAddr(addr.clone()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        // Bootnodes and relays are expected to be public, which makes
+        // them good judges of our own reachability.
+        for (peer, addr) in bootnodes.iter().chain(&relays) {
+            swarm
+                .behaviour_mut()
+                .autonat
+                .add_server(*peer, Some(addr.clone()));
+        }
+        let account_proof = config
+            .account
+            .as_ref()
+            .and_then(|account| account.key.as_ref())
+            .map(|key| AccountProof::sign(key, swarm.local_peer_id()));
+
+        let duplicates = DuplicateFilter::default();
+        duplicates.register(registry);
+
+        Ok(Self {
+            swarm,
+            probe_transport,
+            duplicates,
+            pending_tasks: HashMap::new(),
+            inbound_tasks: InboundTasks::default(),
+            event_subscribers: Subscribers::default(),
+            task_subscribers: Subscribers::default(),
+            // The first attempt runs as soon as the node is driven.
+            bootstrap_timer: (!bootnodes.is_empty() && !config.observer_mode)
+                .then(|| Delay::new(Duration::ZERO)),
+            bootnodes,
+            bootstrap: Bootstrap::new(
+                config.bootstrap_attempts,
+                config.bootstrap_backoff,
+                config.bootstrap_retry_interval,
+            ),
+            bootstrap_query: None,
+            task_protocols: config.task_protocols,
+            peer_protocols: HashMap::new(),
+            unidentified_mismatches: HashSet::new(),
+            queued_events: VecDeque::new(),
+            handshake_rejections,
+            max_gossipsub_message_size: config.max_gossipsub_message_size,
+            rejected_messages: HashMap::new(),
+            account_proof,
+            account_resolver: config.account.map(|account| account.resolver),
+            peer_accounts: HashMap::new(),
+            require_authenticated_handshake: config.require_authenticated_handshake,
+            observer_mode: config.observer_mode,
+            subnet_versions: HashMap::new(),
+            leaving_topics: FuturesUnordered::new(),
+            topic_grace_period: config.topic_grace_period,
+            min_subnet_peers: config.min_subnet_peers,
+            outbox: Outbox::new(config.max_queued_messages, config.queued_message_ttl),
+            outbox_timer: None,
+            outbound: OutboundMessages::default(),
+            max_outbound_messages: config.max_queued_messages,
+            publishing_paused: false,
+            publish_burst: config.publish_burst,
+            published_in_burst: 0,
+            relay_addrs: config.relay_addrs,
+            relay_listeners: Vec::new(),
+        })
+    }
+
+    /// The peer id derived from the node's keypair.
+    pub fn local_peer_id(&self) -> PeerId {
+        *self.swarm.local_peer_id()
+    }
+
+    /// Start listening on `addr`.
+    pub fn listen_on(&mut self, addr: Multiaddr) -> Result<(), NodeError> {
+        self.swarm.listen_on(addr)?;
+        Ok(())
+    }
+
+    /// Dial `addr`.  Fails with [`NodeError::ObserverMode`] on an
+    /// observer.
+    pub fn dial(&mut self, addr: Multiaddr) -> Result<(), NodeError> {
+        self.ensure_not_observer()?;
+        self.swarm.dial(addr)?;
+        Ok(())
+    }
+
+    /// Dial `peer` on all of `addresses` at once, happy‑eyeballs
+    /// style: each address starts `dial_stagger` after the previous
+    /// one, the first connection to succeed is kept and the remaining
+    /// attempts are aborted.
+    pub fn dial_peer(&mut self, peer: PeerId, addresses: Vec<Multiaddr>) -> Result<(), NodeError> {
+        self.ensure_not_observer()?;
+        let concurrency = NonZeroU8::new(addresses.len().clamp(1, u8::MAX as usize) as u8)
+            .expect("clamped to at least one");
+        self.swarm.dial(
+            DialOpts::peer_id(peer)
+                .addresses(addresses)
+                .override_dial_concurrency_factor(concurrency)
+                .build(),
+        )?;
+        Ok(())
+    }
+
+    /// Check that `addr` can be dialed, for instance before using it as
+    /// a bootnode, and return the id of the peer listening there.  The
+    /// probe connects on a transport of its own and closes the
+    /// connection as soon as the peer is authenticated, so the peer is
+    /// neither reported nor added to the routing table.  Fails with
+    /// [`NodeError::Unreachable`] if the dial fails or does not
+    /// complete within `timeout`, or with [`NodeError::ObserverMode`]
+    /// on an observer.
+    pub async fn probe_address(
+        &mut self,
+        addr: Multiaddr,
+        timeout: Duration,
+    ) -> Result<PeerId, NodeError> {
+        self.ensure_not_observer()?;
+        let dial = self
+            .probe_transport
+            .dial(addr.clone())
+            .map_err(|_| NodeError::Unreachable(addr.clone()))?;
+        match future::select(dial, Delay::new(timeout)).await {
+            Either::Left((Ok((peer, muxer)), _)) => {
+                // The peer is reachable whether or not the close is
+                // acknowledged.
+                let _ = muxer.close().await;
+                Ok(peer)
+            }
+            _ => Err(NodeError::Unreachable(addr)),
+        }
+    }
+
+    /// Add `peers` to the bootnodes and bootstrap Kademlia through
+    /// them right away.  The outcome is reported like that of the
+    /// configured bootnodes, which are used again on every retry.
+    /// Observers never bootstrap, so this does nothing on them.
+    pub fn bootstrap(&mut self, peers: Vec<(PeerId, Multiaddr)>) {
+        if self.observer_mode {
+            return;
+        }
+        for peer in peers {
+            if !self.bootnodes.contains(&peer) {
+                self.bootnodes.push(peer);
+            }
+        }
+        self.bootstrap_timer = None;
+        if let Some(event) = self.start_bootstrap() {
+            self.queued_events.push_back(event);
+        }
+    }
+
+    /// Look up the peers closest to `key` in the DHT.  The result is
+    /// reported as a [`NodeEvent::ClosestPeers`] carrying the returned
+    /// id.
+    pub fn get_closest_peers(&mut self, key: PeerId) -> QueryId {
+        self.swarm.behaviour_mut().kademlia.get_closest_peers(key)
+    }
+
+    /// Subscribe to a gossipsub topic.
+    pub fn subscribe(&mut self, topic: &str) -> Result<(), NodeError> {
+        self.swarm
+            .behaviour_mut()
+            .gossipsub
+            .subscribe(&gossipsub::IdentTopic::new(topic))?;
+        Ok(())
+    }
+
+    /// Move `subnet_id` to the topic of `schema_version`, see
+    /// [`subnet_topic`].  The topic of the previous version is left
+    /// once `topic_grace_period` has passed, so messages still in
+    /// flight on it are received; its departure is reported as a
+    /// [`NodeEvent::SubnetTopicLeft`].  The first call for a subnet
+    /// simply subscribes to its topic.
+    pub fn resubscribe_subnet_version(
+        &mut self,
+        subnet_id: u32,
+        schema_version: u32,
+    ) -> Result<(), NodeError> {
+        self.subscribe(&subnet_topic(subnet_id, schema_version))?;
+        let previous = self.subnet_versions.insert(subnet_id, schema_version);
+        if let Some(previous) = previous.filter(|previous| *previous != schema_version) {
+            self.leaving_topics.push(
+                Delay::new(self.topic_grace_period)
+                    .map(move |()| (subnet_id, previous))
+                    .boxed(),
+            );
+        }
+        Ok(())
+    }
+
+    /// Publish `data` on a gossipsub topic.  Fails with
+    /// [`NodeError::MessageTooLarge`] if `data` exceeds
+    /// `max_gossipsub_message_size`, or with
+    /// [`NodeError::ObserverMode`] on an observer.
+    pub fn publish(&mut self, topic: &str, data: Vec<u8>) -> Result<(), NodeError> {
+        self.ensure_not_observer()?;
+        if data.len() > self.max_gossipsub_message_size {
+            return Err(NodeError::MessageTooLarge(data.len()));
+        }
+        self.swarm
+            .behaviour_mut()
+            .gossipsub
+            .publish(gossipsub::IdentTopic::new(topic), data)?;
+        Ok(())
+    }
+
+    /// Publish `data` on a gossipsub topic ahead of messages of lower
+    /// `priority`.  Up to `publish_burst` messages go out per turn of
+    /// the event loop, or none while publishing is
+    /// [paused](Self::pause_publishing); the rest wait, highest
+    /// priority first and in publishing order within a priority.
+    /// Fails with [`NodeError::PublishQueueFull`] when
+    /// `max_queued_messages` are already waiting, and otherwise like
+    /// [`publish`](Self::publish).  Messages that fail once their turn
+    /// comes are logged and dropped.
+    pub fn publish_with_priority(
+        &mut self,
+        topic: &str,
+        data: Vec<u8>,
+        priority: u32,
+    ) -> Result<(), NodeError> {
+        self.ensure_not_observer()?;
+        if data.len() > self.max_gossipsub_message_size {
+            return Err(NodeError::MessageTooLarge(data.len()));
+        }
+        if self.outbound.len() >= self.max_outbound_messages {
+            return Err(NodeError::PublishQueueFull);
+        }
+        let topic = topic.to_string();
+        self.outbound
+            .push(priority, OutboundMessage { topic, data });
+        self.drain_outbound();
+        Ok(())
+    }
+
+    /// Hold back every message published with
+    /// [`publish_with_priority`](Self::publish_with_priority), for
+    /// instance while the application's uplink is congested.
+    pub fn pause_publishing(&mut self) {
+        self.publishing_paused = true;
+    }
+
+    /// Publish the messages held back since
+    /// [`pause_publishing`](Self::pause_publishing), highest priority
+    /// first.
+    pub fn resume_publishing(&mut self) {
+        self.publishing_paused = false;
+        self.drain_outbound();
+    }
+
+    /// Publish `data` on the topic of `subnet_id` under
+    /// `schema_version`, see [`subnet_topic`].  Fails with
+    /// [`NodeError::InsufficientPeers`] if fewer than
+    /// `min_subnet_peers` peers are subscribed to the topic, and
+    /// otherwise like [`publish`](Self::publish).
+    pub fn publish_to_subnet(
+        &mut self,
+        subnet_id: u32,
+        schema_version: u32,
+        data: Vec<u8>,
+    ) -> Result<(), NodeError> {
+        self.ensure_not_observer()?;
+        let present = self.subnet_peer_count(subnet_id, schema_version);
+        if present < self.min_subnet_peers {
+            return Err(NodeError::InsufficientPeers {
+                required: self.min_subnet_peers,
+                present,
+            });
+        }
+        self.publish(&subnet_topic(subnet_id, schema_version), data)
+    }
+
+    /// Like [`publish_to_subnet`](Self::publish_to_subnet), but when too
+    /// few peers are subscribed `data` is held back and published once
+    /// enough have joined.  At most `max_queued_messages` messages are
+    /// held, each for `queued_message_ttl`; a full outbox fails with
+    /// [`NodeError::PublishQueueFull`] and an expired message is
+    /// reported as a [`NodeEvent::QueuedMessageExpired`].
+    pub fn publish_to_subnet_or_queue(
+        &mut self,
+        subnet_id: u32,
+        schema_version: u32,
+        data: Vec<u8>,
+    ) -> Result<(), NodeError> {
+        self.ensure_not_observer()?;
+        if self.subnet_peer_count(subnet_id, schema_version) >= self.min_subnet_peers {
+            return self.publish(&subnet_topic(subnet_id, schema_version), data);
+        }
+        if data.len() > self.max_gossipsub_message_size {
+            return Err(NodeError::MessageTooLarge(data.len()));
+        }
+        self.outbox
+            .push(subnet_id, schema_version, data)
+            .map_err(|_| NodeError::PublishQueueFull)?;
+        if self.outbox_timer.is_none() {
+            self.reset_outbox_timer();
+        }
+        Ok(())
+    }
+
+    /// Number of peers known to be subscribed to the topic of
+    /// `subnet_id` under `schema_version`.
+    pub fn subnet_peer_count(&self, subnet_id: u32, schema_version: u32) -> usize {
+        topic_peer_count(&self.swarm.behaviour().gossipsub, subnet_id, schema_version)
+    }
+
+    /// Whether Kademlia discovery is working.  Turns `false` once
+    /// bootstrap has failed through every retry and back to `true`
+    /// when a background retry succeeds.
+    pub fn discovery_healthy(&self) -> bool {
+        self.bootstrap.is_healthy()
+    }
+
+    /// Whether the node is publicly reachable, as last determined by
+    /// AutoNAT.
+    pub fn nat_status(&self) -> NatStatus {
+        self.swarm.behaviour().autonat.nat_status().into()
+    }
+
+    /// Number of peers the node has at least one connection open to.
+    pub fn peer_count(&self) -> usize {
+        self.swarm.network_info().num_peers()
+    }
+
+    /// The gossipsub score of `peer`, if it is known.
+    pub fn peer_score(&self, peer: &PeerId) -> Option<f64> {
+        self.swarm.behaviour().gossipsub.peer_score(peer)
+    }
+
+    /// Number of gossipsub messages dropped because an identical
+    /// payload on the same topic had already been delivered.
+    pub fn duplicate_messages_suppressed(&self) -> u64 {
+        self.duplicates.suppressed()
+    }
+
+    /// The on‑chain account `peer` has proven, if it has.
+    pub fn peer_account(&self, peer: &PeerId) -> Option<AccountId> {
+        self.peer_accounts.get(peer).copied()
+    }
+
+    /// Send a task to `peer`.  The outcome is reported as a
+    /// [`TaskEvent`] carrying the returned id.
+    pub fn send_task_request(&mut self, peer: &PeerId, request: TaskRequest) -> RequestId {
+        self.swarm.behaviour_mut().task.send_request(peer, request)
+    }
+
+    /// Send a task to `peer` and drive the node until it answers.
+    /// Fails right away with [`SendError::NotConnected`] if no
+    /// connection to `peer` is open, and with [`SendError::Timeout`]
+    /// if it does not answer within `task_timeout`.  Events produced
+    /// meanwhile still reach subscribers but are not returned.
+    pub async fn send_task(
+        &mut self,
+        peer: PeerId,
+        request: TaskRequest,
+    ) -> Result<TaskResponse, SendError> {
+        if !self.swarm.is_connected(&peer) {
+            return Err(SendError::NotConnected(peer));
+        }
+        let id = self.send_task_request(&peer, request);
+        loop {
+            match self.next_event().await {
+                NodeEvent::Task(TaskEvent::TaskResponseReceived {
+                    request_id,
+                    response,
+                    ..
+                }) if request_id == id => return Ok(response),
+                NodeEvent::Task(TaskEvent::TaskRequestFailed {
+                    request_id, error, ..
+                }) if request_id == id => return Err(SendError::from_failure(peer, error)),
+                _ => {}
+            }
+        }
+    }
+
+    /// Set the priority of each subnet's inbound task requests,
+    /// usually the subnets' emission weights.  When several requests
+    /// are waiting, those of higher‑priority subnets are reported
+    /// first; subnets missing from `priorities` have priority zero.
+    pub fn set_subnet_priorities(&mut self, priorities: HashMap<u32, u32>) {
+        self.inbound_tasks.set_priorities(priorities);
+    }
+
+    /// Answer a task previously reported as
+    /// [`TaskEvent::TaskRequestReceived`].
+    pub fn respond_to_task(
+        &mut self,
+        request_id: RequestId,
+        response: TaskResponse,
+    ) -> Result<(), NodeError> {
+        let channel = self
+            .pending_tasks
+            .remove(&request_id)
+            .ok_or(NodeError::UnknownTaskRequest(request_id))?;
+        self.swarm
+            .behaviour_mut()
+            .task
+            .send_response(channel, response)
+            .map_err(|_| NodeError::TaskResponseFailed(request_id))
+    }
+
+    /// Subscribe to every event the node produces.  Events are only
+    /// produced while the node is driven by
+    /// [`next_event`](Self::next_event) or
+    /// [`next_message`](Self::next_message).
+    pub fn event_stream(&mut self) -> mpsc::Receiver<NodeEvent> {
+        self.event_subscribers.subscribe()
+    }
+
+    /// Subscribe to task dispatch events only.  Connection, discovery
+    /// and gossip events never appear on this stream.
+    pub fn task_event_stream(&mut self) -> mpsc::Receiver<TaskEvent> {
+        self.task_subscribers.subscribe()
+    }
+
+    /// Drive the swarm until it produces the next [`NodeEvent`],
+    /// notify subscribers and return the event.
+    pub async fn next_event(&mut self) -> NodeEvent {
+        let event = loop {
+            if let Some(event) = self.queued_events.pop_front() {
+                break event;
+            }
+            // Prioritized messages beyond the burst go out once the
+            // swarm has nothing else ready, which starts a new turn.
+            if !self.publishing_paused && !self.outbound.is_empty() {
+                match self.swarm.select_next_some().now_or_never() {
+                    Some(event) => match self.handle_swarm_event(event) {
+                        Some(event) => break event,
+                        None => continue,
+                    },
+                    None => {
+                        self.published_in_burst = 0;
+                        self.drain_outbound();
+                        continue;
+                    }
+                }
+            }
+            // Inbound task requests are reported once the swarm has
+            // nothing else ready, so that every request that has
+            // arrived by then competes on priority.
+            if !self.inbound_tasks.is_empty() {
+                match self.swarm.select_next_some().now_or_never() {
+                    Some(event) => match self.handle_swarm_event(event) {
+                        Some(event) => break event,
+                        None => continue,
+                    },
+                    None => {
+                        let task = self.inbound_tasks.pop().expect("queue is not empty");
+                        break NodeEvent::Task(TaskEvent::TaskRequestReceived {
+                            peer: task.peer,
+                            request_id: task.request_id,
+                            request: task.request,
+                        });
+                    }
+                }
+            }
+            let event = match self.wait().await {
+                Wake::Swarm(event) => self.handle_swarm_event(event),
+                Wake::Bootstrap => {
+                    self.bootstrap_timer = None;
+                    self.start_bootstrap()
+                }
+                Wake::LeaveTopic {
+                    subnet_id,
+                    schema_version,
+                } => self.leave_subnet_topic(subnet_id, schema_version),
+                Wake::OutboxExpiry => {
+                    self.expire_outbox();
+                    None
+                }
+            };
+            if let Some(event) = event {
+                break event;
+            }
+        };
+        if let NodeEvent::Task(task_event) = &event {
+            self.task_subscribers.notify(task_event);
+        }
+        self.event_subscribers.notify(&event);
+        event
+    }
+
+    /// Drive the swarm until the next gossipsub message arrives and
+    /// return it along with the peer that forwarded it.  Duplicate
+    /// payloads are suppressed and never returned.
+    pub async fn next_message(&mut self) -> (PeerId, gossipsub::Message) {
+        loop {
+            if let NodeEvent::Message { source, message } = self.next_event().await {
+                return (source, message);
+            }
+        }
+    }
+
+    /// Shut the node down: leave every topic, give pending gossipsub
+    /// messages [`SHUTDOWN_FLUSH_PERIOD`] to go out, then close every
+    /// connection and every listener.  Events produced meanwhile are
+    /// dropped.  Returns the number of peers that were connected.
+    pub async fn shutdown(mut self) -> usize {
+        close_swarm(&mut self.swarm).await
+    }
+
+    fn handle_swarm_event<E>(
+        &mut self,
+        event: SwarmEvent<MyBehaviourEvent, E>,
+    ) -> Option<NodeEvent> {
+        match event {
+            SwarmEvent::NewListenAddr { address, .. } => Some(NodeEvent::NewListenAddr { address }),
+            SwarmEvent::ConnectionEstablished {
+                peer_id,
+                num_established,
+                ..
+            } => {
+                if let (1, Some(proof)) = (num_established.get(), &self.account_proof) {
+                    self.swarm
+                        .behaviour_mut()
+                        .account
+                        .send_request(&peer_id, proof.clone());
+                }
+                Some(NodeEvent::ConnectionEstablished { peer: peer_id })
+            }
+            SwarmEvent::ConnectionClosed {
+                peer_id,
+                num_established,
+                ..
+            } => {
+                if num_established == 0 {
+                    self.peer_protocols.remove(&peer_id);
+                    self.unidentified_mismatches.remove(&peer_id);
+                    self.rejected_messages.remove(&peer_id);
+                    self.peer_accounts.remove(&peer_id);
+                }
+                Some(NodeEvent::ConnectionClosed { peer: peer_id })
+            }
+            SwarmEvent::IncomingConnectionError {
+                send_back_addr,
+                error,
+                ..
+            } => {
+                self.report_handshake_rejections();
+                match error {
+                    ListenError::Denied { cause } => {
+                        connection_rejected(None, Some(send_back_addr), cause)
+                    }
+                    _ => None,
+                }
+            }
+            SwarmEvent::OutgoingConnectionError { peer_id, error } => {
+                self.report_handshake_rejections();
+                match error {
+                    DialError::Denied { cause } => connection_rejected(peer_id, None, cause),
+                    _ => None,
+                }
+            }
+            SwarmEvent::Behaviour(MyBehaviourEvent::Mdns(event)) => {
+                let dial = !self.observer_mode;
+                let events = handle_mdns_event(self.swarm.behaviour_mut(), event, dial);
+                self.queued_events.extend(events);
+                None
+            }
+            SwarmEvent::Behaviour(MyBehaviourEvent::Gossipsub(gossipsub::Event::Subscribed {
+                ..
+            })) => {
+                self.flush_outbox();
+                None
+            }
+            SwarmEvent::Behaviour(MyBehaviourEvent::Gossipsub(gossipsub::Event::Message {
+                propagation_source,
+                message_id,
+                message,
+            })) => self.handle_gossip_message(propagation_source, message_id, message),
+            SwarmEvent::Behaviour(MyBehaviourEvent::Task(event)) => {
+                self.handle_task_event(event).map(NodeEvent::Task)
+            }
+            SwarmEvent::Behaviour(MyBehaviourEvent::Account(event)) => {
+                self.handle_account_event(event)
+            }
+            SwarmEvent::Behaviour(MyBehaviourEvent::Kademlia(event)) => {
+                self.handle_kademlia_event(event)
+            }
+            SwarmEvent::Behaviour(MyBehaviourEvent::Autonat(autonat::Event::StatusChanged {
+                new,
+                ..
+            })) => {
+                let status = NatStatus::from(new);
+                self.update_relay_listeners(&status);
+                Some(NodeEvent::NatStatusChanged { status })
+            }
+            SwarmEvent::Behaviour(MyBehaviourEvent::Relay(event)) => {
+                log::debug!("relay client: {event:?}");
+                None
+            }
+            SwarmEvent::Behaviour(MyBehaviourEvent::Identify(identify::Event::Received {
+                peer_id,
+                info,
+            })) => {
+                // Peers that dialed us are only routable once we know
+                // where they listen.
+                let kademlia = &mut self.swarm.behaviour_mut().kademlia;
+                if info
+                    .protocols
+                    .iter()
+                    .any(|name| name.as_bytes() == kad::protocol::DEFAULT_PROTO_NAME)
+                {
+                    for addr in &info.listen_addrs {
+                        kademlia.add_address(&peer_id, addr.clone());
+                    }
+                }
+                // What the peer saw us dial from is a candidate for
+                // the address others can reach us on, which identify
+                // and Kademlia then advertise.
+                self.swarm
+                    .add_external_address(info.observed_addr.clone(), AddressScore::Finite(1));
+                self.peer_protocols.insert(peer_id, info.protocols);
+                if self.unidentified_mismatches.remove(&peer_id) {
+                    let mismatch = self.protocol_mismatch(peer_id);
+                    self.queued_events.push_back(mismatch);
+                }
+                Some(NodeEvent::PeerIdentified {
+                    peer: peer_id,
+                    protocol_version: info.protocol_version,
+                    agent_version: info.agent_version,
+                    listen_addrs: info.listen_addrs,
+                    observed_addr: info.observed_addr,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Start a bootstrap query through the configured bootnodes.
+    /// Wait for the next swarm event or timer.
+    async fn wait(&mut self) -> Wake {
+        let Self {
+            swarm,
+            bootstrap_timer,
+            leaving_topics,
+            outbox_timer,
+            ..
+        } = self;
+        let bootstrap = async move {
+            match bootstrap_timer {
+                Some(timer) => timer.await,
+                None => future::pending().await,
+            }
+        };
+        let leave = async move {
+            if leaving_topics.is_empty() {
+                future::pending().await
+            } else {
+                leaving_topics.select_next_some().await
+            }
+        };
+        let expiry = async move {
+            match outbox_timer {
+                Some(timer) => timer.await,
+                None => future::pending().await,
+            }
+        };
+        futures::pin_mut!(bootstrap, leave, expiry);
+        let timers = future::select(bootstrap, future::select(leave, expiry));
+        match future::select(swarm.select_next_some(), timers).await {
+            Either::Left((event, _)) => Wake::Swarm(event),
+            Either::Right((Either::Left(_), _)) => Wake::Bootstrap,
+            Either::Right((
+                Either::Right((Either::Left(((subnet_id, schema_version), _)), _)),
+                _,
+            )) => Wake::LeaveTopic {
+                subnet_id,
+                schema_version,
+            },
+            Either::Right((Either::Right((Either::Right(_), _)), _)) => Wake::OutboxExpiry,
+        }
+    }
+
+    /// Listen through every relay while the node is private, and stop
+    /// once it is public.  An unknown status changes nothing.
+    fn update_relay_listeners(&mut self, status: &NatStatus) {
+        match status {
+            NatStatus::Private if self.relay_listeners.is_empty() && !self.observer_mode => {
+                for relay in &self.relay_addrs {
+                    match self.swarm.listen_on(circuit_addr(relay)) {
+                        Ok(id) => self.relay_listeners.push(id),
+                        Err(err) => log::warn!("cannot listen through relay {relay}: {err}"),
+                    }
+                }
+            }
+            NatStatus::Public(_) => {
+                for id in self.relay_listeners.drain(..) {
+                    self.swarm.remove_listener(id);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Leave the topic of `schema_version` of `subnet_id`, unless the
+    /// subnet has moved back to it since.
+    fn leave_subnet_topic(&mut self, subnet_id: u32, schema_version: u32) -> Option<NodeEvent> {
+        if self.subnet_versions.get(&subnet_id) == Some(&schema_version) {
+            return None;
+        }
+        let topic = gossipsub::IdentTopic::new(subnet_topic(subnet_id, schema_version));
+        let _ = self.swarm.behaviour_mut().gossipsub.unsubscribe(&topic);
+        Some(NodeEvent::SubnetTopicLeft {
+            subnet_id,
+            schema_version,
+        })
+    }
+
+    /// Publish prioritized messages, highest priority first, until the
+    /// burst is used up or the queue is empty.
+    fn drain_outbound(&mut self) {
+        while !self.publishing_paused && self.published_in_burst < self.publish_burst {
+            let Some(message) = self.outbound.pop() else {
+                break;
+            };
+            self.published_in_burst += 1;
+            let topic = gossipsub::IdentTopic::new(message.topic);
+            if let Err(err) = self
+                .swarm
+                .behaviour_mut()
+                .gossipsub
+                .publish(topic.clone(), message.data)
+            {
+                log::warn!("dropping prioritized message on {topic}: {err}");
+            }
+        }
+    }
+
+    /// Publish the queued messages whose topic now has enough peers.
+    fn flush_outbox(&mut self) {
+        self.expire_outbox();
+        let gossipsub = &self.swarm.behaviour().gossipsub;
+        let min_subnet_peers = self.min_subnet_peers;
+        let ready = self.outbox.take_ready(|subnet_id, schema_version| {
+            topic_peer_count(gossipsub, subnet_id, schema_version) >= min_subnet_peers
+        });
+        for message in ready {
+            // With the peers subscribed, publishing only fails if an
+            // identical message was published recently.
+            let _ = self.publish(
+                &subnet_topic(message.subnet_id, message.schema_version),
+                message.data,
+            );
+        }
+        self.reset_outbox_timer();
+    }
+
+    /// Drop queued messages whose time to live has run out and report
+    /// them.
+    fn expire_outbox(&mut self) {
+        let expired = self.outbox.expire(Instant::now());
+        self.queued_events
+            .extend(
+                expired
+                    .into_iter()
+                    .map(|message| NodeEvent::QueuedMessageExpired {
+                        subnet_id: message.subnet_id,
+                        schema_version: message.schema_version,
+                    }),
+            );
+        self.reset_outbox_timer();
+    }
+
+    fn reset_outbox_timer(&mut self) {
+        self.outbox_timer = self
+            .outbox
+            .next_expiry()
+            .map(|at| Delay::new(at.saturating_duration_since(Instant::now())));
+    }
+
+    fn ensure_not_observer(&self) -> Result<(), NodeError> {
+        if self.observer_mode {
+            return Err(NodeError::ObserverMode);
+        }
+        Ok(())
+    }
+
+    fn start_bootstrap(&mut self) -> Option<NodeEvent> {
+        let kademlia = &mut self.swarm.behaviour_mut().kademlia;
+        // Failed dials evict bootnodes from the routing table, so add
+        // them back before every attempt.
+        for (peer, addr) in &self.bootnodes {
+            kademlia.add_address(peer, addr.clone());
+        }
+        match kademlia.bootstrap() {
+            Ok(id) => {
+                self.bootstrap_query = Some((id, false));
+                None
+            }
+            Err(_) => self.bootstrap_failed(),
+        }
+    }
+
+    fn bootstrap_failed(&mut self) -> Option<NodeEvent> {
+        let (retry_in, change) = self.bootstrap.failed();
+        self.bootstrap_timer = Some(Delay::new(retry_in));
+        change.map(health_event)
+    }
+
+    fn handle_kademlia_event(&mut self, event: KademliaEvent) -> Option<NodeEvent> {
+        match event {
+            KademliaEvent::RoutingUpdated {
+                peer,
+                is_new_peer,
+                addresses,
+                ..
+            } => Some(NodeEvent::RoutingUpdated {
+                peer,
+                is_new_peer,
+                addresses: addresses.into_vec(),
+            }),
+            KademliaEvent::OutboundQueryProgressed {
+                id,
+                result: QueryResult::GetClosestPeers(result),
+                ..
+            } => {
+                let peers = match result {
+                    Ok(ok) => ok.peers,
+                    Err(GetClosestPeersError::Timeout { peers, .. }) => peers,
+                };
+                Some(NodeEvent::ClosestPeers { query: id, peers })
+            }
+            KademliaEvent::OutboundQueryProgressed {
+                id,
+                result: QueryResult::Bootstrap(_),
+                stats,
+                step,
+            } => self.bootstrap_progressed(id, stats, step),
+            _ => None,
+        }
+    }
+
+    /// Track the bootstrap query and report a change in discovery
+    /// health once it finishes.
+    fn bootstrap_progressed(
+        &mut self,
+        id: QueryId,
+        stats: QueryStats,
+        step: ProgressStep,
+    ) -> Option<NodeEvent> {
+        let (query, reached_peer) = self.bootstrap_query.as_mut()?;
+        if *query != id {
+            return None;
+        }
+        *reached_peer |= stats.num_successes() > 0;
+        if !step.last {
+            return None;
+        }
+
+        let reached_peer = *reached_peer;
+        self.bootstrap_query = None;
+        if reached_peer {
+            self.bootstrap.succeeded().map(health_event)
+        } else {
+            self.bootstrap_failed()
+        }
+    }
+
+    /// Validate a gossipsub message: oversized ones, and with
+    /// `require_authenticated_handshake` those forwarded by peers
+    /// without a proven account, are rejected and their sender
+    /// penalized.  The rest are forwarded and delivered unless they
+    /// duplicate a recent payload.
+    fn handle_gossip_message(
+        &mut self,
+        source: PeerId,
+        message_id: gossipsub::MessageId,
+        message: gossipsub::Message,
+    ) -> Option<NodeEvent> {
+        let size = message.data.len();
+        if size > self.max_gossipsub_message_size {
+            self.reject_message(source, &message_id);
+            return Some(NodeEvent::OversizedMessageRejected { peer: source, size });
+        }
+        if self.require_authenticated_handshake && !self.peer_accounts.contains_key(&source) {
+            self.reject_message(source, &message_id);
+            return Some(NodeEvent::UnauthenticatedMessageRejected { peer: source });
+        }
+        // Reporting only fails if the message has already left the
+        // cache, in which case there is nothing left to forward.
+        let _ = self
+            .swarm
+            .behaviour_mut()
+            .gossipsub
+            .report_message_validation_result(&message_id, &source, MessageAcceptance::Accept);
+        self.duplicates
+            .check(&message.topic, &message.data)
+            .then_some(NodeEvent::Message { source, message })
+    }
+
+    /// Reject a gossipsub message forwarded by `source` and lower the
+    /// peer's score by one more strike.
+    fn reject_message(&mut self, source: PeerId, message_id: &gossipsub::MessageId) {
+        let gossipsub = &mut self.swarm.behaviour_mut().gossipsub;
+        let _ = gossipsub.report_message_validation_result(
+            message_id,
+            &source,
+            MessageAcceptance::Reject,
+        );
+        let strikes = self.rejected_messages.entry(source).or_default();
+        *strikes += 1;
+        gossipsub.set_application_score(&source, -f64::from(*strikes));
+    }
+
+    /// Check an account proof sent by a peer, remember the account it
+    /// maps to and tell the peer whether it was accepted.
+    fn handle_account_event(
+        &mut self,
+        event: request_response::Event<AccountProof, bool>,
+    ) -> Option<NodeEvent> {
+        let request_response::Event::Message {
+            peer,
+            message:
+                request_response::Message::Request {
+                    request, channel, ..
+                },
+        } = event
+        else {
+            return None;
+        };
+        let account = match &self.account_resolver {
+            Some(resolver) if request.verify(&peer) => resolver.resolve(&request.public),
+            _ => None,
+        };
+        // Fails only if the peer has disconnected meanwhile.
+        let _ = self
+            .swarm
+            .behaviour_mut()
+            .account
+            .send_response(channel, account.is_some());
+        let account = account?;
+        self.peer_accounts.insert(peer, account);
+        Some(NodeEvent::PeerAuthenticated { peer, account })
+    }
+
+    fn handle_task_event(
+        &mut self,
+        event: request_response::Event<TaskRequest, TaskResponse>,
+    ) -> Option<TaskEvent> {
+        match event {
+            request_response::Event::Message {
+                peer,
+                message:
+                    request_response::Message::Request {
+                        request_id,
+                        request,
+                        channel,
+                    },
+            } => {
+                self.pending_tasks.insert(request_id, channel);
+                self.inbound_tasks.push(InboundTask {
+                    peer,
+                    request_id,
+                    request,
+                });
+                None
+            }
+            request_response::Event::Message {
+                peer,
+                message:
+                    request_response::Message::Response {
+                        request_id,
+                        response,
+                    },
+            } => Some(TaskEvent::TaskResponseReceived {
+                peer,
+                request_id,
+                response,
+            }),
+            request_response::Event::OutboundFailure {
+                peer,
+                request_id,
+                error,
+            } => {
+                if matches!(error, OutboundFailure::UnsupportedProtocols) {
+                    if self.peer_protocols.contains_key(&peer) {
+                        let mismatch = self.protocol_mismatch(peer);
+                        self.queued_events.push_back(mismatch);
+                    } else {
+                        self.unidentified_mismatches.insert(peer);
+                    }
+                }
+                Some(TaskEvent::TaskRequestFailed {
+                    peer,
+                    request_id,
+                    error,
+                })
+            }
+            request_response::Event::InboundFailure { request_id, .. } => {
+                self.pending_tasks.remove(&request_id);
+                self.inbound_tasks.remove(request_id);
+                None
+            }
+            request_response::Event::ResponseSent { .. } => None,
+        }
+    }
+
+    /// Queue an event for every peer the handshake refused.  The
+    /// refusal fails the connection, so this runs on connection errors.
+    fn report_handshake_rejections(&mut self) {
+        let mut rejections = self
+            .handshake_rejections
+            .lock()
+            .expect("lock is not poisoned");
+        self.queued_events.extend(
+            rejections
+                .drain(..)
+                .map(|peer| NodeEvent::HandshakeRejected { peer }),
+        );
+    }
+
+    /// Describe why `peer` could not take our task requests, using the
+    /// protocols it reported through identify.
+    fn protocol_mismatch(&self, peer: PeerId) -> NodeEvent {
+        let available = self
+            .peer_protocols
+            .get(&peer)
+            .into_iter()
+            .flatten()
+            .filter(|name| name.starts_with(PROTOCOL_PREFIX))
+            .cloned()
+            .collect();
+        NodeEvent::ProtocolMismatch {
+            peer,
+            wanted: self.task_protocols.clone(),
+            available,
+        }
+    }
+}
+
+fn health_event(change: HealthChange) -> NodeEvent {
+    match change {
+        HealthChange::Lost => NodeEvent::DiscoveryUnavailable,
+        HealthChange::Restored => NodeEvent::DiscoveryRestored,
+    }
+}
+
+/// Build the swarm shared by every node: the transport described by
+/// `config` carrying [`MyBehaviour`].  Refused handshakes are recorded
+/// in `rejections`.
+pub(crate) fn build_swarm(
+    keypair: identity::Keypair,
+    config: &NodeConfig,
+    rejections: Rejections,
+) -> Result<Swarm<MyBehaviour>, NodeError> {
+    let peer_id = PeerId::from(keypair.public());
+    let (relay_transport, relay) = relay::client::new(peer_id);
+    let transport = build_transport(&keypair, config, Some(relay_transport), rejections)?;
+
+    let identify = identify::Behaviour::new(
+        identify::Config::new(IDENTIFY_PROTOCOL_VERSION.to_string(), keypair.public())
+            .with_agent_version(AGENT_VERSION.to_string()),
+    );
+    // Oversized messages are let through the codec, up to a point,
+    // so that they can be rejected and their sender penalized.
+    let gossipsub_config = gossipsub::ConfigBuilder::default()
+        .validate_messages()
+        .max_transmit_size(config.max_gossipsub_message_size.saturating_mul(2))
+        .build()
+        .map_err(NodeError::Gossipsub)?;
+    let mut gossipsub = gossipsub::Behaviour::new(
+        gossipsub::MessageAuthenticity::Signed(keypair),
+        gossipsub_config,
+    )
+    .map_err(NodeError::Gossipsub)?;
+    gossipsub
+        .with_peer_score(PeerScoreParams::default(), PeerScoreThresholds::default())
+        .expect("default peer score parameters are valid");
+    let mut kademlia_config = KademliaConfig::default();
+    if !config.kademlia_server {
+        // Records put by others are then reported as inbound requests,
+        // which the node ignores.
+        kademlia_config.set_record_filtering(KademliaStoreInserts::FilterBoth);
+    }
+    let mut task_config = request_response::Config::default();
+    task_config.set_request_timeout(config.task_timeout);
+    let limits = config.connection_limits;
+    let behaviour = MyBehaviour {
+        mdns: config
+            .enable_mdns
+            .then(|| mdns::tokio::Behaviour::new(mdns::Config::default(), peer_id))
+            .transpose()?
+            .into(),
+        kademlia: Kademlia::with_config(peer_id, MemoryStore::new(peer_id), kademlia_config),
+        gossipsub,
+        task: request_response::Behaviour::new(
+            TaskCodec,
+            config
+                .task_protocols
+                .iter()
+                .map(|name| (TaskProtocol::new(name.clone()), ProtocolSupport::Full)),
+            task_config,
+        ),
+        identify,
+        account: request_response::Behaviour::new(
+            AccountCodec,
+            iter::once((AccountProtocol, ProtocolSupport::Full)),
+            request_response::Config::default(),
+        ),
+        limits: connection_limits::Behaviour::new(
+            connection_limits::ConnectionLimits::default()
+                .with_max_established_incoming(limits.max_established_incoming)
+                .with_max_established_outgoing(limits.max_established_outgoing)
+                .with_max_pending_incoming(limits.max_pending)
+                .with_max_pending_outgoing(limits.max_pending),
+        ),
+        autonat: autonat::Behaviour::new(peer_id, autonat::Config::default()),
+        relay,
+    };
+    Ok(SwarmBuilder::with_tokio_executor(transport, behaviour, peer_id).build())
+}
+
+/// The peer id an address ends in, as in `/p2p/<peer id>`.
+fn addr_peer(addr: &Multiaddr) -> Option<PeerId> {
+    match addr.iter().last() {
+        Some(Protocol::P2p(hash)) => PeerId::from_multihash(hash).ok(),
+        _ => None,
+    }
+}
+
+/// Number of peers `gossipsub` knows to be subscribed to the topic of
+/// `subnet_id` under `schema_version`.
+fn topic_peer_count(
+    gossipsub: &gossipsub::Behaviour,
+    subnet_id: u32,
+    schema_version: u32,
+) -> usize {
+    let topic = gossipsub::IdentTopic::new(subnet_topic(subnet_id, schema_version)).hash();
+    gossipsub
+        .all_peers()
+        .filter(|(_, topics)| topics.contains(&&topic))
+        .count()
+}
+
+/// Report a connection refused by the connection limits.  Refusals by
+/// other behaviours are not reported.
+fn connection_rejected(
+    peer: Option<PeerId>,
+    address: Option<Multiaddr>,
+    cause: ConnectionDenied,
+) -> Option<NodeEvent> {
+    let exceeded = cause.downcast::<Exceeded>().ok()?;
+    Some(NodeEvent::ConnectionRejected {
+        peer,
+        address,
+        reason: exceeded.to_string(),
+    })
+}
+
+/// Make peers found over mDNS known to Kademlia and, if we `dial`
+/// them, to gossipsub as explicit peers, and forget expired ones.
+/// Returns one [`NodeEvent::PeerDiscovered`] or
+/// [`NodeEvent::PeerExpired`] per peer.
+pub(crate) fn handle_mdns_event(
+    behaviour: &mut MyBehaviour,
+    event: mdns::Event,
+    dial: bool,
+) -> Vec<NodeEvent> {
+    match event {
+        mdns::Event::Discovered(peers) => {
+            let mut discovered: Vec<(PeerId, Vec<Multiaddr>)> = Vec::new();
+            for (peer, addr) in peers {
+                if dial {
+                    behaviour.gossipsub.add_explicit_peer(&peer);
+                }
+                behaviour.kademlia.add_address(&peer, addr.clone());
+                match discovered.iter_mut().find(|(known, _)| *known == peer) {
+                    Some((_, addresses)) => addresses.push(addr),
+                    None => discovered.push((peer, vec![addr])),
+                }
+            }
+            discovered
+                .into_iter()
+                .map(|(peer, addresses)| NodeEvent::PeerDiscovered { peer, addresses })
+                .collect()
+        }
+        mdns::Event::Expired(peers) => {
+            let mut expired: Vec<PeerId> = Vec::new();
+            for (peer, _) in peers {
+                if !expired.contains(&peer) {
+                    behaviour.gossipsub.remove_explicit_peer(&peer);
+                    expired.push(peer);
+                }
+            }
+            expired
+                .into_iter()
+                .map(|peer| NodeEvent::PeerExpired { peer })
+                .collect()
+        }
+    }
+}
+
+/// Leave every topic, keep `swarm` running for
+/// [`SHUTDOWN_FLUSH_PERIOD`] so that pending messages go out, then
+/// close every connection, waiting up to [`SHUTDOWN_TIMEOUT`] for them
+/// to close.  Returns the number of peers that were connected.
+pub(crate) async fn close_swarm(swarm: &mut Swarm<MyBehaviour>) -> usize {
+    let gossipsub = &mut swarm.behaviour_mut().gossipsub;
+    let topics: Vec<_> = gossipsub.topics().cloned().collect();
+    for topic in topics {
+        // Topics are identity hashed, so a topic's hash is its name.
+        let _ = gossipsub.unsubscribe(&gossipsub::IdentTopic::new(topic.into_string()));
+    }
+
+    let peers: Vec<PeerId> = swarm.connected_peers().copied().collect();
+    if !peers.is_empty() {
+        drive_until(swarm, SHUTDOWN_FLUSH_PERIOD, |_| false).await;
+    }
+    for peer in &peers {
+        let _ = swarm.disconnect_peer_id(*peer);
+    }
+    drive_until(swarm, SHUTDOWN_TIMEOUT, |swarm| {
+        swarm.network_info().num_peers() == 0
+    })
+    .await;
+    log::info!(
+        "{} shut down, disconnected from {} peers",
+        swarm.local_peer_id(),
+        peers.len()
+    );
+    peers.len()
+}
+
+/// Drive `swarm`, dropping its events, until `done` holds or `timeout`
+/// has passed.
+async fn drive_until(
+    swarm: &mut Swarm<MyBehaviour>,
+    timeout: Duration,
+    done: impl Fn(&Swarm<MyBehaviour>) -> bool,
+) {
+    let mut deadline = Delay::new(timeout);
+    while !done(swarm) {
+        if let Either::Right(_) = future::select(swarm.select_next_some(), &mut deadline).await {
+            break;
+        }
+    }
+}
+
+/// Build the transport described by `config`: TCP, and circuits
+/// through relays if a `relay` client transport is given.
+fn build_transport(
+    keypair: &identity::Keypair,
+    config: &NodeConfig,
+    relay: Option<relay::client::Transport>,
+    rejections: Rejections,
+) -> Result<Boxed<(PeerId, StreamMuxerBox)>, NodeError> {
+    let handshake = config.handshake.clone();
+    let relay = relay
+        .map_or_else(OptionalTransport::none, OptionalTransport::some)
+        .map_err(|err| io::Error::new(io::ErrorKind::Other, err));
+    let transport = relay
+        .or_transport(tcp::tokio::Transport::new(
+            tcp::Config::default().nodelay(true),
+        ))
+        .map_err(|err| err.into_inner());
+    let transport = ListenOnly::new(transport, config.observer_mode);
+    #[cfg(feature = "testing")]
+    let transport = crate::fault::FaultInjection::new(transport, config.faults.clone());
+    let transport = transport
+        .upgrade(upgrade::Version::V1)
+        .authenticate(noise::Config::new(keypair)?)
+        .multiplex_ext(move |peer: &PeerId, _: &ConnectedPoint| {
+            Handshake::new(*peer, handshake.clone(), rejections.clone())
+        })
+        .map(|(peer, muxer), _| (peer, StreamMuxerBox::new(muxer)));
+    #[cfg(feature = "quic")]
+    let transport = transport
+        .or_transport(quic_transport(keypair, config))
+        .map(|output, _| output.into_inner());
+    Ok(HappyEyeballs::new(transport, config.dial_stagger, config.dial_timeout).boxed())
+}
+
+/// QUIC, which secures and multiplexes connections itself.  The
+/// credential handshake runs between noise and the multiplexer, which
+/// QUIC leaves no room for, so QUIC is left out when a handshake is
+/// configured and `/quic-v1` addresses are refused instead.
+#[cfg(feature = "quic")]
+fn quic_transport(
+    keypair: &identity::Keypair,
+    config: &NodeConfig,
+) -> impl Transport<Output = (PeerId, StreamMuxerBox), Error = io::Error> + Unpin {
+    let quic = config.handshake.is_none().then(|| {
+        libp2p_quic::tokio::Transport::new(libp2p_quic::Config::new(keypair))
+            .map(|(peer, connection), _| (peer, StreamMuxerBox::new(connection)))
+    });
+    let quic = quic
+        .map_or_else(OptionalTransport::none, OptionalTransport::some)
+        .map_err(|err| io::Error::new(io::ErrorKind::Other, err));
+    ListenOnly::new(quic, config.observer_mode)
+}
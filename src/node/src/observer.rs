@@ -0,0 +1,78 @@
+//! Transport guard for observer mode.
+//!
+//! An observer must not affect the mesh, so [`ListenOnly`] refuses
+//! every outbound dial at the transport, whichever behaviour asked for
+//! it, while inbound connections are accepted as usual.
+
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use libp2p::core::transport::{ListenerId, TransportError, TransportEvent};
+use libp2p::{Multiaddr, Transport};
+
+/// Transport wrapper that refuses to dial when `listen_only` is set
+/// and otherwise passes everything through to `T`.
+pub(crate) struct ListenOnly<T> {
+    inner: T,
+    listen_only: bool,
+}
+
+impl<T> ListenOnly<T> {
+    pub(crate) fn new(inner: T, listen_only: bool) -> Self {
+        Self { inner, listen_only }
+    }
+
+    fn refused(&self) -> TransportError<io::Error> {
+        TransportError::Other(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            "observer nodes do not dial",
+        ))
+    }
+}
+
+impl<T> Transport for ListenOnly<T>
+where
+    T: Transport<Error = io::Error> + Unpin,
+{
+    type Output = T::Output;
+    type Error = io::Error;
+    type ListenerUpgrade = T::ListenerUpgrade;
+    type Dial = T::Dial;
+
+    fn listen_on(&mut self, addr: Multiaddr) -> Result<ListenerId, TransportError<Self::Error>> {
+        self.inner.listen_on(addr)
+    }
+
+    fn remove_listener(&mut self, id: ListenerId) -> bool {
+        self.inner.remove_listener(id)
+    }
+
+    fn dial(&mut self, addr: Multiaddr) -> Result<Self::Dial, TransportError<Self::Error>> {
+        if self.listen_only {
+            return Err(self.refused());
+        }
+        self.inner.dial(addr)
+    }
+
+    fn dial_as_listener(
+        &mut self,
+        addr: Multiaddr,
+    ) -> Result<Self::Dial, TransportError<Self::Error>> {
+        if self.listen_only {
+            return Err(self.refused());
+        }
+        self.inner.dial_as_listener(addr)
+    }
+
+    fn poll(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<TransportEvent<Self::ListenerUpgrade, Self::Error>> {
+        Pin::new(&mut self.inner).poll(cx)
+    }
+
+    fn address_translation(&self, listen: &Multiaddr, observed: &Multiaddr) -> Option<Multiaddr> {
+        self.inner.address_translation(listen, observed)
+    }
+}
@@ -0,0 +1,93 @@
+//! Subnet messages held back until enough peers listen.
+//!
+//! [`Node::publish_to_subnet_or_queue`](crate::Node::publish_to_subnet_or_queue)
+//! places a message in the [`Outbox`] when too few peers are
+//! subscribed to its subnet topic, rather than publishing it into the
+//! void.  Messages leave the outbox once enough peers have joined the
+//! topic, or are dropped when their time to live runs out.  The outbox
+//! holds a bounded number of messages.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// A message waiting for peers on its subnet topic.
+#[derive(Debug)]
+pub(crate) struct QueuedMessage {
+    pub(crate) subnet_id: u32,
+    pub(crate) schema_version: u32,
+    pub(crate) data: Vec<u8>,
+    expires_at: Instant,
+}
+
+/// Messages waiting for peers, oldest first.
+#[derive(Debug)]
+pub(crate) struct Outbox {
+    queue: VecDeque<QueuedMessage>,
+    capacity: usize,
+    ttl: Duration,
+}
+
+impl Outbox {
+    pub(crate) fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            queue: VecDeque::new(),
+            capacity,
+            ttl,
+        }
+    }
+
+    /// Hold `data` for the topic of `subnet_id` under
+    /// `schema_version`.  Hands `data` back if the outbox is full.
+    pub(crate) fn push(
+        &mut self,
+        subnet_id: u32,
+        schema_version: u32,
+        data: Vec<u8>,
+    ) -> Result<(), Vec<u8>> {
+        if self.queue.len() >= self.capacity {
+            return Err(data);
+        }
+        self.queue.push_back(QueuedMessage {
+            subnet_id,
+            schema_version,
+            data,
+            expires_at: Instant::now() + self.ttl,
+        });
+        Ok(())
+    }
+
+    /// When the oldest message expires.  Every message lives for the
+    /// same time, so no later one expires before it.
+    pub(crate) fn next_expiry(&self) -> Option<Instant> {
+        self.queue.front().map(|message| message.expires_at)
+    }
+
+    /// Remove and return the messages that have expired by `now`.
+    pub(crate) fn expire(&mut self, now: Instant) -> Vec<QueuedMessage> {
+        let expired = self
+            .queue
+            .iter()
+            .take_while(|message| message.expires_at <= now)
+            .count();
+        self.queue.drain(..expired).collect()
+    }
+
+    /// Remove and return the messages whose topic is `ready`, keeping
+    /// the order of the rest.
+    pub(crate) fn take_ready(
+        &mut self,
+        mut ready: impl FnMut(u32, u32) -> bool,
+    ) -> Vec<QueuedMessage> {
+        let mut taken = Vec::new();
+        let mut kept = VecDeque::with_capacity(self.queue.len());
+        for message in self.queue.drain(..) {
+            if ready(message.subnet_id, message.schema_version) {
+                taken.push(message);
+            } else {
+                kept.push_back(message);
+            }
+        }
+        self.queue = kept;
+        taken
+    }
+}
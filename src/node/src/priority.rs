@@ -0,0 +1,138 @@
+//! Ordering of inbound task requests by subnet priority.
+//!
+//! A miner serving several subnets may not keep up with every request.
+//! Inbound requests are held in [`InboundTasks`] and handed out highest
+//! priority first, where a subnet's priority is typically its emission
+//! weight as read from chain state by the application.  Requests from
+//! subnets of equal priority keep their arrival order.
+//!
+//! Outbound gossip is ordered the same way: messages that cannot go
+//! out at once wait in [`OutboundMessages`] and leave highest priority
+//! first, so task assignments overtake telemetry under load.
+
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+
+use libp2p::request_response::RequestId;
+use libp2p::PeerId;
+
+use crate::task::TaskRequest;
+
+/// An inbound task request waiting to be handed out.
+#[derive(Debug)]
+pub(crate) struct InboundTask {
+    pub(crate) peer: PeerId,
+    pub(crate) request_id: RequestId,
+    pub(crate) request: TaskRequest,
+}
+
+/// Inbound task requests waiting to be handed out.
+#[derive(Debug, Default)]
+pub(crate) struct InboundTasks {
+    queue: VecDeque<InboundTask>,
+    /// Priority of each subnet.  Subnets without an entry have
+    /// priority zero.
+    priorities: HashMap<u32, u32>,
+}
+
+impl InboundTasks {
+    pub(crate) fn set_priorities(&mut self, priorities: HashMap<u32, u32>) {
+        self.priorities = priorities;
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    pub(crate) fn push(&mut self, task: InboundTask) {
+        self.queue.push_back(task);
+    }
+
+    /// Drop a request that can no longer be answered.
+    pub(crate) fn remove(&mut self, request_id: RequestId) {
+        self.queue.retain(|task| task.request_id != request_id);
+    }
+
+    /// Remove the oldest request of the highest‑priority subnet.
+    pub(crate) fn pop(&mut self) -> Option<InboundTask> {
+        let (index, _) = self
+            .queue
+            .iter()
+            .enumerate()
+            .rev()
+            .max_by_key(|(_, task)| self.priority(task.request.subnet_id))?;
+        self.queue.remove(index)
+    }
+
+    fn priority(&self, subnet_id: u32) -> u32 {
+        self.priorities.get(&subnet_id).copied().unwrap_or(0)
+    }
+}
+
+/// A gossipsub message waiting to be published.
+#[derive(Debug)]
+pub(crate) struct OutboundMessage {
+    pub(crate) topic: String,
+    pub(crate) data: Vec<u8>,
+}
+
+/// A queued message with the key it is ordered by: its priority, then
+/// its position in the queue.
+#[derive(Debug)]
+struct Queued {
+    priority: u32,
+    sequence: Reverse<u64>,
+    message: OutboundMessage,
+}
+
+impl PartialEq for Queued {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Queued {}
+
+impl PartialOrd for Queued {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Queued {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.priority, self.sequence).cmp(&(other.priority, other.sequence))
+    }
+}
+
+/// Gossipsub messages waiting to be published.
+#[derive(Debug, Default)]
+pub(crate) struct OutboundMessages {
+    heap: BinaryHeap<Queued>,
+    next_sequence: u64,
+}
+
+impl OutboundMessages {
+    pub(crate) fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    pub(crate) fn push(&mut self, priority: u32, message: OutboundMessage) {
+        let sequence = Reverse(self.next_sequence);
+        self.next_sequence += 1;
+        self.heap.push(Queued {
+            priority,
+            sequence,
+            message,
+        });
+    }
+
+    /// Remove the oldest message of the highest priority.
+    pub(crate) fn pop(&mut self) -> Option<OutboundMessage> {
+        self.heap.pop().map(|queued| queued.message)
+    }
+}
@@ -0,0 +1,365 @@
+//! gRPC service for off‑chain workers.
+//!
+//! Workers that do not run a libp2p stack hand tasks to a node, and
+//! collect their results, over a conventional gRPC channel.
+//! [`MeshService`] is the server side, served by wrapping an
+//! implementation in a [`MeshServiceServer`]; [`MeshServiceClient`]
+//! calls it.  Both are laid out like the code `tonic-build` generates,
+//! but messages are SCALE‑encoded by [`ScaleCodec`] instead of
+//! protobuf, so task inputs and outputs keep the encoding they have
+//! everywhere else.  Inputs and outputs travel as the bytes of a
+//! [`NeuralTask`]'s types, built and read with the helpers on the
+//! messages.
+
+use std::marker::PhantomData;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use bytes::{Buf, BufMut};
+use codec::{Decode, DecodeAll, Encode};
+use sp_neuro_core::{NeuralTask, NeuralTaskError, TaskError, TaskId};
+use tonic::codec::{Codec, DecodeBuf, Decoder, EncodeBuf, Encoder};
+use tonic::codegen::{empty_body, http, Body, BoxFuture, Bytes, Service, StdError};
+use tonic::{Request, Response, Status};
+
+/// Fully qualified name of the service.
+pub const SERVICE_NAME: &str = "neuromesh.MeshService";
+
+const SUBMIT_TASK_PATH: &str = "/neuromesh.MeshService/SubmitTask";
+const GET_TASK_RESULT_PATH: &str = "/neuromesh.MeshService/GetTaskResult";
+const HEARTBEAT_PATH: &str = "/neuromesh.MeshService/Heartbeat";
+
+/// A task handed to the node for `subnet_id`.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+pub struct SubmitTaskRequest {
+    pub subnet_id: u32,
+    /// The SCALE‑encoded task input.
+    pub input: Vec<u8>,
+}
+
+impl SubmitTaskRequest {
+    /// A request for `subnet_id` carrying `input` of task `T`.
+    pub fn encode<T: NeuralTask>(subnet_id: u32, input: &T::Input) -> Self {
+        Self {
+            subnet_id,
+            input: input.encode(),
+        }
+    }
+
+    /// Decode the input as one of task `T` and check it with
+    /// [`NeuralTask::validate_input`].
+    pub fn decode_input<T: NeuralTask>(&self) -> Result<T::Input, TaskError> {
+        T::decode_and_validate_input(&self.input)
+    }
+}
+
+/// The node accepted a task under `task_id`.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+pub struct SubmitTaskResponse {
+    pub task_id: TaskId,
+}
+
+/// Asks for the result of the task accepted under `task_id`.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+pub struct GetTaskResultRequest {
+    pub task_id: TaskId,
+}
+
+/// The result of a task, `None` while it is still being worked on.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+pub struct GetTaskResultResponse {
+    /// The SCALE‑encoded task output.
+    pub output: Option<Vec<u8>>,
+}
+
+impl GetTaskResultResponse {
+    /// A response carrying `output` of task `T`.
+    pub fn encode<T: NeuralTask>(output: &T::Output) -> Self {
+        Self {
+            output: Some(output.encode()),
+        }
+    }
+
+    /// Decode the output of task `task_id`, if there is one yet, as one
+    /// of task `T`.
+    pub fn decode_output<T: NeuralTask>(
+        &self,
+        task_id: TaskId,
+    ) -> Result<Option<T::Output>, NeuralTaskError> {
+        self.output
+            .as_ref()
+            .map(|output| T::decode_output(task_id, output))
+            .transpose()
+    }
+}
+
+/// A worker checking that the node is alive.  The node echoes
+/// `sequence`.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+pub struct HeartbeatRequest {
+    pub sequence: u64,
+}
+
+/// The node's answer to a [`HeartbeatRequest`].
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+pub struct HeartbeatResponse {
+    pub sequence: u64,
+    /// Peers the node is connected to.
+    pub peer_count: u32,
+}
+
+/// gRPC codec encoding messages of type `E` and decoding messages of
+/// type `D` as SCALE.
+#[derive(Debug)]
+pub struct ScaleCodec<E, D>(PhantomData<(E, D)>);
+
+impl<E, D> Default for ScaleCodec<E, D> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<E, D> Codec for ScaleCodec<E, D>
+where
+    E: Encode + Send + 'static,
+    D: Decode + Send + 'static,
+{
+    type Encode = E;
+    type Decode = D;
+    type Encoder = ScaleEncoder<E>;
+    type Decoder = ScaleDecoder<D>;
+
+    fn encoder(&mut self) -> Self::Encoder {
+        ScaleEncoder(PhantomData)
+    }
+
+    fn decoder(&mut self) -> Self::Decoder {
+        ScaleDecoder(PhantomData)
+    }
+}
+
+/// Encoder half of [`ScaleCodec`].
+#[derive(Debug)]
+pub struct ScaleEncoder<E>(PhantomData<E>);
+
+impl<E: Encode> Encoder for ScaleEncoder<E> {
+    type Item = E;
+    type Error = Status;
+
+    fn encode(&mut self, item: E, dst: &mut EncodeBuf<'_>) -> Result<(), Status> {
+        dst.put_slice(&item.encode());
+        Ok(())
+    }
+}
+
+/// Decoder half of [`ScaleCodec`].
+#[derive(Debug)]
+pub struct ScaleDecoder<D>(PhantomData<D>);
+
+impl<D: Decode> Decoder for ScaleDecoder<D> {
+    type Item = D;
+    type Error = Status;
+
+    /// `src` holds exactly one message, which must be decoded whole.
+    fn decode(&mut self, src: &mut DecodeBuf<'_>) -> Result<Option<D>, Status> {
+        let bytes = src.copy_to_bytes(src.remaining());
+        D::decode_all(&mut &bytes[..])
+            .map(Some)
+            .map_err(|err| Status::invalid_argument(format!("invalid SCALE message: {err}")))
+    }
+}
+
+/// The server side of the service.
+#[tonic::async_trait]
+pub trait MeshService: Send + Sync + 'static {
+    /// Accept a task and return the id its result is collected under.
+    async fn submit_task(
+        &self,
+        request: Request<SubmitTaskRequest>,
+    ) -> Result<Response<SubmitTaskResponse>, Status>;
+
+    /// Return the result of a task accepted earlier.
+    async fn get_task_result(
+        &self,
+        request: Request<GetTaskResultRequest>,
+    ) -> Result<Response<GetTaskResultResponse>, Status>;
+
+    /// Answer a worker's liveness check.
+    async fn heartbeat(
+        &self,
+        request: Request<HeartbeatRequest>,
+    ) -> Result<Response<HeartbeatResponse>, Status>;
+}
+
+/// Serves a [`MeshService`] implementation, for instance through
+/// [`tonic::transport::Server::add_service`].
+#[derive(Debug)]
+pub struct MeshServiceServer<T> {
+    inner: Arc<T>,
+}
+
+impl<T: MeshService> MeshServiceServer<T> {
+    pub fn new(inner: T) -> Self {
+        Self::from_arc(Arc::new(inner))
+    }
+
+    pub fn from_arc(inner: Arc<T>) -> Self {
+        Self { inner }
+    }
+}
+
+impl<T> Clone for MeshServiceServer<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T: MeshService> tonic::server::NamedService for MeshServiceServer<T> {
+    const NAME: &'static str = SERVICE_NAME;
+}
+
+/// Adapts one method of a [`MeshService`] to tonic's unary service.
+struct Unary<T, Req, Res> {
+    inner: Arc<T>,
+    call: fn(Arc<T>, Request<Req>) -> BoxFuture<Response<Res>, Status>,
+}
+
+impl<T, Req, Res> tonic::server::UnaryService<Req> for Unary<T, Req, Res> {
+    type Response = Res;
+    type Future = BoxFuture<Response<Res>, Status>;
+
+    fn call(&mut self, request: Request<Req>) -> Self::Future {
+        (self.call)(self.inner.clone(), request)
+    }
+}
+
+impl<T, B> Service<http::Request<B>> for MeshServiceServer<T>
+where
+    T: MeshService,
+    B: Body + Send + 'static,
+    B::Error: Into<StdError> + Send + 'static,
+{
+    type Response = http::Response<tonic::body::BoxBody>;
+    type Error = std::convert::Infallible;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        let inner = self.inner.clone();
+        match req.uri().path() {
+            SUBMIT_TASK_PATH => Box::pin(async move {
+                let method = Unary {
+                    inner,
+                    call: |inner, request| {
+                        Box::pin(async move { inner.submit_task(request).await })
+                    },
+                };
+                let mut grpc = tonic::server::Grpc::new(ScaleCodec::default());
+                Ok(grpc.unary(method, req).await)
+            }),
+            GET_TASK_RESULT_PATH => Box::pin(async move {
+                let method = Unary {
+                    inner,
+                    call: |inner, request| {
+                        Box::pin(async move { inner.get_task_result(request).await })
+                    },
+                };
+                let mut grpc = tonic::server::Grpc::new(ScaleCodec::default());
+                Ok(grpc.unary(method, req).await)
+            }),
+            HEARTBEAT_PATH => Box::pin(async move {
+                let method = Unary {
+                    inner,
+                    call: |inner, request| Box::pin(async move { inner.heartbeat(request).await }),
+                };
+                let mut grpc = tonic::server::Grpc::new(ScaleCodec::default());
+                Ok(grpc.unary(method, req).await)
+            }),
+            _ => Box::pin(async move {
+                Ok(http::Response::builder()
+                    .status(200)
+                    .header("grpc-status", tonic::Code::Unimplemented as i32)
+                    .header("content-type", "application/grpc")
+                    .body(empty_body())
+                    .expect("valid response"))
+            }),
+        }
+    }
+}
+
+/// Calls a node's [`MeshService`].
+#[derive(Clone, Debug)]
+pub struct MeshServiceClient<T> {
+    inner: tonic::client::Grpc<T>,
+}
+
+impl MeshServiceClient<tonic::transport::Channel> {
+    /// Connect to the service at `dst`, such as
+    /// `http://127.0.0.1:50051`.
+    pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
+    where
+        D: TryInto<tonic::transport::Endpoint>,
+        D::Error: Into<StdError>,
+    {
+        let channel = tonic::transport::Endpoint::new(dst)?.connect().await?;
+        Ok(Self::new(channel))
+    }
+}
+
+impl<T> MeshServiceClient<T>
+where
+    T: tonic::client::GrpcService<tonic::body::BoxBody>,
+    T::Error: Into<StdError>,
+    T::ResponseBody: Body<Data = Bytes> + Send + 'static,
+    <T::ResponseBody as Body>::Error: Into<StdError> + Send,
+{
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner: tonic::client::Grpc::new(inner),
+        }
+    }
+
+    pub async fn submit_task(
+        &mut self,
+        request: impl tonic::IntoRequest<SubmitTaskRequest>,
+    ) -> Result<Response<SubmitTaskResponse>, Status> {
+        self.unary(request.into_request(), SUBMIT_TASK_PATH).await
+    }
+
+    pub async fn get_task_result(
+        &mut self,
+        request: impl tonic::IntoRequest<GetTaskResultRequest>,
+    ) -> Result<Response<GetTaskResultResponse>, Status> {
+        self.unary(request.into_request(), GET_TASK_RESULT_PATH)
+            .await
+    }
+
+    pub async fn heartbeat(
+        &mut self,
+        request: impl tonic::IntoRequest<HeartbeatRequest>,
+    ) -> Result<Response<HeartbeatResponse>, Status> {
+        self.unary(request.into_request(), HEARTBEAT_PATH).await
+    }
+
+    async fn unary<Req, Res>(
+        &mut self,
+        request: Request<Req>,
+        path: &'static str,
+    ) -> Result<Response<Res>, Status>
+    where
+        Req: Encode + Send + Sync + 'static,
+        Res: Decode + Send + Sync + 'static,
+    {
+        self.inner
+            .ready()
+            .await
+            .map_err(|err| Status::unknown(format!("service was not ready: {}", err.into())))?;
+        let path = http::uri::PathAndQuery::from_static(path);
+        self.inner.unary(request, path, ScaleCodec::default()).await
+    }
+}
@@ -0,0 +1,172 @@
+//! Direct task dispatch between peers.
+//!
+//! Tasks are sent over a request‑response protocol rather than
+//! gossipsub so that a miner can answer the validator that asked.
+//! Each frame is length prefixed; a request carries the id of the
+//! subnet it belongs to followed by the opaque task payload.
+//!
+//! Payloads are opaque to the protocol.  Peers serving a
+//! [`NeuralTask`] build and read them with the SCALE helpers on
+//! [`TaskRequest`] and [`TaskResponse`].
+//!
+//! A node may offer several versions of the protocol.  The framing is
+//! the same for all of them, so [`TaskCodec`] serves whichever version
+//! the two peers agree on.
+
+use std::io;
+
+use async_trait::async_trait;
+use codec::Encode;
+use futures::{AsyncRead, AsyncWrite, AsyncWriteExt};
+use libp2p::core::upgrade::{read_length_prefixed, write_length_prefixed};
+use libp2p::request_response::{self, ProtocolName};
+use sp_neuro_core::{NeuralTask, NeuralTaskError, TaskError, TaskId};
+
+/// Protocol name negotiated for task dispatch.
+pub const PROTOCOL_NAME: &str = "/neuromesh/task/1.0.0";
+
+/// Prefix shared by every version of the task dispatch protocol.
+pub const PROTOCOL_PREFIX: &str = "/neuromesh/task/";
+
+/// Largest task payload accepted in either direction.
+pub const MAX_TASK_SIZE: usize = 1024 * 1024;
+
+/// A task sent to a peer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TaskRequest {
+    pub subnet_id: u32,
+    pub payload: Vec<u8>,
+}
+
+impl TaskRequest {
+    /// A request for `subnet_id` carrying `input` of task `T`,
+    /// SCALE‑encoded.
+    pub fn encode<T: NeuralTask>(subnet_id: u32, input: &T::Input) -> Self {
+        Self {
+            subnet_id,
+            payload: input.encode(),
+        }
+    }
+
+    /// Decode the payload as an input of task `T` and check it with
+    /// [`NeuralTask::validate_input`].
+    pub fn decode_input<T: NeuralTask>(&self) -> Result<T::Input, TaskError> {
+        T::decode_and_validate_input(&self.payload)
+    }
+}
+
+/// A peer's answer to a [`TaskRequest`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TaskResponse {
+    pub payload: Vec<u8>,
+}
+
+impl TaskResponse {
+    /// A response carrying `output` of task `T`, SCALE‑encoded.
+    pub fn encode<T: NeuralTask>(output: &T::Output) -> Self {
+        Self {
+            payload: output.encode(),
+        }
+    }
+
+    /// Decode the payload as the output of task `task_id`, of type `T`.
+    pub fn decode_output<T: NeuralTask>(
+        &self,
+        task_id: TaskId,
+    ) -> Result<T::Output, NeuralTaskError> {
+        T::decode_output(task_id, &self.payload)
+    }
+}
+
+/// One version of the task dispatch protocol, identified by its
+/// protocol name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TaskProtocol(String);
+
+impl TaskProtocol {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self(name.into())
+    }
+
+    pub fn name(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Default for TaskProtocol {
+    fn default() -> Self {
+        Self::new(PROTOCOL_NAME)
+    }
+}
+
+impl ProtocolName for TaskProtocol {
+    fn protocol_name(&self) -> &[u8] {
+        self.0.as_bytes()
+    }
+}
+
+/// Wire codec for [`TaskRequest`] and [`TaskResponse`].
+#[derive(Clone, Debug, Default)]
+pub struct TaskCodec;
+
+#[async_trait]
+impl request_response::Codec for TaskCodec {
+    type Protocol = TaskProtocol;
+    type Request = TaskRequest;
+    type Response = TaskResponse;
+
+    async fn read_request<T>(&mut self, _: &TaskProtocol, io: &mut T) -> io::Result<TaskRequest>
+    where
+        T: AsyncRead + Unpin + Send,
+    {
+        let frame = read_length_prefixed(io, MAX_TASK_SIZE + 4).await?;
+        if frame.len() < 4 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "task request is missing its subnet id",
+            ));
+        }
+        let (subnet_id, payload) = frame.split_at(4);
+        Ok(TaskRequest {
+            subnet_id: u32::from_be_bytes(subnet_id.try_into().expect("split at 4 bytes")),
+            payload: payload.to_vec(),
+        })
+    }
+
+    async fn read_response<T>(&mut self, _: &TaskProtocol, io: &mut T) -> io::Result<TaskResponse>
+    where
+        T: AsyncRead + Unpin + Send,
+    {
+        let payload = read_length_prefixed(io, MAX_TASK_SIZE).await?;
+        Ok(TaskResponse { payload })
+    }
+
+    async fn write_request<T>(
+        &mut self,
+        _: &TaskProtocol,
+        io: &mut T,
+        request: TaskRequest,
+    ) -> io::Result<()>
+    where
+        T: AsyncWrite + Unpin + Send,
+    {
+        let mut frame = Vec::with_capacity(4 + request.payload.len());
+        frame.extend_from_slice(&request.subnet_id.to_be_bytes());
+        frame.extend_from_slice(&request.payload);
+        write_length_prefixed(io, frame).await?;
+        io.close().await
+    }
+
+    async fn write_response<T>(
+        &mut self,
+        _: &TaskProtocol,
+        io: &mut T,
+        response: TaskResponse,
+    ) -> io::Result<()>
+    where
+        T: AsyncWrite + Unpin + Send,
+    {
+        write_length_prefixed(io, response.payload).await?;
+        io.close().await
+    }
+}
@@ -0,0 +1,112 @@
+use std::time::Duration;
+
+use libp2p::{identity, Multiaddr};
+use neurochain_node::auth::{AccountConfig, AccountId, AccountKey, AccountProof};
+use neurochain_node::{Node, NodeConfig, NodeEvent};
+use prometheus_client::registry::Registry;
+use tokio::time;
+
+const TOPIC: &str = "subnet-1-weights";
+const ACCOUNT: AccountId = [42; 32];
+
+fn node(config: NodeConfig) -> Node {
+    Node::with_config(
+        identity::Keypair::generate_ed25519(),
+        config,
+        &mut Registry::default(),
+    )
+    .unwrap()
+}
+
+/// Dial `address` from `node` and keep publishing distinct messages,
+/// since the first ones may arrive before the peer has checked our
+/// account.
+fn publish_in_background(mut node: Node, address: Multiaddr) {
+    node.subscribe(TOPIC).unwrap();
+    node.dial(address).unwrap();
+    tokio::spawn(async move {
+        for round in 0u32.. {
+            let _ = node.publish(TOPIC, round.to_be_bytes().to_vec());
+            let _ = time::timeout(Duration::from_millis(100), async {
+                loop {
+                    node.next_event().await;
+                }
+            })
+            .await;
+        }
+    });
+}
+
+#[tokio::test]
+async fn only_messages_from_authenticated_peers_are_accepted() {
+    let member_key = AccountKey::from_seed(&[1; 32]);
+    let registered = member_key.public();
+    let mut gatekeeper = node(NodeConfig {
+        account: Some(AccountConfig::new(None, move |public: &[u8; 32]| {
+            (*public == registered).then_some(ACCOUNT)
+        })),
+        require_authenticated_handshake: true,
+        ..NodeConfig::default()
+    });
+    gatekeeper.subscribe(TOPIC).unwrap();
+    gatekeeper
+        .listen_on("/ip4/127.0.0.1/tcp/0".parse().unwrap())
+        .unwrap();
+    let address = loop {
+        if let NodeEvent::NewListenAddr { address } = gatekeeper.next_event().await {
+            break address;
+        }
+    };
+
+    let member = node(NodeConfig {
+        account: Some(AccountConfig::new(Some(member_key), |_: &[u8; 32]| None)),
+        ..NodeConfig::default()
+    });
+    let member_id = member.local_peer_id();
+    publish_in_background(member, address.clone());
+    time::timeout(Duration::from_secs(30), async {
+        loop {
+            match gatekeeper.next_event().await {
+                NodeEvent::Message { source, .. } if source == member_id => break,
+                NodeEvent::UnauthenticatedMessageRejected { peer } => {
+                    assert_ne!(peer, member_id, "member's message was rejected")
+                }
+                _ => {}
+            }
+        }
+    })
+    .await
+    .expect("member's message is accepted");
+    assert_eq!(gatekeeper.peer_account(&member_id), Some(ACCOUNT));
+
+    // An outsider proves no account at all.
+    let outsider = node(NodeConfig::default());
+    let outsider_id = outsider.local_peer_id();
+    publish_in_background(outsider, address);
+    time::timeout(Duration::from_secs(30), async {
+        loop {
+            match gatekeeper.next_event().await {
+                NodeEvent::UnauthenticatedMessageRejected { peer } if peer == outsider_id => break,
+                NodeEvent::Message { source, .. } if source == outsider_id => {
+                    panic!("outsider's message was accepted")
+                }
+                _ => {}
+            }
+        }
+    })
+    .await
+    .expect("outsider's message is rejected");
+    assert_eq!(gatekeeper.peer_account(&outsider_id), None);
+    assert!(gatekeeper.peer_score(&outsider_id).unwrap() < 0.0);
+}
+
+#[test]
+fn proof_only_verifies_for_the_signed_peer() {
+    let key = AccountKey::from_seed(&[1; 32]);
+    let peer = identity::Keypair::generate_ed25519().public().to_peer_id();
+    let other = identity::Keypair::generate_ed25519().public().to_peer_id();
+
+    let proof = AccountProof::sign(&key, &peer);
+    assert!(proof.verify(&peer));
+    assert!(!proof.verify(&other));
+}
@@ -0,0 +1,83 @@
+use std::time::Duration;
+
+use libp2p::{identity, Multiaddr};
+use neurochain_node::{ConnectionLimits, Node, NodeConfig, NodeEvent};
+use prometheus_client::registry::Registry;
+use tokio::time;
+
+fn node(connection_limits: ConnectionLimits) -> Node {
+    let config = NodeConfig {
+        enable_mdns: false,
+        connection_limits,
+        ..NodeConfig::default()
+    };
+    Node::with_config(
+        identity::Keypair::generate_ed25519(),
+        config,
+        &mut Registry::default(),
+    )
+    .unwrap()
+}
+
+async fn listen(node: &mut Node) -> Multiaddr {
+    node.listen_on("/ip4/127.0.0.1/tcp/0".parse().unwrap())
+        .unwrap();
+    loop {
+        if let NodeEvent::NewListenAddr { address } = node.next_event().await {
+            return address;
+        }
+    }
+}
+
+/// Dial `address` from a fresh node and keep that node running.
+fn connect_from_new_node(address: Multiaddr) {
+    let mut client = node(ConnectionLimits::default());
+    client.dial(address).unwrap();
+    tokio::spawn(async move {
+        loop {
+            client.next_event().await;
+        }
+    });
+}
+
+#[tokio::test]
+async fn inbound_connections_beyond_the_limit_are_rejected() {
+    let mut server = node(ConnectionLimits {
+        max_established_incoming: Some(1),
+        ..ConnectionLimits::default()
+    });
+    assert_eq!(server.peer_count(), 0);
+    let address = listen(&mut server).await;
+
+    connect_from_new_node(address.clone());
+    time::timeout(Duration::from_secs(10), async {
+        while !matches!(
+            server.next_event().await,
+            NodeEvent::ConnectionEstablished { .. }
+        ) {}
+    })
+    .await
+    .expect("first peer connects");
+    assert_eq!(server.peer_count(), 1);
+
+    connect_from_new_node(address);
+    let reason = time::timeout(Duration::from_secs(10), async {
+        loop {
+            match server.next_event().await {
+                NodeEvent::ConnectionRejected {
+                    peer: None,
+                    address: Some(_),
+                    reason,
+                } => break reason,
+                NodeEvent::ConnectionEstablished { peer } => {
+                    panic!("{peer} connected beyond the limit")
+                }
+                _ => {}
+            }
+        }
+    })
+    .await
+    .expect("second peer is rejected");
+    assert!(reason.contains("incoming"), "{reason}");
+    assert_eq!(server.peer_count(), 1);
+}
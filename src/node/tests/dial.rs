@@ -0,0 +1,119 @@
+use std::net::TcpListener;
+use std::time::{Duration, Instant};
+
+use libp2p::{identity, Multiaddr};
+use neurochain_node::{Node, NodeConfig, NodeError, NodeEvent};
+use prometheus_client::registry::Registry;
+use tokio::time;
+
+fn node(config: NodeConfig) -> Node {
+    Node::with_config(
+        identity::Keypair::generate_ed25519(),
+        config,
+        &mut Registry::default(),
+    )
+    .unwrap()
+}
+
+#[tokio::test]
+async fn live_address_wins_over_dead_one() {
+    let dial_timeout = Duration::from_secs(5);
+    let config = NodeConfig {
+        dial_timeout,
+        dial_stagger: Duration::from_millis(100),
+        ..NodeConfig::default()
+    };
+
+    let mut miner = node(NodeConfig::default());
+    let miner_id = miner.local_peer_id();
+    miner
+        .listen_on("/ip4/127.0.0.1/tcp/0".parse().unwrap())
+        .unwrap();
+    let live = loop {
+        if let NodeEvent::NewListenAddr { address } = miner.next_event().await {
+            break address;
+        }
+    };
+    tokio::spawn(async move {
+        loop {
+            miner.next_event().await;
+        }
+    });
+
+    // Accepts TCP connections but never completes the handshake.
+    let black_hole = TcpListener::bind("127.0.0.1:0").unwrap();
+    let dead = format!(
+        "/ip4/127.0.0.1/tcp/{}",
+        black_hole.local_addr().unwrap().port()
+    )
+    .parse()
+    .unwrap();
+
+    let mut validator = node(config);
+    let started = Instant::now();
+    validator.dial_peer(miner_id, vec![dead, live]).unwrap();
+
+    let connected = time::timeout(dial_timeout, async {
+        loop {
+            if let NodeEvent::ConnectionEstablished { peer } = validator.next_event().await {
+                break peer;
+            }
+        }
+    })
+    .await
+    .expect("live address connects before the dead one times out");
+
+    assert_eq!(connected, miner_id);
+    assert!(started.elapsed() < dial_timeout);
+}
+
+#[tokio::test]
+async fn probe_reports_the_peer_at_a_live_address_only() {
+    let mut miner = node(NodeConfig::default());
+    let miner_id = miner.local_peer_id();
+    miner
+        .listen_on("/ip4/127.0.0.1/tcp/0".parse().unwrap())
+        .unwrap();
+    let live = loop {
+        if let NodeEvent::NewListenAddr { address } = miner.next_event().await {
+            break address;
+        }
+    };
+    tokio::spawn(async move {
+        loop {
+            miner.next_event().await;
+        }
+    });
+
+    // Without mDNS, nothing else would connect the two nodes.
+    let mut validator = node(NodeConfig {
+        enable_mdns: false,
+        ..NodeConfig::default()
+    });
+    let probed = validator
+        .probe_address(live, Duration::from_secs(5))
+        .await
+        .unwrap();
+    assert_eq!(probed, miner_id);
+    // The probe connection is never handed to the swarm.
+    assert!(
+        time::timeout(Duration::from_millis(500), validator.next_event())
+            .await
+            .is_err()
+    );
+
+    // Accepts TCP connections but never completes the handshake.
+    let black_hole = TcpListener::bind("127.0.0.1:0").unwrap();
+    let dead: Multiaddr = format!(
+        "/ip4/127.0.0.1/tcp/{}",
+        black_hole.local_addr().unwrap().port()
+    )
+    .parse()
+    .unwrap();
+    assert!(matches!(
+        validator
+            .probe_address(dead.clone(), Duration::from_millis(500))
+            .await,
+        Err(NodeError::Unreachable(addr)) if addr == dead
+    ));
+}
@@ -0,0 +1,151 @@
+use std::net::TcpListener;
+use std::time::Duration;
+
+use futures::StreamExt;
+use libp2p::{identity, multiaddr::Protocol, Multiaddr, PeerId};
+use neurochain_node::{Node, NodeConfig, NodeEvent};
+use prometheus_client::registry::Registry;
+use tokio::time;
+
+#[tokio::test]
+async fn discovery_recovers_once_bootnode_comes_up() {
+    // Reserve a port for the bootnode, which is not running yet.
+    let port = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let bootnode_key = identity::Keypair::generate_ed25519();
+    let bootnode_id = PeerId::from(bootnode_key.public());
+    let listen: Multiaddr = format!("/ip4/127.0.0.1/tcp/{port}").parse().unwrap();
+
+    let config = NodeConfig {
+        bootnodes: vec![listen.clone().with(Protocol::P2p(bootnode_id.into()))],
+        bootstrap_attempts: 2,
+        bootstrap_backoff: Duration::from_millis(50),
+        bootstrap_retry_interval: Duration::from_millis(200),
+        ..NodeConfig::default()
+    };
+    let mut node = Node::with_config(
+        identity::Keypair::generate_ed25519(),
+        config,
+        &mut Registry::default(),
+    )
+    .unwrap();
+    assert!(node.discovery_healthy());
+
+    while !matches!(node.next_event().await, NodeEvent::DiscoveryUnavailable) {}
+    assert!(!node.discovery_healthy());
+
+    let mut bootnode = Node::new(bootnode_key, &mut Registry::default()).unwrap();
+    bootnode.listen_on(listen).unwrap();
+    tokio::spawn(async move {
+        loop {
+            bootnode.next_event().await;
+        }
+    });
+
+    while !matches!(node.next_event().await, NodeEvent::DiscoveryRestored) {}
+    assert!(node.discovery_healthy());
+}
+
+/// A node without mDNS, so that peers can only find it through
+/// Kademlia, listening on a local port.
+async fn wide_area_node() -> (Node, Multiaddr) {
+    let config = NodeConfig {
+        enable_mdns: false,
+        ..NodeConfig::default()
+    };
+    let mut node = Node::with_config(
+        identity::Keypair::generate_ed25519(),
+        config,
+        &mut Registry::default(),
+    )
+    .unwrap();
+    node.listen_on("/ip4/127.0.0.1/tcp/0".parse().unwrap())
+        .unwrap();
+    let address = loop {
+        if let NodeEvent::NewListenAddr { address } = node.next_event().await {
+            break address;
+        }
+    };
+    (node, address)
+}
+
+fn drive_in_background(mut node: Node) {
+    tokio::spawn(async move {
+        loop {
+            node.next_event().await;
+        }
+    });
+}
+
+#[tokio::test]
+async fn peer_is_discovered_through_a_bootstrap_node() {
+    let (b, b_address) = wide_area_node().await;
+    let b_id = b.local_peer_id();
+    drive_in_background(b);
+
+    let (mut a, _) = wide_area_node().await;
+    let a_id = a.local_peer_id();
+    a.bootstrap(vec![(b_id, b_address.clone())]);
+    drive_in_background(a);
+
+    // C only knows B, and B only learns where A listens once A has
+    // identified itself, so keep asking until B hands A out.
+    let (mut c, _) = wide_area_node().await;
+    c.bootstrap(vec![(b_id, b_address)]);
+    let mut query = c.get_closest_peers(a_id);
+    let (mut routed, mut found) = (false, false);
+    time::timeout(Duration::from_secs(30), async {
+        while !(routed && found) {
+            match c.next_event().await {
+                NodeEvent::RoutingUpdated { peer, .. } if peer == a_id => routed = true,
+                NodeEvent::ClosestPeers { query: id, peers } if id == query => {
+                    found = peers.contains(&a_id);
+                    if !found {
+                        time::sleep(Duration::from_millis(200)).await;
+                        query = c.get_closest_peers(a_id);
+                    }
+                }
+                _ => {}
+            }
+        }
+    })
+    .await
+    .expect("C discovers A through B");
+}
+
+#[tokio::test]
+#[ignore = "mDNS skips loopback, so this needs a multicast-capable interface"]
+async fn mdns_peers_appear_on_the_event_stream() {
+    let mut a = Node::new(
+        identity::Keypair::generate_ed25519(),
+        &mut Registry::default(),
+    )
+    .unwrap();
+    let mut b = Node::new(
+        identity::Keypair::generate_ed25519(),
+        &mut Registry::default(),
+    )
+    .unwrap();
+    let b_id = b.local_peer_id();
+    a.listen_on("/ip4/0.0.0.0/tcp/0".parse().unwrap()).unwrap();
+    b.listen_on("/ip4/0.0.0.0/tcp/0".parse().unwrap()).unwrap();
+    let mut events = a.event_stream();
+    drive_in_background(a);
+    drive_in_background(b);
+
+    time::timeout(Duration::from_secs(30), async {
+        loop {
+            if let Some(NodeEvent::PeerDiscovered { peer, addresses }) = events.next().await {
+                if peer == b_id {
+                    assert!(!addresses.is_empty());
+                    break;
+                }
+            }
+        }
+    })
+    .await
+    .expect("A discovers B over mDNS");
+}
@@ -0,0 +1,59 @@
+use libp2p::gossipsub::IdentTopic;
+use libp2p::identity;
+use neurochain_node::dedup::DuplicateFilter;
+use neurochain_node::Node;
+use prometheus_client::encoding::text::encode;
+use prometheus_client::registry::Registry;
+
+#[test]
+fn same_payload_from_two_peers_is_suppressed_once() {
+    let mut registry = Registry::default();
+    let mut filter = DuplicateFilter::default();
+    filter.register(&mut registry);
+
+    let topic = IdentTopic::new("subnet-1-validators").hash();
+    let weights = b"weights for epoch 7".to_vec();
+
+    // First copy, forwarded by peer A, is delivered.
+    assert!(filter.check(&topic, &weights));
+    // Second copy, forwarded by peer B, is dropped.
+    assert!(!filter.check(&topic, &weights));
+    assert_eq!(filter.suppressed(), 1);
+
+    let mut metrics = String::new();
+    encode(&mut metrics, &registry).unwrap();
+    assert!(metrics.contains("gossipsub_duplicate_messages_suppressed_total 1"));
+}
+
+#[test]
+fn same_payload_on_another_topic_is_not_a_duplicate() {
+    let mut filter = DuplicateFilter::default();
+    let data = b"hello".to_vec();
+
+    assert!(filter.check(&IdentTopic::new("a").hash(), &data));
+    assert!(filter.check(&IdentTopic::new("b").hash(), &data));
+    assert_eq!(filter.suppressed(), 0);
+}
+
+#[test]
+fn oldest_payload_is_forgotten_when_full() {
+    let mut filter = DuplicateFilter::new(2);
+    let topic = IdentTopic::new("t").hash();
+
+    assert!(filter.check(&topic, b"1"));
+    assert!(filter.check(&topic, b"2"));
+    assert!(filter.check(&topic, b"3"));
+    assert!(filter.check(&topic, b"1"));
+    assert!(!filter.check(&topic, b"3"));
+}
+
+#[tokio::test]
+async fn node_registers_suppression_metric() {
+    let mut registry = Registry::default();
+    let node = Node::new(identity::Keypair::generate_ed25519(), &mut registry).unwrap();
+    assert_eq!(node.duplicate_messages_suppressed(), 0);
+
+    let mut metrics = String::new();
+    encode(&mut metrics, &registry).unwrap();
+    assert!(metrics.contains("gossipsub_duplicate_messages_suppressed"));
+}
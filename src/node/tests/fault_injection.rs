@@ -0,0 +1,74 @@
+use std::time::Duration;
+
+use libp2p::identity;
+use neurochain_node::fault::FaultConfig;
+use neurochain_node::{Node, NodeConfig, NodeEvent};
+use prometheus_client::registry::Registry;
+use tokio::time;
+
+const TOPIC: &str = "subnet-1-weights";
+
+fn node(faults: FaultConfig) -> Node {
+    let config = NodeConfig {
+        faults,
+        ..NodeConfig::default()
+    };
+    Node::with_config(
+        identity::Keypair::generate_ed25519(),
+        config,
+        &mut Registry::default(),
+    )
+    .unwrap()
+}
+
+#[tokio::test]
+async fn message_is_delivered_despite_dropped_connections() {
+    let mut receiver = node(FaultConfig::default());
+    receiver.subscribe(TOPIC).unwrap();
+    receiver
+        .listen_on("/ip4/127.0.0.1/tcp/0".parse().unwrap())
+        .unwrap();
+    let address = loop {
+        if let NodeEvent::NewListenAddr { address } = receiver.next_event().await {
+            break address;
+        }
+    };
+
+    // Half of the sender's connections are dropped as they are set up.
+    let mut sender = node(FaultConfig {
+        latency: Duration::from_millis(10),
+        drop_rate: 0.5,
+        seed: 7,
+        ..FaultConfig::default()
+    });
+    sender.subscribe(TOPIC).unwrap();
+    tokio::spawn(async move {
+        // Redial until connected, and keep publishing until the
+        // receiver's subscription is known.
+        let mut connected = false;
+        loop {
+            if !connected {
+                sender.dial(address.clone()).unwrap();
+            }
+            if connected && sender.publish(TOPIC, b"weights".to_vec()).is_ok() {
+                break;
+            }
+            if let Ok(event) = time::timeout(Duration::from_millis(250), sender.next_event()).await
+            {
+                match event {
+                    NodeEvent::ConnectionEstablished { .. } => connected = true,
+                    NodeEvent::ConnectionClosed { .. } => connected = false,
+                    _ => {}
+                }
+            }
+        }
+        loop {
+            sender.next_event().await;
+        }
+    });
+
+    let (_, message) = time::timeout(Duration::from_secs(30), receiver.next_message())
+        .await
+        .expect("message delivered despite dropped connections");
+    assert_eq!(message.data, b"weights");
+}
@@ -0,0 +1,79 @@
+use libp2p::{identity, Multiaddr, PeerId};
+use neurochain_node::handshake::{Credential, HandshakeConfig, MAX_CREDENTIAL_LEN};
+use neurochain_node::{Node, NodeConfig, NodeEvent};
+use prometheus_client::registry::Registry;
+
+const VALID: &[u8] = b"signed-by-mesh-authority";
+
+/// A node presenting `credential` and accepting only peers that
+/// present [`VALID`].
+fn node(credential: &[u8]) -> Node {
+    let config = NodeConfig {
+        handshake: Some(HandshakeConfig::new(
+            Credential::try_from(credential.to_vec()).unwrap(),
+            |_: &PeerId, credential: &[u8]| credential == VALID,
+        )),
+        ..NodeConfig::default()
+    };
+    Node::with_config(
+        identity::Keypair::generate_ed25519(),
+        config,
+        &mut Registry::default(),
+    )
+    .unwrap()
+}
+
+/// Dial `address` from `node` and keep driving it in the background.
+fn dial_in_background(mut node: Node, address: Multiaddr) {
+    node.dial(address).unwrap();
+    tokio::spawn(async move {
+        loop {
+            node.next_event().await;
+        }
+    });
+}
+
+#[tokio::test]
+async fn peers_are_admitted_by_credential() {
+    let mut gatekeeper = node(VALID);
+    gatekeeper
+        .listen_on("/ip4/127.0.0.1/tcp/0".parse().unwrap())
+        .unwrap();
+    let address = loop {
+        if let NodeEvent::NewListenAddr { address } = gatekeeper.next_event().await {
+            break address;
+        }
+    };
+
+    let member = node(VALID);
+    let member_id = member.local_peer_id();
+    dial_in_background(member, address.clone());
+    loop {
+        match gatekeeper.next_event().await {
+            NodeEvent::ConnectionEstablished { peer } if peer == member_id => break,
+            NodeEvent::HandshakeRejected { peer } => panic!("{peer} was rejected"),
+            _ => {}
+        }
+    }
+
+    let impostor = node(b"forged");
+    let impostor_id = impostor.local_peer_id();
+    dial_in_background(impostor, address);
+    loop {
+        match gatekeeper.next_event().await {
+            NodeEvent::HandshakeRejected { peer } => {
+                assert_eq!(peer, impostor_id);
+                break;
+            }
+            NodeEvent::ConnectionEstablished { peer } if peer == impostor_id => {
+                panic!("impostor was admitted")
+            }
+            _ => {}
+        }
+    }
+}
+
+#[test]
+fn oversized_credential_is_refused() {
+    assert!(Credential::try_from(vec![0; MAX_CREDENTIAL_LEN + 1]).is_err());
+}
@@ -0,0 +1,69 @@
+use std::time::Duration;
+
+use libp2p::{identity, multiaddr::Protocol};
+use neurochain_node::{Node, NodeConfig, NodeEvent, AGENT_VERSION, IDENTIFY_PROTOCOL_VERSION};
+use prometheus_client::registry::Registry;
+use tokio::time;
+
+fn node() -> Node {
+    let config = NodeConfig {
+        enable_mdns: false,
+        ..NodeConfig::default()
+    };
+    Node::with_config(
+        identity::Keypair::generate_ed25519(),
+        config,
+        &mut Registry::default(),
+    )
+    .unwrap()
+}
+
+#[tokio::test]
+async fn peers_exchange_versions_and_observed_addresses() {
+    let mut listener = node();
+    let listener_id = listener.local_peer_id();
+    listener
+        .listen_on("/ip4/127.0.0.1/tcp/0".parse().unwrap())
+        .unwrap();
+    let address = loop {
+        if let NodeEvent::NewListenAddr { address } = listener.next_event().await {
+            break address;
+        }
+    };
+    tokio::spawn(async move {
+        loop {
+            listener.next_event().await;
+        }
+    });
+
+    let mut dialer = node();
+    dialer.dial(address.clone()).unwrap();
+    let event = time::timeout(Duration::from_secs(30), async {
+        loop {
+            if let event @ NodeEvent::PeerIdentified { .. } = dialer.next_event().await {
+                break event;
+            }
+        }
+    })
+    .await
+    .expect("the listener identifies itself");
+    let NodeEvent::PeerIdentified {
+        peer,
+        protocol_version,
+        agent_version,
+        listen_addrs,
+        observed_addr,
+    } = event
+    else {
+        unreachable!()
+    };
+    assert_eq!(peer, listener_id);
+    assert_eq!(protocol_version, IDENTIFY_PROTOCOL_VERSION);
+    assert_eq!(agent_version, AGENT_VERSION);
+    assert!(listen_addrs.contains(&address), "{listen_addrs:?}");
+    // The listener saw us on loopback, from an ephemeral port.
+    assert!(matches!(
+        observed_addr.iter().next(),
+        Some(Protocol::Ip4(ip)) if ip.is_loopback()
+    ));
+}
@@ -0,0 +1,84 @@
+use std::fs;
+use std::path::PathBuf;
+
+use neurochain_node::{
+    load_or_create_identity, spawn_mdns_node_with_config, start_node_with_config, NodeConfig,
+};
+
+/// A path in a fresh directory under the system temp dir.
+fn key_path(test: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("neuromesh-{test}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    dir.join("node.key")
+}
+
+#[test]
+fn identity_is_reloaded_from_disk() {
+    let path = key_path("identity");
+
+    let created = load_or_create_identity(&path).unwrap();
+    let reloaded = load_or_create_identity(&path).unwrap();
+    assert_eq!(
+        created.public().to_peer_id(),
+        reloaded.public().to_peer_id()
+    );
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
+
+#[tokio::test]
+async fn node_keeps_its_peer_id_across_restarts() {
+    let path = key_path("restart");
+    let config = NodeConfig {
+        key_path: Some(path.clone()),
+        ..NodeConfig::default()
+    };
+
+    let first = start_node_with_config(config.clone())
+        .unwrap()
+        .local_peer_id();
+    let second = start_node_with_config(config).unwrap().local_peer_id();
+    assert_eq!(first, second);
+    assert_eq!(
+        load_or_create_identity(&path)
+            .unwrap()
+            .public()
+            .to_peer_id(),
+        first
+    );
+}
+
+#[tokio::test]
+async fn mdns_node_keeps_its_peer_id_across_restarts() {
+    let path = key_path("mdns-restart");
+    let config = NodeConfig {
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".parse().unwrap()],
+        key_path: Some(path.clone()),
+        ..NodeConfig::default()
+    };
+
+    // The first start generates and saves the key, the second loads it.
+    let first = spawn_mdns_node_with_config(&config).unwrap();
+    let peer_id = first.local_peer_id();
+    assert!(path.exists());
+    first.shutdown().await;
+
+    let second = spawn_mdns_node_with_config(&config).unwrap();
+    assert_eq!(second.local_peer_id(), peer_id);
+    second.shutdown().await;
+}
+
+#[test]
+fn corrupt_key_file_is_rejected() {
+    let path = key_path("corrupt");
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(&path, b"not a keypair").unwrap();
+
+    let err = load_or_create_identity(&path).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
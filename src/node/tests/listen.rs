@@ -0,0 +1,89 @@
+use std::net::TcpListener;
+
+use libp2p::{Multiaddr, PeerId};
+use neurochain_node::{start_node_with_config, Node, NodeConfig, NodeError, NodeEvent};
+
+fn free_port() -> u16 {
+    TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port()
+}
+
+fn start_on(port: u16) -> Node {
+    start_node_with_config(NodeConfig {
+        listen_addrs: vec![format!("/ip4/127.0.0.1/tcp/{port}").parse().unwrap()],
+        ..NodeConfig::default()
+    })
+    .unwrap()
+}
+
+/// Drive `node` until it is connected to `peer`.
+async fn connected_to(mut node: Node, peer: PeerId) {
+    loop {
+        if let NodeEvent::ConnectionEstablished { peer: connected } = node.next_event().await {
+            if connected == peer {
+                return;
+            }
+        }
+    }
+}
+
+#[tokio::test]
+async fn nodes_on_fixed_ports_discover_each_other() {
+    let (port_a, port_b) = (free_port(), free_port());
+    let mut a = start_on(port_a);
+    let b = start_on(port_b);
+    let (a_id, b_id) = (a.local_peer_id(), b.local_peer_id());
+
+    let address = loop {
+        if let NodeEvent::NewListenAddr { address } = a.next_event().await {
+            break address;
+        }
+    };
+    assert_eq!(
+        address,
+        format!("/ip4/127.0.0.1/tcp/{port_a}")
+            .parse::<Multiaddr>()
+            .unwrap()
+    );
+
+    let b = tokio::spawn(connected_to(b, a_id));
+    connected_to(a, b_id).await;
+    b.await.unwrap();
+}
+
+#[test]
+fn unsupported_listen_address_is_rejected() {
+    let config = NodeConfig {
+        listen_addrs: vec!["/dns4/example.com/tcp/4001".parse().unwrap()],
+        ..NodeConfig::default()
+    };
+    assert!(matches!(
+        start_node_with_config(config),
+        Err(NodeError::InvalidListenAddr(_))
+    ));
+}
+
+#[tokio::test]
+async fn every_failed_listen_address_is_reported() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let taken: Multiaddr = format!(
+        "/ip4/127.0.0.1/tcp/{}",
+        listener.local_addr().unwrap().port()
+    )
+    .parse()
+    .unwrap();
+    let config = NodeConfig {
+        listen_addrs: vec![taken.clone(), "/ip4/127.0.0.1/tcp/0".parse().unwrap()],
+        ..NodeConfig::default()
+    };
+    match start_node_with_config(config) {
+        Err(NodeError::ListenFailed(failures)) => {
+            assert_eq!(failures.len(), 1);
+            assert_eq!(failures[0].0, taken);
+        }
+        other => panic!("expected a listen failure, got {:?}", other.err()),
+    }
+}
@@ -0,0 +1,88 @@
+use std::time::Duration;
+
+use futures::StreamExt;
+use libp2p::swarm::SwarmEvent;
+use neurochain_node::{build_mdns_swarm, spawn_mdns_node, NodeEvent};
+use tokio::time;
+
+#[tokio::test]
+async fn swarm_reports_its_listen_address() {
+    let mut swarm = build_mdns_swarm("/ip4/127.0.0.1/tcp/0".parse().unwrap()).unwrap();
+    loop {
+        if let SwarmEvent::NewListenAddr { address, .. } = swarm.select_next_some().await {
+            assert!(address.to_string().starts_with("/ip4/127.0.0.1/tcp/"));
+            break;
+        }
+    }
+}
+
+#[tokio::test]
+async fn spawned_node_stops_on_shutdown() {
+    let handle = spawn_mdns_node();
+    time::sleep(Duration::from_millis(100)).await;
+    assert!(!handle.is_finished());
+
+    time::timeout(Duration::from_secs(10), handle.shutdown())
+        .await
+        .expect("the node stops");
+}
+
+#[tokio::test]
+#[ignore = "mDNS skips loopback, so this needs a multicast-capable interface"]
+async fn spawned_nodes_discover_each_other() {
+    let mut a = spawn_mdns_node();
+    let mut b = spawn_mdns_node();
+
+    for handle in [&mut a, &mut b] {
+        time::timeout(Duration::from_secs(30), async {
+            loop {
+                if let Some(NodeEvent::PeerDiscovered { addresses, .. }) =
+                    handle.events.next().await
+                {
+                    assert!(!addresses.is_empty());
+                    break;
+                }
+            }
+        })
+        .await
+        .expect("each node discovers the other");
+    }
+
+    a.shutdown().await;
+    b.shutdown().await;
+}
+
+#[tokio::test]
+#[ignore = "mDNS skips loopback, so this needs a multicast-capable interface"]
+async fn spawned_nodes_exchange_messages() {
+    let a = spawn_mdns_node();
+    let mut b = spawn_mdns_node();
+    a.subscribe("weights").await.unwrap();
+    b.subscribe("weights").await.unwrap();
+
+    // Publishing fails until the nodes have met and seen each other's
+    // subscription, so keep trying until B hears from A.
+    time::timeout(Duration::from_secs(30), async {
+        loop {
+            let _ = a.publish("weights", b"0.25".to_vec()).await;
+            let heard = time::timeout(Duration::from_millis(500), async {
+                loop {
+                    if let Some(NodeEvent::Message { message, .. }) = b.events.next().await {
+                        return message;
+                    }
+                }
+            })
+            .await;
+            if let Ok(message) = heard {
+                assert_eq!(message.topic.as_str(), "weights");
+                assert_eq!(message.data, b"0.25");
+                break;
+            }
+        }
+    })
+    .await
+    .expect("B receives A's message");
+
+    a.shutdown().await;
+    b.shutdown().await;
+}
@@ -0,0 +1,34 @@
+use libp2p::{identity, multiaddr::Protocol, Multiaddr, PeerId};
+use neurochain_node::{NatStatus, Node, NodeConfig, NodeError};
+use prometheus_client::registry::Registry;
+
+fn node(relay_addrs: Vec<Multiaddr>) -> Result<Node, NodeError> {
+    let config = NodeConfig {
+        enable_mdns: false,
+        relay_addrs,
+        ..NodeConfig::default()
+    };
+    Node::with_config(
+        identity::Keypair::generate_ed25519(),
+        config,
+        &mut Registry::default(),
+    )
+}
+
+#[tokio::test]
+async fn status_is_unknown_until_probed() {
+    let node = node(Vec::new()).unwrap();
+    assert_eq!(node.nat_status(), NatStatus::Unknown);
+}
+
+#[tokio::test]
+async fn relay_addresses_need_a_peer_id() {
+    let addr: Multiaddr = "/ip4/198.51.100.7/tcp/4001".parse().unwrap();
+    match node(vec![addr.clone()]) {
+        Err(NodeError::InvalidRelayAddr(invalid)) => assert_eq!(invalid, addr),
+        other => panic!("expected an invalid relay address, got {:?}", other.err()),
+    }
+
+    let relay = addr.with(Protocol::P2p(PeerId::random().into()));
+    assert!(node(vec![relay]).is_ok());
+}
@@ -0,0 +1,92 @@
+use std::time::Duration;
+
+use libp2p::{identity, multiaddr::Protocol, Multiaddr};
+use neurochain_node::{Node, NodeConfig, NodeError, NodeEvent};
+use prometheus_client::registry::Registry;
+use tokio::time;
+
+const TOPIC: &str = "subnet-1-weights";
+
+fn node(config: NodeConfig) -> Node {
+    Node::with_config(
+        identity::Keypair::generate_ed25519(),
+        config,
+        &mut Registry::default(),
+    )
+    .unwrap()
+}
+
+async fn listen(node: &mut Node) -> Multiaddr {
+    node.listen_on("/ip4/127.0.0.1/tcp/0".parse().unwrap())
+        .unwrap();
+    loop {
+        if let NodeEvent::NewListenAddr { address } = node.next_event().await {
+            return address;
+        }
+    }
+}
+
+#[tokio::test]
+async fn observer_receives_gossip_but_never_dials_or_publishes() {
+    let mut publisher = node(NodeConfig {
+        enable_mdns: false,
+        ..NodeConfig::default()
+    });
+    let publisher_id = publisher.local_peer_id();
+    publisher.subscribe(TOPIC).unwrap();
+    let publisher_address = listen(&mut publisher).await;
+
+    // The observer knows the publisher as a bootnode but must not
+    // reach out to it.
+    let mut observer = node(NodeConfig {
+        enable_mdns: false,
+        observer_mode: true,
+        bootnodes: vec![publisher_address
+            .clone()
+            .with(Protocol::P2p(publisher_id.into()))],
+        ..NodeConfig::default()
+    });
+    observer.subscribe(TOPIC).unwrap();
+    let observer_address = listen(&mut observer).await;
+    assert!(matches!(
+        observer.dial(publisher_address.clone()),
+        Err(NodeError::ObserverMode)
+    ));
+    assert!(matches!(
+        observer
+            .probe_address(publisher_address, Duration::from_secs(1))
+            .await,
+        Err(NodeError::ObserverMode)
+    ));
+    let idle = time::timeout(Duration::from_secs(1), async {
+        loop {
+            if let NodeEvent::ConnectionEstablished { peer } = observer.next_event().await {
+                break peer;
+            }
+        }
+    })
+    .await;
+    assert!(idle.is_err(), "observer connected on its own: {idle:?}");
+
+    publisher.dial(observer_address).unwrap();
+    let publisher = tokio::spawn(async move {
+        // Publishing fails until the observer's subscription is known.
+        while publisher.publish(TOPIC, b"weights".to_vec()).is_err() {
+            let _ = time::timeout(Duration::from_millis(100), publisher.next_event()).await;
+        }
+        loop {
+            publisher.next_event().await;
+        }
+    });
+
+    let (source, message) = time::timeout(Duration::from_secs(30), observer.next_message())
+        .await
+        .expect("observer receives the message");
+    assert_eq!(source, publisher_id);
+    assert_eq!(message.data, b"weights");
+    assert!(matches!(
+        observer.publish(TOPIC, b"reply".to_vec()),
+        Err(NodeError::ObserverMode)
+    ));
+    publisher.abort();
+}
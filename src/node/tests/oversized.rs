@@ -0,0 +1,74 @@
+use std::time::Duration;
+
+use libp2p::identity;
+use neurochain_node::{Node, NodeConfig, NodeError, NodeEvent};
+use prometheus_client::registry::Registry;
+use tokio::time;
+
+const TOPIC: &str = "subnet-1-weights";
+const LIMIT: usize = 1024;
+
+fn node(max_gossipsub_message_size: usize) -> Node {
+    let config = NodeConfig {
+        max_gossipsub_message_size,
+        ..NodeConfig::default()
+    };
+    Node::with_config(
+        identity::Keypair::generate_ed25519(),
+        config,
+        &mut Registry::default(),
+    )
+    .unwrap()
+}
+
+#[tokio::test]
+async fn publishing_oversized_message_fails() {
+    let mut node = node(LIMIT);
+    node.subscribe(TOPIC).unwrap();
+    assert!(matches!(
+        node.publish(TOPIC, vec![0; LIMIT + 1]),
+        Err(NodeError::MessageTooLarge(size)) if size == LIMIT + 1
+    ));
+}
+
+#[tokio::test]
+async fn oversized_inbound_message_is_rejected_and_sender_penalized() {
+    let mut receiver = node(LIMIT);
+    receiver.subscribe(TOPIC).unwrap();
+    receiver
+        .listen_on("/ip4/127.0.0.1/tcp/0".parse().unwrap())
+        .unwrap();
+    let address = loop {
+        if let NodeEvent::NewListenAddr { address } = receiver.next_event().await {
+            break address;
+        }
+    };
+
+    // The sender allows larger messages than the receiver does.
+    let mut sender = node(4 * LIMIT);
+    let sender_id = sender.local_peer_id();
+    sender.subscribe(TOPIC).unwrap();
+    sender.dial(address).unwrap();
+    tokio::spawn(async move {
+        // Keep trying until the receiver's subscription is known.
+        while sender.publish(TOPIC, vec![7; LIMIT + 500]).is_err() {
+            let _ = time::timeout(Duration::from_millis(100), sender.next_event()).await;
+        }
+        loop {
+            sender.next_event().await;
+        }
+    });
+
+    loop {
+        match receiver.next_event().await {
+            NodeEvent::OversizedMessageRejected { peer, size } => {
+                assert_eq!(peer, sender_id);
+                assert_eq!(size, LIMIT + 500);
+                break;
+            }
+            NodeEvent::Message { .. } => panic!("oversized message was delivered"),
+            _ => {}
+        }
+    }
+    assert!(receiver.peer_score(&sender_id).unwrap() < 0.0);
+}
@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use libp2p::identity;
+use neurochain_node::task::TaskRequest;
+use neurochain_node::{Node, NodeEvent, TaskEvent};
+use prometheus_client::registry::Registry;
+use tokio::time;
+
+const LOW_PRIORITY_SUBNET: u32 = 1;
+const HIGH_PRIORITY_SUBNET: u32 = 2;
+
+fn node() -> Node {
+    Node::new(
+        identity::Keypair::generate_ed25519(),
+        &mut Registry::default(),
+    )
+    .unwrap()
+}
+
+fn request(subnet_id: u32) -> TaskRequest {
+    TaskRequest {
+        subnet_id,
+        payload: subnet_id.to_be_bytes().to_vec(),
+    }
+}
+
+#[tokio::test]
+async fn higher_priority_subnet_is_served_first() {
+    let mut miner = node();
+    let mut validator = node();
+    let miner_id = miner.local_peer_id();
+    miner.set_subnet_priorities(HashMap::from([
+        (LOW_PRIORITY_SUBNET, 10),
+        (HIGH_PRIORITY_SUBNET, 100),
+    ]));
+
+    miner
+        .listen_on("/ip4/127.0.0.1/tcp/0".parse().unwrap())
+        .unwrap();
+    let address = loop {
+        if let NodeEvent::NewListenAddr { address } = miner.next_event().await {
+            break address;
+        }
+    };
+
+    // Once connected, the validator sends the low‑priority task first.
+    validator.dial(address).unwrap();
+    tokio::spawn(async move {
+        while !matches!(
+            validator.next_event().await,
+            NodeEvent::ConnectionEstablished { .. }
+        ) {}
+        validator.send_task_request(&miner_id, request(LOW_PRIORITY_SUBNET));
+        validator.send_task_request(&miner_id, request(HIGH_PRIORITY_SUBNET));
+        loop {
+            validator.next_event().await;
+        }
+    });
+    while !matches!(
+        miner.next_event().await,
+        NodeEvent::ConnectionEstablished { .. }
+    ) {}
+
+    // Let both requests arrive while the miner is busy elsewhere.
+    time::sleep(Duration::from_millis(500)).await;
+
+    let mut served = Vec::new();
+    while served.len() < 2 {
+        if let NodeEvent::Task(TaskEvent::TaskRequestReceived { request, .. }) =
+            miner.next_event().await
+        {
+            served.push(request.subnet_id);
+        }
+    }
+    assert_eq!(served, vec![HIGH_PRIORITY_SUBNET, LOW_PRIORITY_SUBNET]);
+}
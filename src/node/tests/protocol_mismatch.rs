@@ -0,0 +1,115 @@
+use libp2p::identity;
+use neurochain_node::task::{TaskRequest, TaskResponse, PROTOCOL_NAME};
+use neurochain_node::{Node, NodeConfig, NodeEvent, TaskEvent};
+use prometheus_client::registry::Registry;
+
+const NEXT_PROTOCOL: &str = "/neuromesh/task/2.0.0";
+
+fn node(task_protocols: &[&str]) -> Node {
+    let config = NodeConfig {
+        task_protocols: task_protocols.iter().map(|name| name.to_string()).collect(),
+        ..NodeConfig::default()
+    };
+    Node::with_config(
+        identity::Keypair::generate_ed25519(),
+        config,
+        &mut Registry::default(),
+    )
+    .unwrap()
+}
+
+/// Start `miner` listening, drive it in the background answering every
+/// task with its own payload, and connect `validator` to it.
+async fn connect(validator: &mut Node, mut miner: Node) {
+    miner
+        .listen_on("/ip4/127.0.0.1/tcp/0".parse().unwrap())
+        .unwrap();
+    let address = loop {
+        if let NodeEvent::NewListenAddr { address } = miner.next_event().await {
+            break address;
+        }
+    };
+    tokio::spawn(async move {
+        loop {
+            if let NodeEvent::Task(TaskEvent::TaskRequestReceived {
+                request_id,
+                request,
+                ..
+            }) = miner.next_event().await
+            {
+                let response = TaskResponse {
+                    payload: request.payload,
+                };
+                miner.respond_to_task(request_id, response).unwrap();
+            }
+        }
+    });
+
+    validator.dial(address).unwrap();
+    while !matches!(
+        validator.next_event().await,
+        NodeEvent::ConnectionEstablished { .. }
+    ) {}
+}
+
+fn request() -> TaskRequest {
+    TaskRequest {
+        subnet_id: 3,
+        payload: b"task".to_vec(),
+    }
+}
+
+#[tokio::test]
+async fn mismatched_task_protocols_are_reported() {
+    let mut validator = node(&[NEXT_PROTOCOL]);
+    let miner = node(&[PROTOCOL_NAME]);
+    let miner_id = miner.local_peer_id();
+    connect(&mut validator, miner).await;
+
+    validator.send_task_request(&miner_id, request());
+    let mut request_failed = false;
+    loop {
+        match validator.next_event().await {
+            NodeEvent::Task(TaskEvent::TaskRequestFailed { .. }) => request_failed = true,
+            NodeEvent::ProtocolMismatch {
+                peer,
+                wanted,
+                available,
+            } => {
+                assert_eq!(peer, miner_id);
+                assert_eq!(wanted, vec![NEXT_PROTOCOL.to_string()]);
+                assert_eq!(available, vec![PROTOCOL_NAME.to_string()]);
+                break;
+            }
+            _ => {}
+        }
+    }
+    assert!(request_failed);
+}
+
+#[tokio::test]
+async fn older_registered_protocol_is_used_as_fallback() {
+    let mut validator = node(&[NEXT_PROTOCOL, PROTOCOL_NAME]);
+    let miner = node(&[PROTOCOL_NAME]);
+    let miner_id = miner.local_peer_id();
+    connect(&mut validator, miner).await;
+
+    let request_id = validator.send_task_request(&miner_id, request());
+    loop {
+        match validator.next_event().await {
+            NodeEvent::Task(TaskEvent::TaskResponseReceived {
+                request_id: id,
+                response,
+                ..
+            }) if id == request_id => {
+                assert_eq!(response.payload, b"task".to_vec());
+                break;
+            }
+            NodeEvent::Task(TaskEvent::TaskRequestFailed { error, .. }) => {
+                panic!("task failed: {error}")
+            }
+            NodeEvent::ProtocolMismatch { .. } => panic!("fallback protocol was not used"),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use std::time::Duration;
+
+use futures::{channel::mpsc, StreamExt};
+use libp2p::{identity, Multiaddr};
+use neurochain_node::{subnet_topic, Node, NodeConfig, NodeEvent};
+use prometheus_client::registry::Registry;
+use tokio::time;
+
+const SUBNET: u32 = 5;
+const VERSION: u32 = 1;
+const TELEMETRY: u32 = 0;
+const TASK: u32 = 10;
+
+fn node(publish_burst: usize) -> Node {
+    let config = NodeConfig {
+        enable_mdns: false,
+        publish_burst,
+        ..NodeConfig::default()
+    };
+    Node::with_config(
+        identity::Keypair::generate_ed25519(),
+        config,
+        &mut Registry::default(),
+    )
+    .unwrap()
+}
+
+async fn listen(node: &mut Node) -> Multiaddr {
+    node.listen_on("/ip4/127.0.0.1/tcp/0".parse().unwrap())
+        .unwrap();
+    loop {
+        if let NodeEvent::NewListenAddr { address } = node.next_event().await {
+            return address;
+        }
+    }
+}
+
+/// A validator with a miner subscribed to the subnet topic.  The miner
+/// is driven in the background and its messages come out of the
+/// returned channel.
+async fn connected(publish_burst: usize) -> (Node, mpsc::UnboundedReceiver<Vec<u8>>) {
+    let mut validator = node(publish_burst);
+    let address = listen(&mut validator).await;
+
+    let mut miner = node(publish_burst);
+    miner.resubscribe_subnet_version(SUBNET, VERSION).unwrap();
+    miner.dial(address).unwrap();
+    let (sender, receiver) = mpsc::unbounded();
+    tokio::spawn(async move {
+        loop {
+            let (_, message) = miner.next_message().await;
+            if sender.unbounded_send(message.data).is_err() {
+                break;
+            }
+        }
+    });
+
+    time::timeout(Duration::from_secs(30), async {
+        while validator.subnet_peer_count(SUBNET, VERSION) == 0 {
+            validator.next_event().await;
+        }
+    })
+    .await
+    .expect("the miner's subscription is seen");
+    (validator, receiver)
+}
+
+async fn received(
+    mut validator: Node,
+    receiver: &mut mpsc::UnboundedReceiver<Vec<u8>>,
+    count: usize,
+) -> Vec<Vec<u8>> {
+    tokio::spawn(async move {
+        loop {
+            validator.next_event().await;
+        }
+    });
+    let mut messages = Vec::new();
+    while messages.len() < count {
+        let message = time::timeout(Duration::from_secs(30), receiver.next())
+            .await
+            .expect("the miner receives every message")
+            .unwrap();
+        messages.push(message);
+    }
+    messages
+}
+
+#[tokio::test]
+async fn high_priority_messages_go_first_on_resume() {
+    let (mut validator, mut receiver) = connected(32).await;
+    let topic = subnet_topic(SUBNET, VERSION);
+
+    validator.pause_publishing();
+    validator
+        .publish_with_priority(&topic, b"telemetry".to_vec(), TELEMETRY)
+        .unwrap();
+    validator
+        .publish_with_priority(&topic, b"task".to_vec(), TASK)
+        .unwrap();
+    validator.resume_publishing();
+
+    let messages = received(validator, &mut receiver, 2).await;
+    assert_eq!(messages, vec![b"task".to_vec(), b"telemetry".to_vec()]);
+}
+
+#[tokio::test]
+async fn messages_beyond_the_burst_wait_by_priority() {
+    let (mut validator, mut receiver) = connected(1).await;
+    let topic = subnet_topic(SUBNET, VERSION);
+
+    for (data, priority) in [
+        (&b"telemetry 1"[..], TELEMETRY),
+        (b"telemetry 2", TELEMETRY),
+        (b"telemetry 3", TELEMETRY),
+        (b"task", TASK),
+    ] {
+        validator
+            .publish_with_priority(&topic, data.to_vec(), priority)
+            .unwrap();
+    }
+
+    let messages = received(validator, &mut receiver, 4).await;
+    assert_eq!(
+        messages,
+        vec![
+            b"telemetry 1".to_vec(),
+            b"task".to_vec(),
+            b"telemetry 2".to_vec(),
+            b"telemetry 3".to_vec(),
+        ]
+    );
+}
@@ -0,0 +1,126 @@
+use std::time::Duration;
+
+use libp2p::{identity, Multiaddr};
+use neurochain_node::task::{TaskRequest, TaskResponse};
+use neurochain_node::{Node, NodeConfig, NodeEvent, SendError, TaskEvent};
+use prometheus_client::registry::Registry;
+use sp_neuro_core::{NeuralTask, TaskError};
+
+/// Adds up a non‑empty list of numbers.
+struct Sum;
+
+impl NeuralTask for Sum {
+    type Input = Vec<u32>;
+    type Output = u64;
+    type Error = ();
+
+    fn validate_input(input: &Vec<u32>) -> Result<(), TaskError> {
+        if input.is_empty() {
+            return Err(TaskError::EmptyInput);
+        }
+        Ok(())
+    }
+}
+
+fn node(task_timeout: Duration) -> Node {
+    let config = NodeConfig {
+        enable_mdns: false,
+        task_timeout,
+        ..NodeConfig::default()
+    };
+    Node::with_config(
+        identity::Keypair::generate_ed25519(),
+        config,
+        &mut Registry::default(),
+    )
+    .unwrap()
+}
+
+async fn listen(node: &mut Node) -> Multiaddr {
+    node.listen_on("/ip4/127.0.0.1/tcp/0".parse().unwrap())
+        .unwrap();
+    loop {
+        if let NodeEvent::NewListenAddr { address } = node.next_event().await {
+            return address;
+        }
+    }
+}
+
+async fn connect(validator: &mut Node, address: Multiaddr) {
+    validator.dial(address).unwrap();
+    while !matches!(
+        validator.next_event().await,
+        NodeEvent::ConnectionEstablished { .. }
+    ) {}
+}
+
+#[tokio::test]
+async fn task_round_trips_between_connected_peers() {
+    let mut miner = node(Duration::from_secs(10));
+    let miner_id = miner.local_peer_id();
+    let address = listen(&mut miner).await;
+    tokio::spawn(async move {
+        loop {
+            if let NodeEvent::Task(TaskEvent::TaskRequestReceived {
+                request_id,
+                request,
+                ..
+            }) = miner.next_event().await
+            {
+                let response = match request.decode_input::<Sum>() {
+                    Ok(numbers) => {
+                        TaskResponse::encode::<Sum>(&numbers.iter().map(|&n| n as u64).sum())
+                    }
+                    Err(_) => TaskResponse {
+                        payload: Vec::new(),
+                    },
+                };
+                miner.respond_to_task(request_id, response).unwrap();
+            }
+        }
+    });
+
+    let mut validator = node(Duration::from_secs(10));
+    connect(&mut validator, address).await;
+    let response = validator
+        .send_task(miner_id, TaskRequest::encode::<Sum>(3, &vec![1, 2, 39]))
+        .await
+        .unwrap();
+    assert_eq!(response.decode_output::<Sum>(0).unwrap(), 42);
+
+    // An input the task refuses is answered with an empty payload.
+    let response = validator
+        .send_task(miner_id, TaskRequest::encode::<Sum>(3, &Vec::new()))
+        .await
+        .unwrap();
+    assert!(response.decode_output::<Sum>(0).is_err());
+}
+
+#[tokio::test]
+async fn unconnected_and_silent_peers_are_reported() {
+    let mut validator = node(Duration::from_millis(500));
+    let stranger = identity::Keypair::generate_ed25519().public().to_peer_id();
+    assert_eq!(
+        validator
+            .send_task(stranger, TaskRequest::encode::<Sum>(3, &vec![1]))
+            .await,
+        Err(SendError::NotConnected(stranger))
+    );
+
+    // This miner takes the request but never answers it.
+    let mut miner = node(Duration::from_secs(10));
+    let miner_id = miner.local_peer_id();
+    let address = listen(&mut miner).await;
+    tokio::spawn(async move {
+        loop {
+            miner.next_event().await;
+        }
+    });
+    connect(&mut validator, address).await;
+    assert_eq!(
+        validator
+            .send_task(miner_id, TaskRequest::encode::<Sum>(3, &vec![1]))
+            .await,
+        Err(SendError::Timeout)
+    );
+}
@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use neurochain_node::service::{
+    GetTaskResultRequest, GetTaskResultResponse, HeartbeatRequest, HeartbeatResponse, MeshService,
+    MeshServiceClient, MeshServiceServer, SubmitTaskRequest, SubmitTaskResponse,
+};
+use sp_neuro_core::NeuralTask;
+use tonic::{Code, Request, Response, Status};
+
+/// Adds up a list of numbers.
+struct Sum;
+
+impl NeuralTask for Sum {
+    type Input = Vec<u32>;
+    type Output = u64;
+    type Error = ();
+}
+
+/// A worker that completes every task as soon as it is submitted.
+#[derive(Default)]
+struct Worker {
+    results: Mutex<HashMap<u64, u64>>,
+}
+
+#[tonic::async_trait]
+impl MeshService for Worker {
+    async fn submit_task(
+        &self,
+        request: Request<SubmitTaskRequest>,
+    ) -> Result<Response<SubmitTaskResponse>, Status> {
+        let input = request
+            .get_ref()
+            .decode_input::<Sum>()
+            .map_err(|err| Status::invalid_argument(format!("{err:?}")))?;
+        let mut results = self.results.lock().unwrap();
+        let task_id = results.len() as u64;
+        results.insert(task_id, input.iter().map(|n| u64::from(*n)).sum());
+        Ok(Response::new(SubmitTaskResponse { task_id }))
+    }
+
+    async fn get_task_result(
+        &self,
+        request: Request<GetTaskResultRequest>,
+    ) -> Result<Response<GetTaskResultResponse>, Status> {
+        let results = self.results.lock().unwrap();
+        Ok(Response::new(
+            match results.get(&request.get_ref().task_id) {
+                Some(output) => GetTaskResultResponse::encode::<Sum>(output),
+                None => GetTaskResultResponse { output: None },
+            },
+        ))
+    }
+
+    async fn heartbeat(
+        &self,
+        request: Request<HeartbeatRequest>,
+    ) -> Result<Response<HeartbeatResponse>, Status> {
+        Ok(Response::new(HeartbeatResponse {
+            sequence: request.get_ref().sequence,
+            peer_count: 0,
+        }))
+    }
+}
+
+/// A client whose channel is the server itself, so that every call
+/// goes through the codec and the server's routing in process.
+fn client() -> MeshServiceClient<MeshServiceServer<Worker>> {
+    MeshServiceClient::new(MeshServiceServer::new(Worker::default()))
+}
+
+#[tokio::test]
+async fn tasks_round_trip() {
+    let mut client = client();
+    let task_id = client
+        .submit_task(SubmitTaskRequest::encode::<Sum>(1, &vec![1, 2, 3]))
+        .await
+        .unwrap()
+        .into_inner()
+        .task_id;
+
+    let result = client
+        .get_task_result(GetTaskResultRequest { task_id })
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(result.decode_output::<Sum>(task_id).unwrap(), Some(6));
+
+    let unknown = client
+        .get_task_result(GetTaskResultRequest {
+            task_id: task_id + 1,
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(unknown.decode_output::<Sum>(task_id + 1).unwrap(), None);
+}
+
+#[tokio::test]
+async fn heartbeats_are_echoed() {
+    let response = client()
+        .heartbeat(HeartbeatRequest { sequence: 7 })
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(response.sequence, 7);
+}
+
+#[tokio::test]
+async fn malformed_inputs_are_refused() {
+    let status = client()
+        .submit_task(SubmitTaskRequest {
+            subnet_id: 1,
+            input: vec![0xff],
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::InvalidArgument);
+}
@@ -0,0 +1,67 @@
+use std::time::Duration;
+
+use libp2p::identity;
+use neurochain_node::{Node, NodeConfig, NodeEvent};
+use prometheus_client::registry::Registry;
+use tokio::time;
+
+const TOPIC: &str = "subnet-1-weights";
+
+fn node() -> Node {
+    let config = NodeConfig {
+        enable_mdns: false,
+        ..NodeConfig::default()
+    };
+    Node::with_config(
+        identity::Keypair::generate_ed25519(),
+        config,
+        &mut Registry::default(),
+    )
+    .unwrap()
+}
+
+#[tokio::test]
+async fn shutdown_flushes_messages_and_closes_connections() {
+    let mut receiver = node();
+    receiver.subscribe(TOPIC).unwrap();
+    receiver
+        .listen_on("/ip4/127.0.0.1/tcp/0".parse().unwrap())
+        .unwrap();
+    let address = loop {
+        if let NodeEvent::NewListenAddr { address } = receiver.next_event().await {
+            break address;
+        }
+    };
+
+    let mut sender = node();
+    let sender_id = sender.local_peer_id();
+    sender.subscribe(TOPIC).unwrap();
+    sender.dial(address).unwrap();
+    let sender = tokio::spawn(async move {
+        // Publishing fails until the receiver's subscription is known.
+        while sender.publish(TOPIC, b"last words".to_vec()).is_err() {
+            let _ = time::timeout(Duration::from_millis(100), sender.next_event()).await;
+        }
+        sender.shutdown().await
+    });
+
+    let (mut delivered, mut closed) = (false, false);
+    time::timeout(Duration::from_secs(30), async {
+        while !(delivered && closed) {
+            match receiver.next_event().await {
+                NodeEvent::Message { source, message } if source == sender_id => {
+                    assert_eq!(message.data, b"last words");
+                    delivered = true;
+                }
+                NodeEvent::ConnectionClosed { peer } if peer == sender_id => {
+                    assert!(delivered, "connection closed before the message arrived");
+                    closed = true;
+                }
+                _ => {}
+            }
+        }
+    })
+    .await
+    .expect("message delivered and connection closed");
+    assert_eq!(sender.await.unwrap(), 1, "one peer was connected");
+}
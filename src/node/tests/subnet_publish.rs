@@ -0,0 +1,120 @@
+use std::time::Duration;
+
+use libp2p::{identity, Multiaddr};
+use neurochain_node::{Node, NodeConfig, NodeError, NodeEvent};
+use prometheus_client::registry::Registry;
+use tokio::time;
+
+const SUBNET: u32 = 3;
+const VERSION: u32 = 1;
+
+fn node(queued_message_ttl: Duration) -> Node {
+    let config = NodeConfig {
+        enable_mdns: false,
+        queued_message_ttl,
+        ..NodeConfig::default()
+    };
+    Node::with_config(
+        identity::Keypair::generate_ed25519(),
+        config,
+        &mut Registry::default(),
+    )
+    .unwrap()
+}
+
+async fn listen(node: &mut Node) -> Multiaddr {
+    node.listen_on("/ip4/127.0.0.1/tcp/0".parse().unwrap())
+        .unwrap();
+    loop {
+        if let NodeEvent::NewListenAddr { address } = node.next_event().await {
+            return address;
+        }
+    }
+}
+
+/// A miner subscribed to the subnet topic, dialing `address`.  Returns
+/// the miner, to be driven by the caller.
+fn miner(address: Multiaddr) -> Node {
+    let mut miner = node(Duration::from_secs(30));
+    miner.resubscribe_subnet_version(SUBNET, VERSION).unwrap();
+    miner.dial(address).unwrap();
+    miner
+}
+
+async fn next_task(miner: &mut Node) -> Vec<u8> {
+    time::timeout(Duration::from_secs(30), miner.next_message())
+        .await
+        .expect("miner receives the task")
+        .1
+        .data
+}
+
+#[tokio::test]
+async fn publishing_needs_peers_on_the_subnet_topic() {
+    let mut validator = node(Duration::from_secs(30));
+    let address = listen(&mut validator).await;
+    assert!(matches!(
+        validator.publish_to_subnet(SUBNET, VERSION, b"task".to_vec()),
+        Err(NodeError::InsufficientPeers {
+            required: 1,
+            present: 0,
+        })
+    ));
+
+    let mut miner = miner(address);
+    let miner_task = tokio::spawn(async move { next_task(&mut miner).await });
+    time::timeout(Duration::from_secs(30), async {
+        while validator.subnet_peer_count(SUBNET, VERSION) == 0 {
+            validator.next_event().await;
+        }
+    })
+    .await
+    .expect("the miner's subscription is seen");
+    validator
+        .publish_to_subnet(SUBNET, VERSION, b"task".to_vec())
+        .unwrap();
+    tokio::spawn(async move {
+        loop {
+            validator.next_event().await;
+        }
+    });
+    assert_eq!(miner_task.await.unwrap(), b"task");
+}
+
+#[tokio::test]
+async fn queued_messages_go_out_once_peers_join() {
+    let mut validator = node(Duration::from_secs(30));
+    let address = listen(&mut validator).await;
+    validator
+        .publish_to_subnet_or_queue(SUBNET, VERSION, b"queued".to_vec())
+        .unwrap();
+
+    let mut miner = miner(address);
+    tokio::spawn(async move {
+        loop {
+            validator.next_event().await;
+        }
+    });
+    assert_eq!(next_task(&mut miner).await, b"queued");
+}
+
+#[tokio::test]
+async fn queued_messages_expire_without_peers() {
+    let mut validator = node(Duration::from_millis(200));
+    validator
+        .publish_to_subnet_or_queue(SUBNET, VERSION, b"lost".to_vec())
+        .unwrap();
+    let expired = time::timeout(Duration::from_secs(5), async {
+        loop {
+            if let NodeEvent::QueuedMessageExpired {
+                subnet_id,
+                schema_version,
+            } = validator.next_event().await
+            {
+                break (subnet_id, schema_version);
+            }
+        }
+    })
+    .await;
+    assert_eq!(expired, Ok((SUBNET, VERSION)));
+}
@@ -0,0 +1,75 @@
+use futures::StreamExt;
+use libp2p::identity;
+use neurochain_node::task::TaskRequest;
+use neurochain_node::{Node, NodeEvent, TaskEvent};
+use prometheus_client::registry::Registry;
+
+fn node() -> Node {
+    Node::new(
+        identity::Keypair::generate_ed25519(),
+        &mut Registry::default(),
+    )
+    .unwrap()
+}
+
+#[tokio::test]
+async fn task_stream_skips_connection_events() {
+    let mut validator = node();
+    let mut miner = node();
+    let miner_id = miner.local_peer_id();
+    let mut miner_events = miner.event_stream();
+    let mut miner_tasks = miner.task_event_stream();
+
+    miner
+        .listen_on("/ip4/127.0.0.1/tcp/0".parse().unwrap())
+        .unwrap();
+    let address = loop {
+        if let NodeEvent::NewListenAddr { address } = miner.next_event().await {
+            break address;
+        }
+    };
+    tokio::spawn(async move {
+        loop {
+            miner.next_event().await;
+        }
+    });
+
+    validator.dial(address).unwrap();
+    while !matches!(
+        validator.next_event().await,
+        NodeEvent::ConnectionEstablished { .. }
+    ) {}
+    validator.send_task_request(
+        &miner_id,
+        TaskRequest {
+            subnet_id: 7,
+            payload: b"fold this".to_vec(),
+        },
+    );
+    tokio::spawn(async move {
+        loop {
+            validator.next_event().await;
+        }
+    });
+
+    // The connection was established before the task arrived, yet the
+    // first thing on the task stream is the task itself.
+    match miner_tasks.next().await.unwrap() {
+        TaskEvent::TaskRequestReceived { request, .. } => {
+            assert_eq!(request.subnet_id, 7);
+            assert_eq!(request.payload, b"fold this".to_vec());
+        }
+        other => panic!("unexpected task event {other:?}"),
+    }
+
+    // The full stream saw the connection first.
+    let mut saw_connection = false;
+    loop {
+        match miner_events.next().await.unwrap() {
+            NodeEvent::ConnectionEstablished { .. } => saw_connection = true,
+            NodeEvent::Task(TaskEvent::TaskRequestReceived { .. }) => break,
+            _ => {}
+        }
+    }
+    assert!(saw_connection);
+}
@@ -0,0 +1,110 @@
+use std::time::Duration;
+
+use libp2p::identity;
+use neurochain_node::{subnet_topic, Node, NodeConfig, NodeEvent};
+use prometheus_client::registry::Registry;
+use tokio::time;
+
+const SUBNET: u32 = 7;
+
+fn node() -> Node {
+    let config = NodeConfig {
+        enable_mdns: false,
+        topic_grace_period: Duration::from_secs(1),
+        ..NodeConfig::default()
+    };
+    Node::with_config(
+        identity::Keypair::generate_ed25519(),
+        config,
+        &mut Registry::default(),
+    )
+    .unwrap()
+}
+
+/// Publish `data` on `topic` as soon as a peer subscribed to it is
+/// known, then keep `node` running.
+fn publish_when_ready(mut node: Node, topic: String, data: &'static [u8]) {
+    tokio::spawn(async move {
+        while node.publish(&topic, data.to_vec()).is_err() {
+            let _ = time::timeout(Duration::from_millis(100), node.next_event()).await;
+        }
+        loop {
+            node.next_event().await;
+        }
+    });
+}
+
+#[tokio::test]
+async fn previous_topic_is_kept_for_the_grace_period_then_left() {
+    assert_eq!(subnet_topic(SUBNET, 2), "neuromesh/subnet/7/v2");
+
+    let mut receiver = node();
+    receiver.resubscribe_subnet_version(SUBNET, 1).unwrap();
+    receiver
+        .listen_on("/ip4/127.0.0.1/tcp/0".parse().unwrap())
+        .unwrap();
+    let address = loop {
+        if let NodeEvent::NewListenAddr { address } = receiver.next_event().await {
+            break address;
+        }
+    };
+
+    // One sender has not moved on yet and one has.
+    let mut lagging = node();
+    lagging.dial(address.clone()).unwrap();
+    publish_when_ready(lagging, subnet_topic(SUBNET, 1), b"old");
+    let mut current = node();
+    current.dial(address).unwrap();
+    publish_when_ready(current, subnet_topic(SUBNET, 2), b"new");
+
+    let (mut connected, mut old, mut new, mut left) = (0, false, false, false);
+    time::timeout(Duration::from_secs(30), async {
+        while !(old && new && left) {
+            match receiver.next_event().await {
+                // Move on once both senders are connected.
+                NodeEvent::ConnectionEstablished { .. } => {
+                    connected += 1;
+                    if connected == 2 {
+                        receiver.resubscribe_subnet_version(SUBNET, 2).unwrap();
+                    }
+                }
+                NodeEvent::Message { message, .. } => match &message.data[..] {
+                    b"old" => {
+                        assert!(!left, "message on the old topic after leaving it");
+                        old = true;
+                    }
+                    b"new" => new = true,
+                    data => panic!("unexpected message {data:?}"),
+                },
+                NodeEvent::SubnetTopicLeft {
+                    subnet_id,
+                    schema_version,
+                } => {
+                    assert_eq!((subnet_id, schema_version), (SUBNET, 1));
+                    left = true;
+                }
+                _ => {}
+            }
+        }
+    })
+    .await
+    .expect("both messages delivered and the old topic left");
+}
+
+#[tokio::test]
+async fn moving_back_within_the_grace_period_keeps_the_topic() {
+    let mut node = node();
+    node.resubscribe_subnet_version(SUBNET, 1).unwrap();
+    node.resubscribe_subnet_version(SUBNET, 2).unwrap();
+    node.resubscribe_subnet_version(SUBNET, 1).unwrap();
+
+    let left = time::timeout(Duration::from_secs(2), async {
+        loop {
+            if let NodeEvent::SubnetTopicLeft { schema_version, .. } = node.next_event().await {
+                break schema_version;
+            }
+        }
+    })
+    .await;
+    assert_eq!(left, Ok(2));
+}
@@ -0,0 +1,88 @@
+//! Overflow‑safe accumulation of emission rewards.
+//!
+//! Emissions are credited every block and only paid out periodically,
+//! so the running total has to survive a long time without wrapping.
+//! [`Accrual`] refuses any credit that would overflow instead of
+//! silently saturating or wrapping, and keeps a count of the refused
+//! credits so operators can tell when payouts are falling behind.
+
+use codec::{Decode, Encode, MaxEncodedLen};
+use scale_info::TypeInfo;
+
+/// Error returned when a reward cannot be added to an [`Accrual`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub enum AccrualError {
+    /// Adding `reward` to the current total would exceed `u128::MAX`.
+    Overflow {
+        /// Total accrued before the failed addition.
+        accrued: u128,
+        /// Reward that was refused.
+        reward: u128,
+    },
+}
+
+/// Running total of rewards awaiting payout.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Encode, Decode, MaxEncodedLen, TypeInfo)]
+pub struct Accrual {
+    accrued: u128,
+    rejected: u32,
+}
+
+impl Accrual {
+    /// Create an empty accrual.
+    pub const fn new() -> Self {
+        Self {
+            accrued: 0,
+            rejected: 0,
+        }
+    }
+
+    /// Add `reward` to the running total, returning the new total.
+    ///
+    /// On overflow the total is left untouched, the rejection counter
+    /// is bumped, and an error is returned.
+    pub fn checked_add_reward(&mut self, reward: u128) -> Result<u128, AccrualError> {
+        match self.accrued.checked_add(reward) {
+            Some(accrued) => {
+                self.accrued = accrued;
+                Ok(accrued)
+            }
+            None => {
+                self.rejected = self.rejected.saturating_add(1);
+                Err(AccrualError::Overflow {
+                    accrued: self.accrued,
+                    reward,
+                })
+            }
+        }
+    }
+
+    /// Take the accrued total for payout and reset it to zero.
+    ///
+    /// The rejection counter is kept so that overflows remain visible
+    /// after the payout that would have cleared them.
+    pub fn drain(&mut self) -> u128 {
+        core::mem::take(&mut self.accrued)
+    }
+
+    /// Total accrued since the last drain.
+    pub fn accrued(&self) -> u128 {
+        self.accrued
+    }
+
+    /// How much more can be accrued before an addition overflows.
+    pub fn headroom(&self) -> u128 {
+        u128::MAX - self.accrued
+    }
+
+    /// Whether the accrual has reached `u128::MAX` and will refuse any
+    /// non‑zero reward.
+    pub fn is_saturated(&self) -> bool {
+        self.accrued == u128::MAX
+    }
+
+    /// Number of rewards refused because they would have overflowed.
+    pub fn rejected_rewards(&self) -> u32 {
+        self.rejected
+    }
+}
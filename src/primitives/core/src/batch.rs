@@ -0,0 +1,117 @@
+//! Batches of task inputs.
+//!
+//! A batch is the SCALE compact item count followed by each input,
+//! SCALE‑encoded and prefixed with its compact length.  The length
+//! prefix lets [`TaskStreamDecoder`] decode one input at a time
+//! without holding the whole batch in memory, and lets it step over
+//! an input that fails to decode.
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use codec::{Compact, Decode, DecodeAll, Encode, Input};
+
+use crate::task::NeuralTask;
+
+/// Error returned while decoding a batch.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CoreError {
+    /// The batch does not start with an item count.
+    InvalidHeader,
+    /// The batch ended inside the item at `index`.
+    Truncated { index: u32 },
+    /// The item at `index` is not a valid encoding of the task input.
+    InvalidItem { index: u32 },
+}
+
+/// Encode `inputs` as a batch.
+pub fn encode_batch<T: NeuralTask>(inputs: &[T::Input]) -> Vec<u8> {
+    let mut batch = Compact(inputs.len() as u32).encode();
+    for input in inputs {
+        input.encode().encode_to(&mut batch);
+    }
+    batch
+}
+
+/// Decodes the inputs of a batch read from a byte source, one per call
+/// to [`Iterator::next`].
+///
+/// An input that fails to decode yields an error and decoding resumes
+/// with the next one.  A batch cut short yields an error for the first
+/// missing input and nothing after it.
+pub struct TaskStreamDecoder<T, I> {
+    source: I,
+    /// Inputs left to decode, once the item count has been read.
+    remaining: Option<u32>,
+    index: u32,
+    done: bool,
+    _task: PhantomData<T>,
+}
+
+impl<T: NeuralTask, I: Input> TaskStreamDecoder<T, I> {
+    pub fn new(source: I) -> Self {
+        Self {
+            source,
+            remaining: None,
+            index: 0,
+            done: false,
+            _task: PhantomData,
+        }
+    }
+
+    /// Number of inputs not yet decoded, once the item count has been
+    /// read.
+    pub fn remaining(&self) -> Option<u32> {
+        self.remaining
+    }
+
+    fn next_item(&mut self) -> Result<T::Input, CoreError> {
+        let index = self.index;
+        let truncated = CoreError::Truncated { index };
+        let len = Compact::<u32>::decode(&mut self.source)
+            .map_err(|_| truncated)?
+            .0 as usize;
+        // A length beyond what the source holds is a truncated item;
+        // catching it here avoids allocating for it.
+        if let Ok(Some(left)) = self.source.remaining_len() {
+            if len > left {
+                return Err(truncated);
+            }
+        }
+        let mut item = vec![0; len];
+        self.source.read(&mut item).map_err(|_| truncated)?;
+        T::Input::decode_all(&mut &item[..]).map_err(|_| CoreError::InvalidItem { index })
+    }
+}
+
+impl<T: NeuralTask, I: Input> Iterator for TaskStreamDecoder<T, I> {
+    type Item = Result<T::Input, CoreError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let remaining = match self.remaining {
+            Some(remaining) => remaining,
+            None => match Compact::<u32>::decode(&mut self.source) {
+                Ok(Compact(count)) => count,
+                Err(_) => {
+                    self.done = true;
+                    return Some(Err(CoreError::InvalidHeader));
+                }
+            },
+        };
+        if remaining == 0 {
+            self.done = true;
+            return None;
+        }
+
+        let item = self.next_item();
+        // Nothing after a truncated item can be located.
+        self.done = matches!(item, Err(CoreError::Truncated { .. }));
+        self.remaining = Some(remaining - 1);
+        self.index += 1;
+        Some(item)
+    }
+}
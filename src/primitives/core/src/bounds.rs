@@ -0,0 +1,17 @@
+//! Bounds that depend on the enabled features.
+
+/// Serializable with serde when the `serde` feature is enabled, and
+/// implemented by every type otherwise.
+#[cfg(feature = "serde")]
+pub trait MaybeSerde: serde::Serialize + serde::de::DeserializeOwned {}
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize + serde::de::DeserializeOwned> MaybeSerde for T {}
+
+/// Serializable with serde when the `serde` feature is enabled, and
+/// implemented by every type otherwise.
+#[cfg(not(feature = "serde"))]
+pub trait MaybeSerde {}
+
+#[cfg(not(feature = "serde"))]
+impl<T> MaybeSerde for T {}
@@ -0,0 +1,218 @@
+//! Dispatch of tasks to the worker threads of a miner.
+//!
+//! A [`WorkStealingDispatcher`] keeps one deque per worker.  Submitted
+//! tasks are spread over the deques in turn; a worker takes tasks from
+//! the front of its own deque and, once that is empty, steals from the
+//! back of the others, so workers only contend when one runs dry.  The
+//! dispatcher holds at most `capacity` tasks at a time.
+//!
+//! [`WorkStealingDispatcher::shutdown`] stops new submissions.  Workers
+//! still drain the tasks already queued and only then see the end of
+//! their [`WorkerHandle`].
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+
+/// A SCALE‑encoded task input waiting for a worker.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EncodedTask {
+    /// Identifier of the task.
+    pub id: u64,
+    /// The encoded input.
+    pub payload: Vec<u8>,
+}
+
+/// Why a task was not accepted.  The task is handed back.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SubmitError {
+    /// `capacity` tasks are already queued.
+    Full(EncodedTask),
+    /// The dispatcher has been shut down.
+    ShutDown(EncodedTask),
+}
+
+struct Shared {
+    deques: Vec<Mutex<VecDeque<EncodedTask>>>,
+    capacity: usize,
+    /// Tasks queued, whether or not a worker has claimed them yet.
+    queued: AtomicUsize,
+    /// Tasks queued and not yet claimed by a worker.
+    unclaimed: AtomicUsize,
+    /// Whether the dispatcher has been shut down.  Submissions hold
+    /// the lock, so no task is queued once it is set.
+    closed: Mutex<bool>,
+    /// Signalled when a task is queued or the dispatcher shuts down.
+    wakeup: Condvar,
+}
+
+impl Shared {
+    /// Claim one unclaimed task, if there is any.
+    fn try_claim(&self) -> bool {
+        self.unclaimed
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| n.checked_sub(1))
+            .is_ok()
+    }
+
+    /// Take a claimed task, from the front of `own` or else from the
+    /// back of another deque.
+    fn take(&self, own: usize) -> EncodedTask {
+        // The claim guarantees a task is queued somewhere, though
+        // another worker may empty a deque as we look at it.
+        loop {
+            if let Some(task) = lock(&self.deques[own]).pop_front() {
+                return self.taken(task);
+            }
+            let others = (1..self.deques.len()).map(|i| (own + i) % self.deques.len());
+            for victim in others {
+                if let Some(task) = lock(&self.deques[victim]).pop_back() {
+                    return self.taken(task);
+                }
+            }
+        }
+    }
+
+    fn taken(&self, task: EncodedTask) -> EncodedTask {
+        self.queued.fetch_sub(1, Ordering::AcqRel);
+        task
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Bounded task queue shared by a fixed set of workers.
+pub struct WorkStealingDispatcher {
+    shared: Arc<Shared>,
+    /// Deque the next submitted task goes to.
+    next_deque: AtomicUsize,
+    /// Workers that have been handed a [`WorkerHandle`].
+    handles: AtomicUsize,
+}
+
+impl WorkStealingDispatcher {
+    /// A dispatcher for `workers` workers holding up to `capacity`
+    /// tasks.  Panics if `workers` is zero.
+    pub fn new(workers: usize, capacity: usize) -> Self {
+        assert!(workers > 0, "a dispatcher needs at least one worker");
+        Self {
+            shared: Arc::new(Shared {
+                deques: (0..workers).map(|_| Mutex::new(VecDeque::new())).collect(),
+                capacity,
+                queued: AtomicUsize::new(0),
+                unclaimed: AtomicUsize::new(0),
+                closed: Mutex::new(false),
+                wakeup: Condvar::new(),
+            }),
+            next_deque: AtomicUsize::new(0),
+            handles: AtomicUsize::new(0),
+        }
+    }
+
+    /// Queue `task` for the next idle worker.
+    pub fn submit(&self, task: EncodedTask) -> Result<(), SubmitError> {
+        let closed = lock(&self.shared.closed);
+        if *closed {
+            return Err(SubmitError::ShutDown(task));
+        }
+        let capacity = self.shared.capacity;
+        let reserved = self
+            .shared
+            .queued
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| {
+                (n < capacity).then_some(n + 1)
+            });
+        if reserved.is_err() {
+            return Err(SubmitError::Full(task));
+        }
+        let deque = self.next_deque.fetch_add(1, Ordering::Relaxed) % self.shared.deques.len();
+        lock(&self.shared.deques[deque]).push_back(task);
+        self.shared.unclaimed.fetch_add(1, Ordering::AcqRel);
+        self.shared.wakeup.notify_one();
+        Ok(())
+    }
+
+    /// Handle for the next worker, or `None` once every worker has
+    /// one.
+    pub fn worker_handle(&self) -> Option<WorkerHandle> {
+        let index = self
+            .handles
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| {
+                (n < self.shared.deques.len()).then_some(n + 1)
+            })
+            .ok()?;
+        Some(WorkerHandle {
+            shared: self.shared.clone(),
+            index,
+        })
+    }
+
+    /// Number of tasks queued and not yet taken by a worker.
+    pub fn len(&self) -> usize {
+        self.shared.queued.load(Ordering::Acquire)
+    }
+
+    /// Whether no task is waiting for a worker.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Refuse further submissions.  Workers finish the tasks already
+    /// queued, then their handles yield `None`.
+    pub fn shutdown(&self) {
+        *lock(&self.shared.closed) = true;
+        self.shared.wakeup.notify_all();
+    }
+}
+
+/// One worker's end of a [`WorkStealingDispatcher`].  Iterating it
+/// blocks until a task is available and ends once the dispatcher is
+/// shut down and drained.
+pub struct WorkerHandle {
+    shared: Arc<Shared>,
+    index: usize,
+}
+
+impl WorkerHandle {
+    /// Index of the worker's own deque.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Take the next task without blocking.
+    pub fn try_next(&self) -> Option<EncodedTask> {
+        self.shared
+            .try_claim()
+            .then(|| self.shared.take(self.index))
+    }
+}
+
+impl Iterator for WorkerHandle {
+    type Item = EncodedTask;
+
+    fn next(&mut self) -> Option<EncodedTask> {
+        if let Some(task) = self.try_next() {
+            return Some(task);
+        }
+        let mut closed = lock(&self.shared.closed);
+        loop {
+            // Submissions queue tasks under the lock we hold, so none
+            // can slip in between this check and the wait.
+            if self.shared.try_claim() {
+                drop(closed);
+                return Some(self.shared.take(self.index));
+            }
+            if *closed {
+                return None;
+            }
+            closed = self
+                .shared
+                .wakeup
+                .wait(closed)
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+        }
+    }
+}
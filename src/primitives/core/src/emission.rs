@@ -0,0 +1,117 @@
+//! Splitting emissions between recipients.
+//!
+//! Emissions are split in proportion to integer weights, which rarely
+//! divides the total exactly.  [`Rounding`] decides how each share is
+//! rounded; [`distribute`] then settles the difference between the
+//! rounded shares and the total so that nothing is minted or lost.
+
+use alloc::vec::Vec;
+
+use codec::{Decode, Encode, MaxEncodedLen};
+use scale_info::TypeInfo;
+
+/// How a fractional share is rounded to a whole amount.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Encode, Decode, MaxEncodedLen, TypeInfo)]
+pub enum Rounding {
+    /// Round down.  [`distribute`] gives the dust to the largest share.
+    #[default]
+    Floor,
+    /// Round up.  [`distribute`] takes the excess back from the
+    /// smallest shares first.
+    Ceil,
+    /// Round to the nearest amount, halves to the even one.
+    HalfEven,
+}
+
+/// `total * weight / total_weight`, rounded according to `rounding`.
+/// Returns zero if `total_weight` is zero.
+pub fn emission_share(total: u128, weight: u64, total_weight: u64, rounding: Rounding) -> u128 {
+    if total_weight == 0 {
+        return 0;
+    }
+    let (weight, total_weight) = (weight as u128, total_weight as u128);
+    // Split `total` so that no product can overflow: the remainder is
+    // below `total_weight`, and both weights fit in 64 bits.
+    let (quotient, remainder) = (total / total_weight, total % total_weight);
+    let fraction = remainder * weight;
+    let share = quotient * weight + fraction / total_weight;
+    let left_over = fraction % total_weight;
+
+    let round_up = match rounding {
+        Rounding::Floor => false,
+        Rounding::Ceil => left_over > 0,
+        Rounding::HalfEven => {
+            let twice = left_over * 2;
+            twice > total_weight || (twice == total_weight && share % 2 == 1)
+        }
+    };
+    share + round_up as u128
+}
+
+/// Split `total` in proportion to `weights`.  The shares always add up
+/// to `total`, unless every weight is zero, in which case every share
+/// is zero.  Ties between equal weights go to the earliest recipient.
+pub fn distribute(total: u128, weights: &[u32], rounding: Rounding) -> Vec<u128> {
+    let total_weight: u64 = weights.iter().map(|&weight| weight as u64).sum();
+    let mut shares: Vec<u128> = weights
+        .iter()
+        .map(|&weight| emission_share(total, weight as u64, total_weight, rounding))
+        .collect();
+    if total_weight == 0 {
+        return shares;
+    }
+
+    // Compare the shares with the total without summing them, which
+    // could overflow when shares are rounded up.
+    let mut unassigned = total;
+    let mut excess = 0;
+    for &share in &shares {
+        let taken = share.min(unassigned);
+        unassigned -= taken;
+        excess += share - taken;
+    }
+    if unassigned > 0 {
+        let largest = (0..weights.len())
+            .rev()
+            .max_by_key(|&index| weights[index])
+            .expect("total weight is non-zero");
+        shares[largest] += unassigned;
+    } else if excess > 0 {
+        let mut order: Vec<usize> = (0..weights.len()).collect();
+        order.sort_by_key(|&index| weights[index]);
+        for index in order {
+            let taken = excess.min(shares[index]);
+            shares[index] -= taken;
+            excess -= taken;
+        }
+    }
+    shares
+}
+
+/// Relative weights of the recipients of a subnet's emissions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Encode, Decode, MaxEncodedLen, TypeInfo)]
+pub struct RewardSplit {
+    pub miners: u32,
+    pub validators: u32,
+    pub owner: u32,
+}
+
+/// Amounts paid out by a [`RewardSplit`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RewardShares {
+    pub miners: u128,
+    pub validators: u128,
+    pub owner: u128,
+}
+
+impl RewardSplit {
+    /// Split `total` between miners, validators and the subnet owner.
+    pub fn split(&self, total: u128, rounding: Rounding) -> RewardShares {
+        let shares = distribute(total, &[self.miners, self.validators, self.owner], rounding);
+        RewardShares {
+            miners: shares[0],
+            validators: shares[1],
+            owner: shares[2],
+        }
+    }
+}
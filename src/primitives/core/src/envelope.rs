@@ -0,0 +1,82 @@
+//! A common wire format for events from every layer.
+//!
+//! The node, the runtime and the SDK each have their own event types.
+//! Wrapping them in an [`EventEnvelope`] gives them one SCALE encoding
+//! so they can be logged, persisted and replayed side by side.  The
+//! envelope records where the event came from and a per‑source `kind`
+//! that says how to decode the payload.
+
+use alloc::vec::Vec;
+
+use codec::{Decode, DecodeAll, Encode, MaxEncodedLen};
+use scale_info::TypeInfo;
+
+/// The layer an event originated in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Encode, Decode, MaxEncodedLen, TypeInfo)]
+pub enum EventSource {
+    Node,
+    Runtime,
+    Sdk,
+}
+
+/// A SCALE‑encoded event together with its origin.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct EventEnvelope {
+    pub source: EventSource,
+    /// Identifies the payload type among events from `source`.
+    pub kind: u16,
+    /// The SCALE encoding of the event.
+    pub payload: Vec<u8>,
+    /// When the event happened, in milliseconds since the Unix epoch.
+    pub timestamp: u64,
+}
+
+/// Error returned when an envelope does not hold the requested event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EnvelopeError {
+    /// The envelope came from another layer.
+    WrongSource {
+        expected: EventSource,
+        found: EventSource,
+    },
+    /// The envelope holds another kind of event.
+    WrongKind { expected: u16, found: u16 },
+    /// The payload is not a valid encoding of the event.
+    InvalidPayload,
+}
+
+/// An event type that can be carried in an [`EventEnvelope`].
+pub trait EnvelopedEvent: Encode + Decode {
+    /// The layer that emits this event.
+    const SOURCE: EventSource;
+    /// The kind recorded in envelopes holding this event.  Must be
+    /// unique among event types with the same source.
+    const KIND: u16;
+
+    /// Wrap the event in an envelope stamped with `timestamp`.
+    fn into_envelope(self, timestamp: u64) -> EventEnvelope {
+        EventEnvelope {
+            source: Self::SOURCE,
+            kind: Self::KIND,
+            payload: self.encode(),
+            timestamp,
+        }
+    }
+
+    /// Decode the event carried by `envelope`.
+    fn from_envelope(envelope: &EventEnvelope) -> Result<Self, EnvelopeError> {
+        if envelope.source != Self::SOURCE {
+            return Err(EnvelopeError::WrongSource {
+                expected: Self::SOURCE,
+                found: envelope.source,
+            });
+        }
+        if envelope.kind != Self::KIND {
+            return Err(EnvelopeError::WrongKind {
+                expected: Self::KIND,
+                found: envelope.kind,
+            });
+        }
+        Self::decode_all(&mut &envelope.payload[..]).map_err(|_| EnvelopeError::InvalidPayload)
+    }
+}
@@ -0,0 +1,112 @@
+//! Self‑describing frames for task payloads sent over a stream.
+//!
+//! A frame is the payload length as a little‑endian `u32`, a version
+//! tag, then the payload itself.  Frames can be written back to back on
+//! one connection and split again with [`decode_frames`].  The length
+//! is checked against [`MAX_FRAME_LEN`] before anything is read, so a
+//! corrupt or hostile prefix cannot make a reader wait for, or
+//! allocate, gigabytes.
+
+use alloc::vec::Vec;
+
+/// Version tag of the frames written by this crate.
+pub const FRAME_VERSION: u8 = 1;
+
+/// Bytes in front of the payload: the length and the version tag.
+pub const FRAME_HEADER_LEN: usize = 5;
+
+/// Largest payload a frame may announce.
+pub const MAX_FRAME_LEN: u32 = 16 * 1024 * 1024;
+
+/// Why a frame could not be read.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FrameError {
+    /// The buffer ends inside the frame.
+    Truncated,
+    /// The frame announces a payload larger than [`MAX_FRAME_LEN`].
+    TooLarge { len: u32 },
+    /// The frame was written with a version this crate cannot read.
+    UnsupportedVersion(u8),
+    /// Bytes follow a frame that was expected to fill the buffer.
+    TrailingBytes,
+    /// The payload is not a valid encoding of the task input.
+    InvalidPayload,
+}
+
+/// Wrap `payload` in a frame.
+///
+/// # Panics
+///
+/// If `payload` is longer than [`MAX_FRAME_LEN`].
+pub fn encode_frame(payload: &[u8]) -> Vec<u8> {
+    assert!(
+        payload.len() <= MAX_FRAME_LEN as usize,
+        "frame payload exceeds MAX_FRAME_LEN"
+    );
+    let mut frame = Vec::with_capacity(FRAME_HEADER_LEN + payload.len());
+    frame.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    frame.push(FRAME_VERSION);
+    frame.extend_from_slice(payload);
+    frame
+}
+
+/// Split the first frame off `buffer`, returning its payload and the
+/// bytes after it.
+pub fn split_frame(buffer: &[u8]) -> Result<(&[u8], &[u8]), FrameError> {
+    if buffer.len() < FRAME_HEADER_LEN {
+        return Err(FrameError::Truncated);
+    }
+    let (header, rest) = buffer.split_at(FRAME_HEADER_LEN);
+    let len = u32::from_le_bytes([header[0], header[1], header[2], header[3]]);
+    if len > MAX_FRAME_LEN {
+        return Err(FrameError::TooLarge { len });
+    }
+    if header[4] != FRAME_VERSION {
+        return Err(FrameError::UnsupportedVersion(header[4]));
+    }
+    if rest.len() < len as usize {
+        return Err(FrameError::Truncated);
+    }
+    Ok(rest.split_at(len as usize))
+}
+
+/// The payloads of the frames in `buffer`, in order.
+pub fn decode_frames(buffer: &[u8]) -> Frames<'_> {
+    Frames { buffer }
+}
+
+/// Iterator returned by [`decode_frames`].
+///
+/// A frame that cannot be read yields an error and ends the iteration,
+/// since the frames after it cannot be located.
+#[derive(Clone, Debug)]
+pub struct Frames<'a> {
+    buffer: &'a [u8],
+}
+
+impl<'a> Frames<'a> {
+    /// Bytes not yet split into frames.
+    pub fn remainder(&self) -> &'a [u8] {
+        self.buffer
+    }
+}
+
+impl<'a> Iterator for Frames<'a> {
+    type Item = Result<&'a [u8], FrameError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.buffer.is_empty() {
+            return None;
+        }
+        match split_frame(self.buffer) {
+            Ok((payload, rest)) => {
+                self.buffer = rest;
+                Some(Ok(payload))
+            }
+            Err(error) => {
+                self.buffer = &[];
+                Some(Err(error))
+            }
+        }
+    }
+}
@@ -0,0 +1,222 @@
+//! Minimal JSON well‑formedness checking.
+//!
+//! Subnet schemas are stored on chain as raw bytes.  The runtime cannot
+//! afford a full JSON‑Schema implementation, but it can at least make
+//! sure a schema is well‑formed JSON before accepting it.  [`validate`]
+//! walks the document without allocating and reports the kind of the
+//! root value.
+
+/// Maximum nesting depth accepted by [`validate`].  Deeper documents
+/// are rejected so that validation has bounded stack usage.
+pub const MAX_DEPTH: usize = 64;
+
+/// Kind of the root value of a JSON document.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JsonKind {
+    Object,
+    Array,
+    String,
+    Number,
+    Bool,
+    Null,
+}
+
+/// Reasons a document is not well‑formed JSON.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JsonError {
+    /// The bytes are not valid UTF‑8.
+    InvalidUtf8,
+    /// Unexpected byte or end of input at the given offset.
+    Syntax(usize),
+    /// Nesting exceeds [`MAX_DEPTH`].
+    TooDeep,
+}
+
+/// Check that `bytes` hold exactly one well‑formed JSON value,
+/// optionally surrounded by whitespace, and return the kind of that
+/// value.
+pub fn validate(bytes: &[u8]) -> Result<JsonKind, JsonError> {
+    core::str::from_utf8(bytes).map_err(|_| JsonError::InvalidUtf8)?;
+    let mut parser = Parser { bytes, pos: 0 };
+    parser.skip_whitespace();
+    let kind = parser.value(0)?;
+    parser.skip_whitespace();
+    if parser.pos != bytes.len() {
+        return Err(JsonError::Syntax(parser.pos));
+    }
+    Ok(kind)
+}
+
+struct Parser<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn error(&self) -> JsonError {
+        JsonError::Syntax(self.pos)
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), JsonError> {
+        if self.peek() != Some(byte) {
+            return Err(self.error());
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.pos += 1;
+        }
+    }
+
+    fn value(&mut self, depth: usize) -> Result<JsonKind, JsonError> {
+        match self.peek() {
+            Some(b'{') => self.object(depth + 1),
+            Some(b'[') => self.array(depth + 1),
+            Some(b'"') => self.string().map(|_| JsonKind::String),
+            Some(b't') => self.literal(b"true").map(|_| JsonKind::Bool),
+            Some(b'f') => self.literal(b"false").map(|_| JsonKind::Bool),
+            Some(b'n') => self.literal(b"null").map(|_| JsonKind::Null),
+            Some(b'-' | b'0'..=b'9') => self.number().map(|_| JsonKind::Number),
+            _ => Err(self.error()),
+        }
+    }
+
+    fn object(&mut self, depth: usize) -> Result<JsonKind, JsonError> {
+        if depth > MAX_DEPTH {
+            return Err(JsonError::TooDeep);
+        }
+        self.expect(b'{')?;
+        self.skip_whitespace();
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+            return Ok(JsonKind::Object);
+        }
+        loop {
+            self.skip_whitespace();
+            self.string()?;
+            self.skip_whitespace();
+            self.expect(b':')?;
+            self.skip_whitespace();
+            self.value(depth)?;
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b'}') => {
+                    self.pos += 1;
+                    return Ok(JsonKind::Object);
+                }
+                _ => return Err(self.error()),
+            }
+        }
+    }
+
+    fn array(&mut self, depth: usize) -> Result<JsonKind, JsonError> {
+        if depth > MAX_DEPTH {
+            return Err(JsonError::TooDeep);
+        }
+        self.expect(b'[')?;
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            self.pos += 1;
+            return Ok(JsonKind::Array);
+        }
+        loop {
+            self.skip_whitespace();
+            self.value(depth)?;
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b']') => {
+                    self.pos += 1;
+                    return Ok(JsonKind::Array);
+                }
+                _ => return Err(self.error()),
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<(), JsonError> {
+        self.expect(b'"')?;
+        loop {
+            match self.peek() {
+                Some(b'"') => {
+                    self.pos += 1;
+                    return Ok(());
+                }
+                Some(b'\\') => {
+                    self.pos += 1;
+                    match self.peek() {
+                        Some(b'"' | b'\\' | b'/' | b'b' | b'f' | b'n' | b'r' | b't') => {
+                            self.pos += 1
+                        }
+                        Some(b'u') => {
+                            self.pos += 1;
+                            for _ in 0..4 {
+                                if !matches!(self.peek(), Some(b) if b.is_ascii_hexdigit()) {
+                                    return Err(self.error());
+                                }
+                                self.pos += 1;
+                            }
+                        }
+                        _ => return Err(self.error()),
+                    }
+                }
+                // Control characters must be escaped.
+                Some(0x00..=0x1f) | None => return Err(self.error()),
+                Some(_) => self.pos += 1,
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<(), JsonError> {
+        if self.peek() == Some(b'-') {
+            self.pos += 1;
+        }
+        match self.peek() {
+            Some(b'0') => self.pos += 1,
+            Some(b'1'..=b'9') => self.digits(),
+            _ => return Err(self.error()),
+        }
+        if self.peek() == Some(b'.') {
+            self.pos += 1;
+            self.required_digits()?;
+        }
+        if matches!(self.peek(), Some(b'e' | b'E')) {
+            self.pos += 1;
+            if matches!(self.peek(), Some(b'+' | b'-')) {
+                self.pos += 1;
+            }
+            self.required_digits()?;
+        }
+        Ok(())
+    }
+
+    fn digits(&mut self) {
+        while matches!(self.peek(), Some(b'0'..=b'9')) {
+            self.pos += 1;
+        }
+    }
+
+    fn required_digits(&mut self) -> Result<(), JsonError> {
+        if !matches!(self.peek(), Some(b'0'..=b'9')) {
+            return Err(self.error());
+        }
+        self.digits();
+        Ok(())
+    }
+
+    fn literal(&mut self, word: &[u8]) -> Result<(), JsonError> {
+        if !self.bytes[self.pos..].starts_with(word) {
+            return Err(self.error());
+        }
+        self.pos += word.len();
+        Ok(())
+    }
+}
@@ -0,0 +1,49 @@
+//! NeuroMesh core primitives
+//!
+//! This crate holds types and traits shared between the runtime, the
+//! node, and off‑chain workers.  Everything here is `no_std` unless
+//! explicitly gated behind the `std` feature.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub mod accrual;
+pub mod batch;
+pub mod bounds;
+#[cfg(feature = "std")]
+pub mod dispatch;
+pub mod emission;
+pub mod envelope;
+pub mod frame;
+pub mod json;
+pub mod provider;
+pub mod quota;
+pub mod registry;
+pub mod scheduler;
+pub mod task;
+
+pub use accrual::{Accrual, AccrualError};
+pub use batch::{encode_batch, CoreError, TaskStreamDecoder};
+pub use bounds::MaybeSerde;
+#[cfg(feature = "std")]
+pub use dispatch::{EncodedTask, SubmitError, WorkStealingDispatcher, WorkerHandle};
+pub use emission::{distribute, emission_share, RewardShares, RewardSplit, Rounding};
+pub use envelope::{EnvelopeError, EnvelopedEvent, EventEnvelope, EventSource};
+pub use frame::{decode_frames, encode_frame, FrameError, Frames};
+pub use provider::{
+    decode_capabilities, Capability, CapabilityList, MeshProvider, MAX_CAPABILITIES,
+};
+pub use quota::Quota;
+pub use registry::{
+    CapabilityFlags, InMemoryProviderRegistry, OnConflict, ProviderId, RegisteredProvider,
+    RegistryError,
+};
+pub use scheduler::{
+    EarliestDeadlineScheduler, FifoScheduler, HighestRewardScheduler, LeastLoadedScheduler,
+    PendingTask, Provider, ProviderScheduler, StakeWeightedScheduler, TaskScheduler,
+};
+pub use task::{
+    ContentAddressedTask, ContentTaskId, NeuralTask, NeuralTaskError, TaskError, TaskId,
+    TaskOutcome,
+};
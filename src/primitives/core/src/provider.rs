@@ -0,0 +1,109 @@
+//! Providers serving tasks on the mesh.
+//!
+//! A [`MeshProvider`] describes what it can run through its
+//! [`Capabilities`](MeshProvider::Capabilities), which travel as SCALE
+//! like everything else shared with the runtime.  Consumers that only
+//! need to know whether a provider can run a given task ask
+//! [`supports_task`](MeshProvider::supports_task) rather than decoding
+//! the capabilities themselves.
+//!
+//! Providers whose capabilities are a list of [`Capability`] opt into
+//! [`supports`](MeshProvider::supports) by implementing
+//! [`CapabilityList`] for them.  The default implementation reads the
+//! list back from the provider's metadata, refusing lists longer than
+//! [`MAX_CAPABILITIES`] before decoding them.
+
+use alloc::vec::Vec;
+
+use codec::{Compact, Decode, Encode, Input};
+use scale_info::TypeInfo;
+
+use crate::bounds::MaybeSerde;
+
+/// Most capabilities read from a provider's metadata.
+pub const MAX_CAPABILITIES: u32 = 64;
+
+/// Something a provider can run, or a task needs.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Capability {
+    /// Discriminant of the task, as passed to
+    /// [`supports_task`](MeshProvider::supports_task).
+    pub task_type: Vec<u8>,
+    /// Memory, in MiB, the provider has for the task or the task needs.
+    pub memory_mb: u64,
+    /// Hash of the input schema the task is run with.
+    pub input_schema_hash: [u8; 32],
+}
+
+impl Capability {
+    /// Whether a provider with this capability can meet `requirement`:
+    /// the same task and input schema, with at least as much memory.
+    pub fn satisfies(&self, requirement: &Capability) -> bool {
+        self.task_type == requirement.task_type
+            && self.input_schema_hash == requirement.input_schema_hash
+            && self.memory_mb >= requirement.memory_mb
+    }
+}
+
+/// Capabilities encoded as a list of [`Capability`], which
+/// [`MeshProvider::supports`] can read back from the metadata.
+pub trait CapabilityList: Encode + Decode {}
+
+impl CapabilityList for Vec<Capability> {}
+
+/// Decode a list of at most [`MAX_CAPABILITIES`] capabilities filling
+/// `bytes`.
+pub fn decode_capabilities(bytes: &[u8]) -> Result<Vec<Capability>, codec::Error> {
+    let mut input = bytes;
+    let Compact(len) = Compact::<u32>::decode(&mut input)?;
+    if len > MAX_CAPABILITIES {
+        return Err("too many capabilities".into());
+    }
+    let capabilities = (0..len)
+        .map(|_| Capability::decode(&mut input))
+        .collect::<Result<Vec<_>, _>>()?;
+    if input.remaining_len()? != Some(0) {
+        return Err("trailing bytes after the capabilities".into());
+    }
+    Ok(capabilities)
+}
+
+/// Something that runs tasks for the mesh.
+pub trait MeshProvider {
+    /// What the provider can run.  Providers are expected to list the
+    /// discriminants of the tasks they support here.
+    type Capabilities: Encode + Decode + MaybeSerde;
+
+    /// The provider's capabilities.
+    fn capabilities(&self) -> &Self::Capabilities;
+
+    /// Whether the provider can run the task identified by `task_id`,
+    /// one of the discriminants listed in its capabilities.  Providers
+    /// that do not override this support nothing.
+    fn supports_task(&self, task_id: &impl AsRef<[u8]>) -> bool {
+        let _ = task_id;
+        false
+    }
+
+    /// Whether one of the provider's capabilities satisfies
+    /// `requirement`.  Defaults to reading the capabilities from
+    /// [`metadata`](Self::metadata); metadata that does not decode
+    /// supports nothing.
+    fn supports(&self, requirement: &Capability) -> bool
+    where
+        Self::Capabilities: CapabilityList,
+    {
+        decode_capabilities(&self.metadata()).is_ok_and(|capabilities| {
+            capabilities
+                .iter()
+                .any(|capability| capability.satisfies(requirement))
+        })
+    }
+
+    /// Opaque metadata announced to peers.  Defaults to the SCALE
+    /// encoding of the capabilities.
+    fn metadata(&self) -> Vec<u8> {
+        self.capabilities().encode()
+    }
+}
@@ -0,0 +1,73 @@
+//! Per‑provider rate limiting.
+//!
+//! A scheduler that keeps sending work to the best provider will
+//! overload it.  [`Quota`] is a token bucket: each task assigned to a
+//! provider takes a token, and tokens come back at a fixed rate up to
+//! the bucket's capacity, so a provider can absorb a short burst but
+//! not a sustained stream above its rate.
+
+/// Milli‑tokens per token.  Tokens are tracked in thousandths so that
+/// refills are exact for times given in milliseconds.
+const MILLI: u64 = 1_000;
+
+/// Token bucket holding up to `capacity` tokens and refilling
+/// `refill_per_sec` of them every second.  The bucket starts full.
+///
+/// Times are milliseconds on any clock that never goes backwards.  A
+/// time earlier than one already seen refills nothing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Quota {
+    /// Most tokens the bucket holds.
+    pub capacity: u32,
+    /// Tokens added back every second.
+    pub refill_per_sec: u32,
+    /// Tokens available, in milli‑tokens.
+    available: u64,
+    /// Time of the last refill, unset until the quota is first used.
+    refilled_at: Option<u64>,
+}
+
+impl Quota {
+    /// A full bucket of `capacity` tokens refilling `refill_per_sec`
+    /// tokens a second.
+    pub const fn new(capacity: u32, refill_per_sec: u32) -> Self {
+        Self {
+            capacity,
+            refill_per_sec,
+            available: capacity as u64 * MILLI,
+            refilled_at: None,
+        }
+    }
+
+    /// Whether a token is available at `now`, without taking it.
+    pub fn has_token(&self, now: u64) -> bool {
+        self.available_at(now) >= MILLI
+    }
+
+    /// Take a token at `now`.  Returns `false`, and takes nothing, if
+    /// the bucket is empty.
+    pub fn try_acquire(&mut self, now: u64) -> bool {
+        self.available = self.available_at(now);
+        if self.refilled_at.is_none_or(|at| now > at) {
+            self.refilled_at = Some(now);
+        }
+        if self.available < MILLI {
+            return false;
+        }
+        self.available -= MILLI;
+        true
+    }
+
+    /// Whole tokens available at `now`.
+    pub fn tokens(&self, now: u64) -> u32 {
+        (self.available_at(now) / MILLI) as u32
+    }
+
+    fn available_at(&self, now: u64) -> u64 {
+        let elapsed = self.refilled_at.map_or(0, |at| now.saturating_sub(at));
+        let refill = elapsed.saturating_mul(u64::from(self.refill_per_sec));
+        self.available
+            .saturating_add(refill)
+            .min(u64::from(self.capacity) * MILLI)
+    }
+}
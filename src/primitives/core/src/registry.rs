@@ -0,0 +1,121 @@
+//! Registry of the providers known to a node or worker.
+//!
+//! Providers announce what they can run as [`CapabilityFlags`], one
+//! bit per kind of task.  A provider announcing itself again is a
+//! conflict with its earlier registration, and the caller decides
+//! through [`OnConflict`] how it is resolved, so that a stale
+//! announcement never silently drops capabilities.
+
+use alloc::collections::BTreeMap;
+
+use codec::{Decode, Encode, MaxEncodedLen};
+use scale_info::TypeInfo;
+
+/// Identifier of a provider, as in [`Provider::id`](crate::Provider::id).
+pub type ProviderId = u64;
+
+/// Kinds of task a provider can run, one bit each.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Encode, Decode, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CapabilityFlags(pub u64);
+
+impl CapabilityFlags {
+    /// Whether every flag of `other` is set.
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// The flags set in either.
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
+/// A provider as registered.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Encode, Decode, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RegisteredProvider {
+    pub id: ProviderId,
+    pub capabilities: CapabilityFlags,
+}
+
+/// What to do when a provider is registered under an id that already
+/// is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OnConflict {
+    /// Keep the existing registration and fail.
+    Reject,
+    /// Overwrite the existing registration.
+    Replace,
+    /// Keep the existing registration, adding the new capabilities to
+    /// it.
+    MergeCapabilities,
+}
+
+/// Why a registration failed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RegistryError {
+    /// A provider is already registered under this id.
+    AlreadyRegistered(ProviderId),
+}
+
+/// Providers kept in memory, ordered by id.
+#[derive(Clone, Debug, Default)]
+pub struct InMemoryProviderRegistry {
+    providers: BTreeMap<ProviderId, RegisteredProvider>,
+}
+
+impl InMemoryProviderRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register `provider`, resolving a clash with an existing
+    /// registration under the same id by `policy`.
+    pub fn register(
+        &mut self,
+        provider: RegisteredProvider,
+        policy: OnConflict,
+    ) -> Result<(), RegistryError> {
+        match (self.providers.get_mut(&provider.id), policy) {
+            (None, _) | (Some(_), OnConflict::Replace) => {
+                self.providers.insert(provider.id, provider);
+            }
+            (Some(_), OnConflict::Reject) => {
+                return Err(RegistryError::AlreadyRegistered(provider.id));
+            }
+            (Some(existing), OnConflict::MergeCapabilities) => {
+                existing.capabilities = existing.capabilities.union(provider.capabilities);
+            }
+        }
+        Ok(())
+    }
+
+    /// The registration of provider `id`.
+    pub fn get(&self, id: ProviderId) -> Option<&RegisteredProvider> {
+        self.providers.get(&id)
+    }
+
+    /// Forget provider `id`, returning its registration.
+    pub fn remove(&mut self, id: ProviderId) -> Option<RegisteredProvider> {
+        self.providers.remove(&id)
+    }
+
+    /// Registered providers with every flag of `capabilities`, by id.
+    pub fn providers_with(
+        &self,
+        capabilities: CapabilityFlags,
+    ) -> impl Iterator<Item = &RegisteredProvider> {
+        self.providers
+            .values()
+            .filter(move |provider| provider.capabilities.contains(capabilities))
+    }
+
+    pub fn len(&self) -> usize {
+        self.providers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.providers.is_empty()
+    }
+}
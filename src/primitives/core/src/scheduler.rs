@@ -0,0 +1,144 @@
+//! Task scheduling policies for off‑chain workers.
+//!
+//! A worker holding several pending tasks uses a [`TaskScheduler`] to
+//! decide which one to execute next.  The policies here only inspect
+//! the slice they are given and never allocate, so they can be used in
+//! `no_std` contexts.
+//!
+//! A node handing tasks out to several providers uses a
+//! [`ProviderScheduler`] instead to decide who gets the next task.
+//! Each provider is rate limited by its [`Quota`], so a provider at its
+//! quota is passed over even if the policy prefers it.
+
+use core::cmp::{Ordering, Reverse};
+
+use crate::quota::Quota;
+
+/// A task waiting to be executed by an off‑chain worker.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PendingTask {
+    /// Identifier of the task.
+    pub id: u64,
+    /// Reward paid for completing the task.
+    pub reward: u128,
+    /// Block number by which the task must be completed.
+    pub deadline: u64,
+}
+
+/// Policy for picking the next task to run from a set of pending tasks.
+///
+/// `pending` is expected to be in arrival order.  Implementations
+/// return `None` only when `pending` is empty.
+pub trait TaskScheduler {
+    /// Select the task that should be executed next.
+    fn next<'a>(&self, pending: &'a [PendingTask]) -> Option<&'a PendingTask>;
+}
+
+/// Runs tasks in the order they arrived.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FifoScheduler;
+
+impl TaskScheduler for FifoScheduler {
+    fn next<'a>(&self, pending: &'a [PendingTask]) -> Option<&'a PendingTask> {
+        pending.first()
+    }
+}
+
+/// Runs the task with the highest reward first.  Ties go to the task
+/// that arrived first.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct HighestRewardScheduler;
+
+impl TaskScheduler for HighestRewardScheduler {
+    fn next<'a>(&self, pending: &'a [PendingTask]) -> Option<&'a PendingTask> {
+        pending.iter().min_by_key(|task| Reverse(task.reward))
+    }
+}
+
+/// Runs the task with the earliest deadline first.  Ties go to the task
+/// that arrived first.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct EarliestDeadlineScheduler;
+
+impl TaskScheduler for EarliestDeadlineScheduler {
+    fn next<'a>(&self, pending: &'a [PendingTask]) -> Option<&'a PendingTask> {
+        pending.iter().min_by_key(|task| task.deadline)
+    }
+}
+
+/// A provider tasks can be assigned to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Provider {
+    /// Identifier of the provider.
+    pub id: u64,
+    /// Tasks currently assigned to the provider.
+    pub load: u32,
+    /// Stake backing the provider.
+    pub stake: u128,
+    /// Rate at which the provider accepts new tasks.
+    pub quota: Quota,
+}
+
+/// Policy for picking the provider the next task is assigned to.
+///
+/// Providers without a token in their [`Quota`] at `now` are skipped,
+/// and the chosen provider has a token taken.  Implementations return
+/// `None` when no provider is eligible.  Updating `load` is left to
+/// the caller.
+pub trait ProviderScheduler {
+    /// Select the provider the next task is assigned to.
+    fn assign<'a>(&self, providers: &'a mut [Provider], now: u64) -> Option<&'a mut Provider>;
+}
+
+/// Assign to the provider with the smallest `load`.  Ties go to the
+/// first provider in the slice.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LeastLoadedScheduler;
+
+impl ProviderScheduler for LeastLoadedScheduler {
+    fn assign<'a>(&self, providers: &'a mut [Provider], now: u64) -> Option<&'a mut Provider> {
+        assign_by(providers, now, |_| true, |a, b| a.load.cmp(&b.load))
+    }
+}
+
+/// Assign to the provider with the smallest load relative to its
+/// stake, so load spreads in proportion to stake.  Providers without
+/// stake are never chosen.  Ties go to the first provider in the
+/// slice.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StakeWeightedScheduler;
+
+impl ProviderScheduler for StakeWeightedScheduler {
+    fn assign<'a>(&self, providers: &'a mut [Provider], now: u64) -> Option<&'a mut Provider> {
+        assign_by(
+            providers,
+            now,
+            |provider| provider.stake > 0,
+            // a.load / a.stake against b.load / b.stake.
+            |a, b| {
+                u128::from(a.load)
+                    .saturating_mul(b.stake)
+                    .cmp(&u128::from(b.load).saturating_mul(a.stake))
+            },
+        )
+    }
+}
+
+/// Take a token from the first provider ordered lowest by `compare`
+/// among the `eligible` ones with a token at `now`.
+fn assign_by(
+    providers: &mut [Provider],
+    now: u64,
+    eligible: impl Fn(&Provider) -> bool,
+    compare: impl Fn(&Provider, &Provider) -> Ordering,
+) -> Option<&mut Provider> {
+    let index = providers
+        .iter()
+        .enumerate()
+        .filter(|(_, provider)| eligible(provider) && provider.quota.has_token(now))
+        .min_by(|(_, a), (_, b)| compare(a, b))
+        .map(|(index, _)| index)?;
+    let provider = &mut providers[index];
+    provider.quota.try_acquire(now);
+    Some(provider)
+}
@@ -0,0 +1,193 @@
+//! Tasks served by subnets.
+//!
+//! A [`NeuralTask`] names the input a miner receives and the output it
+//! returns.  Both travel between nodes and the runtime as SCALE, so
+//! they only need to be codable.  A task may also reject inputs it
+//! cannot work on through [`NeuralTask::validate_input`], so they are
+//! turned away before being scheduled.
+//!
+//! How a miner's work ended is a [`TaskOutcome`]: an output, an error
+//! of the task's own [`NeuralTask::Error`] type, or no answer in time.
+//! Telling these apart lets a miner returning a wrong answer be scored
+//! differently from one that crashed.
+//!
+//! Identical inputs can be recognised across the mesh by their
+//! [`NeuralTask::derive_task_id`], a hash of their encoding.  Tasks
+//! implementing [`ContentAddressedTask`] are identified by it alone.
+//!
+//! Decoding failures are reported as a [`NeuralTaskError`] naming the
+//! task and whether its input or its output was being decoded, since a
+//! bare codec error says neither.
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use codec::{Decode, DecodeAll, Encode};
+use scale_info::TypeInfo;
+use sp_core_hashing::blake2_256;
+
+use crate::bounds::MaybeSerde;
+use crate::frame::{self, FrameError};
+
+/// Identifier of a task, as assigned by the task queue.
+pub type TaskId = u64;
+
+/// Identifier of a task derived from its input, see
+/// [`NeuralTask::derive_task_id`].
+pub type ContentTaskId = [u8; 32];
+
+/// Why a task input was refused.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TaskError {
+    /// The bytes are not a valid encoding of the task input.
+    InvalidEncoding,
+    /// The input has nothing to work on.
+    EmptyInput,
+    /// The input is larger than the task accepts.
+    SizeExceeded,
+    /// A task‑specific reason, numbered by the task.
+    Custom(u16),
+}
+
+/// Why the bytes of a task could not be read.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NeuralTaskError {
+    /// The bytes of task `task_id` are not a valid encoding.  `what` is
+    /// `"input"`, `"output"` or `"outcome"`.
+    Decode {
+        task_id: TaskId,
+        what: &'static str,
+        error: codec::Error,
+    },
+}
+
+impl fmt::Display for NeuralTaskError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Decode {
+                task_id,
+                what,
+                error,
+            } => write!(f, "failed to decode the {what} of task {task_id}: {error}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for NeuralTaskError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Decode { error, .. } => Some(error),
+        }
+    }
+}
+
+/// Decode exactly one `V` from `bytes`, the `what` of task `task_id`.
+fn decode<V: Decode>(
+    task_id: TaskId,
+    what: &'static str,
+    bytes: &[u8],
+) -> Result<V, NeuralTaskError> {
+    V::decode_all(&mut &bytes[..]).map_err(|error| NeuralTaskError::Decode {
+        task_id,
+        what,
+        error,
+    })
+}
+
+/// How a miner's work on a task ended.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TaskOutcome<O, E> {
+    /// The miner returned an output.
+    Completed(O),
+    /// The miner reported that the task failed.
+    Failed(E),
+    /// The miner did not answer in time.
+    TimedOut,
+}
+
+/// A kind of task a subnet serves.
+pub trait NeuralTask {
+    /// What a miner is asked to work on.
+    type Input: Encode + Decode + MaybeSerde;
+    /// What a miner hands back.
+    type Output: Encode + Decode + MaybeSerde;
+    /// Why a miner could not produce an output.
+    type Error: Encode + Decode + MaybeSerde;
+
+    /// Check that `input` can be worked on.  Accepts everything unless
+    /// overridden.
+    fn validate_input(input: &Self::Input) -> Result<(), TaskError> {
+        let _ = input;
+        Ok(())
+    }
+
+    /// Decode the input of task `task_id` from `bytes`, which must hold
+    /// exactly one.
+    fn decode_input(task_id: TaskId, bytes: &[u8]) -> Result<Self::Input, NeuralTaskError> {
+        decode(task_id, "input", bytes)
+    }
+
+    /// Decode the output of task `task_id` from `bytes`, which must hold
+    /// exactly one.
+    fn decode_output(task_id: TaskId, bytes: &[u8]) -> Result<Self::Output, NeuralTaskError> {
+        decode(task_id, "output", bytes)
+    }
+
+    /// The Blake2‑256 hash of the encoded `input`, the same for every
+    /// node given the same input.
+    fn derive_task_id(input: &Self::Input) -> ContentTaskId {
+        blake2_256(&input.encode())
+    }
+
+    /// Encode how the work on a task ended.
+    fn encode_outcome(outcome: &TaskOutcome<Self::Output, Self::Error>) -> Vec<u8> {
+        outcome.encode()
+    }
+
+    /// Decode how the work on task `task_id` ended from `bytes`, which
+    /// must hold exactly one outcome.
+    fn decode_outcome(
+        task_id: TaskId,
+        bytes: &[u8],
+    ) -> Result<TaskOutcome<Self::Output, Self::Error>, NeuralTaskError> {
+        decode(task_id, "outcome", bytes)
+    }
+
+    /// Decode an input from `bytes`, which must hold exactly one, then
+    /// check it with [`validate_input`](Self::validate_input).
+    fn decode_and_validate_input(bytes: &[u8]) -> Result<Self::Input, TaskError> {
+        let input =
+            Self::Input::decode_all(&mut &bytes[..]).map_err(|_| TaskError::InvalidEncoding)?;
+        Self::validate_input(&input)?;
+        Ok(input)
+    }
+
+    /// Encode `input` as a frame, see [`frame`](crate::frame).
+    fn encode_input_framed(input: &Self::Input) -> Vec<u8> {
+        frame::encode_frame(&input.encode())
+    }
+
+    /// Decode an input from `bytes`, which must hold exactly one frame.
+    fn decode_input_framed(bytes: &[u8]) -> Result<Self::Input, FrameError> {
+        let (payload, rest) = frame::split_frame(bytes)?;
+        if !rest.is_empty() {
+            return Err(FrameError::TrailingBytes);
+        }
+        Self::Input::decode_all(&mut &payload[..]).map_err(|_| FrameError::InvalidPayload)
+    }
+}
+
+/// A task identified by the content of its input, so that the same
+/// input submitted twice anywhere on the mesh is recognised as one
+/// task.
+pub trait ContentAddressedTask: NeuralTask {
+    /// The id of the task working on `input`.  Always
+    /// [`derive_task_id`](NeuralTask::derive_task_id), so that every
+    /// node agrees on it.
+    fn task_id(input: &Self::Input) -> ContentTaskId {
+        Self::derive_task_id(input)
+    }
+}
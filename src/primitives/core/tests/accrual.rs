@@ -0,0 +1,44 @@
+use sp_neuro_core::{Accrual, AccrualError};
+
+#[test]
+fn rewards_accumulate() {
+    let mut accrual = Accrual::new();
+    assert_eq!(accrual.checked_add_reward(100), Ok(100));
+    assert_eq!(accrual.checked_add_reward(250), Ok(350));
+    assert_eq!(accrual.accrued(), 350);
+    assert_eq!(accrual.headroom(), u128::MAX - 350);
+    assert!(!accrual.is_saturated());
+}
+
+#[test]
+fn drain_returns_total_and_resets_to_zero() {
+    let mut accrual = Accrual::new();
+    accrual.checked_add_reward(42).unwrap();
+    assert_eq!(accrual.drain(), 42);
+    assert_eq!(accrual.accrued(), 0);
+    assert_eq!(accrual.drain(), 0);
+}
+
+#[test]
+fn overflowing_add_is_rejected_without_wrapping() {
+    let mut accrual = Accrual::new();
+    accrual.checked_add_reward(u128::MAX - 1).unwrap();
+
+    assert_eq!(
+        accrual.checked_add_reward(2),
+        Err(AccrualError::Overflow {
+            accrued: u128::MAX - 1,
+            reward: 2,
+        })
+    );
+    assert_eq!(accrual.accrued(), u128::MAX - 1);
+    assert_eq!(accrual.rejected_rewards(), 1);
+
+    accrual.checked_add_reward(1).unwrap();
+    assert!(accrual.is_saturated());
+    assert!(accrual.checked_add_reward(1).is_err());
+    assert_eq!(accrual.rejected_rewards(), 2);
+
+    assert_eq!(accrual.drain(), u128::MAX);
+    assert_eq!(accrual.rejected_rewards(), 2);
+}
@@ -0,0 +1,80 @@
+use codec::{Compact, Decode, Encode};
+use sp_neuro_core::{encode_batch, CoreError, NeuralTask, TaskStreamDecoder};
+
+#[derive(Clone, Debug, PartialEq, Encode, Decode)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Prompt {
+    subnet_id: u32,
+    text: Vec<u8>,
+}
+
+struct CodeGen;
+
+impl NeuralTask for CodeGen {
+    type Input = Prompt;
+    type Output = Vec<u8>;
+    type Error = ();
+}
+
+fn prompts() -> Vec<Prompt> {
+    (0..3)
+        .map(|i| Prompt {
+            subnet_id: i,
+            text: vec![b'a' + i as u8; 10 * (i as usize + 1)],
+        })
+        .collect()
+}
+
+#[test]
+fn batch_is_decoded_one_input_at_a_time() {
+    let batch = encode_batch::<CodeGen>(&prompts());
+    let mut decoder = TaskStreamDecoder::<CodeGen, _>::new(&batch[..]);
+    assert_eq!(decoder.remaining(), None);
+
+    for (index, expected) in prompts().into_iter().enumerate() {
+        assert_eq!(decoder.next(), Some(Ok(expected)));
+        assert_eq!(decoder.remaining(), Some(2 - index as u32));
+    }
+    assert_eq!(decoder.next(), None);
+}
+
+#[test]
+fn truncated_batch_fails_only_the_cut_input() {
+    let mut batch = encode_batch::<CodeGen>(&prompts());
+    batch.truncate(batch.len() - 5);
+
+    let items: Vec<_> = TaskStreamDecoder::<CodeGen, _>::new(&batch[..]).collect();
+    assert_eq!(
+        items,
+        vec![
+            Ok(prompts()[0].clone()),
+            Ok(prompts()[1].clone()),
+            Err(CoreError::Truncated { index: 2 }),
+        ]
+    );
+}
+
+#[test]
+fn invalid_input_is_skipped() {
+    let mut batch = Compact(3u32).encode();
+    prompts()[0].encode().encode_to(&mut batch);
+    vec![0xffu8; 3].encode_to(&mut batch);
+    prompts()[2].encode().encode_to(&mut batch);
+
+    let items: Vec<_> = TaskStreamDecoder::<CodeGen, _>::new(&batch[..]).collect();
+    assert_eq!(
+        items,
+        vec![
+            Ok(prompts()[0].clone()),
+            Err(CoreError::InvalidItem { index: 1 }),
+            Ok(prompts()[2].clone()),
+        ]
+    );
+}
+
+#[test]
+fn missing_header_is_reported() {
+    let mut decoder = TaskStreamDecoder::<CodeGen, _>::new(&[][..]);
+    assert_eq!(decoder.next(), Some(Err(CoreError::InvalidHeader)));
+    assert_eq!(decoder.next(), None);
+}
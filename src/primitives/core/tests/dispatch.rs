@@ -0,0 +1,74 @@
+#![cfg(feature = "std")]
+
+use std::collections::HashSet;
+use std::thread;
+
+use sp_neuro_core::{EncodedTask, SubmitError, WorkStealingDispatcher};
+
+fn task(id: u64) -> EncodedTask {
+    EncodedTask {
+        id,
+        payload: id.to_le_bytes().to_vec(),
+    }
+}
+
+#[test]
+fn two_workers_process_every_task_exactly_once() {
+    let dispatcher = WorkStealingDispatcher::new(2, 1_000);
+    let workers: Vec<_> = (0..2)
+        .map(|_| {
+            let handle = dispatcher.worker_handle().unwrap();
+            thread::spawn(move || handle.map(|task| task.id).collect::<Vec<_>>())
+        })
+        .collect();
+    assert!(dispatcher.worker_handle().is_none());
+
+    for id in 0..1_000 {
+        dispatcher.submit(task(id)).unwrap();
+    }
+    dispatcher.shutdown();
+
+    let processed: Vec<u64> = workers
+        .into_iter()
+        .flat_map(|worker| worker.join().unwrap())
+        .collect();
+    assert_eq!(processed.len(), 1_000);
+    let unique: HashSet<u64> = processed.into_iter().collect();
+    assert_eq!(unique, (0..1_000).collect());
+    assert!(dispatcher.is_empty());
+}
+
+#[test]
+fn idle_worker_steals_from_the_other_deque() {
+    let dispatcher = WorkStealingDispatcher::new(2, 10);
+    let first = dispatcher.worker_handle().unwrap();
+    let second = dispatcher.worker_handle().unwrap();
+    for id in 0..4 {
+        dispatcher.submit(task(id)).unwrap();
+    }
+
+    // Tasks alternate between the deques.  The first worker takes its
+    // own from the front, then steals the second's from the back.
+    let taken: Vec<u64> = (0..4).map(|_| first.try_next().unwrap().id).collect();
+    assert_eq!(taken, vec![0, 2, 3, 1]);
+    assert_eq!(second.try_next(), None);
+}
+
+#[test]
+fn submissions_are_bounded_and_end_at_shutdown() {
+    let dispatcher = WorkStealingDispatcher::new(1, 2);
+    dispatcher.submit(task(0)).unwrap();
+    dispatcher.submit(task(1)).unwrap();
+    assert_eq!(dispatcher.submit(task(2)), Err(SubmitError::Full(task(2))));
+    assert_eq!(dispatcher.len(), 2);
+
+    dispatcher.shutdown();
+    assert_eq!(
+        dispatcher.submit(task(3)),
+        Err(SubmitError::ShutDown(task(3)))
+    );
+
+    // Tasks queued before the shutdown are still handed out.
+    let handle = dispatcher.worker_handle().unwrap();
+    assert_eq!(handle.map(|task| task.id).collect::<Vec<_>>(), vec![0, 1]);
+}
@@ -0,0 +1,78 @@
+use sp_neuro_core::{distribute, emission_share, RewardShares, RewardSplit, Rounding};
+
+const MODES: [Rounding; 3] = [Rounding::Floor, Rounding::Ceil, Rounding::HalfEven];
+
+#[test]
+fn single_share_is_rounded_per_mode() {
+    // 10 * 1 / 4 = 2.5
+    assert_eq!(emission_share(10, 1, 4, Rounding::Floor), 2);
+    assert_eq!(emission_share(10, 1, 4, Rounding::Ceil), 3);
+    assert_eq!(emission_share(10, 1, 4, Rounding::HalfEven), 2);
+    // 14 * 1 / 4 = 3.5
+    assert_eq!(emission_share(14, 1, 4, Rounding::HalfEven), 4);
+    assert_eq!(
+        emission_share(u128::MAX, u64::MAX, u64::MAX, Rounding::Ceil),
+        u128::MAX
+    );
+    assert_eq!(emission_share(100, 1, 0, Rounding::Ceil), 0);
+}
+
+#[test]
+fn floor_is_the_default() {
+    assert_eq!(Rounding::default(), Rounding::Floor);
+}
+
+#[test]
+fn modes_differ_but_preserve_the_total() {
+    // Exact shares 4.5 and 1.5.
+    assert_eq!(distribute(6, &[3, 1], Rounding::Floor), vec![5, 1]);
+    assert_eq!(distribute(6, &[3, 1], Rounding::HalfEven), vec![4, 2]);
+    // Exact shares 2.5, 2.5 and 5.
+    assert_eq!(distribute(10, &[1, 1, 2], Rounding::Floor), vec![2, 2, 6]);
+    assert_eq!(distribute(10, &[1, 1, 2], Rounding::Ceil), vec![2, 3, 5]);
+
+    for total in [0, 1, 7, 100, 12_345, u128::MAX] {
+        for weights in [&[1, 1, 1][..], &[3, 1], &[0, 5, 2, 9], &[u32::MAX, 1]] {
+            for rounding in MODES {
+                let shares = distribute(total, weights, rounding);
+                assert_eq!(
+                    shares.iter().sum::<u128>(),
+                    total,
+                    "{weights:?} {rounding:?}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn zero_weights_distribute_nothing() {
+    for rounding in MODES {
+        assert_eq!(distribute(100, &[0, 0], rounding), vec![0, 0]);
+    }
+}
+
+#[test]
+fn reward_split_uses_rounding() {
+    let split = RewardSplit {
+        miners: 3,
+        validators: 1,
+        owner: 0,
+    };
+    assert_eq!(
+        split.split(6, Rounding::Floor),
+        RewardShares {
+            miners: 5,
+            validators: 1,
+            owner: 0,
+        }
+    );
+    assert_eq!(
+        split.split(6, Rounding::HalfEven),
+        RewardShares {
+            miners: 4,
+            validators: 2,
+            owner: 0,
+        }
+    );
+}
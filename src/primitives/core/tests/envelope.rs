@@ -0,0 +1,87 @@
+use codec::{Decode, Encode};
+use sp_neuro_core::{EnvelopeError, EnvelopedEvent, EventEnvelope, EventSource};
+
+#[derive(Clone, Debug, PartialEq, Encode, Decode)]
+struct TaskCompleted {
+    task_id: u64,
+    miner: [u8; 32],
+}
+
+impl EnvelopedEvent for TaskCompleted {
+    const SOURCE: EventSource = EventSource::Node;
+    const KIND: u16 = 1;
+}
+
+#[derive(Clone, Debug, PartialEq, Encode, Decode)]
+enum SubnetEvent {
+    Created { subnet_id: u32 },
+    Retired { subnet_id: u32 },
+}
+
+impl EnvelopedEvent for SubnetEvent {
+    const SOURCE: EventSource = EventSource::Runtime;
+    const KIND: u16 = 1;
+}
+
+/// Encode an envelope as it would be persisted and decode it again.
+fn persist(envelope: &EventEnvelope) -> EventEnvelope {
+    EventEnvelope::decode(&mut &envelope.encode()[..]).unwrap()
+}
+
+#[test]
+fn node_event_round_trips() {
+    let event = TaskCompleted {
+        task_id: 42,
+        miner: [7; 32],
+    };
+    let envelope = persist(&event.clone().into_envelope(1_700_000_000_000));
+
+    assert_eq!(envelope.source, EventSource::Node);
+    assert_eq!(envelope.kind, 1);
+    assert_eq!(envelope.timestamp, 1_700_000_000_000);
+    assert_eq!(TaskCompleted::from_envelope(&envelope), Ok(event));
+}
+
+#[test]
+fn runtime_event_round_trips() {
+    for event in [
+        SubnetEvent::Created { subnet_id: 3 },
+        SubnetEvent::Retired { subnet_id: 3 },
+    ] {
+        let envelope = persist(&event.clone().into_envelope(5));
+        assert_eq!(SubnetEvent::from_envelope(&envelope), Ok(event));
+    }
+}
+
+#[test]
+fn mismatched_envelopes_are_rejected() {
+    let envelope = SubnetEvent::Created { subnet_id: 1 }.into_envelope(0);
+    assert_eq!(
+        TaskCompleted::from_envelope(&envelope),
+        Err(EnvelopeError::WrongSource {
+            expected: EventSource::Node,
+            found: EventSource::Runtime,
+        })
+    );
+
+    let mut envelope = TaskCompleted {
+        task_id: 1,
+        miner: [0; 32],
+    }
+    .into_envelope(0);
+    envelope.kind = 2;
+    assert_eq!(
+        TaskCompleted::from_envelope(&envelope),
+        Err(EnvelopeError::WrongKind {
+            expected: 1,
+            found: 2
+        })
+    );
+
+    envelope.kind = 1;
+    envelope.payload.push(0);
+    assert_eq!(
+        TaskCompleted::from_envelope(&envelope),
+        Err(EnvelopeError::InvalidPayload)
+    );
+}
@@ -0,0 +1,87 @@
+use codec::{Decode, Encode};
+use sp_neuro_core::frame::{split_frame, FRAME_HEADER_LEN, FRAME_VERSION, MAX_FRAME_LEN};
+use sp_neuro_core::{decode_frames, encode_frame, FrameError, NeuralTask};
+
+struct Echo;
+
+impl NeuralTask for Echo {
+    type Input = Vec<u8>;
+    type Output = Vec<u8>;
+    type Error = ();
+}
+
+#[test]
+fn frames_carry_length_and_version() {
+    let frame = encode_frame(b"abc");
+    assert_eq!(frame, [3, 0, 0, 0, FRAME_VERSION, b'a', b'b', b'c']);
+    assert_eq!(split_frame(&frame), Ok((&b"abc"[..], &[][..])));
+}
+
+#[test]
+fn inputs_round_trip_through_frames() {
+    let input = b"hello".to_vec();
+    let frame = Echo::encode_input_framed(&input);
+    assert_eq!(frame.len(), FRAME_HEADER_LEN + input.encode().len());
+    assert_eq!(Echo::decode_input_framed(&frame), Ok(input));
+
+    let mut padded = frame.clone();
+    padded.push(0);
+    assert_eq!(
+        Echo::decode_input_framed(&padded),
+        Err(FrameError::TrailingBytes)
+    );
+    // Claims four bytes but carries one.
+    assert_eq!(
+        Echo::decode_input_framed(&encode_frame(&[16, 1])),
+        Err(FrameError::InvalidPayload)
+    );
+}
+
+#[test]
+fn a_buffer_is_split_into_its_frames() {
+    let buffer = [
+        Echo::encode_input_framed(&b"one".to_vec()),
+        Echo::encode_input_framed(&Vec::new()),
+        Echo::encode_input_framed(&b"three".to_vec()),
+    ]
+    .concat();
+
+    let inputs: Vec<_> = decode_frames(&buffer)
+        .map(|payload| Vec::<u8>::decode(&mut payload.unwrap()).unwrap())
+        .collect();
+    assert_eq!(inputs, vec![b"one".to_vec(), Vec::new(), b"three".to_vec()]);
+    assert_eq!(decode_frames(&[]).count(), 0);
+}
+
+#[test]
+fn truncated_frames_end_the_stream() {
+    let mut buffer = [encode_frame(b"whole"), encode_frame(b"cut short")].concat();
+    buffer.truncate(buffer.len() - 1);
+
+    let mut frames = decode_frames(&buffer);
+    assert_eq!(frames.next(), Some(Ok(&b"whole"[..])));
+    assert_eq!(frames.next(), Some(Err(FrameError::Truncated)));
+    assert_eq!(frames.next(), None);
+
+    // A header cut short is truncated too.
+    assert_eq!(split_frame(&[3, 0]), Err(FrameError::Truncated));
+}
+
+#[test]
+fn absurd_lengths_and_unknown_versions_are_rejected() {
+    let mut huge = (MAX_FRAME_LEN + 1).to_le_bytes().to_vec();
+    huge.push(FRAME_VERSION);
+    assert_eq!(
+        split_frame(&huge),
+        Err(FrameError::TooLarge {
+            len: MAX_FRAME_LEN + 1
+        })
+    );
+
+    let mut future = encode_frame(b"abc");
+    future[4] = FRAME_VERSION + 1;
+    assert_eq!(
+        split_frame(&future),
+        Err(FrameError::UnsupportedVersion(FRAME_VERSION + 1))
+    );
+}
@@ -0,0 +1,52 @@
+use sp_neuro_core::json::{validate, JsonError, JsonKind, MAX_DEPTH};
+
+#[test]
+fn accepts_well_formed_documents() {
+    assert_eq!(
+        validate(br#" {"type":"object","properties":{"a":{"type":"string"}},"required":["a"]} "#),
+        Ok(JsonKind::Object)
+    );
+    assert_eq!(
+        validate("[1, -2.5e3, true, null, \"é\\n\"]".as_bytes()),
+        Ok(JsonKind::Array)
+    );
+    assert_eq!(validate(b"0"), Ok(JsonKind::Number));
+    assert_eq!(validate(b"{}"), Ok(JsonKind::Object));
+}
+
+#[test]
+fn rejects_malformed_documents() {
+    for doc in [
+        &b""[..],
+        b"{",
+        br#"{"a":1,}"#,
+        br#"{"a" 1}"#,
+        br#"{a:1}"#,
+        b"[1 2]",
+        b"01",
+        b"1.",
+        b"tru",
+        br#""unterminated"#,
+        br#""bad \x escape""#,
+        b"{} {}",
+    ] {
+        assert!(
+            matches!(validate(doc), Err(JsonError::Syntax(_))),
+            "{doc:?}"
+        );
+    }
+}
+
+#[test]
+fn rejects_invalid_utf8() {
+    assert_eq!(validate(b"\"\xff\""), Err(JsonError::InvalidUtf8));
+}
+
+#[test]
+fn rejects_excessive_nesting() {
+    let deep = [vec![b'['; MAX_DEPTH + 1], vec![b']'; MAX_DEPTH + 1]].concat();
+    assert_eq!(validate(&deep), Err(JsonError::TooDeep));
+
+    let ok = [vec![b'['; MAX_DEPTH], vec![b']'; MAX_DEPTH]].concat();
+    assert_eq!(validate(&ok), Ok(JsonKind::Array));
+}
@@ -0,0 +1,121 @@
+use codec::{Compact, Decode, Encode};
+use sp_neuro_core::{decode_capabilities, Capability, MeshProvider, MAX_CAPABILITIES};
+
+#[derive(Clone, Debug, PartialEq, Encode, Decode)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Capabilities {
+    tasks: Vec<Vec<u8>>,
+}
+
+/// Runs the tasks listed in its capabilities.
+struct Miner {
+    capabilities: Capabilities,
+}
+
+impl MeshProvider for Miner {
+    type Capabilities = Capabilities;
+
+    fn capabilities(&self) -> &Capabilities {
+        &self.capabilities
+    }
+
+    fn supports_task(&self, task_id: &impl AsRef<[u8]>) -> bool {
+        self.capabilities
+            .tasks
+            .iter()
+            .any(|task| task[..] == *task_id.as_ref())
+    }
+}
+
+/// Leaves `supports_task` to the default.
+struct Silent;
+
+impl MeshProvider for Silent {
+    type Capabilities = ();
+
+    fn capabilities(&self) -> &() {
+        &()
+    }
+}
+
+fn miner() -> Miner {
+    Miner {
+        capabilities: Capabilities {
+            tasks: vec![b"codegen".to_vec(), b"summarize".to_vec()],
+        },
+    }
+}
+
+#[test]
+fn provider_supports_the_tasks_it_lists() {
+    let miner = miner();
+    assert!(miner.supports_task(b"codegen"));
+    assert!(miner.supports_task(&"summarize"));
+    assert!(!miner.supports_task(b"translate"));
+}
+
+#[test]
+fn providers_support_nothing_by_default() {
+    assert!(!Silent.supports_task(b"codegen"));
+    assert!(Silent.metadata().is_empty());
+}
+
+#[test]
+fn metadata_defaults_to_the_encoded_capabilities() {
+    let miner = miner();
+    assert_eq!(
+        Capabilities::decode(&mut &miner.metadata()[..]).unwrap(),
+        miner.capabilities
+    );
+}
+
+/// Announces a list of capabilities.
+struct Worker {
+    capabilities: Vec<Capability>,
+}
+
+impl MeshProvider for Worker {
+    type Capabilities = Vec<Capability>;
+
+    fn capabilities(&self) -> &Vec<Capability> {
+        &self.capabilities
+    }
+}
+
+fn capability(task_type: &[u8], memory_mb: u64, schema: u8) -> Capability {
+    Capability {
+        task_type: task_type.to_vec(),
+        memory_mb,
+        input_schema_hash: [schema; 32],
+    }
+}
+
+#[test]
+fn providers_support_requirements_their_capabilities_meet() {
+    let worker = Worker {
+        capabilities: vec![
+            capability(b"codegen", 8192, 1),
+            capability(b"summarize", 2048, 2),
+        ],
+    };
+    assert!(worker.supports(&capability(b"codegen", 4096, 1)));
+    assert!(worker.supports(&capability(b"summarize", 2048, 2)));
+    // Too little memory, another schema, another task.
+    assert!(!worker.supports(&capability(b"summarize", 4096, 2)));
+    assert!(!worker.supports(&capability(b"codegen", 4096, 2)));
+    assert!(!worker.supports(&capability(b"translate", 0, 1)));
+}
+
+#[test]
+fn capability_lists_are_bounded() {
+    let list = vec![capability(b"codegen", 1, 1); MAX_CAPABILITIES as usize];
+    assert_eq!(decode_capabilities(&list.encode()), Ok(list));
+
+    // The length is refused before any capability is decoded.
+    let too_long = Compact(MAX_CAPABILITIES + 1).encode();
+    assert!(decode_capabilities(&too_long).is_err());
+
+    let mut trailing = vec![capability(b"codegen", 1, 1)].encode();
+    trailing.push(0);
+    assert!(decode_capabilities(&trailing).is_err());
+}
@@ -0,0 +1,39 @@
+use sp_neuro_core::Quota;
+
+#[test]
+fn acquiring_consumes_a_token() {
+    let mut quota = Quota::new(2, 1);
+    assert_eq!(quota.tokens(0), 2);
+
+    assert!(quota.try_acquire(0));
+    assert_eq!(quota.tokens(0), 1);
+    assert!(quota.try_acquire(0));
+    assert_eq!(quota.tokens(0), 0);
+    assert!(!quota.has_token(0));
+    assert!(!quota.try_acquire(0));
+}
+
+#[test]
+fn tokens_refill_over_time_up_to_capacity() {
+    let mut quota = Quota::new(2, 4);
+    assert!(quota.try_acquire(1_000));
+    assert!(quota.try_acquire(1_000));
+
+    // Four tokens a second: one every 250 ms.
+    assert!(!quota.has_token(1_249));
+    assert!(quota.has_token(1_250));
+    assert!(quota.try_acquire(1_250));
+    assert!(!quota.try_acquire(1_300));
+
+    // Partial refills carry over.
+    assert!(quota.try_acquire(1_500));
+    assert_eq!(quota.tokens(60_000), 2);
+}
+
+#[test]
+fn earlier_time_refills_nothing() {
+    let mut quota = Quota::new(1, 1);
+    assert!(quota.try_acquire(5_000));
+    assert!(!quota.try_acquire(4_000));
+    assert!(quota.try_acquire(6_000));
+}
@@ -0,0 +1,67 @@
+use sp_neuro_core::{
+    CapabilityFlags, InMemoryProviderRegistry, OnConflict, RegisteredProvider, RegistryError,
+};
+
+const CODEGEN: CapabilityFlags = CapabilityFlags(0b01);
+const SUMMARIZE: CapabilityFlags = CapabilityFlags(0b10);
+
+fn provider(id: u64, capabilities: CapabilityFlags) -> RegisteredProvider {
+    RegisteredProvider { id, capabilities }
+}
+
+fn registry() -> InMemoryProviderRegistry {
+    let mut registry = InMemoryProviderRegistry::new();
+    registry
+        .register(provider(1, CODEGEN), OnConflict::Reject)
+        .unwrap();
+    registry
+}
+
+#[test]
+fn reject_keeps_the_existing_registration() {
+    let mut registry = registry();
+    assert_eq!(
+        registry.register(provider(1, SUMMARIZE), OnConflict::Reject),
+        Err(RegistryError::AlreadyRegistered(1))
+    );
+    assert_eq!(registry.get(1), Some(&provider(1, CODEGEN)));
+}
+
+#[test]
+fn replace_overwrites_the_existing_registration() {
+    let mut registry = registry();
+    registry
+        .register(provider(1, SUMMARIZE), OnConflict::Replace)
+        .unwrap();
+    assert_eq!(registry.get(1), Some(&provider(1, SUMMARIZE)));
+    assert_eq!(registry.len(), 1);
+}
+
+#[test]
+fn merge_unions_the_capabilities() {
+    let mut registry = registry();
+    registry
+        .register(provider(1, SUMMARIZE), OnConflict::MergeCapabilities)
+        .unwrap();
+    assert_eq!(registry.get(1), Some(&provider(1, CapabilityFlags(0b11))));
+}
+
+#[test]
+fn new_providers_register_under_any_policy() {
+    let mut registry = InMemoryProviderRegistry::new();
+    for (id, policy) in [
+        (1, OnConflict::Reject),
+        (2, OnConflict::Replace),
+        (3, OnConflict::MergeCapabilities),
+    ] {
+        registry.register(provider(id, CODEGEN), policy).unwrap();
+    }
+    registry
+        .register(provider(4, SUMMARIZE), OnConflict::Reject)
+        .unwrap();
+
+    let ids: Vec<u64> = registry.providers_with(CODEGEN).map(|p| p.id).collect();
+    assert_eq!(ids, [1, 2, 3]);
+    assert_eq!(registry.remove(4), Some(provider(4, SUMMARIZE)));
+    assert!(registry.get(4).is_none());
+}
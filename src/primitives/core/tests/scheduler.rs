@@ -0,0 +1,119 @@
+use sp_neuro_core::{
+    EarliestDeadlineScheduler, FifoScheduler, HighestRewardScheduler, LeastLoadedScheduler,
+    PendingTask, Provider, ProviderScheduler, Quota, StakeWeightedScheduler, TaskScheduler,
+};
+
+fn task(id: u64, reward: u128, deadline: u64) -> PendingTask {
+    PendingTask {
+        id,
+        reward,
+        deadline,
+    }
+}
+
+fn pending() -> Vec<PendingTask> {
+    vec![
+        task(1, 10, 300),
+        task(2, 50, 200),
+        task(3, 20, 100),
+        task(4, 50, 100),
+    ]
+}
+
+#[test]
+fn fifo_picks_first_arrival() {
+    let tasks = pending();
+    assert_eq!(FifoScheduler.next(&tasks).map(|t| t.id), Some(1));
+}
+
+#[test]
+fn highest_reward_picks_largest_reward_breaking_ties_by_arrival() {
+    let tasks = pending();
+    assert_eq!(HighestRewardScheduler.next(&tasks).map(|t| t.id), Some(2));
+}
+
+#[test]
+fn earliest_deadline_picks_soonest_deadline_breaking_ties_by_arrival() {
+    let tasks = pending();
+    assert_eq!(
+        EarliestDeadlineScheduler.next(&tasks).map(|t| t.id),
+        Some(3)
+    );
+}
+
+#[test]
+fn schedulers_return_none_when_nothing_is_pending() {
+    assert!(FifoScheduler.next(&[]).is_none());
+    assert!(HighestRewardScheduler.next(&[]).is_none());
+    assert!(EarliestDeadlineScheduler.next(&[]).is_none());
+}
+
+fn provider(id: u64, load: u32, stake: u128) -> Provider {
+    Provider {
+        id,
+        load,
+        stake,
+        quota: Quota::new(1, 1),
+    }
+}
+
+fn assigned(
+    scheduler: &impl ProviderScheduler,
+    providers: &mut [Provider],
+    now: u64,
+) -> Option<u64> {
+    scheduler.assign(providers, now).map(|p| p.id)
+}
+
+#[test]
+fn least_loaded_picks_smallest_load_breaking_ties_by_order() {
+    let mut providers = [provider(1, 3, 0), provider(2, 1, 0), provider(3, 1, 0)];
+    assert_eq!(assigned(&LeastLoadedScheduler, &mut providers, 0), Some(2));
+}
+
+#[test]
+fn stake_weighted_picks_smallest_load_per_stake() {
+    // Loads per stake: 4/100, 3/50 and 0/0.
+    let mut providers = [provider(1, 4, 100), provider(2, 3, 50), provider(3, 0, 0)];
+    assert_eq!(
+        assigned(&StakeWeightedScheduler, &mut providers, 0),
+        Some(1)
+    );
+}
+
+#[test]
+fn provider_at_quota_is_skipped_until_it_refills() {
+    let mut providers = [provider(1, 0, 100), provider(2, 5, 100)];
+
+    assert_eq!(assigned(&LeastLoadedScheduler, &mut providers, 0), Some(1));
+    assert_eq!(providers[0].quota.tokens(0), 0);
+    assert_eq!(
+        assigned(&LeastLoadedScheduler, &mut providers, 500),
+        Some(2)
+    );
+    assert_eq!(assigned(&LeastLoadedScheduler, &mut providers, 800), None);
+    assert_eq!(
+        assigned(&LeastLoadedScheduler, &mut providers, 1_000),
+        Some(1)
+    );
+
+    assert_eq!(
+        assigned(&StakeWeightedScheduler, &mut providers, 2_000),
+        Some(1)
+    );
+    assert_eq!(
+        assigned(&StakeWeightedScheduler, &mut providers, 2_000),
+        Some(2)
+    );
+}
+
+#[test]
+fn stake_weighted_never_picks_unstaked_providers() {
+    let mut providers = [provider(1, 0, 100), provider(2, 0, 0)];
+
+    assert_eq!(
+        assigned(&StakeWeightedScheduler, &mut providers, 0),
+        Some(1)
+    );
+    assert_eq!(assigned(&StakeWeightedScheduler, &mut providers, 0), None);
+}
@@ -0,0 +1,42 @@
+#![cfg(feature = "serde")]
+
+use sp_neuro_core::{Capability, CapabilityFlags, RegisteredProvider, TaskError, TaskOutcome};
+
+#[test]
+fn task_outcomes_round_trip_through_json() {
+    let outcomes: Vec<TaskOutcome<Vec<u8>, TaskError>> = vec![
+        TaskOutcome::Completed(b"hi".to_vec()),
+        TaskOutcome::Failed(TaskError::Custom(7)),
+        TaskOutcome::TimedOut,
+    ];
+    let json = serde_json::to_string(&outcomes).unwrap();
+    assert_eq!(
+        serde_json::from_str::<Vec<TaskOutcome<Vec<u8>, TaskError>>>(&json).unwrap(),
+        outcomes
+    );
+}
+
+#[test]
+fn providers_round_trip_through_json() {
+    let capability = Capability {
+        task_type: b"codegen".to_vec(),
+        memory_mb: 4096,
+        input_schema_hash: [3; 32],
+    };
+    let json = serde_json::to_value(&capability).unwrap();
+    assert_eq!(json["memory_mb"], 4096);
+    assert_eq!(
+        serde_json::from_value::<Capability>(json).unwrap(),
+        capability
+    );
+
+    let provider = RegisteredProvider {
+        id: 9,
+        capabilities: CapabilityFlags(0b101),
+    };
+    let json = serde_json::to_string(&provider).unwrap();
+    assert_eq!(
+        serde_json::from_str::<RegisteredProvider>(&json).unwrap(),
+        provider
+    );
+}
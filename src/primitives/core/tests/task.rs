@@ -0,0 +1,139 @@
+use codec::Encode;
+use sp_core_hashing::blake2_256;
+use sp_neuro_core::{ContentAddressedTask, NeuralTask, NeuralTaskError, TaskError, TaskOutcome};
+
+/// Echoes its input back, but needs something to echo.
+struct Echo;
+
+impl NeuralTask for Echo {
+    type Input = Vec<u8>;
+    type Output = Vec<u8>;
+    /// What went wrong, in words.
+    type Error = Vec<u8>;
+
+    fn validate_input(input: &Vec<u8>) -> Result<(), TaskError> {
+        if input.is_empty() {
+            return Err(TaskError::EmptyInput);
+        }
+        if input.len() > 4 {
+            return Err(TaskError::SizeExceeded);
+        }
+        Ok(())
+    }
+}
+
+impl ContentAddressedTask for Echo {}
+
+/// Leaves validation to the default.
+struct Passthrough;
+
+impl NeuralTask for Passthrough {
+    type Input = Vec<u8>;
+    type Output = Vec<u8>;
+    type Error = ();
+}
+
+#[test]
+fn custom_validation_rejects_empty_inputs() {
+    assert_eq!(
+        Echo::validate_input(&Vec::new()),
+        Err(TaskError::EmptyInput)
+    );
+    assert_eq!(
+        Echo::decode_and_validate_input(&Vec::<u8>::new().encode()),
+        Err(TaskError::EmptyInput)
+    );
+    assert_eq!(
+        Echo::decode_and_validate_input(&b"hello".to_vec().encode()),
+        Err(TaskError::SizeExceeded)
+    );
+    assert_eq!(
+        Echo::decode_and_validate_input(&b"hi".to_vec().encode()),
+        Ok(b"hi".to_vec())
+    );
+}
+
+#[test]
+fn inputs_are_accepted_by_default() {
+    assert_eq!(Passthrough::validate_input(&Vec::new()), Ok(()));
+    assert_eq!(
+        Passthrough::decode_and_validate_input(&Vec::<u8>::new().encode()),
+        Ok(Vec::new())
+    );
+}
+
+#[test]
+fn undecodable_inputs_are_rejected() {
+    // Claims four bytes but carries one.
+    assert_eq!(
+        Echo::decode_and_validate_input(&[16, 1]),
+        Err(TaskError::InvalidEncoding)
+    );
+    // Trailing bytes are not part of any input.
+    let mut bytes = b"hi".to_vec().encode();
+    bytes.push(0);
+    assert_eq!(
+        Echo::decode_and_validate_input(&bytes),
+        Err(TaskError::InvalidEncoding)
+    );
+}
+
+#[test]
+fn decode_errors_name_the_task_and_what_was_decoded() {
+    let input = Echo::decode_input(7, &[16, 1]).unwrap_err();
+    assert!(matches!(
+        input,
+        NeuralTaskError::Decode {
+            task_id: 7,
+            what: "input",
+            ..
+        }
+    ));
+    assert!(input
+        .to_string()
+        .starts_with("failed to decode the input of task 7: "));
+
+    let mut bytes = b"hi".to_vec().encode();
+    bytes.push(0);
+    assert!(matches!(
+        Echo::decode_output(8, &bytes),
+        Err(NeuralTaskError::Decode {
+            task_id: 8,
+            what: "output",
+            ..
+        })
+    ));
+    assert_eq!(
+        Echo::decode_output(8, &b"hi".to_vec().encode()),
+        Ok(b"hi".to_vec())
+    );
+}
+
+#[test]
+fn outcomes_round_trip() {
+    for outcome in [
+        TaskOutcome::Completed(b"hi".to_vec()),
+        TaskOutcome::Failed(b"out of memory".to_vec()),
+        TaskOutcome::TimedOut,
+    ] {
+        let bytes = Echo::encode_outcome(&outcome);
+        assert_eq!(Echo::decode_outcome(3, &bytes), Ok(outcome));
+    }
+
+    assert!(matches!(
+        Echo::decode_outcome(3, &[3]),
+        Err(NeuralTaskError::Decode {
+            task_id: 3,
+            what: "outcome",
+            ..
+        })
+    ));
+}
+
+#[test]
+fn identical_inputs_share_a_task_id() {
+    let input = b"hi".to_vec();
+    assert_eq!(Echo::derive_task_id(&input), blake2_256(&input.encode()));
+    assert_eq!(Echo::task_id(&input), Echo::derive_task_id(&b"hi".to_vec()));
+    assert_ne!(Echo::task_id(&input), Echo::task_id(&b"ho".to_vec()));
+}
@@ -1,23 +1,275 @@
 //! Client for interacting with NeuroChain nodes.
 
-use jsonrpsee::core::client::ClientT;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use codec::{Decode, Encode};
+use futures::future::{self, Either};
+use futures::stream::{self, Stream, StreamExt};
+use futures_timer::Delay;
+use jsonrpsee::core::client::{ClientT, Subscription, SubscriptionClientT};
+use jsonrpsee::core::params::ArrayParams;
+use jsonrpsee::core::DeserializeOwned;
 use jsonrpsee::http_client::{HttpClient, HttpClientBuilder};
-use sp_core::sr25519;
+use jsonrpsee::rpc_params;
+use jsonrpsee::ws_client::{WsClient, WsClientBuilder};
+use sp_core::hashing::blake2_256;
+use sp_core::{sr25519, Pair, H256};
+
+use crate::events::{
+    decode_events, decode_events_as_values, DecodeEvent, DecodedEvent, Phase, RawEvent,
+};
+use crate::extrinsic::{self, ExtrinsicStatus, SigningContext};
+use crate::header::Header;
+use crate::metadata::{Metadata, StorageKeyPart};
+use crate::parachain::{self, ParachainConfig};
+use crate::subnet::{CreateSubnetArgs, SubnetInfo, SubnetParticipation};
+use crate::version::RuntimeVersion;
+
+/// Number of blocks after submission the client waits for an
+/// extrinsic to be included before giving up.
+pub const INCLUSION_TIMEOUT_BLOCKS: u64 = 32;
+
+/// How often the client polls for new blocks while waiting for an
+/// extrinsic to be included.
+pub const INCLUSION_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// How often [`NeurochainClient::subscribe_events`] polls for new
+/// blocks.
+pub const EVENT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Number of most recent blocks [`NeurochainClient::extrinsic_status`]
+/// searches for an extrinsic that is no longer pending.
+pub const EXTRINSIC_STATUS_SCAN_BLOCKS: u64 = 32;
+
+/// Most subnets [`NeurochainClient::subnets`] reads in one call.
+pub const MAX_SUBNETS_PER_QUERY: usize = 256;
+
+/// Errors returned by [`NeurochainClient`].
+#[derive(Debug)]
+pub enum ClientError {
+    /// The client could not be built, usually because the node URL is
+    /// malformed or, over WebSocket, the node cannot be reached.
+    Build(jsonrpsee::core::Error),
+    /// The JSON‑RPC request failed.
+    Rpc(jsonrpsee::core::Error),
+    /// A hex string returned by the node was malformed.
+    Hex(hex::FromHexError),
+    /// A SCALE payload returned by the node could not be decoded.
+    Codec(codec::Error),
+    /// Metadata has not been fetched yet; call
+    /// [`NeurochainClient::metadata`] first.
+    MetadataNotLoaded,
+    /// The node returned a metadata version the SDK does not support.
+    UnsupportedMetadata(u32),
+    /// The runtime has no pallet with this name.
+    UnknownPallet(String),
+    /// The pallet has no storage item with this name.
+    UnknownStorage { pallet: String, item: String },
+    /// The pallet has no call with this name.
+    UnknownCall { pallet: String, call: String },
+    /// The pallet has no constant with this name.
+    UnknownConstant { pallet: String, name: String },
+    /// The number of key parts does not match the storage map hashers.
+    StorageKeyMismatch { expected: usize, got: usize },
+    /// A response from the node lacked this field.
+    MissingField(&'static str),
+    /// A hex number returned by the node was malformed.
+    HexParse(std::num::ParseIntError),
+    /// A response from the node did not have the expected shape.
+    InvalidResponse(&'static str),
+    /// Extrinsics cannot be signed without a signer; attach one with
+    /// [`NeurochainClient::with_signer`].
+    NoSigner,
+    /// The runtime expects a signed extension the SDK cannot encode.
+    UnsupportedSignedExtension(String),
+    /// A submitted extrinsic was not included within
+    /// [`INCLUSION_TIMEOUT_BLOCKS`] blocks.
+    NotIncluded,
+    /// The extrinsic was included but did not emit the expected event.
+    NoMatchingEvent { pallet: String, event: String },
+    /// More keys were requested in one call than the SDK allows.
+    TooManyKeys { max: usize, got: usize },
+    /// Subscriptions need a WebSocket connection; build the client with
+    /// [`NeurochainClient::new_ws`].
+    SubscriptionsUnsupported,
+    /// The client was not configured with a [`ParachainConfig`].
+    NotAParachain,
+    /// The relay chain has no head for this parachain id.
+    UnknownParachain(u32),
+    /// The runtime does not offer this runtime API at the version
+    /// needed.
+    UnsupportedApi { name: &'static str, version: u32 },
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::Build(err) => write!(f, "failed to build client: {err}"),
+            ClientError::Rpc(err) => write!(f, "rpc error: {err}"),
+            ClientError::Hex(err) => write!(f, "invalid hex in response: {err}"),
+            ClientError::Codec(err) => write!(f, "failed to decode response: {err}"),
+            ClientError::MetadataNotLoaded => write!(f, "runtime metadata has not been fetched"),
+            ClientError::UnsupportedMetadata(version) => {
+                write!(f, "unsupported metadata version {version}")
+            }
+            ClientError::UnknownPallet(pallet) => write!(f, "unknown pallet {pallet}"),
+            ClientError::UnknownStorage { pallet, item } => {
+                write!(f, "unknown storage item {pallet}::{item}")
+            }
+            ClientError::UnknownCall { pallet, call } => {
+                write!(f, "unknown call {pallet}::{call}")
+            }
+            ClientError::UnknownConstant { pallet, name } => {
+                write!(f, "unknown constant {pallet}::{name}")
+            }
+            ClientError::StorageKeyMismatch { expected, got } => {
+                write!(f, "storage item takes {expected} keys, got {got}")
+            }
+            ClientError::MissingField(field) => write!(f, "response has no {field} field"),
+            ClientError::HexParse(err) => write!(f, "invalid hex number in response: {err}"),
+            ClientError::InvalidResponse(reason) => write!(f, "invalid response: {reason}"),
+            ClientError::NoSigner => write!(f, "no signer is attached to the client"),
+            ClientError::UnsupportedSignedExtension(name) => {
+                write!(f, "unsupported signed extension {name}")
+            }
+            ClientError::NotIncluded => write!(f, "extrinsic was not included in time"),
+            ClientError::NoMatchingEvent { pallet, event } => {
+                write!(f, "extrinsic did not emit {pallet}::{event}")
+            }
+            ClientError::TooManyKeys { max, got } => {
+                write!(f, "at most {max} keys may be read at once, got {got}")
+            }
+            ClientError::SubscriptionsUnsupported => {
+                write!(f, "subscriptions need a WebSocket connection")
+            }
+            ClientError::NotAParachain => write!(f, "no relay chain is configured"),
+            ClientError::UnknownParachain(para_id) => {
+                write!(f, "relay chain has no head for parachain {para_id}")
+            }
+            ClientError::UnsupportedApi { name, version } => {
+                write!(f, "runtime does not offer {name} version {version}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
+
+impl From<jsonrpsee::core::Error> for ClientError {
+    fn from(err: jsonrpsee::core::Error) -> Self {
+        ClientError::Rpc(err)
+    }
+}
+
+impl From<hex::FromHexError> for ClientError {
+    fn from(err: hex::FromHexError) -> Self {
+        ClientError::Hex(err)
+    }
+}
+
+impl From<std::num::ParseIntError> for ClientError {
+    fn from(err: std::num::ParseIntError) -> Self {
+        ClientError::HexParse(err)
+    }
+}
+
+impl From<codec::Error> for ClientError {
+    fn from(err: codec::Error) -> Self {
+        ClientError::Codec(err)
+    }
+}
 
 /// A simple wrapper around a JSON‑RPC client that connects to a
-/// NeuroChain node and exposes common API methods.
+/// NeuroChain node, over HTTP or WebSocket, and exposes common API
+/// methods.
 pub struct NeurochainClient {
-    client: HttpClient,
+    client: Transport,
     signer: Option<sr25519::Pair>,
+    metadata: RwLock<Option<Arc<Metadata>>>,
+    runtime_version: RwLock<Option<RuntimeVersion>>,
+    relay: Option<Relay>,
+}
+
+/// Connection to the node.  Only WebSocket supports subscriptions.
+enum Transport {
+    Http(Box<HttpClient>),
+    Ws(WsClient),
+}
+
+impl Transport {
+    async fn request<R: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: ArrayParams,
+    ) -> Result<R, jsonrpsee::core::Error> {
+        match self {
+            Transport::Http(client) => client.request(method, params).await,
+            Transport::Ws(client) => client.request(method, params).await,
+        }
+    }
+}
+
+/// The relay chain of a client talking to a parachain.
+struct Relay {
+    para_id: u32,
+    client: HttpClient,
 }
 
 impl NeurochainClient {
-    /// Create a new client for the given node URL.
+    /// Create a new client for the given node URL.  Panics if the
+    /// client cannot be built; use [`try_new`](Self::try_new) to handle
+    /// a malformed URL instead.
     pub fn new(url: &str) -> Self {
+        Self::try_new(url).expect("Failed to create HTTP client")
+    }
+
+    /// Create a new client for the given node URL, failing with
+    /// [`ClientError::Build`] if the URL is malformed.
+    pub fn try_new(url: &str) -> Result<Self, ClientError> {
         let client = HttpClientBuilder::default()
             .build(url)
+            .map_err(ClientError::Build)?;
+        Ok(Self::with_transport(Transport::Http(Box::new(client))))
+    }
+
+    /// Connect to the node at a `ws://` or `wss://` URL.  Besides every
+    /// method available over HTTP, the client can then
+    /// [subscribe to new blocks](Self::subscribe_new_heads).  Fails
+    /// with [`ClientError::Build`] if the node cannot be reached.
+    pub async fn new_ws(url: &str) -> Result<Self, ClientError> {
+        let client = WsClientBuilder::default()
+            .build(url)
+            .await
+            .map_err(ClientError::Build)?;
+        Ok(Self::with_transport(Transport::Ws(client)))
+    }
+
+    fn with_transport(client: Transport) -> Self {
+        Self {
+            client,
+            signer: None,
+            metadata: RwLock::new(None),
+            runtime_version: RwLock::new(None),
+            relay: None,
+        }
+    }
+
+    /// Treat the node as a collator of parachain `config.para_id`.
+    /// Storage is still read from and extrinsics submitted to the
+    /// node; submitted extrinsics are additionally awaited until their
+    /// block is included on the relay chain.
+    pub fn with_parachain(mut self, config: ParachainConfig) -> Self {
+        let client = HttpClientBuilder::default()
+            .build(&config.relay_url)
             .expect("Failed to create HTTP client");
-        Self { client, signer: None }
+        self.relay = Some(Relay {
+            para_id: config.para_id,
+            client,
+        });
+        self
     }
 
     /// Attach a signer (keypair) for sending signed extrinsics.
@@ -27,15 +279,716 @@ impl NeurochainClient {
     }
 
     /// Fetch the current block number.
-    pub async fn block_number(&self) -> Result<u64, Box<dyn std::error::Error>> {
+    pub async fn block_number(&self) -> Result<u64, ClientError> {
         let result: serde_json::Value = self
             .client
-            .request("chain_getHeader", None)
+            .request("chain_getHeader", rpc_params![])
             .await?;
         let block_number_hex = result["number"]
             .as_str()
-            .ok_or("Invalid response")?;
+            .ok_or(ClientError::MissingField("number"))?;
         let block_number = u64::from_str_radix(block_number_hex.trim_start_matches("0x"), 16)?;
         Ok(block_number)
     }
-}
\ No newline at end of file
+
+    /// Subscribe to the header of every new best block.  Fails with
+    /// [`ClientError::SubscriptionsUnsupported`] unless the client was
+    /// built with [`new_ws`](Self::new_ws).
+    pub async fn subscribe_new_heads(&self) -> Result<Subscription<Header>, ClientError> {
+        match &self.client {
+            Transport::Ws(client) => Ok(client
+                .subscribe(
+                    "chain_subscribeNewHeads",
+                    rpc_params![],
+                    "chain_unsubscribeNewHeads",
+                )
+                .await?),
+            Transport::Http(_) => Err(ClientError::SubscriptionsUnsupported),
+        }
+    }
+
+    /// Stream the best block number, yielding only when it increases.
+    ///
+    /// Over WebSocket the client follows
+    /// [`subscribe_new_heads`](Self::subscribe_new_heads) and the stream
+    /// ends when the subscription does.  HTTP has no subscriptions, so
+    /// the node is polled every `poll_interval` instead.  Either way
+    /// repeated numbers are dropped, and an error is yielded as it
+    /// occurs.
+    pub async fn watch_block_number(
+        &self,
+        poll_interval: Duration,
+    ) -> impl Stream<Item = Result<u64, ClientError>> + '_ {
+        if let Transport::Ws(_) = self.client {
+            return Either::Left(self.follow_block_number().await);
+        }
+        Either::Right(stream::unfold(
+            (None, false),
+            move |(last, wait)| async move {
+                if wait {
+                    Delay::new(poll_interval).await;
+                }
+                loop {
+                    match self.best_number().await {
+                        Ok(number) if last.is_some_and(|last| number <= last) => {
+                            Delay::new(poll_interval).await
+                        }
+                        Ok(number) => return Some((Ok(number), (Some(number), true))),
+                        Err(err) => return Some((Err(err), (last, true))),
+                    }
+                }
+            },
+        ))
+    }
+
+    /// Return the runtime metadata, fetching it from the node on first
+    /// use.  Later calls return the cached copy.
+    pub async fn metadata(&self) -> Result<Arc<Metadata>, ClientError> {
+        if let Some(metadata) = self.cached_metadata() {
+            return Ok(metadata);
+        }
+        self.refresh_metadata().await
+    }
+
+    /// Fetch the runtime metadata from the node and replace the cached
+    /// copy.  Call this after a runtime upgrade.
+    pub async fn refresh_metadata(&self) -> Result<Arc<Metadata>, ClientError> {
+        let encoded: String = self
+            .client
+            .request("state_getMetadata", rpc_params![])
+            .await?;
+        let bytes = hex::decode(encoded.trim_start_matches("0x"))?;
+        let metadata = Arc::new(Metadata::decode(&bytes)?);
+        *self.metadata.write().expect("metadata lock poisoned") = Some(metadata.clone());
+        Ok(metadata)
+    }
+
+    /// Fetch the node's runtime version and cache it.  Signed
+    /// extrinsics are built with the cached `transaction_version`.
+    pub async fn runtime_version(&self) -> Result<RuntimeVersion, ClientError> {
+        let value: serde_json::Value = self
+            .client
+            .request("state_getRuntimeVersion", rpc_params![])
+            .await?;
+        let version = RuntimeVersion::from_json(&value)?;
+        *self
+            .runtime_version
+            .write()
+            .expect("runtime version lock poisoned") = Some(version.clone());
+        Ok(version)
+    }
+
+    /// Whether the last fetched runtime version reports API `name` at
+    /// `version` or later.  Returns `false` until
+    /// [`runtime_version`](Self::runtime_version) has been called.
+    pub fn supports_api(&self, name: &str, version: u32) -> bool {
+        self.runtime_version
+            .read()
+            .expect("runtime version lock poisoned")
+            .as_ref()
+            .is_some_and(|runtime| runtime.supports_api(name, version))
+    }
+
+    /// Transaction version to sign extrinsics with, fetching the
+    /// runtime version if it has not been fetched yet.
+    pub async fn transaction_version(&self) -> Result<u32, ClientError> {
+        Ok(self.signing_version().await?.transaction_version)
+    }
+
+    /// Decode the runtime constant `pallet::name`, such as
+    /// `SubnetRegistry::MaxSchemaSize`, from the metadata, fetching the
+    /// metadata on first use.
+    pub async fn constant<T: Decode>(&self, pallet: &str, name: &str) -> Result<T, ClientError> {
+        let metadata = self.metadata().await?;
+        Ok(T::decode(&mut metadata.constant(pallet, name)?)?)
+    }
+
+    /// Build the storage key for `pallet::item` using the hashers and
+    /// prefixes from the cached metadata.
+    pub fn storage_key(
+        &self,
+        pallet: &str,
+        item: &str,
+        keys: &[StorageKeyPart],
+    ) -> Result<Vec<u8>, ClientError> {
+        self.cached_metadata()
+            .ok_or(ClientError::MetadataNotLoaded)?
+            .storage_key(pallet, item, keys)
+    }
+
+    /// The encoded header of the parachain's latest block included on
+    /// the relay chain, read from the relay's `Paras::Heads`.
+    pub async fn parachain_head(&self) -> Result<Vec<u8>, ClientError> {
+        let relay = self.relay.as_ref().ok_or(ClientError::NotAParachain)?;
+        let key = parachain::heads_key(relay.para_id);
+        let encoded: Option<String> = relay
+            .client
+            .request("state_getStorage", rpc_params![to_hex(&key)])
+            .await?;
+        let encoded = encoded.ok_or(ClientError::UnknownParachain(relay.para_id))?;
+        // `HeadData` is stored as a byte vector.
+        let head = Vec::<u8>::decode(&mut &hex::decode(encoded.trim_start_matches("0x"))?[..])?;
+        Ok(head)
+    }
+
+    /// Number of the parachain's latest block included on the relay
+    /// chain.
+    pub async fn included_block_number(&self) -> Result<u64, ClientError> {
+        parachain::head_number(&self.parachain_head().await?)
+    }
+
+    /// Read subnet `subnet_id` from `SubnetRegistry::Subnets`, `None`
+    /// if there is no such subnet.
+    pub async fn get_subnet(&self, subnet_id: u32) -> Result<Option<SubnetInfo>, ClientError> {
+        let metadata = self.metadata().await?;
+        let key = metadata.storage_key(
+            "SubnetRegistry",
+            "Subnets",
+            &[StorageKeyPart::new(&subnet_id)],
+        )?;
+        self.storage_value(&key).await
+    }
+
+    /// Read the subnets with the given ids in a single request.  The
+    /// result follows the order of `ids`, with `None` for ids that
+    /// have no subnet.  At most [`MAX_SUBNETS_PER_QUERY`] ids may be
+    /// passed.
+    pub async fn subnets(
+        &self,
+        ids: &[u32],
+    ) -> Result<Vec<(u32, Option<SubnetInfo>)>, ClientError> {
+        if ids.len() > MAX_SUBNETS_PER_QUERY {
+            return Err(ClientError::TooManyKeys {
+                max: MAX_SUBNETS_PER_QUERY,
+                got: ids.len(),
+            });
+        }
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let metadata = self.metadata().await?;
+        let keys = ids
+            .iter()
+            .map(|id| {
+                metadata
+                    .storage_key("SubnetRegistry", "Subnets", &[StorageKeyPart::new(id)])
+                    .map(|key| to_hex(&key))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let change_sets: serde_json::Value = self
+            .client
+            .request("state_queryStorageAt", rpc_params![&keys])
+            .await?;
+
+        let mut values = HashMap::new();
+        for change_set in change_sets
+            .as_array()
+            .ok_or(ClientError::InvalidResponse("storage query is not a list"))?
+        {
+            let changes = change_set["changes"]
+                .as_array()
+                .ok_or(ClientError::InvalidResponse("change set has no changes"))?;
+            for change in changes {
+                let key = change[0]
+                    .as_str()
+                    .ok_or(ClientError::InvalidResponse("storage change has no key"))?;
+                values.insert(key.to_ascii_lowercase(), change[1].as_str());
+            }
+        }
+
+        ids.iter()
+            .zip(&keys)
+            .map(|(id, key)| {
+                let info = match values.get(key).copied().flatten() {
+                    Some(value) => Some(SubnetInfo::decode(
+                        &mut &hex::decode(value.trim_start_matches("0x"))?[..],
+                    )?),
+                    None => None,
+                };
+                Ok((*id, info))
+            })
+            .collect()
+    }
+
+    /// Every subnet owned by `account`, with its full info, by id.
+    /// Subnets are read through `SubnetRegistryApi` when the runtime
+    /// offers it at version 2, otherwise from storage, at most
+    /// [`MAX_SUBNETS_PER_QUERY`] per request.  A subnet listed for the
+    /// owner but gone from storage, because it was removed in between,
+    /// is skipped.
+    pub async fn owner_subnets_detailed(
+        &self,
+        account: [u8; 32],
+    ) -> Result<Vec<(u32, SubnetInfo)>, ClientError> {
+        let metadata = self.metadata().await?;
+        let key = metadata.storage_key(
+            "SubnetRegistry",
+            "OwnerSubnets",
+            &[StorageKeyPart::new(&account)],
+        )?;
+        let ids: Vec<u32> = self.storage_value(&key).await?.unwrap_or_default();
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        self.signing_version().await?;
+        let mut subnets = if self.supports_api("SubnetRegistryApi", 2) {
+            self.runtime_call("SubnetRegistryApi_subnets", &ids).await?
+        } else {
+            let mut subnets = Vec::with_capacity(ids.len());
+            for page in ids.chunks(MAX_SUBNETS_PER_QUERY) {
+                subnets.extend(
+                    self.subnets(page)
+                        .await?
+                        .into_iter()
+                        .filter_map(|(id, info)| Some((id, info?))),
+                );
+            }
+            subnets
+        };
+        subnets.sort_by_key(|(id, _)| *id);
+        Ok(subnets)
+    }
+
+    /// How many miners and active validators take part in `subnet_id`
+    /// and their total stake, `None` if there is no such subnet.  Needs
+    /// `SubnetRegistryApi` version 4, otherwise fails with
+    /// [`ClientError::UnsupportedApi`].
+    pub async fn subnet_participants(
+        &self,
+        subnet_id: u32,
+    ) -> Result<Option<SubnetParticipation>, ClientError> {
+        self.signing_version().await?;
+        if !self.supports_api("SubnetRegistryApi", 4) {
+            return Err(ClientError::UnsupportedApi {
+                name: "SubnetRegistryApi",
+                version: 4,
+            });
+        }
+        self.runtime_call("SubnetRegistryApi_subnet_participants", &subnet_id)
+            .await
+    }
+
+    /// Create a subnet: sign a `SubnetRegistry::create_subnet` call
+    /// with the attached signer, submit it and return the extrinsic
+    /// hash the node reports.  Fails with [`ClientError::NoSigner`]
+    /// without a signer.  The extrinsic is immortal, pays no tip and
+    /// uses the signer's nonce as stored on chain, so extrinsics of the
+    /// same signer still in the pool are not accounted for.
+    pub async fn create_subnet(&self, args: CreateSubnetArgs) -> Result<H256, ClientError> {
+        let signer = self.signer.as_ref().ok_or(ClientError::NoSigner)?;
+        let metadata = self.metadata().await?;
+        let mut call = metadata
+            .call_index("SubnetRegistry", "create_subnet")?
+            .to_vec();
+        args.encode_to(&mut call);
+
+        let context = SigningContext {
+            spec_version: self.signing_version().await?.spec_version,
+            transaction_version: self.transaction_version().await?,
+            genesis_hash: self.genesis_hash().await?,
+            nonce: self.nonce(&metadata, signer.public().0).await?,
+        };
+        let extrinsic = extrinsic::sign(signer, &call, metadata.signed_extensions(), &context)?;
+        let hash: String = self
+            .client
+            .request("author_submitExtrinsic", rpc_params![to_hex(&extrinsic)])
+            .await?;
+        parse_hash(&hash)
+    }
+
+    /// Submit a signed, SCALE‑encoded extrinsic, wait for it to be
+    /// included in a block and return the first `pallet::event` it
+    /// emitted, decoded as `E`.
+    pub async fn submit_and_await_event<E: DecodeEvent>(
+        &self,
+        extrinsic: &[u8],
+        pallet: &str,
+        event: &str,
+    ) -> Result<E, ClientError> {
+        let metadata = self.metadata().await?;
+        let submitted_at = self.best_number().await?;
+        let encoded = to_hex(extrinsic);
+        let _: String = self
+            .client
+            .request("author_submitExtrinsic", rpc_params![&encoded])
+            .await?;
+
+        let (block_hash, number, index) = self.wait_for_inclusion(&encoded, submitted_at).await?;
+        if self.relay.is_some() {
+            self.wait_for_relay_inclusion(number).await?;
+        }
+        self.events_at(&metadata, &block_hash)
+            .await?
+            .into_iter()
+            .find(|raw| raw.phase == Phase::ApplyExtrinsic(index) && raw.is(pallet, event))
+            .ok_or_else(|| ClientError::NoMatchingEvent {
+                pallet: pallet.into(),
+                event: event.into(),
+            })?
+            .decode()
+    }
+
+    /// Find the extrinsic with hash `tx_hash` in the node's pool or in
+    /// the last [`EXTRINSIC_STATUS_SCAN_BLOCKS`] blocks.  See
+    /// [`ExtrinsicStatus::Unknown`] for what not finding it means.
+    pub async fn extrinsic_status(&self, tx_hash: H256) -> Result<ExtrinsicStatus, ClientError> {
+        let pending: Vec<String> = self
+            .client
+            .request("author_pendingExtrinsics", rpc_params![])
+            .await?;
+        for extrinsic in &pending {
+            if extrinsic_hash(extrinsic)? == tx_hash {
+                return Ok(ExtrinsicStatus::Pending);
+            }
+        }
+
+        let best = self.best_number().await?;
+        let finalized = self.finalized_number().await?;
+        let oldest = best.saturating_sub(EXTRINSIC_STATUS_SCAN_BLOCKS - 1);
+        for number in (oldest..=best).rev() {
+            let (block_hash, extrinsics) = self.block_extrinsics(number).await?;
+            for extrinsic in &extrinsics {
+                if extrinsic_hash(extrinsic)? == tx_hash {
+                    let block_hash = parse_hash(&block_hash)?;
+                    return Ok(if number <= finalized {
+                        ExtrinsicStatus::Finalized(block_hash)
+                    } else {
+                        ExtrinsicStatus::InBlock(block_hash)
+                    });
+                }
+            }
+        }
+        Ok(ExtrinsicStatus::Unknown)
+    }
+
+    /// Whether the extrinsic with hash `ext_hash` is in the block with
+    /// hash `block_hash` and that block is still on the canonical
+    /// chain, i.e. is the block the node returns for its number.  A
+    /// block the node does not know, or one a reorg has replaced, is
+    /// not canonical.
+    pub async fn is_extrinsic_canonical(
+        &self,
+        block_hash: H256,
+        ext_hash: H256,
+    ) -> Result<bool, ClientError> {
+        let block: Option<serde_json::Value> = self
+            .client
+            .request("chain_getBlock", rpc_params![to_hex(block_hash.as_bytes())])
+            .await?;
+        let Some(block) = block else {
+            return Ok(false);
+        };
+        let number = header_number(&block["block"]["header"])?;
+        let canonical: Option<String> = self
+            .client
+            .request("chain_getBlockHash", rpc_params![number])
+            .await?;
+        if canonical.as_deref().map(parse_hash).transpose()? != Some(block_hash) {
+            return Ok(false);
+        }
+        for extrinsic in block["block"]["extrinsics"]
+            .as_array()
+            .ok_or(ClientError::InvalidResponse("block has no extrinsics"))?
+        {
+            let extrinsic = extrinsic
+                .as_str()
+                .ok_or(ClientError::InvalidResponse("extrinsic is not hex"))?;
+            if extrinsic_hash(extrinsic)? == ext_hash {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// The events emitted in the block with hash `block_hash`.
+    pub async fn events_at(
+        &self,
+        metadata: &Metadata,
+        block_hash: &str,
+    ) -> Result<Vec<RawEvent>, ClientError> {
+        match self.encoded_events(metadata, block_hash).await? {
+            Some(encoded) => decode_events(metadata, &encoded),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Stream the events of every new block, decoded into
+    /// [`DecodedEvent`]s, together with the block's hash.
+    ///
+    /// Only events of `pallet_filter` are kept if it is given; a block
+    /// is yielded even when none of its events are.  The stream starts
+    /// at the current best block and polls for later ones every
+    /// [`EVENT_POLL_INTERVAL`], so no block is skipped when several
+    /// arrive between polls.  An error is yielded as it occurs and the
+    /// failed block is retried on the next poll.
+    pub fn subscribe_events(
+        &self,
+        pallet_filter: Option<&str>,
+    ) -> impl Stream<Item = Result<(H256, Vec<DecodedEvent>), ClientError>> + '_ {
+        let pallet_filter = pallet_filter.map(str::to_owned);
+        stream::unfold((None, false), move |(next, wait)| {
+            let pallet_filter = pallet_filter.clone();
+            async move {
+                if wait {
+                    Delay::new(EVENT_POLL_INTERVAL).await;
+                }
+                loop {
+                    let best = match self.best_number().await {
+                        Ok(best) => best,
+                        Err(err) => return Some((Err(err), (next, true))),
+                    };
+                    let number = next.unwrap_or(best);
+                    if number > best {
+                        Delay::new(EVENT_POLL_INTERVAL).await;
+                        continue;
+                    }
+                    return match self.block_events(number, pallet_filter.as_deref()).await {
+                        Ok(events) => Some((Ok(events), (Some(number + 1), false))),
+                        Err(err) => Some((Err(err), (Some(number), true))),
+                    };
+                }
+            }
+        })
+    }
+
+    /// Hash and decoded events of block `number`.
+    async fn block_events(
+        &self,
+        number: u64,
+        pallet_filter: Option<&str>,
+    ) -> Result<(H256, Vec<DecodedEvent>), ClientError> {
+        let metadata = self.metadata().await?;
+        let hash: String = self
+            .client
+            .request("chain_getBlockHash", rpc_params![number])
+            .await?;
+        let events = match self.encoded_events(&metadata, &hash).await? {
+            Some(encoded) => decode_events_as_values(&metadata, &encoded, pallet_filter)?,
+            None => Vec::new(),
+        };
+        Ok((parse_hash(&hash)?, events))
+    }
+
+    /// The encoded contents of `System::Events` in the block with hash
+    /// `block_hash`.
+    async fn encoded_events(
+        &self,
+        metadata: &Metadata,
+        block_hash: &str,
+    ) -> Result<Option<Vec<u8>>, ClientError> {
+        let key = metadata.storage_key("System", "Events", &[])?;
+        let encoded: Option<String> = self
+            .client
+            .request("state_getStorage", rpc_params![to_hex(&key), block_hash])
+            .await?;
+        Ok(encoded
+            .map(|encoded| hex::decode(encoded.trim_start_matches("0x")))
+            .transpose()?)
+    }
+
+    /// Numbers of new best blocks, as they are announced, dropping
+    /// those not above the last one.
+    async fn follow_block_number(&self) -> impl Stream<Item = Result<u64, ClientError>> {
+        let heads = match self.subscribe_new_heads().await {
+            Ok(heads) => Either::Left(heads.map(|head| Ok(head?.number))),
+            Err(err) => Either::Right(stream::once(future::ready(Err(err)))),
+        };
+        let mut last = None;
+        heads.filter(move |number| {
+            let keep = match number {
+                Ok(number) if last.is_some_and(|last| *number <= last) => false,
+                Ok(number) => {
+                    last = Some(*number);
+                    true
+                }
+                Err(_) => true,
+            };
+            future::ready(keep)
+        })
+    }
+
+    /// The cached runtime version, fetched if it has not been yet.
+    async fn signing_version(&self) -> Result<RuntimeVersion, ClientError> {
+        let cached = self
+            .runtime_version
+            .read()
+            .expect("runtime version lock poisoned")
+            .clone();
+        match cached {
+            Some(version) => Ok(version),
+            None => self.runtime_version().await,
+        }
+    }
+
+    async fn genesis_hash(&self) -> Result<H256, ClientError> {
+        let hash: String = self
+            .client
+            .request("chain_getBlockHash", rpc_params![0])
+            .await?;
+        parse_hash(&hash)
+    }
+
+    /// Nonce of `account`, read from `System::Account`.  Accounts that
+    /// do not exist yet start at zero.
+    async fn nonce(&self, metadata: &Metadata, account: [u8; 32]) -> Result<u32, ClientError> {
+        let key = metadata.storage_key("System", "Account", &[StorageKeyPart::new(&account)])?;
+        // The nonce is the first field of `AccountInfo`.
+        Ok(self.storage_value(&key).await?.unwrap_or(0))
+    }
+
+    /// Decode the value stored under `key`, `None` if there is none.
+    /// Only the start of the value needs to decode as a `T`.
+    async fn storage_value<T: Decode>(&self, key: &[u8]) -> Result<Option<T>, ClientError> {
+        let encoded: Option<String> = self
+            .client
+            .request("state_getStorage", rpc_params![to_hex(key)])
+            .await?;
+        encoded
+            .map(|encoded| {
+                Ok(T::decode(
+                    &mut &hex::decode(encoded.trim_start_matches("0x"))?[..],
+                )?)
+            })
+            .transpose()
+    }
+
+    /// Call runtime API `method` with the encoded `args` at the best
+    /// block and decode what it returns.
+    async fn runtime_call<T: Decode>(
+        &self,
+        method: &str,
+        args: &impl Encode,
+    ) -> Result<T, ClientError> {
+        let encoded: String = self
+            .client
+            .request("state_call", rpc_params![method, to_hex(&args.encode())])
+            .await?;
+        Ok(T::decode(
+            &mut &hex::decode(encoded.trim_start_matches("0x"))?[..],
+        )?)
+    }
+
+    /// Number of the best block.
+    async fn best_number(&self) -> Result<u64, ClientError> {
+        let header: serde_json::Value = self
+            .client
+            .request("chain_getHeader", rpc_params![])
+            .await?;
+        header_number(&header)
+    }
+
+    /// Number of the last finalized block.
+    async fn finalized_number(&self) -> Result<u64, ClientError> {
+        let hash: String = self
+            .client
+            .request("chain_getFinalizedHead", rpc_params![])
+            .await?;
+        let header: serde_json::Value = self
+            .client
+            .request("chain_getHeader", rpc_params![&hash])
+            .await?;
+        header_number(&header)
+    }
+
+    /// Hash and hex‑encoded extrinsics of block `number`.
+    async fn block_extrinsics(&self, number: u64) -> Result<(String, Vec<String>), ClientError> {
+        let hash: String = self
+            .client
+            .request("chain_getBlockHash", rpc_params![number])
+            .await?;
+        let block: serde_json::Value = self
+            .client
+            .request("chain_getBlock", rpc_params![&hash])
+            .await?;
+        let extrinsics = block["block"]["extrinsics"]
+            .as_array()
+            .ok_or(ClientError::InvalidResponse("block has no extrinsics"))?
+            .iter()
+            .map(|extrinsic| {
+                extrinsic
+                    .as_str()
+                    .map(str::to_owned)
+                    .ok_or(ClientError::InvalidResponse("extrinsic is not hex"))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok((hash, extrinsics))
+    }
+
+    /// Poll blocks after `after` until one contains `extrinsic`, and
+    /// return that block's hash and number and the extrinsic's index in
+    /// it.
+    async fn wait_for_inclusion(
+        &self,
+        extrinsic: &str,
+        after: u64,
+    ) -> Result<(String, u64, u32), ClientError> {
+        let mut next = after + 1;
+        let last = after + INCLUSION_TIMEOUT_BLOCKS;
+        loop {
+            let best = self.best_number().await?.min(last);
+            while next <= best {
+                let (hash, extrinsics) = self.block_extrinsics(next).await?;
+                if let Some(index) = extrinsics
+                    .iter()
+                    .position(|candidate| candidate == extrinsic)
+                {
+                    return Ok((hash, next, index as u32));
+                }
+                next += 1;
+            }
+            if next > last {
+                return Err(ClientError::NotIncluded);
+            }
+            Delay::new(INCLUSION_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Poll the relay chain until parachain block `number` has been
+    /// included, for at most [`INCLUSION_TIMEOUT_BLOCKS`] polls.
+    async fn wait_for_relay_inclusion(&self, number: u64) -> Result<(), ClientError> {
+        for _ in 0..INCLUSION_TIMEOUT_BLOCKS {
+            if self.included_block_number().await? >= number {
+                return Ok(());
+            }
+            Delay::new(INCLUSION_POLL_INTERVAL).await;
+        }
+        Err(ClientError::NotIncluded)
+    }
+
+    fn cached_metadata(&self) -> Option<Arc<Metadata>> {
+        self.metadata
+            .read()
+            .expect("metadata lock poisoned")
+            .clone()
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
+
+/// Number in a header returned by `chain_getHeader`.
+fn header_number(header: &serde_json::Value) -> Result<u64, ClientError> {
+    let number = header["number"]
+        .as_str()
+        .ok_or(ClientError::InvalidResponse("header has no number"))?;
+    u64::from_str_radix(number.trim_start_matches("0x"), 16)
+        .map_err(|_| ClientError::InvalidResponse("block number is not hex"))
+}
+
+/// Hash of a hex‑encoded extrinsic, as used by the transaction pool.
+fn extrinsic_hash(extrinsic: &str) -> Result<H256, ClientError> {
+    Ok(H256(blake2_256(&hex::decode(
+        extrinsic.trim_start_matches("0x"),
+    )?)))
+}
+
+fn parse_hash(hash: &str) -> Result<H256, ClientError> {
+    let bytes = hex::decode(hash.trim_start_matches("0x"))?;
+    if bytes.len() != 32 {
+        return Err(ClientError::InvalidResponse("block hash is not 32 bytes"));
+    }
+    Ok(H256::from_slice(&bytes))
+}
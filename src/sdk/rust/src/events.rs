@@ -0,0 +1,390 @@
+//! Decoding of runtime events.
+//!
+//! Events live in the `System::Events` storage item as a SCALE‑encoded
+//! `Vec<EventRecord>`.  Each record holds a runtime event whose layout
+//! depends on the pallet that emitted it, so the SDK walks the type
+//! registry in the metadata to find where one event ends and the next
+//! begins.  The fields of an event the caller cares about are then
+//! decoded into a concrete type through [`DecodeEvent`], or, when no
+//! such type is at hand, into JSON values as a [`DecodedEvent`].
+
+use codec::{Compact, Decode};
+use scale_info::{form::PortableForm, PortableRegistry, TypeDef, TypeDefPrimitive, Variant};
+use serde_json::{Map, Value};
+
+use crate::client::ClientError;
+use crate::metadata::Metadata;
+
+/// An event type the SDK can decode from the fields of a raw event.
+///
+/// Any type whose SCALE encoding matches the event's fields, in
+/// declaration order, implements it.
+pub trait DecodeEvent: Sized {
+    fn decode_event(fields: &[u8]) -> Result<Self, codec::Error>;
+}
+
+impl<T: Decode> DecodeEvent for T {
+    fn decode_event(mut fields: &[u8]) -> Result<Self, codec::Error> {
+        let event = T::decode(&mut fields)?;
+        if !fields.is_empty() {
+            return Err("event has more fields than the target type".into());
+        }
+        Ok(event)
+    }
+}
+
+/// When in the block an event was emitted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Decode)]
+pub enum Phase {
+    /// While applying the extrinsic at this index.
+    ApplyExtrinsic(u32),
+    Finalization,
+    Initialization,
+}
+
+/// An event whose fields have not been decoded yet.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RawEvent {
+    pub phase: Phase,
+    pub pallet: String,
+    pub variant: String,
+    /// The SCALE encoding of the event's fields.
+    pub fields: Vec<u8>,
+}
+
+impl RawEvent {
+    /// Whether this is `pallet::variant`.
+    pub fn is(&self, pallet: &str, variant: &str) -> bool {
+        self.pallet == pallet && self.variant == variant
+    }
+
+    /// Decode the event's fields as `E`.
+    pub fn decode<E: DecodeEvent>(&self) -> Result<E, ClientError> {
+        Ok(E::decode_event(&self.fields)?)
+    }
+}
+
+/// An event whose fields have been decoded into JSON values using the
+/// type information in the metadata.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecodedEvent {
+    pub phase: Phase,
+    pub pallet: String,
+    pub name: String,
+    /// The event's fields by name, or by position for unnamed fields.
+    pub fields: Map<String, Value>,
+}
+
+/// Split the encoded contents of `System::Events` into raw events.
+pub fn decode_events(metadata: &Metadata, bytes: &[u8]) -> Result<Vec<RawEvent>, ClientError> {
+    let mut events = Vec::new();
+    for_each_event(metadata, bytes, |phase, pallet, variant, fields| {
+        events.push(RawEvent {
+            phase,
+            pallet: pallet.to_owned(),
+            variant: variant.name.clone(),
+            fields: fields.to_vec(),
+        });
+        Ok(())
+    })?;
+    Ok(events)
+}
+
+/// Decode the encoded contents of `System::Events` into
+/// [`DecodedEvent`]s, keeping only the events of `pallet_filter` if it
+/// is given.
+pub fn decode_events_as_values(
+    metadata: &Metadata,
+    bytes: &[u8],
+    pallet_filter: Option<&str>,
+) -> Result<Vec<DecodedEvent>, ClientError> {
+    let mut events = Vec::new();
+    for_each_event(metadata, bytes, |phase, pallet, variant, mut input| {
+        if pallet_filter.is_some_and(|filter| filter != pallet) {
+            return Ok(());
+        }
+        let mut fields = Map::new();
+        for (position, field) in variant.fields.iter().enumerate() {
+            let key = field.name.clone().unwrap_or_else(|| position.to_string());
+            fields.insert(
+                key,
+                decode_value(metadata.types(), field.ty.id, &mut input)?,
+            );
+        }
+        events.push(DecodedEvent {
+            phase,
+            pallet: pallet.to_owned(),
+            name: variant.name.clone(),
+            fields,
+        });
+        Ok(())
+    })?;
+    Ok(events)
+}
+
+/// Walk the event records in `bytes`, handing each event's phase,
+/// pallet name, variant and encoded fields to `visit`.
+fn for_each_event(
+    metadata: &Metadata,
+    mut bytes: &[u8],
+    mut visit: impl FnMut(Phase, &str, &Variant<PortableForm>, &[u8]) -> Result<(), codec::Error>,
+) -> Result<(), ClientError> {
+    let input = &mut bytes;
+    let count = Compact::<u32>::decode(input)?.0;
+    for _ in 0..count {
+        let phase = Phase::decode(input)?;
+
+        let pallet_index = u8::decode(input)?;
+        let pallet = metadata
+            .pallet_by_index(pallet_index)
+            .ok_or(codec::Error::from("event from unknown pallet"))?;
+        let event_ty = pallet
+            .event
+            .as_ref()
+            .ok_or(codec::Error::from("pallet has no events"))?
+            .ty
+            .id;
+        let TypeDef::Variant(event_def) = &resolve(metadata.types(), event_ty)?.type_def else {
+            return Err(codec::Error::from("pallet event type is not an enum").into());
+        };
+
+        let variant_index = u8::decode(input)?;
+        let variant = event_def
+            .variants
+            .iter()
+            .find(|variant| variant.index == variant_index)
+            .ok_or(codec::Error::from("unknown event variant"))?;
+        let start = *input;
+        for field in &variant.fields {
+            skip(metadata.types(), field.ty.id, input)?;
+        }
+        visit(
+            phase,
+            &pallet.name,
+            variant,
+            &start[..start.len() - input.len()],
+        )?;
+
+        // Topics.
+        let topics = Compact::<u32>::decode(input)?.0 as usize;
+        advance(input, topics * 32)?;
+    }
+    Ok(())
+}
+
+fn resolve(
+    types: &PortableRegistry,
+    id: u32,
+) -> Result<&scale_info::Type<PortableForm>, codec::Error> {
+    types.resolve(id).ok_or_else(|| "unknown type id".into())
+}
+
+fn advance(input: &mut &[u8], len: usize) -> Result<(), codec::Error> {
+    if input.len() < len {
+        return Err("not enough data".into());
+    }
+    *input = &input[len..];
+    Ok(())
+}
+
+/// Move `input` past one value of type `id`.
+fn skip(types: &PortableRegistry, id: u32, input: &mut &[u8]) -> Result<(), codec::Error> {
+    match &resolve(types, id)?.type_def {
+        TypeDef::Composite(composite) => {
+            for field in &composite.fields {
+                skip(types, field.ty.id, input)?;
+            }
+        }
+        TypeDef::Variant(def) => {
+            let index = u8::decode(input)?;
+            let variant = def
+                .variants
+                .iter()
+                .find(|variant| variant.index == index)
+                .ok_or("unknown variant")?;
+            for field in &variant.fields {
+                skip(types, field.ty.id, input)?;
+            }
+        }
+        TypeDef::Sequence(sequence) => {
+            let len = Compact::<u32>::decode(input)?.0;
+            for _ in 0..len {
+                skip(types, sequence.type_param.id, input)?;
+            }
+        }
+        TypeDef::Array(array) => {
+            for _ in 0..array.len {
+                skip(types, array.type_param.id, input)?;
+            }
+        }
+        TypeDef::Tuple(tuple) => {
+            for field in &tuple.fields {
+                skip(types, field.id, input)?;
+            }
+        }
+        TypeDef::Primitive(TypeDefPrimitive::Str) => {
+            let len = Compact::<u32>::decode(input)?.0 as usize;
+            advance(input, len)?;
+        }
+        TypeDef::Primitive(primitive) => advance(input, primitive_size(primitive))?,
+        TypeDef::Compact(_) => {
+            Compact::<u128>::decode(input)?;
+        }
+        TypeDef::BitSequence(bits) => {
+            let store = match &resolve(types, bits.bit_store_type.id)?.type_def {
+                TypeDef::Primitive(primitive) => primitive_size(primitive),
+                _ => return Err("bit store is not a primitive".into()),
+            };
+            let len = Compact::<u32>::decode(input)?.0 as usize;
+            advance(input, len.div_ceil(store * 8) * store)?;
+        }
+    }
+    Ok(())
+}
+
+/// Decode one value of type `id` from `input` into JSON.
+///
+/// Structs with named fields become objects and other composites
+/// arrays, except that a single unnamed field stands for its inner
+/// value.  Enum variants without fields become their name and others
+/// an object keyed by it.  Byte sequences and arrays become hex
+/// strings, as do bit sequences and 256‑bit integers; 128‑bit integers
+/// become decimal strings so no precision is lost.
+fn decode_value(
+    types: &PortableRegistry,
+    id: u32,
+    input: &mut &[u8],
+) -> Result<Value, codec::Error> {
+    let value = match &resolve(types, id)?.type_def {
+        TypeDef::Composite(composite) => decode_fields(
+            types,
+            composite.fields.iter().map(|f| (&f.name, f.ty.id)),
+            input,
+        )?,
+        TypeDef::Variant(def) => {
+            let index = u8::decode(input)?;
+            let variant = def
+                .variants
+                .iter()
+                .find(|variant| variant.index == index)
+                .ok_or("unknown variant")?;
+            if variant.fields.is_empty() {
+                Value::String(variant.name.clone())
+            } else {
+                let fields = variant.fields.iter().map(|f| (&f.name, f.ty.id));
+                let mut object = Map::new();
+                object.insert(variant.name.clone(), decode_fields(types, fields, input)?);
+                Value::Object(object)
+            }
+        }
+        TypeDef::Sequence(sequence) => {
+            let len = Compact::<u32>::decode(input)?.0 as usize;
+            decode_elements(types, sequence.type_param.id, len, input)?
+        }
+        TypeDef::Array(array) => {
+            decode_elements(types, array.type_param.id, array.len as usize, input)?
+        }
+        TypeDef::Tuple(tuple) => Value::Array(
+            tuple
+                .fields
+                .iter()
+                .map(|field| decode_value(types, field.id, input))
+                .collect::<Result<_, _>>()?,
+        ),
+        TypeDef::Primitive(primitive) => decode_primitive(primitive, input)?,
+        TypeDef::Compact(_) => {
+            let value = Compact::<u128>::decode(input)?.0;
+            u64::try_from(value).map_or_else(|_| Value::String(value.to_string()), Value::from)
+        }
+        TypeDef::BitSequence(_) => {
+            let start = *input;
+            skip(types, id, input)?;
+            hex_value(&start[..start.len() - input.len()])
+        }
+    };
+    Ok(value)
+}
+
+fn decode_fields<'a>(
+    types: &PortableRegistry,
+    fields: impl ExactSizeIterator<Item = (&'a Option<String>, u32)>,
+    input: &mut &[u8],
+) -> Result<Value, codec::Error> {
+    let mut named = Map::new();
+    let mut unnamed = Vec::new();
+    for (name, ty) in fields {
+        let value = decode_value(types, ty, input)?;
+        match name {
+            Some(name) => {
+                named.insert(name.clone(), value);
+            }
+            None => unnamed.push(value),
+        }
+    }
+    Ok(match (named.is_empty(), unnamed.len()) {
+        (false, _) => Value::Object(named),
+        (true, 1) => unnamed.remove(0),
+        (true, _) => Value::Array(unnamed),
+    })
+}
+
+fn decode_elements(
+    types: &PortableRegistry,
+    element: u32,
+    len: usize,
+    input: &mut &[u8],
+) -> Result<Value, codec::Error> {
+    if let TypeDef::Primitive(TypeDefPrimitive::U8) = resolve(types, element)?.type_def {
+        let bytes = input.get(..len).ok_or("not enough data")?;
+        let value = hex_value(bytes);
+        advance(input, len)?;
+        return Ok(value);
+    }
+    Ok(Value::Array(
+        (0..len)
+            .map(|_| decode_value(types, element, input))
+            .collect::<Result<_, _>>()?,
+    ))
+}
+
+fn decode_primitive(
+    primitive: &TypeDefPrimitive,
+    input: &mut &[u8],
+) -> Result<Value, codec::Error> {
+    Ok(match primitive {
+        TypeDefPrimitive::Bool => bool::decode(input)?.into(),
+        TypeDefPrimitive::Char => char::from_u32(u32::decode(input)?)
+            .ok_or("invalid char")?
+            .to_string()
+            .into(),
+        TypeDefPrimitive::Str => String::decode(input)?.into(),
+        TypeDefPrimitive::U8 => u8::decode(input)?.into(),
+        TypeDefPrimitive::U16 => u16::decode(input)?.into(),
+        TypeDefPrimitive::U32 => u32::decode(input)?.into(),
+        TypeDefPrimitive::U64 => u64::decode(input)?.into(),
+        TypeDefPrimitive::U128 => u128::decode(input)?.to_string().into(),
+        TypeDefPrimitive::I8 => i8::decode(input)?.into(),
+        TypeDefPrimitive::I16 => i16::decode(input)?.into(),
+        TypeDefPrimitive::I32 => i32::decode(input)?.into(),
+        TypeDefPrimitive::I64 => i64::decode(input)?.into(),
+        TypeDefPrimitive::I128 => i128::decode(input)?.to_string().into(),
+        TypeDefPrimitive::U256 | TypeDefPrimitive::I256 => hex_value(&<[u8; 32]>::decode(input)?),
+    })
+}
+
+fn hex_value(bytes: &[u8]) -> Value {
+    Value::String(format!("0x{}", hex::encode(bytes)))
+}
+
+fn primitive_size(primitive: &TypeDefPrimitive) -> usize {
+    match primitive {
+        TypeDefPrimitive::Bool | TypeDefPrimitive::U8 | TypeDefPrimitive::I8 => 1,
+        TypeDefPrimitive::U16 | TypeDefPrimitive::I16 => 2,
+        TypeDefPrimitive::U32 | TypeDefPrimitive::I32 | TypeDefPrimitive::Char => 4,
+        TypeDefPrimitive::U64 | TypeDefPrimitive::I64 => 8,
+        TypeDefPrimitive::U128 | TypeDefPrimitive::I128 => 16,
+        TypeDefPrimitive::U256 | TypeDefPrimitive::I256 => 32,
+        // Strings are length prefixed and handled by the caller.
+        TypeDefPrimitive::Str => 0,
+    }
+}
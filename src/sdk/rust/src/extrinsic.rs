@@ -0,0 +1,97 @@
+//! Signed extrinsics and the status of submitted ones.
+//!
+//! Extrinsics are built in the version 4 format: the signer's address,
+//! an sr25519 signature, the signed extensions' extra data and the
+//! call.  Which extensions a runtime expects is read from its
+//! metadata, so only their encoding is hard‑coded here.
+
+use codec::{Compact, Encode};
+use sp_core::hashing::blake2_256;
+use sp_core::{sr25519, Pair, H256};
+
+use crate::client::ClientError;
+
+/// Version byte of a signed version 4 extrinsic.
+const SIGNED_V4: u8 = 0x80 | 4;
+
+/// `MultiAddress::Id`.
+const ADDRESS_ID: u8 = 0;
+
+/// `MultiSignature::Sr25519`.
+const SIGNATURE_SR25519: u8 = 1;
+
+/// Where a submitted extrinsic stands, as reported by
+/// [`NeurochainClient::extrinsic_status`](crate::NeurochainClient::extrinsic_status).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExtrinsicStatus {
+    /// Waiting in the node's transaction pool.
+    Pending,
+    /// Included in the block with this hash, which is not finalized
+    /// yet.
+    InBlock(H256),
+    /// Included in the finalized block with this hash.
+    Finalized(H256),
+    /// Neither in the pool nor in the blocks that were scanned.
+    ///
+    /// This is a best‑effort answer: the extrinsic may have been
+    /// dropped from the pool or rejected, included in a block older
+    /// than the scanned range, or submitted to a node whose pool this
+    /// node has not seen.
+    Unknown,
+}
+
+/// Chain state a signature commits to.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct SigningContext {
+    pub(crate) spec_version: u32,
+    pub(crate) transaction_version: u32,
+    pub(crate) genesis_hash: H256,
+    pub(crate) nonce: u32,
+}
+
+/// Sign `call` with `signer` as an immortal extrinsic without a tip,
+/// carrying the data of `extensions` in order, and return the encoded
+/// extrinsic.
+pub(crate) fn sign<'a>(
+    signer: &sr25519::Pair,
+    call: &[u8],
+    extensions: impl IntoIterator<Item = &'a str>,
+    context: &SigningContext,
+) -> Result<Vec<u8>, ClientError> {
+    let mut extra = Vec::new();
+    let mut additional = Vec::new();
+    for extension in extensions {
+        match extension {
+            "CheckNonZeroSender" | "CheckWeight" => {}
+            "CheckSpecVersion" => context.spec_version.encode_to(&mut additional),
+            "CheckTxVersion" => context.transaction_version.encode_to(&mut additional),
+            "CheckGenesis" => context.genesis_hash.0.encode_to(&mut additional),
+            "CheckMortality" | "CheckEra" => {
+                // An immortal era is valid from genesis on.
+                extra.push(0);
+                context.genesis_hash.0.encode_to(&mut additional);
+            }
+            "CheckNonce" => Compact(context.nonce).encode_to(&mut extra),
+            "ChargeTransactionPayment" => Compact(0u128).encode_to(&mut extra),
+            other => return Err(ClientError::UnsupportedSignedExtension(other.into())),
+        }
+    }
+
+    // Payloads over 256 bytes are signed by their hash.
+    let payload = [call, &extra, &additional].concat();
+    let signature = if payload.len() > 256 {
+        signer.sign(&blake2_256(&payload))
+    } else {
+        signer.sign(&payload)
+    };
+
+    let mut body = vec![SIGNED_V4, ADDRESS_ID];
+    body.extend(signer.public().0);
+    body.push(SIGNATURE_SR25519);
+    body.extend(signature.0);
+    body.extend(extra);
+    body.extend(call);
+    let mut extrinsic = Compact(body.len() as u32).encode();
+    extrinsic.extend(body);
+    Ok(extrinsic)
+}
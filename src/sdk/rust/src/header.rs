@@ -0,0 +1,52 @@
+//! Block headers reported by the node.
+//!
+//! Headers arrive as JSON, from `chain_getHeader` or from a
+//! `chain_subscribeNewHeads` subscription, with hex‑encoded hashes and
+//! a hex block number.
+
+use serde::de::{self, Deserialize, Deserializer};
+use sp_core::H256;
+
+use crate::client::ClientError;
+
+/// The header of a block.  The digest is not decoded.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Header {
+    pub parent_hash: H256,
+    pub number: u64,
+    pub state_root: H256,
+    pub extrinsics_root: H256,
+}
+
+impl Header {
+    /// Parse the JSON of a header.
+    pub fn from_json(value: &serde_json::Value) -> Result<Self, ClientError> {
+        let number = value["number"]
+            .as_str()
+            .ok_or(ClientError::MissingField("number"))?;
+        Ok(Self {
+            parent_hash: hash(value, "parentHash")?,
+            number: u64::from_str_radix(number.trim_start_matches("0x"), 16)?,
+            state_root: hash(value, "stateRoot")?,
+            extrinsics_root: hash(value, "extrinsicsRoot")?,
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for Header {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        Header::from_json(&value).map_err(de::Error::custom)
+    }
+}
+
+fn hash(value: &serde_json::Value, field: &'static str) -> Result<H256, ClientError> {
+    let hash = value[field]
+        .as_str()
+        .ok_or(ClientError::MissingField(field))?;
+    let bytes = hex::decode(hash.trim_start_matches("0x"))?;
+    if bytes.len() != 32 {
+        return Err(ClientError::InvalidResponse("header hash is not 32 bytes"));
+    }
+    Ok(H256::from_slice(&bytes))
+}
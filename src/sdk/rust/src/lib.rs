@@ -7,5 +7,19 @@
 //! includes only minimal scaffolding.
 
 pub mod client;
+pub mod events;
+pub mod extrinsic;
+pub mod header;
+pub mod metadata;
+pub mod parachain;
+pub mod subnet;
+pub mod version;
 
-pub use client::NeurochainClient;
\ No newline at end of file
+pub use client::{ClientError, NeurochainClient};
+pub use events::{DecodeEvent, DecodedEvent, Phase, RawEvent};
+pub use extrinsic::ExtrinsicStatus;
+pub use header::Header;
+pub use metadata::{Metadata, StorageKeyPart};
+pub use parachain::ParachainConfig;
+pub use subnet::{CreateSubnetArgs, SubnetInfo, SubnetParticipation, SubnetStatus, TaskType};
+pub use version::RuntimeVersion;
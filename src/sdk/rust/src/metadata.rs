@@ -0,0 +1,172 @@
+//! Runtime metadata and metadata‑driven storage keys.
+//!
+//! Storage keys depend on the pallet prefix, the storage item name and
+//! the hashers declared for each map key.  All three can change in a
+//! runtime upgrade, so rather than hard‑coding them the SDK reads them
+//! from the metadata reported by the node.
+
+use codec::{Decode, Encode};
+use frame_metadata::{
+    v14::{PalletMetadata, RuntimeMetadataV14, StorageEntryType, StorageHasher},
+    RuntimeMetadata, RuntimeMetadataPrefixed,
+};
+use scale_info::{form::PortableForm, PortableRegistry, TypeDef};
+use sp_core::hashing::{blake2_128, blake2_256, twox_128, twox_256, twox_64};
+
+use crate::client::ClientError;
+
+/// One SCALE‑encoded key of a storage map.  Double maps take two parts,
+/// N‑maps take one part per key.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StorageKeyPart(Vec<u8>);
+
+impl StorageKeyPart {
+    /// SCALE‑encode `key` into a key part.
+    pub fn new<K: Encode>(key: &K) -> Self {
+        Self(key.encode())
+    }
+
+    /// Wrap an already SCALE‑encoded key.
+    pub fn from_encoded(encoded: Vec<u8>) -> Self {
+        Self(encoded)
+    }
+}
+
+/// Decoded runtime metadata.  Only metadata V14 is supported, which is
+/// what `state_getMetadata` returns.
+#[derive(Clone, Debug)]
+pub struct Metadata {
+    inner: RuntimeMetadataV14,
+}
+
+impl Metadata {
+    /// Decode metadata from the bytes returned by `state_getMetadata`.
+    pub fn decode(mut bytes: &[u8]) -> Result<Self, ClientError> {
+        let prefixed = RuntimeMetadataPrefixed::decode(&mut bytes)?;
+        match prefixed.1 {
+            RuntimeMetadata::V14(inner) => Ok(Self { inner }),
+            other => Err(ClientError::UnsupportedMetadata(other.version())),
+        }
+    }
+
+    /// Build the full storage key for `pallet::item` with the given map
+    /// keys.  Plain storage values take no key parts.
+    pub fn storage_key(
+        &self,
+        pallet: &str,
+        item: &str,
+        keys: &[StorageKeyPart],
+    ) -> Result<Vec<u8>, ClientError> {
+        let storage = self
+            .pallet(pallet)?
+            .storage
+            .as_ref()
+            .ok_or_else(|| unknown_storage(pallet, item))?;
+        let entry = storage
+            .entries
+            .iter()
+            .find(|entry| entry.name == item)
+            .ok_or_else(|| unknown_storage(pallet, item))?;
+
+        let mut key = twox_128(storage.prefix.as_bytes()).to_vec();
+        key.extend(twox_128(entry.name.as_bytes()));
+
+        let hashers: &[StorageHasher] = match &entry.ty {
+            StorageEntryType::Plain(_) => &[],
+            StorageEntryType::Map { hashers, .. } => hashers,
+        };
+        if hashers.len() != keys.len() {
+            return Err(ClientError::StorageKeyMismatch {
+                expected: hashers.len(),
+                got: keys.len(),
+            });
+        }
+        for (hasher, part) in hashers.iter().zip(keys) {
+            key.extend(hash(hasher, &part.0));
+        }
+        Ok(key)
+    }
+
+    /// The SCALE‑encoded value of the runtime constant `pallet::name`.
+    pub fn constant(&self, pallet: &str, name: &str) -> Result<&[u8], ClientError> {
+        self.pallet(pallet)?
+            .constants
+            .iter()
+            .find(|constant| constant.name == name)
+            .map(|constant| &constant.value[..])
+            .ok_or_else(|| ClientError::UnknownConstant {
+                pallet: pallet.into(),
+                name: name.into(),
+            })
+    }
+
+    /// The pallet and call indices that start the encoding of the call
+    /// `pallet::call`, such as `SubnetRegistry::create_subnet`.
+    pub fn call_index(&self, pallet: &str, call: &str) -> Result<[u8; 2], ClientError> {
+        let metadata = self.pallet(pallet)?;
+        let unknown = || ClientError::UnknownCall {
+            pallet: pallet.into(),
+            call: call.into(),
+        };
+        let calls = metadata.calls.as_ref().ok_or_else(unknown)?;
+        let Some(TypeDef::Variant(def)) = self.types().resolve(calls.ty.id).map(|ty| &ty.type_def)
+        else {
+            return Err(unknown());
+        };
+        def.variants
+            .iter()
+            .find(|variant| variant.name == call)
+            .map(|variant| [metadata.index, variant.index])
+            .ok_or_else(unknown)
+    }
+
+    /// Identifiers of the signed extensions every signed extrinsic
+    /// carries, in order.
+    pub(crate) fn signed_extensions(&self) -> impl Iterator<Item = &str> {
+        self.inner
+            .extrinsic
+            .signed_extensions
+            .iter()
+            .map(|extension| extension.identifier.as_str())
+    }
+
+    /// The type registry referenced by the metadata.
+    pub(crate) fn types(&self) -> &PortableRegistry {
+        &self.inner.types
+    }
+
+    /// The pallet at `index` in the runtime's `construct_runtime!`.
+    pub(crate) fn pallet_by_index(&self, index: u8) -> Option<&PalletMetadata<PortableForm>> {
+        self.inner
+            .pallets
+            .iter()
+            .find(|pallet| pallet.index == index)
+    }
+
+    fn pallet(&self, name: &str) -> Result<&PalletMetadata<PortableForm>, ClientError> {
+        self.inner
+            .pallets
+            .iter()
+            .find(|pallet| pallet.name == name)
+            .ok_or_else(|| ClientError::UnknownPallet(name.into()))
+    }
+}
+
+fn unknown_storage(pallet: &str, item: &str) -> ClientError {
+    ClientError::UnknownStorage {
+        pallet: pallet.into(),
+        item: item.into(),
+    }
+}
+
+fn hash(hasher: &StorageHasher, data: &[u8]) -> Vec<u8> {
+    match hasher {
+        StorageHasher::Blake2_128 => blake2_128(data).to_vec(),
+        StorageHasher::Blake2_256 => blake2_256(data).to_vec(),
+        StorageHasher::Blake2_128Concat => [&blake2_128(data)[..], data].concat(),
+        StorageHasher::Twox128 => twox_128(data).to_vec(),
+        StorageHasher::Twox256 => twox_256(data).to_vec(),
+        StorageHasher::Twox64Concat => [&twox_64(data)[..], data].concat(),
+        StorageHasher::Identity => data.to_vec(),
+    }
+}
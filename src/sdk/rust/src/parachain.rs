@@ -0,0 +1,43 @@
+//! Access to NeuroChain running as a parachain.
+//!
+//! A parachain's state and extrinsics are served by its own collators,
+//! but which of its blocks count is decided on the relay chain: the
+//! relay keeps the head of every parachain's latest included block in
+//! `Paras::Heads`.  With a [`ParachainConfig`] the client keeps reading
+//! storage from and submitting extrinsics to the parachain node, and
+//! reads that head through a relay‑chain RPC endpoint.
+
+use codec::{Compact, Decode, Encode};
+use sp_core::hashing::{twox_128, twox_64};
+
+use crate::client::ClientError;
+
+/// Where to find the relay chain NeuroChain is a parachain of.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParachainConfig {
+    /// NeuroChain's id on the relay chain.
+    pub para_id: u32,
+    /// URL of a relay‑chain RPC endpoint.
+    pub relay_url: String,
+}
+
+/// Key of `Paras::Heads` for `para_id` in relay‑chain storage.
+pub fn heads_key(para_id: u32) -> Vec<u8> {
+    let id = para_id.encode();
+    let mut key = twox_128(b"Paras").to_vec();
+    key.extend(twox_128(b"Heads"));
+    // `Heads` is a `Twox64Concat` map.
+    key.extend(twox_64(&id));
+    key.extend(id);
+    key
+}
+
+/// Number of the block whose encoded header is `head`.
+pub fn head_number(head: &[u8]) -> Result<u64, ClientError> {
+    // A header starts with the parent hash followed by the number.
+    let mut number = head
+        .get(32..)
+        .ok_or(ClientError::InvalidResponse("parachain head is too short"))?;
+    let Compact(number) = Compact::<u32>::decode(&mut number)?;
+    Ok(number.into())
+}
@@ -0,0 +1,80 @@
+//! Subnet types as stored by the subnet registry pallet.
+//!
+//! These mirror the pallet's storage layout for a runtime using 32‑byte
+//! account ids and `u128` balances, so values read from storage can be
+//! decoded without depending on the runtime crate.
+
+use codec::{Decode, Encode};
+
+/// The kind of intelligence task a subnet serves.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+pub enum TaskType {
+    CodeGen,
+    ImageGen,
+    ProteinFolding,
+    Custom(Vec<u8>),
+}
+
+/// Lifecycle state of a subnet.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Encode, Decode)]
+pub enum SubnetStatus {
+    Active,
+    Retired,
+    Paused,
+}
+
+/// On‑chain definition of a subnet.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+pub struct SubnetInfo {
+    pub owner: [u8; 32],
+    pub task_type: TaskType,
+    pub input_schema: Vec<u8>,
+    pub output_schema: Vec<u8>,
+    pub current_schema_version: u32,
+    pub evaluation_spec: Vec<u8>,
+    /// Share of total emissions, in percent.
+    pub emission_weight: u8,
+    pub min_stake_miner: u128,
+    pub min_stake_validator: u128,
+    pub max_input_bytes: u32,
+    pub max_open_tasks: u32,
+    pub experimental: bool,
+    pub status: SubnetStatus,
+    /// Block in which the subnet was retired.
+    pub retired_at: Option<u32>,
+    pub retirement_reason: Option<Vec<u8>>,
+    /// Amount reserved from the owner for the subnet.
+    pub deposit: u128,
+    pub name: Vec<u8>,
+    pub description: Vec<u8>,
+}
+
+/// Miners and active validators of a subnet and the stake they hold
+/// on it together, as returned by `SubnetRegistryApi` version 4.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Encode, Decode)]
+pub struct SubnetParticipation {
+    pub miners: u32,
+    pub validators: u32,
+    pub total_stake: u128,
+}
+
+/// Arguments of the subnet registry's `create_subnet` call, encoded in
+/// the order the call takes them.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+pub struct CreateSubnetArgs {
+    pub task_type: TaskType,
+    pub name: Vec<u8>,
+    pub description: Vec<u8>,
+    pub input_schema: Vec<u8>,
+    pub output_schema: Vec<u8>,
+    pub evaluation_spec: Vec<u8>,
+    /// Share of total emissions, in percent.
+    pub emission_weight: u8,
+    pub min_stake_miner: u128,
+    pub min_stake_validator: u128,
+    pub max_input_bytes: u32,
+    pub max_open_tasks: u32,
+    pub experimental: bool,
+    /// Makes retries of the same request create a single subnet.
+    pub request_id: Option<[u8; 16]>,
+}
@@ -0,0 +1,73 @@
+//! Runtime version reported by the node.
+//!
+//! The runtime version tells the SDK which runtime it is talking to:
+//! `spec_version` changes with every runtime upgrade,
+//! `transaction_version` whenever the extrinsic format changes, and
+//! `apis` lists the runtime APIs the node exposes.
+
+use sp_core::hashing::blake2_64;
+
+use crate::client::ClientError;
+
+/// The version of the runtime a node is running.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RuntimeVersion {
+    pub spec_name: String,
+    pub spec_version: u32,
+    /// Must be included in signed extrinsics; the node rejects
+    /// signatures made for another transaction version.
+    pub transaction_version: u32,
+    /// Runtime APIs as `(blake2_64(name), version)` pairs.
+    pub apis: Vec<([u8; 8], u32)>,
+}
+
+impl RuntimeVersion {
+    /// Parse the JSON returned by `state_getRuntimeVersion`.
+    pub fn from_json(value: &serde_json::Value) -> Result<Self, ClientError> {
+        let spec_name = value["specName"]
+            .as_str()
+            .ok_or(ClientError::InvalidResponse(
+                "runtime version has no specName",
+            ))?
+            .to_owned();
+        let spec_version = as_u32(&value["specVersion"], "invalid specVersion")?;
+        let transaction_version =
+            as_u32(&value["transactionVersion"], "invalid transactionVersion")?;
+        let apis = value["apis"]
+            .as_array()
+            .ok_or(ClientError::InvalidResponse("runtime version has no apis"))?
+            .iter()
+            .map(|api| {
+                let id = api[0]
+                    .as_str()
+                    .ok_or(ClientError::InvalidResponse("invalid api id"))?;
+                let id = hex::decode(id.trim_start_matches("0x"))?
+                    .try_into()
+                    .map_err(|_| ClientError::InvalidResponse("api id is not 8 bytes"))?;
+                Ok((id, as_u32(&api[1], "invalid api version")?))
+            })
+            .collect::<Result<_, ClientError>>()?;
+        Ok(Self {
+            spec_name,
+            spec_version,
+            transaction_version,
+            apis,
+        })
+    }
+
+    /// Whether the runtime implements API `name` at `version` or
+    /// later.
+    pub fn supports_api(&self, name: &str, version: u32) -> bool {
+        let id = blake2_64(name.as_bytes());
+        self.apis
+            .iter()
+            .any(|(api, reported)| *api == id && *reported >= version)
+    }
+}
+
+fn as_u32(value: &serde_json::Value, reason: &'static str) -> Result<u32, ClientError> {
+    value
+        .as_u64()
+        .and_then(|value| u32::try_from(value).ok())
+        .ok_or(ClientError::InvalidResponse(reason))
+}
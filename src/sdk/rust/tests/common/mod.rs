@@ -0,0 +1,258 @@
+//! Helpers shared by the SDK integration tests: an in‑process JSON‑RPC
+//! server standing in for a node, and a metadata fixture describing the
+//! pallets the SDK talks to.
+
+#![allow(dead_code)]
+
+use codec::{Compact, Encode};
+use frame_metadata::v14::{
+    ExtrinsicMetadata, PalletCallMetadata, PalletConstantMetadata, PalletEventMetadata,
+    PalletMetadata, PalletStorageMetadata, RuntimeMetadataV14, SignedExtensionMetadata,
+    StorageEntryMetadata, StorageEntryModifier, StorageEntryType, StorageHasher,
+};
+use frame_metadata::RuntimeMetadataPrefixed;
+use jsonrpsee::server::{ServerBuilder, ServerHandle};
+use jsonrpsee::RpcModule;
+use scale_info::{meta_type, TypeInfo};
+
+/// Start a JSON‑RPC server with the methods registered by `setup` and
+/// return its URL.  The server stops when the handle is dropped.
+pub async fn mock_node(setup: impl FnOnce(&mut RpcModule<()>)) -> (String, ServerHandle) {
+    let server = ServerBuilder::default()
+        .build("127.0.0.1:0")
+        .await
+        .expect("mock server binds");
+    let url = format!(
+        "http://{}",
+        server.local_addr().expect("mock server address")
+    );
+    let mut module = RpcModule::new(());
+    setup(&mut module);
+    (url, server.start(module).expect("mock server starts"))
+}
+
+/// Hex‑encode bytes the way a node does in JSON‑RPC responses.
+pub fn to_hex(bytes: &[u8]) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
+
+/// Index of the subnet registry in the fixture runtime.
+pub const SUBNET_REGISTRY_INDEX: u8 = 8;
+
+/// `SubnetRegistry::SubnetDeposit` in the fixture runtime.
+pub const SUBNET_DEPOSIT: u128 = 100;
+
+/// `SubnetRegistry::MaxSchemaSize` in the fixture runtime.
+pub const MAX_SCHEMA_SIZE: u32 = 256;
+
+/// Events of `frame_system` used by the fixture runtime.
+#[derive(Encode, TypeInfo)]
+pub enum SystemEvent {
+    ExtrinsicSuccess {
+        weight: u64,
+        class: u8,
+        pays_fee: bool,
+    },
+    ExtrinsicFailed {
+        error: Vec<u8>,
+    },
+}
+
+/// Mirror of the subnet registry's `SubnetField`.
+#[derive(Encode, TypeInfo)]
+pub enum SubnetField {
+    InputSchema,
+    OutputSchema,
+    EvaluationSpec,
+    EmissionWeight,
+    MinStakeMiner,
+    MinStakeValidator,
+    MaxInputBytes,
+    MaxOpenTasks,
+    Name,
+    Description,
+}
+
+/// Mirror of the subnet registry's events.
+#[derive(Encode, TypeInfo)]
+pub enum SubnetRegistryEvent {
+    SubnetCreated {
+        subnet_id: u32,
+        owner: [u8; 32],
+        name: Vec<u8>,
+    },
+    SubnetUpdated {
+        subnet_id: u32,
+        owner: [u8; 32],
+        changed: Vec<SubnetField>,
+    },
+    SubnetRetired {
+        subnet_id: u32,
+        owner: [u8; 32],
+    },
+    SchemaVersionAdded {
+        subnet_id: u32,
+        version: u32,
+    },
+}
+
+/// Mirror of the subnet registry's calls, as far as the SDK makes them.
+#[allow(non_camel_case_types)]
+#[derive(Encode, TypeInfo)]
+pub enum SubnetRegistryCall {
+    create_subnet {
+        task_type: Vec<u8>,
+        name: Vec<u8>,
+        description: Vec<u8>,
+        input_schema: Vec<u8>,
+        output_schema: Vec<u8>,
+        evaluation_spec: Vec<u8>,
+        emission_weight: u8,
+        min_stake_miner: u128,
+        min_stake_validator: u128,
+        max_input_bytes: u32,
+        max_open_tasks: u32,
+        experimental: bool,
+        request_id: Option<[u8; 16]>,
+    },
+}
+
+/// Signed extensions of the fixture runtime, in order.
+pub const SIGNED_EXTENSIONS: [&str; 8] = [
+    "CheckNonZeroSender",
+    "CheckSpecVersion",
+    "CheckTxVersion",
+    "CheckGenesis",
+    "CheckMortality",
+    "CheckNonce",
+    "CheckWeight",
+    "ChargeTransactionPayment",
+];
+
+/// One `frame_system::EventRecord` emitted while applying the
+/// extrinsic at `extrinsic`.
+pub fn event_record(extrinsic: u32, pallet: u8, event: impl Encode) -> Vec<u8> {
+    // Phase::ApplyExtrinsic, the event and an empty list of topics.
+    (0u8, extrinsic, pallet, event, Vec::<[u8; 32]>::new()).encode()
+}
+
+/// Encode event records as the contents of `System::Events`.
+pub fn encode_events(records: &[Vec<u8>]) -> Vec<u8> {
+    let mut encoded = Compact(records.len() as u32).encode();
+    encoded.extend(records.concat());
+    encoded
+}
+
+/// SCALE‑encoded metadata for a runtime containing the subnet registry.
+pub fn metadata_fixture() -> Vec<u8> {
+    let system = PalletMetadata {
+        name: "System",
+        storage: Some(PalletStorageMetadata {
+            prefix: "System",
+            entries: vec![
+                plain_entry("Events", meta_type::<Vec<u8>>()),
+                map_entry("Account", meta_type::<[u8; 32]>(), meta_type::<Vec<u8>>()),
+            ],
+        }),
+        calls: None,
+        event: Some(PalletEventMetadata {
+            ty: meta_type::<SystemEvent>(),
+        }),
+        constants: vec![],
+        error: None,
+        index: 0,
+    };
+    let subnet_registry = PalletMetadata {
+        name: "SubnetRegistry",
+        storage: Some(PalletStorageMetadata {
+            prefix: "SubnetRegistry",
+            entries: vec![
+                map_entry("Subnets", meta_type::<u32>(), meta_type::<Vec<u8>>()),
+                plain_entry("NextSubnetId", meta_type::<u32>()),
+                map_entry(
+                    "OwnerSubnets",
+                    meta_type::<[u8; 32]>(),
+                    meta_type::<Vec<u32>>(),
+                ),
+            ],
+        }),
+        calls: Some(PalletCallMetadata {
+            ty: meta_type::<SubnetRegistryCall>(),
+        }),
+        event: Some(PalletEventMetadata {
+            ty: meta_type::<SubnetRegistryEvent>(),
+        }),
+        constants: vec![
+            constant(
+                "SubnetDeposit",
+                meta_type::<u128>(),
+                SUBNET_DEPOSIT.encode(),
+            ),
+            constant(
+                "MaxSchemaSize",
+                meta_type::<u32>(),
+                MAX_SCHEMA_SIZE.encode(),
+            ),
+        ],
+        error: None,
+        index: SUBNET_REGISTRY_INDEX,
+    };
+    let metadata = RuntimeMetadataV14::new(
+        vec![system, subnet_registry],
+        ExtrinsicMetadata {
+            ty: meta_type::<()>(),
+            version: 4,
+            signed_extensions: SIGNED_EXTENSIONS
+                .iter()
+                .map(|&identifier| SignedExtensionMetadata {
+                    identifier,
+                    ty: meta_type::<()>(),
+                    additional_signed: meta_type::<()>(),
+                })
+                .collect(),
+        },
+        meta_type::<()>(),
+    );
+    RuntimeMetadataPrefixed::from(metadata).encode()
+}
+
+fn constant(
+    name: &'static str,
+    ty: scale_info::MetaType,
+    value: Vec<u8>,
+) -> PalletConstantMetadata {
+    PalletConstantMetadata {
+        name,
+        ty,
+        value,
+        docs: vec![],
+    }
+}
+
+fn map_entry(
+    name: &'static str,
+    key: scale_info::MetaType,
+    value: scale_info::MetaType,
+) -> StorageEntryMetadata {
+    StorageEntryMetadata {
+        name,
+        modifier: StorageEntryModifier::Optional,
+        ty: StorageEntryType::Map {
+            hashers: vec![StorageHasher::Blake2_128Concat],
+            key,
+            value,
+        },
+        default: vec![0],
+        docs: vec![],
+    }
+}
+
+fn plain_entry(name: &'static str, value: scale_info::MetaType) -> StorageEntryMetadata {
+    StorageEntryMetadata {
+        name,
+        modifier: StorageEntryModifier::Default,
+        ty: StorageEntryType::Plain(value),
+        default: vec![0, 0, 0, 0],
+        docs: vec![],
+    }
+}
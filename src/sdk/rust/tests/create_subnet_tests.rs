@@ -0,0 +1,133 @@
+mod common;
+
+use std::sync::{Arc, Mutex};
+
+use codec::{Compact, Decode, Encode};
+use neurochain_sdk::{ClientError, CreateSubnetArgs, NeurochainClient, TaskType};
+use sp_core::hashing::blake2_256;
+use sp_core::{sr25519, Pair, H256};
+
+const SPEC_VERSION: u32 = 104;
+const TRANSACTION_VERSION: u32 = 3;
+const GENESIS: [u8; 32] = [7; 32];
+const NONCE: u32 = 5;
+
+fn args() -> CreateSubnetArgs {
+    CreateSubnetArgs {
+        task_type: TaskType::CodeGen,
+        name: b"Code generation".to_vec(),
+        description: b"Generates code from a prompt".to_vec(),
+        input_schema: b"{}".to_vec(),
+        output_schema: b"{}".to_vec(),
+        evaluation_spec: b"exact match".to_vec(),
+        emission_weight: 10,
+        min_stake_miner: 1_000,
+        min_stake_validator: 5_000,
+        max_input_bytes: 4096,
+        max_open_tasks: 16,
+        experimental: false,
+        request_id: Some([9; 16]),
+    }
+}
+
+/// A node at nonce [`NONCE`] for every account that records each
+/// extrinsic submitted to it.
+async fn node(submitted: Arc<Mutex<Vec<Vec<u8>>>>) -> (String, jsonrpsee::server::ServerHandle) {
+    common::mock_node(move |module| {
+        let metadata = common::to_hex(&common::metadata_fixture());
+        module
+            .register_method("state_getMetadata", move |_, _| Ok(metadata.clone()))
+            .unwrap();
+        module
+            .register_method("state_getRuntimeVersion", |_, _| {
+                Ok(serde_json::json!({
+                    "specName": "neurochain",
+                    "specVersion": SPEC_VERSION,
+                    "transactionVersion": TRANSACTION_VERSION,
+                    "apis": [],
+                }))
+            })
+            .unwrap();
+        module
+            .register_method("chain_getBlockHash", |params, _| {
+                assert_eq!(params.one::<u64>()?, 0);
+                Ok(common::to_hex(&GENESIS))
+            })
+            .unwrap();
+        module
+            .register_method("state_getStorage", |_, _| {
+                // An `AccountInfo` starting with the nonce.
+                Ok(common::to_hex(&(NONCE, 0u32, 1u32, 0u32).encode()))
+            })
+            .unwrap();
+        module
+            .register_method("author_submitExtrinsic", move |params, _| {
+                let extrinsic: String = params.one()?;
+                let bytes = hex::decode(extrinsic.trim_start_matches("0x")).unwrap();
+                let hash = common::to_hex(&blake2_256(&bytes));
+                submitted.lock().unwrap().push(bytes);
+                Ok(hash)
+            })
+            .unwrap();
+    })
+    .await
+}
+
+#[tokio::test]
+async fn create_subnet_submits_a_signed_extrinsic() {
+    let submitted = Arc::new(Mutex::new(Vec::new()));
+    let (url, _node) = node(submitted.clone()).await;
+    let signer = sr25519::Pair::from_seed(&[1; 32]);
+    let client = NeurochainClient::new(&url).with_signer(signer.clone());
+
+    let hash = client.create_subnet(args()).await.unwrap();
+
+    let submitted = submitted.lock().unwrap();
+    assert_eq!(submitted.len(), 1);
+    let extrinsic = &submitted[0];
+    assert_eq!(hash, H256(blake2_256(extrinsic)));
+
+    let mut input = &extrinsic[..];
+    let Compact(len) = Compact::<u32>::decode(&mut input).unwrap();
+    assert_eq!(len as usize, input.len());
+    let (version, address_kind, public, signature_kind, signature) =
+        <(u8, u8, [u8; 32], u8, [u8; 64])>::decode(&mut input).unwrap();
+    assert_eq!(version, 0x84);
+    assert_eq!(address_kind, 0);
+    assert_eq!(public, signer.public().0);
+    assert_eq!(signature_kind, 1);
+
+    // An immortal era, the nonce and no tip, then the call.
+    let extra = (0u8, Compact(NONCE), Compact(0u128)).encode();
+    assert_eq!(&input[..extra.len()], &extra[..]);
+    let call = &input[extra.len()..];
+    let mut expected_call = vec![common::SUBNET_REGISTRY_INDEX, 0];
+    args().encode_to(&mut expected_call);
+    assert_eq!(call, &expected_call[..]);
+
+    let additional = (SPEC_VERSION, TRANSACTION_VERSION, GENESIS, GENESIS).encode();
+    let payload = [call, &extra, &additional].concat();
+    let payload = if payload.len() > 256 {
+        blake2_256(&payload).to_vec()
+    } else {
+        payload
+    };
+    assert!(sr25519::Pair::verify(
+        &sr25519::Signature(signature),
+        payload,
+        &signer.public()
+    ));
+}
+
+#[tokio::test]
+async fn create_subnet_needs_a_signer() {
+    let submitted = Arc::new(Mutex::new(Vec::new()));
+    let (url, _node) = node(submitted.clone()).await;
+    let client = NeurochainClient::new(&url);
+
+    assert!(matches!(
+        client.create_subnet(args()).await,
+        Err(ClientError::NoSigner)
+    ));
+    assert!(submitted.lock().unwrap().is_empty());
+}
@@ -0,0 +1,211 @@
+mod common;
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use codec::Decode;
+use common::{SubnetField, SubnetRegistryEvent, SystemEvent, SUBNET_REGISTRY_INDEX};
+use neurochain_sdk::events::decode_events_as_values;
+use neurochain_sdk::{ClientError, Metadata, NeurochainClient, Phase};
+
+const EXTRINSIC: &[u8] = &[0x84, 0x01, 0x02, 0x03];
+
+#[derive(Debug, PartialEq, Decode)]
+struct SubnetCreated {
+    subnet_id: u32,
+    owner: [u8; 32],
+    name: Vec<u8>,
+}
+
+/// A node whose best block advances on every header request.  The
+/// submitted extrinsic lands at index 1 of block 11, and `events` is
+/// what `System::Events` holds for that block.
+async fn node_including_extrinsic(
+    events: Vec<u8>,
+) -> (NeurochainClient, jsonrpsee::server::ServerHandle) {
+    let (url, handle) = common::mock_node(move |module| {
+        let metadata = common::to_hex(&common::metadata_fixture());
+        module
+            .register_method("state_getMetadata", move |_, _| Ok(metadata.clone()))
+            .unwrap();
+
+        let best = Arc::new(AtomicU64::new(10));
+        module
+            .register_method("chain_getHeader", move |_, _| {
+                let number = best.fetch_add(1, Ordering::SeqCst);
+                Ok(serde_json::json!({ "number": format!("0x{number:x}") }))
+            })
+            .unwrap();
+        module
+            .register_method("chain_getBlockHash", |params, _| {
+                let (number,): (u64,) = params.parse()?;
+                Ok(format!("0x{number:064x}"))
+            })
+            .unwrap();
+        module
+            .register_method("chain_getBlock", |params, _| {
+                let (hash,): (String,) = params.parse()?;
+                let extrinsics = if hash.ends_with("0b") {
+                    vec![common::to_hex(&[0x04]), common::to_hex(EXTRINSIC)]
+                } else {
+                    vec![common::to_hex(&[0x04])]
+                };
+                Ok(serde_json::json!({ "block": { "extrinsics": extrinsics } }))
+            })
+            .unwrap();
+        module
+            .register_method("author_submitExtrinsic", |_, _| {
+                Ok(common::to_hex(&[0; 32]))
+            })
+            .unwrap();
+        let events = common::to_hex(&events);
+        module
+            .register_method("state_getStorage", move |params, _| {
+                let (_, hash): (String, String) = params.parse()?;
+                Ok(hash.ends_with("0b").then(|| events.clone()))
+            })
+            .unwrap();
+    })
+    .await;
+    (NeurochainClient::new(&url), handle)
+}
+
+#[tokio::test]
+async fn new_subnet_id_is_read_from_subnet_created() {
+    let events = common::encode_events(&[
+        // Another extrinsic's subnet, which must be ignored.
+        common::event_record(
+            0,
+            SUBNET_REGISTRY_INDEX,
+            SubnetRegistryEvent::SubnetCreated {
+                subnet_id: 3,
+                owner: [9; 32],
+                name: b"Other".to_vec(),
+            },
+        ),
+        common::event_record(
+            1,
+            SUBNET_REGISTRY_INDEX,
+            SubnetRegistryEvent::SchemaVersionAdded {
+                subnet_id: 4,
+                version: 0,
+            },
+        ),
+        common::event_record(
+            1,
+            SUBNET_REGISTRY_INDEX,
+            SubnetRegistryEvent::SubnetCreated {
+                subnet_id: 4,
+                owner: [1; 32],
+                name: b"Code generation".to_vec(),
+            },
+        ),
+        common::event_record(
+            1,
+            0,
+            SystemEvent::ExtrinsicSuccess {
+                weight: 1_000,
+                class: 0,
+                pays_fee: true,
+            },
+        ),
+    ]);
+    let (client, _node) = node_including_extrinsic(events).await;
+
+    let created: SubnetCreated = client
+        .submit_and_await_event(EXTRINSIC, "SubnetRegistry", "SubnetCreated")
+        .await
+        .unwrap();
+    assert_eq!(
+        created,
+        SubnetCreated {
+            subnet_id: 4,
+            owner: [1; 32],
+            name: b"Code generation".to_vec(),
+        }
+    );
+}
+
+#[tokio::test]
+async fn missing_event_is_reported() {
+    let events = common::encode_events(&[common::event_record(
+        1,
+        0,
+        SystemEvent::ExtrinsicFailed {
+            error: b"TooManyOwnedSubnets".to_vec(),
+        },
+    )]);
+    let (client, _node) = node_including_extrinsic(events).await;
+
+    let result = client
+        .submit_and_await_event::<SubnetCreated>(EXTRINSIC, "SubnetRegistry", "SubnetCreated")
+        .await;
+    assert!(matches!(
+        result,
+        Err(ClientError::NoMatchingEvent { pallet, event })
+            if pallet == "SubnetRegistry" && event == "SubnetCreated"
+    ));
+}
+
+#[test]
+fn events_are_decoded_into_values_and_filtered_by_pallet() {
+    let metadata = Metadata::decode(&common::metadata_fixture()).unwrap();
+    let events = common::encode_events(&[
+        common::event_record(
+            0,
+            0,
+            SystemEvent::ExtrinsicSuccess {
+                weight: 1_000,
+                class: 0,
+                pays_fee: true,
+            },
+        ),
+        common::event_record(
+            1,
+            SUBNET_REGISTRY_INDEX,
+            SubnetRegistryEvent::SubnetUpdated {
+                subnet_id: 4,
+                owner: [1; 32],
+                changed: vec![SubnetField::InputSchema, SubnetField::MaxOpenTasks],
+            },
+        ),
+        common::event_record(
+            1,
+            0,
+            SystemEvent::ExtrinsicFailed {
+                error: b"Oops".to_vec(),
+            },
+        ),
+    ]);
+
+    let all = decode_events_as_values(&metadata, &events, None).unwrap();
+    assert_eq!(
+        all.iter()
+            .map(|event| (event.pallet.as_str(), event.name.as_str()))
+            .collect::<Vec<_>>(),
+        [
+            ("System", "ExtrinsicSuccess"),
+            ("SubnetRegistry", "SubnetUpdated"),
+            ("System", "ExtrinsicFailed"),
+        ]
+    );
+    assert_eq!(
+        serde_json::Value::Object(all[0].fields.clone()),
+        serde_json::json!({ "weight": 1_000, "class": 0, "pays_fee": true })
+    );
+
+    let subnet_events =
+        decode_events_as_values(&metadata, &events, Some("SubnetRegistry")).unwrap();
+    assert_eq!(subnet_events.len(), 1);
+    let updated = &subnet_events[0];
+    assert_eq!(updated.phase, Phase::ApplyExtrinsic(1));
+    assert_eq!(updated.name, "SubnetUpdated");
+    assert_eq!(
+        serde_json::Value::Object(updated.fields.clone()),
+        serde_json::json!({
+            "subnet_id": 4,
+            "owner": format!("0x{}", "01".repeat(32)),
+            "changed": ["InputSchema", "MaxOpenTasks"],
+        })
+    );
+}
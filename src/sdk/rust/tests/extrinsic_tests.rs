@@ -0,0 +1,125 @@
+mod common;
+
+use neurochain_sdk::{ExtrinsicStatus, NeurochainClient};
+use sp_core::hashing::blake2_256;
+use sp_core::H256;
+
+const PENDING: &[u8] = b"pending extrinsic";
+const IN_BLOCK: &[u8] = b"extrinsic in block 4";
+const FINALIZED: &[u8] = b"extrinsic in block 2";
+
+fn hash(extrinsic: &[u8]) -> H256 {
+    H256(blake2_256(extrinsic))
+}
+
+fn block_hash(number: u64) -> H256 {
+    H256([number as u8; 32])
+}
+
+/// Hash of a block 4 that is no longer on the canonical chain.
+const REORGED: H256 = H256([0xaa; 32]);
+
+/// A node with [`PENDING`] in its pool, best block 5 and finalized
+/// block 3.  It does not know the block with hash `0xff…`.
+async fn node() -> (NeurochainClient, jsonrpsee::server::ServerHandle) {
+    let (url, handle) = common::mock_node(|module| {
+        module
+            .register_method("author_pendingExtrinsics", |_, _| {
+                Ok(vec![common::to_hex(PENDING)])
+            })
+            .unwrap();
+        module
+            .register_method("chain_getFinalizedHead", |_, _| {
+                Ok(common::to_hex(block_hash(3).as_bytes()))
+            })
+            .unwrap();
+        module
+            .register_method("chain_getHeader", |params, _| {
+                // Without a hash the best header is requested.
+                let number = match params.one::<String>() {
+                    Ok(_) => 3,
+                    Err(_) => 5,
+                };
+                Ok(serde_json::json!({ "number": format!("0x{number:x}") }))
+            })
+            .unwrap();
+        module
+            .register_method("chain_getBlockHash", |params, _| {
+                let number: u64 = params.one()?;
+                Ok(common::to_hex(block_hash(number).as_bytes()))
+            })
+            .unwrap();
+        module
+            .register_method("chain_getBlock", |params, _| {
+                let hash: String = params.one()?;
+                let (number, extrinsics) = match &hash[2..4] {
+                    "04" => (4, vec![common::to_hex(IN_BLOCK)]),
+                    "02" => (2, vec![common::to_hex(FINALIZED)]),
+                    // A block 4 that lost a reorg, but included the
+                    // same extrinsic.
+                    "aa" => (4, vec![common::to_hex(IN_BLOCK)]),
+                    "ff" => return Ok(serde_json::Value::Null),
+                    number => (u64::from_str_radix(number, 16).unwrap(), vec![]),
+                };
+                Ok(serde_json::json!({ "block": {
+                    "header": { "number": format!("0x{number:x}") },
+                    "extrinsics": extrinsics,
+                } }))
+            })
+            .unwrap();
+    })
+    .await;
+    (NeurochainClient::new(&url), handle)
+}
+
+#[tokio::test]
+async fn extrinsic_in_the_pool_is_pending() {
+    let (client, _node) = node().await;
+
+    assert_eq!(
+        client.extrinsic_status(hash(PENDING)).await.unwrap(),
+        ExtrinsicStatus::Pending
+    );
+}
+
+#[tokio::test]
+async fn included_extrinsics_report_their_block_and_finality() {
+    let (client, _node) = node().await;
+
+    assert_eq!(
+        client.extrinsic_status(hash(IN_BLOCK)).await.unwrap(),
+        ExtrinsicStatus::InBlock(block_hash(4))
+    );
+    assert_eq!(
+        client.extrinsic_status(hash(FINALIZED)).await.unwrap(),
+        ExtrinsicStatus::Finalized(block_hash(2))
+    );
+    assert_eq!(
+        client.extrinsic_status(hash(b"never seen")).await.unwrap(),
+        ExtrinsicStatus::Unknown
+    );
+}
+
+#[tokio::test]
+async fn inclusion_is_canonical_only_in_the_current_block_at_its_height() {
+    let (client, _node) = node().await;
+
+    assert!(client
+        .is_extrinsic_canonical(block_hash(4), hash(IN_BLOCK))
+        .await
+        .unwrap());
+    // Canonical block, but not the block the extrinsic is in.
+    assert!(!client
+        .is_extrinsic_canonical(block_hash(2), hash(IN_BLOCK))
+        .await
+        .unwrap());
+    // The extrinsic is in the block, but the block was reorged away.
+    assert!(!client
+        .is_extrinsic_canonical(REORGED, hash(IN_BLOCK))
+        .await
+        .unwrap());
+    assert!(!client
+        .is_extrinsic_canonical(H256([0xff; 32]), hash(IN_BLOCK))
+        .await
+        .unwrap());
+}
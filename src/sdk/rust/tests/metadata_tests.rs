@@ -0,0 +1,98 @@
+mod common;
+
+use codec::Encode;
+use neurochain_sdk::{ClientError, NeurochainClient, StorageKeyPart};
+use sp_core::hashing::{blake2_128, twox_128};
+
+async fn client_with_metadata() -> (NeurochainClient, jsonrpsee::server::ServerHandle) {
+    let (url, handle) = common::mock_node(|module| {
+        let metadata = common::to_hex(&common::metadata_fixture());
+        module
+            .register_method("state_getMetadata", move |_, _| Ok(metadata.clone()))
+            .unwrap();
+    })
+    .await;
+    (NeurochainClient::new(&url), handle)
+}
+
+#[tokio::test]
+async fn subnets_key_is_built_from_metadata() {
+    let (client, _node) = client_with_metadata().await;
+    client.metadata().await.unwrap();
+
+    let key = client
+        .storage_key("SubnetRegistry", "Subnets", &[StorageKeyPart::new(&7u32)])
+        .unwrap();
+
+    let mut expected = twox_128(b"SubnetRegistry").to_vec();
+    expected.extend(twox_128(b"Subnets"));
+    expected.extend(blake2_128(&7u32.encode()));
+    expected.extend(7u32.encode());
+    assert_eq!(key, expected);
+}
+
+#[tokio::test]
+async fn plain_storage_key_takes_no_parts() {
+    let (client, _node) = client_with_metadata().await;
+    client.metadata().await.unwrap();
+
+    let key = client
+        .storage_key("SubnetRegistry", "NextSubnetId", &[])
+        .unwrap();
+    assert_eq!(
+        key,
+        [twox_128(b"SubnetRegistry"), twox_128(b"NextSubnetId")].concat()
+    );
+
+    assert!(matches!(
+        client.storage_key(
+            "SubnetRegistry",
+            "NextSubnetId",
+            &[StorageKeyPart::new(&1u32)]
+        ),
+        Err(ClientError::StorageKeyMismatch {
+            expected: 0,
+            got: 1
+        })
+    ));
+}
+
+#[tokio::test]
+async fn storage_key_requires_metadata_and_known_items() {
+    let (client, _node) = client_with_metadata().await;
+    assert!(matches!(
+        client.storage_key("SubnetRegistry", "Subnets", &[StorageKeyPart::new(&1u32)]),
+        Err(ClientError::MetadataNotLoaded)
+    ));
+
+    client.metadata().await.unwrap();
+    assert!(matches!(
+        client.storage_key("Nope", "Subnets", &[]),
+        Err(ClientError::UnknownPallet(_))
+    ));
+    assert!(matches!(
+        client.storage_key("SubnetRegistry", "Nope", &[]),
+        Err(ClientError::UnknownStorage { .. })
+    ));
+}
+
+#[tokio::test]
+async fn constants_are_decoded_from_metadata() {
+    let (client, _node) = client_with_metadata().await;
+
+    let max_schema_size: u32 = client
+        .constant("SubnetRegistry", "MaxSchemaSize")
+        .await
+        .unwrap();
+    assert_eq!(max_schema_size, common::MAX_SCHEMA_SIZE);
+    let deposit: u128 = client
+        .constant("SubnetRegistry", "SubnetDeposit")
+        .await
+        .unwrap();
+    assert_eq!(deposit, common::SUBNET_DEPOSIT);
+
+    assert!(matches!(
+        client.constant::<u32>("SubnetRegistry", "Nope").await,
+        Err(ClientError::UnknownConstant { .. })
+    ));
+}
@@ -0,0 +1,87 @@
+mod common;
+
+use std::sync::{Arc, Mutex};
+
+use codec::{Compact, Encode};
+use neurochain_sdk::{ClientError, NeurochainClient, ParachainConfig};
+use sp_core::hashing::{twox_128, twox_64};
+
+const PARA_ID: u32 = 2000;
+
+/// Encoded header of parachain block `number`.
+fn head(number: u32) -> Vec<u8> {
+    let mut header = [7u8; 32].to_vec();
+    header.extend(Compact(number).encode());
+    // State root, extrinsics root and an empty digest.
+    header.extend([0u8; 64]);
+    header.push(0);
+    header
+}
+
+/// A relay chain holding the head of parachain [`PARA_ID`] at block
+/// 42, recording every storage key it is asked for.
+async fn relay(keys: Arc<Mutex<Vec<String>>>) -> (String, jsonrpsee::server::ServerHandle) {
+    common::mock_node(move |module| {
+        let head_key = {
+            let id = PARA_ID.encode();
+            let mut key = twox_128(b"Paras").to_vec();
+            key.extend(twox_128(b"Heads"));
+            key.extend(twox_64(&id));
+            key.extend(id);
+            common::to_hex(&key)
+        };
+        module
+            .register_method("state_getStorage", move |params, _| {
+                let (key,): (String,) = params.parse()?;
+                keys.lock().unwrap().push(key.clone());
+                Ok((key == head_key).then(|| common::to_hex(&head(42).encode())))
+            })
+            .unwrap();
+    })
+    .await
+}
+
+fn client(relay_url: String, para_id: u32) -> NeurochainClient {
+    // Nothing is read from the parachain node itself.
+    NeurochainClient::new("http://127.0.0.1:1")
+        .with_parachain(ParachainConfig { para_id, relay_url })
+}
+
+#[tokio::test]
+async fn parachain_head_is_read_from_relay_paras_heads() {
+    let keys = Arc::new(Mutex::new(Vec::new()));
+    let (relay_url, _relay) = relay(keys.clone()).await;
+    let client = client(relay_url, PARA_ID);
+
+    assert_eq!(client.parachain_head().await.unwrap(), head(42));
+    assert_eq!(client.included_block_number().await.unwrap(), 42);
+
+    let expected = format!(
+        "0xcd710b30bd2eab0352ddcc26417aa194\
+         1b3c252fcb29d88eff4f3de5de4476c3\
+         {}d0070000",
+        hex::encode(twox_64(&PARA_ID.encode())),
+    );
+    assert_eq!(*keys.lock().unwrap(), vec![expected.clone(), expected]);
+}
+
+#[tokio::test]
+async fn unknown_parachain_is_reported() {
+    let (relay_url, _relay) = relay(Default::default()).await;
+    let client = client(relay_url, PARA_ID + 1);
+
+    assert!(matches!(
+        client.parachain_head().await,
+        Err(ClientError::UnknownParachain(id)) if id == PARA_ID + 1
+    ));
+}
+
+#[tokio::test]
+async fn parachain_head_requires_a_relay() {
+    let client = NeurochainClient::new("http://127.0.0.1:1");
+
+    assert!(matches!(
+        client.parachain_head().await,
+        Err(ClientError::NotAParachain)
+    ));
+}
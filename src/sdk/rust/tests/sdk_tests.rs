@@ -1,9 +1,50 @@
+mod common;
+
+use neurochain_sdk::{ClientError, NeurochainClient};
+
+/// A node whose `chain_getHeader` returns `header`.
+async fn node(header: serde_json::Value) -> (NeurochainClient, jsonrpsee::server::ServerHandle) {
+    let (url, handle) = common::mock_node(move |module| {
+        module
+            .register_method("chain_getHeader", move |_, _| Ok(header.clone()))
+            .unwrap();
+    })
+    .await;
+    (NeurochainClient::new(&url), handle)
+}
+
+#[tokio::test]
+async fn test_block_number_mock() {
+    // Nothing listens on this port.
+    let client = NeurochainClient::new("http://127.0.0.1:1");
+    assert!(matches!(
+        client.block_number().await,
+        Err(ClientError::Rpc(_))
+    ));
+}
+
+#[tokio::test]
+async fn block_number_errors_are_typed() {
+    let (client, _node) = node(serde_json::json!({ "number": "0x2a" })).await;
+    assert_eq!(client.block_number().await.unwrap(), 42);
+
+    let (client, _node) = node(serde_json::json!({ "parentHash": "0x00" })).await;
+    assert!(matches!(
+        client.block_number().await,
+        Err(ClientError::MissingField("number"))
+    ));
+
+    let (client, _node) = node(serde_json::json!({ "number": "0xnope" })).await;
+    assert!(matches!(
+        client.block_number().await,
+        Err(ClientError::HexParse(_))
+    ));
+}
+
 #[test]
-fn test_block_number_mock() {
-    // This test simply creates a client instance.  We don't connect to
-    // a real node in this placeholder.  In future, use mock RPC or
-    // integration tests.
-    let client = neurochain_sdk::NeurochainClient::new("http://localhost:9933");
-    // Ensure the client is created without panicking.
-    assert!(client.block_number().is_err());
-}
\ No newline at end of file
+fn malformed_url_is_an_error() {
+    assert!(matches!(
+        NeurochainClient::try_new("not a url"),
+        Err(ClientError::Build(_))
+    ));
+}
@@ -0,0 +1,233 @@
+mod common;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use codec::{Decode, Encode};
+use neurochain_sdk::client::MAX_SUBNETS_PER_QUERY;
+use neurochain_sdk::{
+    ClientError, NeurochainClient, SubnetInfo, SubnetParticipation, SubnetStatus, TaskType,
+};
+use sp_core::hashing::{blake2_128, blake2_64, twox_128};
+
+/// Owns subnets 1 and 3, and subnet 2 which is gone from storage.
+const OWNER: [u8; 32] = [1; 32];
+const OWNED: [u32; 3] = [3, 2, 1];
+
+fn subnet(owner: u8) -> SubnetInfo {
+    SubnetInfo {
+        owner: [owner; 32],
+        task_type: TaskType::CodeGen,
+        input_schema: br#"{"type":"object"}"#.to_vec(),
+        output_schema: br#"{"type":"object"}"#.to_vec(),
+        current_schema_version: 0,
+        evaluation_spec: b"ipfs://evaluation-spec".to_vec(),
+        emission_weight: 10,
+        min_stake_miner: 10,
+        min_stake_validator: 20,
+        max_input_bytes: 1024,
+        max_open_tasks: 16,
+        experimental: false,
+        status: SubnetStatus::Active,
+        retired_at: None,
+        retirement_reason: None,
+        deposit: 100,
+        name: b"Code generation".to_vec(),
+        description: Vec::new(),
+    }
+}
+
+fn stored(id: u32) -> Option<SubnetInfo> {
+    [1, 3].contains(&id).then(|| subnet(id as u8))
+}
+
+/// The hex key of subnet `id` in `SubnetRegistry::Subnets`.
+fn subnet_key(id: u32) -> String {
+    let key = [
+        &twox_128(b"SubnetRegistry")[..],
+        &twox_128(b"Subnets"),
+        &blake2_128(&id.encode()),
+        &id.encode(),
+    ]
+    .concat();
+    common::to_hex(&key)
+}
+
+/// A node holding subnets 1 and 3 that counts storage queries.  Its
+/// runtime offers `SubnetRegistryApi` at `api_version`.
+async fn node_with_subnets(
+    queries: Arc<AtomicUsize>,
+    api_version: u32,
+) -> (NeurochainClient, jsonrpsee::server::ServerHandle) {
+    let (url, handle) = common::mock_node(move |module| {
+        let metadata = common::to_hex(&common::metadata_fixture());
+        module
+            .register_method("state_getMetadata", move |_, _| Ok(metadata.clone()))
+            .unwrap();
+        module
+            .register_method("state_getRuntimeVersion", move |_, _| {
+                let api = common::to_hex(&blake2_64(b"SubnetRegistryApi"));
+                Ok(serde_json::json!({
+                    "specName": "neurochain",
+                    "specVersion": 104,
+                    "transactionVersion": 3,
+                    "apis": [[api, api_version]],
+                }))
+            })
+            .unwrap();
+        module
+            .register_method("state_getStorage", |params, _| {
+                let key: String = params.one()?;
+                if key.ends_with(&hex::encode(OWNER)) {
+                    return Ok(Some(common::to_hex(&OWNED.to_vec().encode())));
+                }
+                Ok([1u32, 2, 3]
+                    .into_iter()
+                    .find(|id| key == subnet_key(*id))
+                    .and_then(stored)
+                    .map(|info| common::to_hex(&info.encode())))
+            })
+            .unwrap();
+        module
+            .register_method("state_call", |params, _| {
+                let (method, args): (String, String) = params.parse()?;
+                let args = hex::decode(&args[2..]).unwrap();
+                if method == "SubnetRegistryApi_subnet_participants" {
+                    let id = u32::decode(&mut &args[..]).unwrap();
+                    let participation = stored(id).map(|_| SubnetParticipation {
+                        miners: id,
+                        validators: 1,
+                        total_stake: 100,
+                    });
+                    return Ok(common::to_hex(&participation.encode()));
+                }
+                assert_eq!(method, "SubnetRegistryApi_subnets");
+                let ids = Vec::<u32>::decode(&mut &args[..]).unwrap();
+                let subnets: Vec<_> = ids
+                    .into_iter()
+                    .filter_map(|id| Some((id, stored(id)?)))
+                    .collect();
+                Ok(common::to_hex(&subnets.encode()))
+            })
+            .unwrap();
+        module
+            .register_method("state_queryStorageAt", move |params, _| {
+                queries.fetch_add(1, Ordering::SeqCst);
+                let (keys,): (Vec<String>,) = params.parse()?;
+                // Blake2_128Concat keys end with the encoded subnet id.
+                let changes: Vec<_> = keys
+                    .into_iter()
+                    .map(|key| {
+                        let value = [1u32, 2, 3]
+                            .into_iter()
+                            .find(|id| key.ends_with(&hex::encode(id.encode())))
+                            .and_then(stored)
+                            .map(|info| common::to_hex(&info.encode()));
+                        serde_json::json!([key, value])
+                    })
+                    .collect();
+                Ok(serde_json::json!([{ "block": common::to_hex(&[0; 32]), "changes": changes }]))
+            })
+            .unwrap();
+    })
+    .await;
+    (NeurochainClient::new(&url), handle)
+}
+
+#[tokio::test]
+async fn subnets_are_read_in_one_query_and_keep_input_order() {
+    let queries = Arc::new(AtomicUsize::new(0));
+    let (client, _handle) = node_with_subnets(queries.clone(), 1).await;
+
+    let subnets = client.subnets(&[3, 2, 1]).await.unwrap();
+
+    assert_eq!(
+        subnets,
+        vec![(3, Some(subnet(3))), (2, None), (1, Some(subnet(1)))]
+    );
+    assert_eq!(queries.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn too_many_subnet_ids_are_rejected() {
+    let queries = Arc::new(AtomicUsize::new(0));
+    let (client, _handle) = node_with_subnets(queries.clone(), 1).await;
+    let ids: Vec<u32> = (0..=MAX_SUBNETS_PER_QUERY as u32).collect();
+
+    let err = client.subnets(&ids).await.unwrap_err();
+
+    assert!(matches!(
+        err,
+        ClientError::TooManyKeys { max, got } if max == MAX_SUBNETS_PER_QUERY && got == ids.len()
+    ));
+    assert_eq!(queries.load(Ordering::SeqCst), 0);
+}
+
+#[tokio::test]
+async fn owner_subnets_are_read_from_storage_without_the_runtime_api() {
+    let queries = Arc::new(AtomicUsize::new(0));
+    let (client, _handle) = node_with_subnets(queries.clone(), 1).await;
+
+    let subnets = client.owner_subnets_detailed(OWNER).await.unwrap();
+
+    assert_eq!(subnets, vec![(1, subnet(1)), (3, subnet(3))]);
+    assert_eq!(queries.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn owner_subnets_are_read_through_the_runtime_api() {
+    let queries = Arc::new(AtomicUsize::new(0));
+    let (client, _handle) = node_with_subnets(queries.clone(), 2).await;
+
+    let subnets = client.owner_subnets_detailed(OWNER).await.unwrap();
+
+    assert_eq!(subnets, vec![(1, subnet(1)), (3, subnet(3))]);
+    assert_eq!(queries.load(Ordering::SeqCst), 0);
+    assert!(client
+        .owner_subnets_detailed([2; 32])
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn a_subnet_is_read_by_id() {
+    let queries = Arc::new(AtomicUsize::new(0));
+    let (client, _handle) = node_with_subnets(queries.clone(), 1).await;
+
+    assert_eq!(client.get_subnet(3).await.unwrap(), Some(subnet(3)));
+    assert_eq!(client.get_subnet(2).await.unwrap(), None);
+    assert_eq!(queries.load(Ordering::SeqCst), 0);
+}
+
+#[tokio::test]
+async fn subnet_participants_are_read_through_the_runtime_api() {
+    let queries = Arc::new(AtomicUsize::new(0));
+    let (client, _handle) = node_with_subnets(queries.clone(), 4).await;
+
+    assert_eq!(
+        client.subnet_participants(3).await.unwrap(),
+        Some(SubnetParticipation {
+            miners: 3,
+            validators: 1,
+            total_stake: 100,
+        })
+    );
+    assert_eq!(client.subnet_participants(2).await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn subnet_participants_need_api_version_4() {
+    let queries = Arc::new(AtomicUsize::new(0));
+    let (client, _handle) = node_with_subnets(queries.clone(), 3).await;
+
+    let err = client.subnet_participants(3).await.unwrap_err();
+
+    assert!(matches!(
+        err,
+        ClientError::UnsupportedApi {
+            name: "SubnetRegistryApi",
+            version: 4
+        }
+    ));
+}
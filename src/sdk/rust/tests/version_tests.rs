@@ -0,0 +1,52 @@
+mod common;
+
+use neurochain_sdk::NeurochainClient;
+use sp_core::hashing::blake2_64;
+
+fn api(name: &str, version: u32) -> serde_json::Value {
+    serde_json::json!([common::to_hex(&blake2_64(name.as_bytes())), version])
+}
+
+async fn client() -> (NeurochainClient, jsonrpsee::server::ServerHandle) {
+    let (url, handle) = common::mock_node(|module| {
+        let version = serde_json::json!({
+            "specName": "neurochain",
+            "implName": "neurochain-node",
+            "authoringVersion": 1,
+            "specVersion": 104,
+            "implVersion": 0,
+            "apis": [api("Core", 4), api("SubnetRegistryApi", 1)],
+            "transactionVersion": 3,
+            "stateVersion": 1,
+        });
+        module
+            .register_method("state_getRuntimeVersion", move |_, _| Ok(version.clone()))
+            .unwrap();
+    })
+    .await;
+    (NeurochainClient::new(&url), handle)
+}
+
+#[tokio::test]
+async fn runtime_version_is_decoded() {
+    let (client, _node) = client().await;
+
+    let version = client.runtime_version().await.unwrap();
+    assert_eq!(version.spec_name, "neurochain");
+    assert_eq!(version.spec_version, 104);
+    assert_eq!(version.transaction_version, 3);
+    assert_eq!(version.apis.len(), 2);
+    assert_eq!(client.transaction_version().await.unwrap(), 3);
+}
+
+#[tokio::test]
+async fn api_support_is_checked_against_reported_apis() {
+    let (client, _node) = client().await;
+    assert!(!client.supports_api("Core", 1));
+
+    client.runtime_version().await.unwrap();
+    assert!(client.supports_api("Core", 4));
+    assert!(client.supports_api("SubnetRegistryApi", 1));
+    assert!(!client.supports_api("SubnetRegistryApi", 2));
+    assert!(!client.supports_api("EmissionsApi", 1));
+}
@@ -0,0 +1,113 @@
+mod common;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::{pin_mut, StreamExt};
+use neurochain_sdk::{ClientError, NeurochainClient};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// A node whose best block is 5, then 5 again, then 7 from then on.
+async fn node() -> (NeurochainClient, jsonrpsee::server::ServerHandle) {
+    let (url, handle) = common::mock_node(|module| {
+        let polls = Arc::new(AtomicUsize::new(0));
+        module
+            .register_method("chain_getHeader", move |_, _| {
+                let number = match polls.fetch_add(1, Ordering::SeqCst) {
+                    0 | 1 => 5,
+                    _ => 7,
+                };
+                Ok(serde_json::json!({ "number": format!("0x{number:x}") }))
+            })
+            .unwrap();
+    })
+    .await;
+    (NeurochainClient::new(&url), handle)
+}
+
+#[tokio::test]
+async fn repeated_block_numbers_are_not_yielded() {
+    let (client, _handle) = node().await;
+    let numbers = client.watch_block_number(POLL_INTERVAL).await;
+    pin_mut!(numbers);
+
+    assert_eq!(numbers.next().await.unwrap().unwrap(), 5);
+    assert_eq!(numbers.next().await.unwrap().unwrap(), 7);
+    // The node stays at 7, so nothing else is yielded.
+    assert!(tokio::time::timeout(POLL_INTERVAL * 20, numbers.next())
+        .await
+        .is_err());
+}
+
+/// A header as announced by a node, with every hash set to `byte`.
+fn header(number: u64, byte: u8) -> serde_json::Value {
+    let hash = common::to_hex(&[byte; 32]);
+    serde_json::json!({
+        "parentHash": hash,
+        "number": format!("0x{number:x}"),
+        "stateRoot": hash,
+        "extrinsicsRoot": hash,
+        "digest": { "logs": [] },
+    })
+}
+
+/// A node that announces blocks 5, 5 and 7 to each new heads
+/// subscriber, then nothing more.  Returns its WebSocket URL.
+async fn announcing_node() -> (String, jsonrpsee::server::ServerHandle) {
+    let (url, handle) = common::mock_node(|module| {
+        module
+            .register_subscription(
+                "chain_subscribeNewHeads",
+                "chain_newHead",
+                "chain_unsubscribeNewHeads",
+                |_, mut sink, _| {
+                    for number in [5, 5, 7] {
+                        sink.send(&header(number, number as u8)).unwrap();
+                    }
+                    Ok(())
+                },
+            )
+            .unwrap();
+    })
+    .await;
+    (url.replacen("http", "ws", 1), handle)
+}
+
+#[tokio::test]
+async fn new_heads_are_streamed_over_websocket() {
+    let (url, _handle) = announcing_node().await;
+    let client = NeurochainClient::new_ws(&url).await.unwrap();
+    let mut heads = client.subscribe_new_heads().await.unwrap();
+
+    let head = heads.next().await.unwrap().unwrap();
+    assert_eq!(head.number, 5);
+    assert_eq!(head.parent_hash.0, [5; 32]);
+    assert_eq!(heads.next().await.unwrap().unwrap().number, 5);
+    assert_eq!(heads.next().await.unwrap().unwrap().number, 7);
+}
+
+#[tokio::test]
+async fn block_numbers_follow_new_heads_over_websocket() {
+    let (url, _handle) = announcing_node().await;
+    let client = NeurochainClient::new_ws(&url).await.unwrap();
+    let numbers = client.watch_block_number(POLL_INTERVAL).await;
+    pin_mut!(numbers);
+
+    assert_eq!(numbers.next().await.unwrap().unwrap(), 5);
+    assert_eq!(numbers.next().await.unwrap().unwrap(), 7);
+    // The node announces nothing after 7.
+    assert!(tokio::time::timeout(POLL_INTERVAL * 20, numbers.next())
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn subscriptions_need_websocket() {
+    let (client, _handle) = node().await;
+    assert!(matches!(
+        client.subscribe_new_heads().await,
+        Err(ClientError::SubscriptionsUnsupported)
+    ));
+}